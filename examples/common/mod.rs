@@ -1,5 +1,7 @@
 //! Common utilities for examples
 
+#![allow(dead_code)]
+
 use anyhow::Result;
 use std::env;
 
@@ -0,0 +1,119 @@
+//! Capture fixture logs from a live RPC
+//!
+//! Fetches one log per fixture (`curve_create` .. `collect`) with `eth_getLogs`
+//! and writes the JSON objects exactly as the node returned them, keyed like
+//! `fixtures/rpc_logs.json`. Each log keeps its `blockNumber` and
+//! `transactionHash`, and the tool prints both together with the decoded event
+//! so the expected literals in `src/fixtures.rs` can be updated to match.
+//!
+//! Use a token that has graduated, so its curve emitted every event type, and
+//! the WMON pool it was listed into.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! export RPC_URL="https://your-rpc-url"
+//! export TOKEN="0xGraduatedToken"
+//! export POOL="0xItsUniswapV3Pool"
+//! export FROM_BLOCK=1000000 TO_BLOCK=1010000
+//! export OUT="fixtures/rpc_logs.json"   # optional, defaults to stdout
+//! cargo run --example capture_fixtures
+//! ```
+
+use alloy::{
+    primitives::{Address, B256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::Log,
+};
+use anyhow::{Context, Result};
+use nadfun_sdk::{
+    ChainConfig,
+    types::{DexEventType, EventType, decode_bonding_curve_event, decode_dex_event},
+};
+use serde_json::{Map, Value, json};
+use std::env;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::Config;
+
+/// Fixture key, emitting contract and topic0, in `fixtures/rpc_logs.json` order
+fn fixture_topics(curve: Address, pool: Address) -> Vec<(&'static str, Address, B256)> {
+    vec![
+        ("curve_create", curve, EventType::Create.signature()),
+        ("curve_buy", curve, EventType::Buy.signature()),
+        ("curve_sell", curve, EventType::Sell.signature()),
+        ("curve_sync", curve, EventType::Sync.signature()),
+        ("curve_lock", curve, EventType::Lock.signature()),
+        ("curve_listed", curve, EventType::Listed.signature()),
+        ("swap", pool, DexEventType::Swap.signature()),
+        ("mint", pool, DexEventType::Mint.signature()),
+        ("burn", pool, DexEventType::Burn.signature()),
+        ("collect", pool, DexEventType::Collect.signature()),
+    ]
+}
+
+fn env_var(name: &str) -> Result<String> {
+    env::var(name).with_context(|| format!("{name} is not set"))
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let config = Config::from_args()?;
+    let token: Address = config.token.context("TOKEN is not set")?.parse()?;
+    let pool: Address = env_var("POOL")?.parse()?;
+    let from_block: u64 = env_var("FROM_BLOCK")?.parse()?;
+    let to_block: u64 = env_var("TO_BLOCK")?.parse()?;
+    let curve = ChainConfig::default().bonding_curve;
+
+    let provider = ProviderBuilder::new().connect_http(config.rpc_url.parse()?);
+    let token_topic = B256::left_padding_from(token.as_slice());
+
+    let mut captured = Map::new();
+    for (name, address, topic0) in fixture_topics(curve, pool) {
+        let filter = json!({
+            "address": address,
+            "topics": [topic0],
+            "fromBlock": format!("{from_block:#x}"),
+            "toBlock": format!("{to_block:#x}"),
+        });
+        // Raw JSON, so the fixture is byte-for-byte what the node sent
+        let logs: Vec<Value> = provider
+            .client()
+            .request("eth_getLogs", (filter,))
+            .await
+            .with_context(|| format!("eth_getLogs for {name}"))?;
+
+        let found = logs.into_iter().find(|raw| {
+            address == pool
+                || raw["topics"]
+                    .as_array()
+                    .is_some_and(|topics| topics.contains(&json!(token_topic)))
+        });
+        let Some(raw) = found else {
+            eprintln!("⚠️  no {name} log for {token} in blocks {from_block}..={to_block}");
+            continue;
+        };
+
+        let log: Log = serde_json::from_value(raw.clone())?;
+        let block = log.block_number.unwrap_or_default();
+        let tx = log.transaction_hash.unwrap_or_default();
+        println!("✅ {name}: block {block}, tx {tx}");
+        if address == curve {
+            println!("   {:?}", decode_bonding_curve_event(log)?);
+        } else {
+            println!("   {:?}", decode_dex_event(log)?);
+        }
+        captured.insert(name.to_string(), raw);
+    }
+
+    let output = serde_json::to_string_pretty(&Value::Object(captured))?;
+    match env::var("OUT") {
+        Ok(path) => {
+            std::fs::write(&path, output + "\n")?;
+            println!("📝 wrote {path}");
+        }
+        Err(_) => println!("{output}"),
+    }
+    Ok(())
+}
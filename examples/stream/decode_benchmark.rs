@@ -0,0 +1,108 @@
+//! Curve log decoding benchmark
+//!
+//! Decodes synthetic batches built from the `test-fixtures` vectors at several
+//! sizes and prints sequential vs parallel timings, to pick a
+//! `CurveIndexer::with_parallel_decoding` threshold for your machine, then owned
+//! vs borrowed (`decode_bonding_curve_event_ref`) timings.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! cargo run --release --example decode_benchmark --features test-fixtures
+//! ```
+
+use nadfun_sdk::fixtures;
+use nadfun_sdk::stream::curve::indexer::decode_curve_logs;
+use nadfun_sdk::types::{BondingCurveEventRef, decode_bonding_curve_event_ref};
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 20;
+
+fn time(logs: &[alloy::rpc::types::Log], parallel: bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let events = decode_curve_logs(logs, None, parallel);
+        assert_eq!(events.len(), logs.len());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn time_decode(logs: &[alloy::rpc::types::Log], owned: bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let mut decoded = 0usize;
+        for log in logs {
+            let event = decode_bonding_curve_event_ref(log).unwrap();
+            if owned {
+                std::hint::black_box(event.into_owned());
+            } else {
+                std::hint::black_box(&event);
+            }
+            decoded += 1;
+        }
+        assert_eq!(decoded, logs.len());
+    }
+    start.elapsed() / ROUNDS
+}
+
+fn batch(samples: &[alloy::rpc::types::Log], size: usize) -> Vec<alloy::rpc::types::Log> {
+    (0..size)
+        .map(|i| {
+            let mut log = samples[i % samples.len()].clone();
+            log.log_index = Some(i as u64);
+            log
+        })
+        .collect()
+}
+
+fn main() {
+    let samples: Vec<_> = fixtures::curve_fixtures()
+        .into_iter()
+        .map(|fixture| fixture.log)
+        .collect();
+
+    println!("{:>10} {:>14} {:>14}", "logs", "sequential", "parallel");
+
+    for size in [100usize, 1_000, 5_000, 20_000, 100_000] {
+        let logs = batch(&samples, size);
+        let sequential = time(&logs, false);
+        let parallel = time(&logs, true);
+
+        println!(
+            "{:>10} {:>14?} {:>14?}{}",
+            size,
+            sequential,
+            parallel,
+            if parallel < sequential {
+                "  <- parallel wins"
+            } else {
+                ""
+            }
+        );
+    }
+
+    // Only Create events own heap data, so compare on Create logs alone
+    let creates: Vec<_> = samples
+        .iter()
+        .filter(|log| {
+            matches!(
+                decode_bonding_curve_event_ref(log),
+                Ok(BondingCurveEventRef::Create(_))
+            )
+        })
+        .cloned()
+        .collect();
+
+    println!();
+    println!("{:>10} {:>14} {:>14}", "creates", "owned", "borrowed");
+
+    for size in [1_000usize, 20_000, 100_000] {
+        let logs = batch(&creates, size);
+        println!(
+            "{:>10} {:>14?} {:>14?}",
+            size,
+            time_decode(&logs, true),
+            time_decode(&logs, false)
+        );
+    }
+}
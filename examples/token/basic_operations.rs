@@ -109,7 +109,8 @@ async fn main() -> Result<()> {
             let approve_tx = token_helper
                 .approve(token, recipient, approve_amount)
                 .await?;
-            println!("  Approval transaction: {}", approve_tx);
+            println!("  Approval transaction: {}", approve_tx.transaction_hash);
+            println!("  Status: {}, gas used: {:?}", approve_tx.status, approve_tx.gas_used);
 
             // Wait for transaction to be mined
             println!("  ⏳ Waiting for approval to be mined...");
@@ -140,7 +141,8 @@ async fn main() -> Result<()> {
             let transfer_tx = token_helper
                 .transfer(token, recipient, transfer_amount)
                 .await?;
-            println!("  Transfer transaction: {}", transfer_tx);
+            println!("  Transfer transaction: {}", transfer_tx.transaction_hash);
+            println!("  Status: {}, gas used: {:?}", transfer_tx.status, transfer_tx.gas_used);
 
             // Wait for transaction to be mined
             println!("  ⏳ Waiting for transfer to be mined...");
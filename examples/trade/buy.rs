@@ -25,7 +25,7 @@ use alloy::primitives::{utils::parse_ether, Address, U256};
 use alloy::providers::Provider;
 use anyhow::Result;
 use nadfun_sdk::types::BuyParams;
-use nadfun_sdk::{GasEstimationParams, SlippageUtils, Trade};
+use nadfun_sdk::{TradeOperation, SlippageUtils, Trade};
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -113,7 +113,7 @@ async fn main() -> Result<()> {
 
     // Use new unified gas estimation system
     let deadline = U256::from(9999999999999999u64);
-    let gas_params = GasEstimationParams::Buy {
+    let gas_params = TradeOperation::Buy {
         token,
         amount_in: mon_amount,
         amount_out_min,
@@ -167,6 +167,7 @@ async fn main() -> Result<()> {
         println!("  Transaction hash: {}", result.transaction_hash);
         println!("  Block number: {:?}", result.block_number);
         println!("  Gas used: {:?}", result.gas_used);
+        println!("  Fee paid (wei): {:?}", result.fee_paid());
     } else {
         println!("❌ Buy failed!");
         println!("  Transaction hash: {}", result.transaction_hash);
@@ -6,8 +6,9 @@
 //! ## Important Notes
 //!
 //! - **Token Approval Required**: For SELL and SELL PERMIT operations, tokens must be
-//!   approved for the router before gas estimation will work properly. This example
-//!   automatically handles token approval when needed.
+//!   approved for the router before plain gas estimation will work. Without an
+//!   approval, the example first estimates the sell with an overridden allowance
+//!   (needs node support for state overrides), then approves automatically.
 //!
 //! - **Real Network Conditions**: Gas estimation uses actual network calls and will
 //!   fail if proper token balances and approvals are not in place.
@@ -17,7 +18,7 @@
 
 use alloy::primitives::{utils::parse_ether, Address, U256};
 use anyhow::Result;
-use nadfun_sdk::{GasEstimationParams, SlippageUtils, TokenHelper, Trade};
+use nadfun_sdk::{TradeOperation, SlippageUtils, TokenHelper, Trade};
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -87,7 +88,7 @@ async fn main() -> Result<()> {
     // === BUY GAS ESTIMATION ===
     println!("⛽ === BUY GAS ESTIMATION ===");
 
-    let buy_params = GasEstimationParams::Buy {
+    let buy_params = TradeOperation::Buy {
         token,
         amount_in: mon_amount,
         amount_out_min: min_tokens,
@@ -175,14 +176,29 @@ async fn main() -> Result<()> {
             "⚠️ Insufficient allowance! Need: {}, Have: {}",
             actual_sell_amount, allowance
         );
+
+        match token_helper
+            .estimate_sell_gas_unapproved(
+                &sell_router,
+                token,
+                actual_sell_amount,
+                U256::from(1),
+                deadline,
+            )
+            .await
+        {
+            Ok(gas) => println!("🧪 Sell gas before approval (allowance override): {}", gas),
+            Err(e) => println!("⚠️ Allowance override estimate unavailable: {}", e),
+        }
+
         println!("🔧 Approving tokens for router...");
 
         match token_helper
             .approve(token, sell_router.address(), actual_sell_amount)
             .await
         {
-            Ok(tx_hash) => {
-                println!("✅ Approval successful: {}", tx_hash);
+            Ok(approval) => {
+                println!("✅ Approval successful: {}", approval.transaction_hash);
                 println!("⏳ Waiting for approval to be mined...");
                 tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
             }
@@ -197,7 +213,7 @@ async fn main() -> Result<()> {
 
     let _min_mon = SlippageUtils::calculate_amount_out_min(expected_mon, 5.0);
 
-    let sell_params = GasEstimationParams::Sell {
+    let sell_params = TradeOperation::Sell {
         token,
         amount_in: actual_sell_amount,
         amount_out_min: U256::from(1), // Use very low minimum to avoid revert
@@ -255,12 +271,13 @@ async fn main() -> Result<()> {
         }
     };
 
-    let sell_permit_params = GasEstimationParams::SellPermit {
+    let sell_permit_params = TradeOperation::SellPermit {
         token,
         amount_in: actual_sell_amount,
         amount_out_min: U256::from(1), // Use very low minimum to avoid revert
         to: wallet,
         deadline,
+        amount_allowance: actual_sell_amount,
         v,
         r,
         s,
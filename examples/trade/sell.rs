@@ -26,7 +26,7 @@ use alloy::primitives::{utils::parse_ether, Address, U256};
 use alloy::providers::Provider;
 use anyhow::Result;
 use nadfun_sdk::types::SellParams;
-use nadfun_sdk::{GasEstimationParams, SlippageUtils, TokenHelper, Trade};
+use nadfun_sdk::{TradeOperation, SlippageUtils, TokenHelper, Trade};
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -100,7 +100,7 @@ async fn main() -> Result<()> {
         let approve_tx = token_helper
             .approve(token, router.address(), token_amount)
             .await?;
-        println!("  Approval tx: {}", approve_tx);
+        println!("  Approval tx: {}", approve_tx.transaction_hash);
 
         // Wait a bit for approval to be mined
         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
@@ -133,7 +133,7 @@ async fn main() -> Result<()> {
     );
 
     // Use new unified gas estimation system
-    let gas_params = GasEstimationParams::Sell {
+    let gas_params = TradeOperation::Sell {
         token,
         amount_in: token_amount,
         amount_out_min: min_eth,
@@ -186,6 +186,7 @@ async fn main() -> Result<()> {
         println!("  Transaction hash: {}", result.transaction_hash);
         println!("  Block number: {:?}", result.block_number);
         println!("  Gas used: {:?}", result.gas_used);
+        println!("  Fee paid (wei): {:?}", result.fee_paid());
     } else {
         println!("❌ Sell failed!");
         println!("  Transaction hash: {}", result.transaction_hash);
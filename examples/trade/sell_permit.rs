@@ -26,7 +26,7 @@ use alloy::primitives::{utils::parse_ether, Address, U256};
 use alloy::providers::Provider;
 use anyhow::Result;
 use nadfun_sdk::types::SellPermitParams;
-use nadfun_sdk::{GasEstimationParams, TokenHelper, Trade};
+use nadfun_sdk::{TradeOperation, TokenHelper, Trade};
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -118,12 +118,13 @@ async fn main() -> Result<()> {
     println!("📊 Current account nonce: {}", current_nonce);
 
     // Use new unified gas estimation system
-    let gas_params = GasEstimationParams::SellPermit {
+    let gas_params = TradeOperation::SellPermit {
         token,
         amount_in: token_amount,
         amount_out_min: min_eth,
         to: wallet,
         deadline,
+        amount_allowance: token_amount,
         v,
         r: r.into(),
         s: s.into(),
@@ -172,6 +173,7 @@ async fn main() -> Result<()> {
         println!("  Transaction hash: {}", result.transaction_hash);
         println!("  Block number: {:?}", result.block_number);
         println!("  Gas used: {:?}", result.gas_used);
+        println!("  Fee paid (wei): {:?}", result.fee_paid());
         println!("  💡 Saved gas by combining approval + sell in one tx!");
     } else {
         println!("❌ Gasless sell failed!");
@@ -0,0 +1,340 @@
+//! Rule-based alerts for bonding curve events
+//!
+//! An [`Alerter`] checks every event against its [`AlertRule`]s and sends a
+//! templated message to each [`AlertSink`] for every rule that matches:
+//!
+//! ```rust,ignore
+//! let alerter = Alerter::new()
+//!     .with_metadata(provider.clone(), MetadataCache::new())
+//!     .with_rule(AlertRule::new(
+//!         "whale buy",
+//!         Predicate::event_type_in([EventType::Buy]).and(Predicate::min_amount(parse_ether("100")?)),
+//!         "{rule}: {sender} bought {symbol} for {mon} MON (tx {tx})",
+//!     ))
+//!     .with_sink(AlertSink::webhook("https://example.com/hooks/nadfun"))
+//!     .with_sink(AlertSink::telegram(bot_token, chat_id));
+//!
+//! let (handle, mut errors) = alerter.run(curve_stream.subscribe().await?);
+//! while let Some(e) = errors.recv().await {
+//!     eprintln!("alert failed: {e}");
+//! }
+//! ```
+//!
+//! Templates may use `{rule}`, `{type}`, `{token}`, `{symbol}`, `{sender}`,
+//! `{mon}` (MON side of a trade, in MON), `{amount_in}`, `{amount_out}` (raw
+//! units), `{block}` and `{tx}`. `{symbol}` falls back to the token address when
+//! no metadata source is configured or the lookup fails.
+
+use crate::{
+    metadata::MetadataCache,
+    stream::{Predicate, PredicateTarget},
+    types::BondingCurveEvent,
+};
+use alloy::{primitives::utils::format_ether, providers::DynProvider};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::sync::Arc;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// Events matching `predicate` raise an alert rendered from `template`
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub predicate: Predicate,
+    pub template: String,
+}
+
+impl AlertRule {
+    pub fn new(name: impl Into<String>, predicate: Predicate, template: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            predicate,
+            template: template.into(),
+        }
+    }
+}
+
+/// Where alerts are delivered
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertSink {
+    /// JSON POST of the alert to `url`
+    Webhook { url: String },
+    /// Message to a Telegram chat through the Bot API
+    Telegram { bot_token: String, chat_id: String },
+}
+
+impl AlertSink {
+    pub fn webhook(url: impl Into<String>) -> Self {
+        AlertSink::Webhook { url: url.into() }
+    }
+
+    pub fn telegram(bot_token: impl Into<String>, chat_id: impl Into<String>) -> Self {
+        AlertSink::Telegram {
+            bot_token: bot_token.into(),
+            chat_id: chat_id.into(),
+        }
+    }
+}
+
+/// A rule that fired, with its rendered message
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub rule: String,
+    pub message: String,
+    pub event: BondingCurveEvent,
+}
+
+impl Alert {
+    /// Body of a webhook POST
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "rule": self.rule,
+            "message": self.message,
+            "eventType": format!("{:?}", self.event.event_type()),
+            "token": self.event.token().to_checksum(None),
+            "blockNumber": self.event.block_number(),
+            "transactionHash": self.event.transaction_hash().to_string(),
+        })
+    }
+}
+
+/// Fill the placeholders of `template` from `event`
+///
+/// `symbol` is used for `{symbol}` when known, the token address otherwise.
+pub fn render_template(
+    template: &str,
+    rule: &str,
+    event: &BondingCurveEvent,
+    symbol: Option<&str>,
+) -> String {
+    let (sender, amount_in, amount_out) = match event {
+        BondingCurveEvent::Buy(e) => (Some(e.sender), Some(e.amount_in), Some(e.amount_out)),
+        BondingCurveEvent::Sell(e) => (Some(e.sender), Some(e.amount_in), Some(e.amount_out)),
+        BondingCurveEvent::Create(e) => (Some(e.creator), None, None),
+        _ => (None, None, None),
+    };
+    let token = event.token().to_checksum(None);
+    let symbol = match (symbol, event) {
+        (Some(symbol), _) => symbol.to_string(),
+        (None, BondingCurveEvent::Create(e)) => e.symbol.clone(),
+        (None, _) => token.clone(),
+    };
+    let text = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+
+    template
+        .replace("{rule}", rule)
+        .replace("{type}", &format!("{:?}", event.event_type()))
+        .replace("{token}", &token)
+        .replace("{symbol}", &symbol)
+        .replace("{sender}", &text(sender.map(|s| s.to_checksum(None))))
+        .replace("{mon}", &text(event.mon_amount().map(format_ether)))
+        .replace("{amount_in}", &text(amount_in.map(|a| a.to_string())))
+        .replace("{amount_out}", &text(amount_out.map(|a| a.to_string())))
+        .replace("{block}", &event.block_number().to_string())
+        .replace("{tx}", &event.transaction_hash().to_string())
+}
+
+/// Evaluates alert rules against events and delivers the alerts
+#[derive(Debug, Clone, Default)]
+pub struct Alerter {
+    rules: Vec<AlertRule>,
+    sinks: Vec<AlertSink>,
+    metadata: Option<(Arc<DynProvider>, MetadataCache)>,
+    client: reqwest::Client,
+}
+
+impl Alerter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rule(mut self, rule: AlertRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    pub fn with_sink(mut self, sink: AlertSink) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Look up token symbols for `{symbol}` through `cache`
+    pub fn with_metadata(mut self, provider: Arc<DynProvider>, cache: MetadataCache) -> Self {
+        self.metadata = Some((provider, cache));
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, timeouts)
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Alerts `event` raises, without sending them
+    ///
+    /// Removed (reorged) events never raise alerts.
+    pub async fn evaluate(&self, event: &BondingCurveEvent) -> Vec<Alert> {
+        if event.removed() {
+            return Vec::new();
+        }
+        let matched: Vec<&AlertRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.predicate.matches(event))
+            .collect();
+        if matched.is_empty() {
+            return Vec::new();
+        }
+
+        let symbol = match &self.metadata {
+            Some((provider, cache)) => cache.symbol(provider.as_ref(), event.token()).await.ok(),
+            None => None,
+        };
+        matched
+            .into_iter()
+            .map(|rule| Alert {
+                rule: rule.name.clone(),
+                message: render_template(&rule.template, &rule.name, event, symbol.as_deref()),
+                event: event.clone(),
+            })
+            .collect()
+    }
+
+    /// Evaluate `event` and deliver its alerts to every sink
+    ///
+    /// Every sink is tried; the first delivery error is returned afterwards.
+    pub async fn handle(&self, event: &BondingCurveEvent) -> Result<Vec<Alert>> {
+        let alerts = self.evaluate(event).await;
+        let mut first_error = None;
+        for alert in &alerts {
+            for sink in &self.sinks {
+                if let Err(e) = self.send(sink, alert).await {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(alerts),
+        }
+    }
+
+    /// Deliver one alert to one sink
+    pub async fn send(&self, sink: &AlertSink, alert: &Alert) -> Result<()> {
+        let request = match sink {
+            AlertSink::Webhook { url } => self.client.post(url).json(&alert.to_json()),
+            AlertSink::Telegram { bot_token, chat_id } => self
+                .client
+                .post(format!(
+                    "https://api.telegram.org/bot{}/sendMessage",
+                    bot_token
+                ))
+                .json(&serde_json::json!({ "chat_id": chat_id, "text": alert.message })),
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Alert '{}' rejected by {} ({}): {}",
+                alert.rule,
+                sink_name(sink),
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Handle every event of `stream` in a background task
+    ///
+    /// Stream and delivery errors don't stop the task; each is sent to the returned
+    /// receiver (drop it to ignore them). The task ends with the stream.
+    pub fn run<S>(self, stream: S) -> (JoinHandle<()>, mpsc::UnboundedReceiver<anyhow::Error>)
+    where
+        S: Stream<Item = Result<BondingCurveEvent>> + Send + 'static,
+    {
+        let (error_tx, error_rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(async move {
+            futures_util::pin_mut!(stream);
+            while let Some(item) = stream.next().await {
+                let result = match item {
+                    Ok(event) => self.handle(&event).await.map(|_| ()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    let _ = error_tx.send(e);
+                }
+            }
+        });
+        (handle, error_rx)
+    }
+}
+
+/// Sink description for error messages, without credentials
+fn sink_name(sink: &AlertSink) -> String {
+    match sink {
+        AlertSink::Webhook { url } => format!("webhook {}", url),
+        AlertSink::Telegram { chat_id, .. } => format!("Telegram chat {}", chat_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, types::EventType};
+
+    #[tokio::test]
+    async fn test_alert_rules_and_templates() {
+        let buy = fixtures::curve_buy().expected;
+        let alerter = Alerter::new()
+            .with_rule(AlertRule::new(
+                "buys",
+                Predicate::event_type_in([EventType::Buy]),
+                "{rule}: {sender} bought {symbol} for {mon} MON at block {block}",
+            ))
+            .with_rule(AlertRule::new(
+                "sells",
+                Predicate::event_type_in([EventType::Sell]),
+                "{type}",
+            ));
+
+        let alerts = alerter.evaluate(&buy).await;
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, "buys");
+        let expected = format!(
+            "buys: {} bought {} for {} MON at block {}",
+            fixtures::FIXTURE_SENDER.to_checksum(None),
+            fixtures::FIXTURE_TOKEN.to_checksum(None),
+            format_ether(buy.mon_amount().unwrap()),
+            fixtures::FIXTURE_BLOCK
+        );
+        assert_eq!(alerts[0].message, expected);
+        assert_eq!(alerts[0].to_json()["rule"], "buys");
+
+        assert_eq!(
+            render_template("{symbol} {amount_out}", "r", &buy, Some("FIX")),
+            format!("FIX {}", buy_amount_out(&buy))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_stream_errors() {
+        let stream = futures_util::stream::iter(vec![
+            Err(anyhow::anyhow!("subscription dropped")),
+            Ok(fixtures::curve_buy().expected),
+        ]);
+        let (handle, mut errors) = Alerter::new().run(stream);
+        handle.await.unwrap();
+
+        let error = errors.recv().await.unwrap();
+        assert_eq!(error.to_string(), "subscription dropped");
+        assert!(errors.recv().await.is_none());
+    }
+
+    fn buy_amount_out(event: &BondingCurveEvent) -> String {
+        match event {
+            BondingCurveEvent::Buy(e) => e.amount_out.to_string(),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Snapshot-consistent reads pinned to one block
+//!
+//! Balances, curve state and quotes fetched one after another can straddle a
+//! block and disagree with each other (e.g. a balance from after a buy next to
+//! reserves from before it). A [`BatchReader`] pins every call of a batch to a
+//! single block and returns that block with the data:
+//!
+//! ```rust,ignore
+//! let reader = BatchReader::new(rpc_url).await?;
+//! let snapshot = reader
+//!     .read(|at| async move {
+//!         tokio::try_join!(
+//!             at.native_balance(wallet),
+//!             at.balance_of(token, wallet),
+//!             at.curve_state(token),
+//!             at.amount_out(token, amount_in, true),
+//!         )
+//!     })
+//!     .await?;
+//! let (mon, tokens, curve, (router, quote)) = snapshot.data;
+//! println!("as of block {}", snapshot.block);
+//! ```
+//!
+//! Calls inside a batch run concurrently; they only share the block.
+
+use crate::{
+    constants::ChainConfig,
+    contracts::{bonding_curve::IBondingCurve, lens::ILensContract},
+    token::token::IToken,
+    types::CurveState,
+};
+use alloy::{
+    eips::{BlockId, BlockNumberOrTag},
+    primitives::{Address, B256, Bytes, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+};
+use anyhow::Result;
+use std::{future::Future, sync::Arc};
+
+/// Data read at a single block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<T> {
+    pub block: u64,
+    /// None when the batch was pinned by number only
+    pub block_hash: Option<B256>,
+    pub data: T,
+}
+
+/// Issues batches of reads pinned to one block
+#[derive(Clone)]
+pub struct BatchReader {
+    provider: Arc<DynProvider>,
+    chain_config: ChainConfig,
+}
+
+impl BatchReader {
+    pub async fn new(rpc_url: String) -> Result<Self> {
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+        Ok(Self::from_provider(Arc::new(DynProvider::new(provider))))
+    }
+
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self {
+            provider,
+            chain_config: ChainConfig::default(),
+        }
+    }
+
+    pub fn with_chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = chain_config;
+        self
+    }
+
+    /// Reads pinned to the current head
+    ///
+    /// Pinned by hash, so a reorg during the batch fails the calls instead of
+    /// silently mixing two chains.
+    pub async fn pin_latest(&self) -> Result<BlockReads> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Latest)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Node returned no latest block"))?;
+        let hash = block.header.hash;
+
+        Ok(BlockReads {
+            provider: self.provider.clone(),
+            chain_config: self.chain_config.clone(),
+            block: block.header.number,
+            block_hash: Some(hash),
+            at: BlockId::hash(hash),
+        })
+    }
+
+    /// Reads pinned to block `number` (needs an archive node for old blocks)
+    pub fn pin(&self, number: u64) -> BlockReads {
+        BlockReads {
+            provider: self.provider.clone(),
+            chain_config: self.chain_config.clone(),
+            block: number,
+            block_hash: None,
+            at: BlockId::number(number),
+        }
+    }
+
+    /// Run `batch` against reads pinned to the current head
+    pub async fn read<T, F, Fut>(&self, batch: F) -> Result<Snapshot<T>>
+    where
+        F: FnOnce(BlockReads) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.pin_latest().await?.read(batch).await
+    }
+
+    /// Run `batch` against reads pinned to block `number`
+    pub async fn read_at<T, F, Fut>(&self, number: u64, batch: F) -> Result<Snapshot<T>>
+    where
+        F: FnOnce(BlockReads) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.pin(number).read(batch).await
+    }
+}
+
+/// Read calls that all execute at the same block
+///
+/// Cheap to clone, so concurrent calls can each hold one.
+#[derive(Clone)]
+pub struct BlockReads {
+    provider: Arc<DynProvider>,
+    chain_config: ChainConfig,
+    block: u64,
+    block_hash: Option<B256>,
+    at: BlockId,
+}
+
+impl BlockReads {
+    pub fn block(&self) -> u64 {
+        self.block
+    }
+
+    pub fn block_hash(&self) -> Option<B256> {
+        self.block_hash
+    }
+
+    /// Wrap `data` with the pinned block
+    pub fn snapshot<T>(&self, data: T) -> Snapshot<T> {
+        Snapshot {
+            block: self.block,
+            block_hash: self.block_hash,
+            data,
+        }
+    }
+
+    pub async fn read<T, F, Fut>(self, batch: F) -> Result<Snapshot<T>>
+    where
+        F: FnOnce(BlockReads) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let data = batch(self.clone()).await?;
+        Ok(self.snapshot(data))
+    }
+
+    pub async fn native_balance(&self, owner: Address) -> Result<U256> {
+        Ok(self.provider.get_balance(owner).block_id(self.at).await?)
+    }
+
+    pub async fn balance_of(&self, token: Address, owner: Address) -> Result<U256> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        Ok(contract.balanceOf(owner).block(self.at).call().await?)
+    }
+
+    pub async fn allowance(
+        &self,
+        token: Address,
+        owner: Address,
+        spender: Address,
+    ) -> Result<U256> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        Ok(contract
+            .allowance(owner, spender)
+            .block(self.at)
+            .call()
+            .await?)
+    }
+
+    pub async fn total_supply(&self, token: Address) -> Result<U256> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        Ok(contract.totalSupply().block(self.at).call().await?)
+    }
+
+    pub async fn curve_state(&self, token: Address) -> Result<CurveState> {
+        let curve = IBondingCurve::new(self.chain_config.bonding_curve, self.provider.as_ref());
+        Ok(curve.curves(token).block(self.at).call().await?.into())
+    }
+
+    pub async fn is_listed(&self, token: Address) -> Result<bool> {
+        let curve = IBondingCurve::new(self.chain_config.bonding_curve, self.provider.as_ref());
+        Ok(curve.isListed(token).block(self.at).call().await?)
+    }
+
+    pub async fn is_locked(&self, token: Address) -> Result<bool> {
+        let curve = IBondingCurve::new(self.chain_config.bonding_curve, self.provider.as_ref());
+        Ok(curve.isLocked(token).block(self.at).call().await?)
+    }
+
+    /// Lens quote: (router address, amount out)
+    pub async fn amount_out(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+    ) -> Result<(Address, U256)> {
+        let lens = ILensContract::new(self.chain_config.lens, self.provider.as_ref());
+        let result = lens
+            .getAmountOut(token, amount_in, is_buy)
+            .block(self.at)
+            .call()
+            .await?;
+        Ok((result._0, result._1))
+    }
+
+    /// Lens quote: (router address, amount in)
+    pub async fn amount_in(
+        &self,
+        token: Address,
+        amount_out: U256,
+        is_buy: bool,
+    ) -> Result<(Address, U256)> {
+        let lens = ILensContract::new(self.chain_config.lens, self.provider.as_ref());
+        let result = lens
+            .getAmountIn(token, amount_out, is_buy)
+            .block(self.at)
+            .call()
+            .await?;
+        Ok((result._0, result._1))
+    }
+
+    /// Any other `eth_call` at the pinned block
+    pub async fn call(&self, tx: TransactionRequest) -> Result<Bytes> {
+        Ok(self.provider.call(tx).block(self.at).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        rpc::{
+            client::RpcClient,
+            json_rpc::{RequestPacket, ResponsePacket},
+        },
+        transports::TransportFut,
+    };
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn test_every_call_uses_the_pinned_block() {
+        let params = Arc::new(Mutex::new(Vec::new()));
+        let seen = params.clone();
+        let transport = tower::service_fn(move |request: RequestPacket| -> TransportFut<'static> {
+            let RequestPacket::Single(request) = request else {
+                panic!("unexpected batch request");
+            };
+            seen.lock().unwrap().push(
+                request
+                    .params()
+                    .map(|p| p.get().to_string())
+                    .unwrap_or_default(),
+            );
+            // A single word decodes as uint256 1 or bool true
+            let result = match request.method() {
+                "eth_call" => format!("\"0x{:064x}\"", 1),
+                _ => "\"0x10\"".to_string(),
+            };
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","id":{},"result":{}}}"#,
+                serde_json::to_string(request.id()).unwrap(),
+                result
+            );
+            Box::pin(async move { Ok(serde_json::from_str::<ResponsePacket>(&body).unwrap()) })
+        });
+        let provider = ProviderBuilder::new().connect_client(RpcClient::new(transport, true));
+        let reader = BatchReader::from_provider(Arc::new(DynProvider::new(provider)));
+
+        let token = Address::repeat_byte(0x11);
+        let owner = Address::repeat_byte(0x22);
+        let snapshot = reader
+            .read_at(42, |at| async move {
+                tokio::try_join!(
+                    at.native_balance(owner),
+                    at.balance_of(token, owner),
+                    at.is_listed(token),
+                )
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(snapshot.block, 42);
+        assert_eq!(snapshot.data, (U256::from(16), U256::from(1), true));
+        let params = params.lock().unwrap();
+        assert_eq!(params.len(), 3);
+        assert!(params.iter().all(|p| p.ends_with("\"0x2a\"]")));
+    }
+}
@@ -0,0 +1,297 @@
+//! One entry point wiring trading, tokens, streams and indexers
+//!
+//! [`Trade`], [`TokenHelper`], the streams and the indexers each take a URL or
+//! a provider of their own. A [`NadfunClient`] connects once, from a
+//! [`ClientConfig`], and hands out components that share its HTTP provider,
+//! signer, WebSocket connection and [`MetadataCache`]:
+//!
+//! ```rust,ignore
+//! let client = NadfunClient::new(
+//!     ClientConfig::new(rpc_url)
+//!         .with_ws_url(ws_url)
+//!         .with_private_key(private_key),
+//! )
+//! .await?;
+//!
+//! let (router, tokens_out) = client.trade()?.get_amount_out(token, mon_amount, true).await?;
+//! let balance = client.tokens()?.balance_of(token, client.wallet_address().unwrap()).await?;
+//! let events = client.streams()?.curve().subscribe().await?;
+//! let history = client.indexers().curve().fetch_events(from, to, vec![EventType::Buy], None).await?;
+//! ```
+//!
+//! The individual types stay available for setups the client doesn't cover,
+//! e.g. an [`EndpointPool`](crate::rpc::EndpointPool) or batching transports.
+
+use crate::{
+    constants::ChainConfig,
+    metadata::MetadataCache,
+    stream::{
+        BlockStream, CurveIndexer, CurveStream, SharedWsClient, UniswapSwapIndexer,
+        UniswapSwapStream, WalletMonitor,
+    },
+    token::TokenHelper,
+    trading::{Trade, TradeOptions},
+};
+use alloy::{
+    network::EthereumWallet,
+    primitives::Address,
+    providers::{DynProvider, ProviderBuilder, WsConnect},
+    signers::local::PrivateKeySigner,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Connection settings of a [`NadfunClient`]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub rpc_url: String,
+    /// WebSocket endpoint for streams, None for a client without streams
+    pub ws_url: Option<String>,
+    /// Signing key for trades and token writes, None for a read-only client
+    pub private_key: Option<String>,
+    pub chain_config: ChainConfig,
+    pub trade_options: TradeOptions,
+}
+
+impl ClientConfig {
+    pub fn new(rpc_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            ws_url: None,
+            private_key: None,
+            chain_config: ChainConfig::default(),
+            trade_options: TradeOptions::default(),
+        }
+    }
+
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = Some(ws_url.into());
+        self
+    }
+
+    pub fn with_private_key(mut self, private_key: impl Into<String>) -> Self {
+        self.private_key = Some(private_key.into());
+        self
+    }
+
+    pub fn with_chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = chain_config;
+        self
+    }
+
+    /// Slippage, deadline and gas defaults of [`NadfunClient::trade`]
+    pub fn with_trade_options(mut self, trade_options: TradeOptions) -> Self {
+        self.trade_options = trade_options;
+        self
+    }
+}
+
+/// Shared providers and signer behind every SDK component
+pub struct NadfunClient {
+    provider: Arc<DynProvider>,
+    ws_provider: Option<Arc<DynProvider>>,
+    trade: Option<Trade>,
+    tokens: Option<TokenHelper>,
+    metadata: MetadataCache,
+    chain_config: ChainConfig,
+}
+
+impl NadfunClient {
+    /// Connect the HTTP provider, and the WebSocket one if configured
+    pub async fn new(config: ClientConfig) -> Result<Self> {
+        config.trade_options.validate()?;
+        let url = config.rpc_url.parse()?;
+        let signer = config
+            .private_key
+            .as_deref()
+            .map(str::parse::<PrivateKeySigner>)
+            .transpose()?;
+
+        let provider = Arc::new(match &signer {
+            Some(signer) => DynProvider::new(
+                ProviderBuilder::new()
+                    .wallet(EthereumWallet::from(signer.clone()))
+                    .connect_http(url),
+            ),
+            None => DynProvider::new(ProviderBuilder::new().connect_http(url)),
+        });
+
+        let ws_provider = match config.ws_url {
+            Some(ws_url) => {
+                let ws = ProviderBuilder::new()
+                    .connect_ws(WsConnect::new(ws_url))
+                    .await?;
+                Some(Arc::new(DynProvider::new(ws)))
+            }
+            None => None,
+        };
+
+        let metadata = MetadataCache::new();
+        let (trade, tokens) = match signer {
+            Some(signer) => {
+                let trade = Trade::from_provider(
+                    provider.clone(),
+                    signer.clone(),
+                    config.chain_config.clone(),
+                )
+                .with_options(config.trade_options);
+                let tokens = TokenHelper::from_provider(provider.clone(), signer)
+                    .with_metadata_cache(metadata.clone());
+                (Some(trade), Some(tokens))
+            }
+            None => (None, None),
+        };
+
+        Ok(Self {
+            provider,
+            ws_provider,
+            trade,
+            tokens,
+            metadata,
+            chain_config: config.chain_config,
+        })
+    }
+
+    /// Trading with the configured key
+    pub fn trade(&self) -> Result<&Trade> {
+        self.trade
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("NadfunClient has no private key; trading is disabled"))
+    }
+
+    /// ERC-20 reads and writes with the configured key
+    pub fn tokens(&self) -> Result<&TokenHelper> {
+        self.tokens.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("NadfunClient has no private key; token helper is disabled")
+        })
+    }
+
+    /// Live streams on the shared WebSocket connection
+    pub fn streams(&self) -> Result<Streams> {
+        let provider = self.ws_provider.clone().ok_or_else(|| {
+            anyhow::anyhow!("NadfunClient has no WebSocket URL; streams are disabled")
+        })?;
+        Ok(Streams { provider })
+    }
+
+    /// Historical indexers on the shared HTTP provider
+    pub fn indexers(&self) -> Indexers {
+        Indexers {
+            provider: self.provider.clone(),
+        }
+    }
+
+    /// The shared HTTP provider, signing if a key is configured
+    pub fn provider(&self) -> &Arc<DynProvider> {
+        &self.provider
+    }
+
+    /// Decimals/symbol cache shared with [`NadfunClient::tokens`]
+    pub fn metadata_cache(&self) -> &MetadataCache {
+        &self.metadata
+    }
+
+    pub fn chain_config(&self) -> &ChainConfig {
+        &self.chain_config
+    }
+
+    /// Address of the configured key, None for a read-only client
+    pub fn wallet_address(&self) -> Option<Address> {
+        self.trade.as_ref().map(Trade::wallet_address)
+    }
+
+    /// Every Nad.fun event and the router call of a mined transaction
+    #[cfg(feature = "experimental")]
+    pub async fn decode_transaction(
+        &self,
+        tx_hash: alloy::primitives::B256,
+    ) -> Result<crate::stream::DecodedTransaction> {
+        crate::stream::decode_transaction(self.provider.as_ref(), tx_hash).await
+    }
+}
+
+/// Stream constructors over a [`NadfunClient`]'s WebSocket connection
+#[derive(Clone)]
+pub struct Streams {
+    provider: Arc<DynProvider>,
+}
+
+impl Streams {
+    pub fn curve(&self) -> CurveStream {
+        CurveStream::from_provider(self.provider.clone())
+    }
+
+    pub fn swaps(&self, pool_addresses: Vec<Address>) -> UniswapSwapStream {
+        UniswapSwapStream::from_provider(self.provider.clone(), pool_addresses)
+    }
+
+    pub fn blocks(&self) -> BlockStream {
+        BlockStream::from_provider(self.provider.clone())
+    }
+
+    pub fn wallets(&self) -> WalletMonitor {
+        WalletMonitor::from_provider(self.provider.clone())
+    }
+
+    /// Several streams behind one `eth_subscribe`
+    pub fn shared(&self) -> SharedWsClient {
+        SharedWsClient::from_provider(self.provider.clone())
+    }
+
+    pub fn provider(&self) -> &Arc<DynProvider> {
+        &self.provider
+    }
+}
+
+/// Indexer constructors over a [`NadfunClient`]'s HTTP provider
+#[derive(Clone)]
+pub struct Indexers {
+    provider: Arc<DynProvider>,
+}
+
+impl Indexers {
+    pub fn curve(&self) -> CurveIndexer<DynProvider> {
+        CurveIndexer::new(self.provider.clone())
+    }
+
+    pub fn swaps(&self, pool_addresses: Vec<Address>) -> UniswapSwapIndexer {
+        UniswapSwapIndexer::from_provider(self.provider.clone(), pool_addresses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Anvil's first development key
+    const KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    #[tokio::test]
+    async fn test_client_components() {
+        let read_only = NadfunClient::new(ClientConfig::new("http://127.0.0.1:1"))
+            .await
+            .unwrap();
+        assert!(read_only.trade().is_err());
+        assert!(read_only.tokens().is_err());
+        assert!(read_only.streams().is_err());
+        assert!(read_only.wallet_address().is_none());
+
+        let client =
+            NadfunClient::new(ClientConfig::new("http://127.0.0.1:1").with_private_key(KEY))
+                .await
+                .unwrap();
+        let wallet = KEY.parse::<PrivateKeySigner>().unwrap().address();
+        assert_eq!(client.wallet_address(), Some(wallet));
+        assert_eq!(client.tokens().unwrap().wallet_address(), wallet);
+        assert!(Arc::ptr_eq(
+            client.trade().unwrap().provider(),
+            client.provider()
+        ));
+
+        assert!(
+            NadfunClient::new(ClientConfig::new("not a url"))
+                .await
+                .is_err()
+        );
+    }
+}
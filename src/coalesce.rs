@@ -0,0 +1,415 @@
+//! Coalescing of concurrent view calls into JSON-RPC batches
+//!
+//! A dashboard refreshing dozens of balances and quotes at once sends dozens of
+//! separate HTTP requests. A [`CoalescingTransport`] holds `eth_call` and
+//! `eth_getBalance` requests for a short window and sends everything that
+//! arrived in it as one JSON-RPC batch:
+//!
+//! ```rust,ignore
+//! let config = CoalesceConfig::default().with_window(Duration::from_millis(5));
+//! let helper = TokenHelper::new_with_batching(rpc_url.clone(), private_key.clone(), config.clone())?;
+//! let trade = Trade::new_with_batching(rpc_url, private_key, ChainConfig::default(), config)?;
+//!
+//! // One HTTP request for all twenty balance reads
+//! let balances = try_join_all(tokens.iter().map(|t| helper.balance_of(*t, wallet))).await?;
+//! ```
+//!
+//! Other methods (transactions, logs, receipts) pass straight through. Any alloy
+//! transport can be wrapped, e.g. an [`EndpointPool`](crate::rpc::EndpointPool):
+//! `CoalescingTransport::new(pool.clone(), config).provider()`. The pool still
+//! cross-checks its quorum methods, sending them outside the batch.
+
+use alloy::{
+    network::EthereumWallet,
+    providers::{DynProvider, ProviderBuilder},
+    rpc::{
+        client::RpcClient,
+        json_rpc::{Id, Request, RequestPacket, Response, ResponsePacket, SerializedRequest},
+    },
+    transports::{
+        TransportError, TransportErrorKind, TransportFut,
+        http::{Http, reqwest},
+    },
+};
+use anyhow::Result;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::oneshot;
+use tower::Service;
+
+/// Methods coalesced by default
+pub const DEFAULT_COALESCED_METHODS: [&str; 2] = ["eth_call", "eth_getBalance"];
+
+/// Batching window and limits of a [`CoalescingTransport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoalesceConfig {
+    /// How long the first request of a batch waits for others
+    pub window: Duration,
+    /// A batch is sent early once it holds this many requests
+    pub max_batch: usize,
+    /// JSON-RPC methods that are coalesced
+    pub methods: HashSet<String>,
+}
+
+impl Default for CoalesceConfig {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(2),
+            max_batch: 50,
+            methods: DEFAULT_COALESCED_METHODS
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl CoalesceConfig {
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    pub fn with_max_batch(mut self, max_batch: usize) -> Self {
+        self.max_batch = max_batch.max(1);
+        self
+    }
+
+    /// Also coalesce `method` (e.g. `eth_getStorageAt`)
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.methods.insert(method.into());
+        self
+    }
+}
+
+type Reply = oneshot::Sender<Result<Response, TransportError>>;
+
+#[derive(Debug, Default)]
+struct Queue {
+    pending: Vec<(SerializedRequest, Reply)>,
+    /// Incremented whenever a batch is taken, so a stale timer doesn't flush the next one
+    generation: u64,
+}
+
+impl Queue {
+    fn take(&mut self) -> Vec<(SerializedRequest, Reply)> {
+        self.generation += 1;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Transport wrapper that sends concurrent view calls as JSON-RPC batches
+///
+/// Cheap to clone; clones share the pending batch.
+#[derive(Debug, Clone)]
+pub struct CoalescingTransport<T> {
+    inner: T,
+    config: Arc<CoalesceConfig>,
+    queue: Arc<Mutex<Queue>>,
+}
+
+impl<T> CoalescingTransport<T> {
+    pub fn new(inner: T, config: CoalesceConfig) -> Self {
+        Self {
+            inner,
+            config: Arc::new(config),
+            queue: Arc::new(Mutex::new(Queue::default())),
+        }
+    }
+
+    pub fn config(&self) -> &CoalesceConfig {
+        &self.config
+    }
+}
+
+impl CoalescingTransport<Http<reqwest::Client>> {
+    /// Coalescing plain HTTP transport to `rpc_url`
+    pub fn http(rpc_url: &str, config: CoalesceConfig) -> Result<Self> {
+        Ok(Self::new(Http::new(rpc_url.parse()?), config))
+    }
+}
+
+impl<T> CoalescingTransport<T>
+where
+    T: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    /// Read-only provider over this transport
+    pub fn provider(self) -> DynProvider {
+        let client = RpcClient::new(self, false);
+        DynProvider::new(ProviderBuilder::new().connect_client(client))
+    }
+
+    /// Signing provider over this transport
+    pub fn wallet_provider(self, wallet: EthereumWallet) -> DynProvider {
+        let client = RpcClient::new(self, false);
+        DynProvider::new(ProviderBuilder::new().wallet(wallet).connect_client(client))
+    }
+
+    /// Queue `request`, sending the batch if it is full or starting its timer if it is new
+    fn enqueue(
+        &self,
+        request: SerializedRequest,
+    ) -> oneshot::Receiver<Result<Response, TransportError>> {
+        let (tx, rx) = oneshot::channel();
+        let mut queue = self.queue.lock().unwrap();
+        queue.pending.push((request, tx));
+
+        if queue.pending.len() >= self.config.max_batch {
+            let batch = queue.take();
+            tokio::spawn(send_batch(self.inner.clone(), batch));
+        } else if queue.pending.len() == 1 {
+            let generation = queue.generation;
+            let this = self.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(this.config.window).await;
+                let batch = {
+                    let mut queue = this.queue.lock().unwrap();
+                    if queue.generation != generation {
+                        return;
+                    }
+                    queue.take()
+                };
+                send_batch(this.inner, batch).await;
+            });
+        }
+        rx
+    }
+}
+
+/// Send `batch` as one packet and hand every caller its own response
+async fn send_batch<T>(mut inner: T, batch: Vec<(SerializedRequest, Reply)>)
+where
+    T: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        >,
+{
+    if batch.is_empty() {
+        return;
+    }
+    let (requests, replies): (Vec<_>, Vec<_>) = batch.into_iter().unzip();
+    // Callers on different clients can share ids; number the batch by position instead
+    let ids: Vec<Id> = requests.iter().map(|r| r.id().clone()).collect();
+    let requests = match requests
+        .into_iter()
+        .enumerate()
+        .map(|(position, request)| with_id(request, Id::Number(position as u64)))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(requests) => requests,
+        Err(e) => {
+            let message = e.to_string();
+            for reply in replies {
+                let _ = reply.send(Err(TransportErrorKind::custom_str(&message)));
+            }
+            return;
+        }
+    };
+    let packet = if requests.len() == 1 {
+        RequestPacket::Single(requests.into_iter().next().unwrap())
+    } else {
+        RequestPacket::Batch(requests)
+    };
+
+    match inner.call(packet).await {
+        Ok(response) => {
+            let responses = match response {
+                ResponsePacket::Single(response) => vec![response],
+                ResponsePacket::Batch(responses) => responses,
+            };
+            let mut by_position: HashMap<u64, Response> = responses
+                .into_iter()
+                .filter_map(|response| match response.id {
+                    Id::Number(position) => Some((position, response)),
+                    _ => None,
+                })
+                .collect();
+            for (position, (id, reply)) in ids.into_iter().zip(replies).enumerate() {
+                let result = match by_position.remove(&(position as u64)) {
+                    Some(mut response) => {
+                        response.id = id;
+                        Ok(response)
+                    }
+                    None => Err(TransportErrorKind::custom_str(&format!(
+                        "No response for request {} in batch",
+                        id
+                    ))),
+                };
+                let _ = reply.send(result);
+            }
+        }
+        Err(e) => {
+            // Transport errors aren't Clone; every caller gets the same message
+            let message = e.to_string();
+            for reply in replies {
+                let _ = reply.send(Err(TransportErrorKind::custom_str(&message)));
+            }
+        }
+    }
+}
+
+/// `request` re-serialized with `id`
+fn with_id(request: SerializedRequest, id: Id) -> Result<SerializedRequest, TransportError> {
+    let method = request.method_clone();
+    match request.params() {
+        Some(params) => Request::new(method, id, params.to_owned()).serialize(),
+        None => Request::new(method, id, ()).serialize(),
+    }
+    .map_err(TransportError::ser_err)
+}
+
+impl<T> Service<RequestPacket> for CoalescingTransport<T>
+where
+    T: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        match request {
+            RequestPacket::Single(request) if self.config.methods.contains(request.method()) => {
+                let rx = self.enqueue(request);
+                Box::pin(async move {
+                    let response = rx.await.map_err(|_| {
+                        TransportErrorKind::custom_str("Request batch was dropped")
+                    })??;
+                    Ok(ResponsePacket::Single(response))
+                })
+            }
+            request => self.inner.call(request),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &'static str, id: u64) -> RequestPacket {
+        RequestPacket::Single(
+            Request::new(method, Id::Number(id), ())
+                .serialize()
+                .unwrap(),
+        )
+    }
+
+    fn response_id(response: ResponsePacket) -> Id {
+        match response {
+            ResponsePacket::Single(response) => response.id,
+            ResponsePacket::Batch(_) => panic!("expected a single response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesces_concurrent_calls() {
+        let packets: Arc<Mutex<Vec<usize>>> = Arc::default();
+        let recorded = packets.clone();
+        // Answers in reverse order to check responses are matched by id
+        let echo = tower::service_fn(move |request: RequestPacket| -> TransportFut<'static> {
+            let ids: Vec<Id> = match &request {
+                RequestPacket::Single(r) => vec![r.id().clone()],
+                RequestPacket::Batch(rs) => rs.iter().map(|r| r.id().clone()).collect(),
+            };
+            recorded.lock().unwrap().push(ids.len());
+            let responses: Vec<String> = ids
+                .iter()
+                .rev()
+                .map(|id| format!(r#"{{"jsonrpc":"2.0","id":{},"result":"0x1"}}"#, id))
+                .collect();
+            let body = match request {
+                RequestPacket::Single(_) => responses[0].clone(),
+                RequestPacket::Batch(_) => format!("[{}]", responses.join(",")),
+            };
+            Box::pin(async move { Ok(serde_json::from_str(&body).unwrap()) })
+        });
+
+        let transport = CoalescingTransport::new(
+            echo,
+            CoalesceConfig::default().with_window(Duration::from_millis(20)),
+        );
+        let (a, b, c) = tokio::join!(
+            transport.clone().call(request("eth_call", 1)),
+            transport.clone().call(request("eth_getBalance", 2)),
+            transport.clone().call(request("eth_call", 3)),
+        );
+        assert_eq!(response_id(a.unwrap()), Id::Number(1));
+        assert_eq!(response_id(b.unwrap()), Id::Number(2));
+        assert_eq!(response_id(c.unwrap()), Id::Number(3));
+
+        // Not coalesced: sent on its own, immediately
+        transport
+            .clone()
+            .call(request("eth_sendRawTransaction", 4))
+            .await
+            .unwrap();
+        assert_eq!(*packets.lock().unwrap(), vec![3, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_providers_sharing_a_transport_get_their_own_results() {
+        use alloy::{primitives::Address, providers::Provider};
+
+        // Answers eth_getBalance with the last byte of the queried address
+        let balances = tower::service_fn(|request: RequestPacket| -> TransportFut<'static> {
+            let requests = match request {
+                RequestPacket::Single(r) => vec![r],
+                RequestPacket::Batch(rs) => rs,
+            };
+            let responses: Vec<String> = requests
+                .iter()
+                .map(|r| {
+                    let params: (Address, String) =
+                        serde_json::from_str(r.params().unwrap().get()).unwrap();
+                    format!(
+                        r#"{{"jsonrpc":"2.0","id":{},"result":"0x{:x}"}}"#,
+                        r.id(),
+                        params.0[19]
+                    )
+                })
+                .collect();
+            let body = format!("[{}]", responses.join(","));
+            Box::pin(async move { Ok(serde_json::from_str(&body).unwrap()) })
+        });
+
+        let transport = CoalescingTransport::new(
+            balances,
+            CoalesceConfig::default().with_window(Duration::from_millis(20)),
+        );
+        // Fresh clients: both number their first request 0
+        let first = transport.clone().provider();
+        let second = transport.clone().provider();
+        let (a, b) = tokio::join!(
+            first.get_balance(Address::repeat_byte(0x0a)),
+            second.get_balance(Address::repeat_byte(0x0b)),
+        );
+        assert_eq!(a.unwrap(), alloy::primitives::U256::from(0x0a));
+        assert_eq!(b.unwrap(), alloy::primitives::U256::from(0x0b));
+    }
+}
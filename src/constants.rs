@@ -80,3 +80,42 @@ pub mod fees {
 // Re-export commonly used constants for convenience
 pub use addresses::*;
 pub use fees::DEFAULT_FEE_TIER;
+
+/// Contract addresses used by a [`crate::Trade`] instance
+///
+/// Defaults to the production deployment; override individual addresses (e.g. the
+/// lens) to target a different deployment without an SDK release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainConfig {
+    pub lens: alloy::primitives::Address,
+    pub bonding_curve: alloy::primitives::Address,
+    pub bonding_curve_router: alloy::primitives::Address,
+    pub dex_router: alloy::primitives::Address,
+    pub wmon: alloy::primitives::Address,
+    pub uniswap_v3_factory: alloy::primitives::Address,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            lens: LENS_ADDRESS.parse().expect("Invalid lens address"),
+            bonding_curve: BONDING_CURVE.parse().expect("Invalid bonding curve address"),
+            bonding_curve_router: BONDING_CURVE_ROUTER
+                .parse()
+                .expect("Invalid bonding curve router address"),
+            dex_router: DEX_ROUTER.parse().expect("Invalid DEX router address"),
+            wmon: WMON.parse().expect("Invalid WMON address"),
+            uniswap_v3_factory: UNISWAP_V3_FACTORY
+                .parse()
+                .expect("Invalid Uniswap V3 factory address"),
+        }
+    }
+}
+
+impl ChainConfig {
+    /// Use a different lens contract
+    pub fn with_lens(mut self, lens: alloy::primitives::Address) -> Self {
+        self.lens = lens;
+        self
+    }
+}
@@ -1,5 +1,7 @@
-use crate::types::*;
+use super::send_trade;
+use crate::{trading::gas::TradeOperation, types::*};
 use alloy::{
+    eips::BlockId,
     primitives::{Address, U256},
     providers::Provider,
     sol,
@@ -37,6 +39,7 @@ sol! {
             bytes32 s;
         }
 
+        // Paid in native MON only; the router has no WMON (allowance/permit) buy
         function buy(BuyParams memory params) external payable returns (uint256);
         function sell(SellParams memory params) external returns (uint256);
         function sellPermit(SellPermitParams memory params) external returns (uint256);
@@ -83,121 +86,89 @@ impl<P: Provider + Clone> DexRouter<P> {
         Ok(result)
     }
 
-    pub async fn buy(&self, params: BuyParams) -> Result<TransactionResult> {
+    /// [`Self::get_amount_out`] against the state at `block` (e.g. `BlockId::pending()`)
+    pub async fn get_amount_out_at(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<U256> {
         let contract = IDexRouter::new(self.address, self.provider.as_ref());
-
-        let router_params = IDexRouter::BuyParams {
-            amountOutMin: params.amount_out_min,
-            token: params.token,
-            to: params.to,
-            deadline: params.deadline,
-        };
-
-        let mut tx_builder = contract.buy(router_params).value(params.amount_in);
-
-        if let Some(gas_limit) = params.gas_limit {
-            tx_builder = tx_builder.gas(gas_limit);
-        }
-
-        if let Some(gas_price) = params.gas_price {
-            tx_builder = tx_builder.gas_price(gas_price);
-        }
-
-        if let Some(nonce) = params.nonce {
-            tx_builder = tx_builder.nonce(nonce);
-        }
-
-        let tx = tx_builder.send().await?;
-
-        let receipt = tx.get_receipt().await?;
-
-        Ok(TransactionResult {
-            transaction_hash: receipt.transaction_hash,
-            block_number: receipt.block_number,
-            gas_used: Some(U256::from(receipt.gas_used)),
-            status: receipt.status(),
-            logs: receipt.logs().to_vec(),
-        })
+        let result = contract
+            .getAmountOut(token, amount_in, is_buy)
+            .block(block)
+            .call()
+            .await?;
+        Ok(result)
     }
 
-    pub async fn sell(&self, params: crate::types::SellParams) -> Result<TransactionResult> {
+    /// [`Self::get_amount_in`] against the state at `block`
+    pub async fn get_amount_in_at(
+        &self,
+        token: Address,
+        amount_out: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<U256> {
         let contract = IDexRouter::new(self.address, self.provider.as_ref());
+        let result = contract
+            .getAmountIn(token, amount_out, is_buy)
+            .block(block)
+            .call()
+            .await?;
+        Ok(result)
+    }
 
-        let router_params = IDexRouter::SellParams {
-            amountIn: params.amount_in,
-            amountOutMin: params.amount_out_min,
-            token: params.token,
-            to: params.to,
-            deadline: params.deadline,
-        };
-
-        let mut tx_builder = contract.sell(router_params);
-
-        if let Some(gas_limit) = params.gas_limit {
-            tx_builder = tx_builder.gas(gas_limit);
-        }
-
-        if let Some(gas_price) = params.gas_price {
-            tx_builder = tx_builder.gas_price(gas_price);
-        }
-
-        if let Some(nonce) = params.nonce {
-            tx_builder = tx_builder.nonce(nonce);
-        }
-
-        let tx = tx_builder.send().await?;
-        let receipt = tx.get_receipt().await?;
+    pub async fn buy(&self, params: BuyParams) -> Result<TransactionResult> {
+        self.send(
+            (&params).into(),
+            params.gas_limit,
+            params.gas_price,
+            params.nonce,
+        )
+        .await
+    }
 
-        Ok(TransactionResult {
-            transaction_hash: receipt.transaction_hash,
-            block_number: receipt.block_number,
-            gas_used: Some(U256::from(receipt.gas_used)),
-            status: receipt.status(),
-            logs: receipt.logs().to_vec(),
-        })
+    pub async fn sell(&self, params: crate::types::SellParams) -> Result<TransactionResult> {
+        self.send(
+            (&params).into(),
+            params.gas_limit,
+            params.gas_price,
+            params.nonce,
+        )
+        .await
     }
 
     pub async fn sell_permit(
         &self,
         params: crate::types::SellPermitParams,
     ) -> Result<TransactionResult> {
-        let contract = IDexRouter::new(self.address, self.provider.as_ref());
-
-        let router_params = IDexRouter::SellPermitParams {
-            amountIn: params.amount_in,
-            amountOutMin: params.amount_out_min,
-            amountAllowance: params.amount_allowance,
-            token: params.token,
-            to: params.to,
-            deadline: params.deadline,
-            v: params.v,
-            r: params.r,
-            s: params.s,
-        };
-
-        let mut tx_builder = contract.sellPermit(router_params);
-
-        if let Some(gas_limit) = params.gas_limit {
-            tx_builder = tx_builder.gas(gas_limit);
-        }
-
-        if let Some(gas_price) = params.gas_price {
-            tx_builder = tx_builder.gas_price(gas_price);
-        }
-
-        if let Some(nonce) = params.nonce {
-            tx_builder = tx_builder.nonce(nonce);
-        }
-
-        let tx = tx_builder.send().await?;
-        let receipt = tx.get_receipt().await?;
+        self.send(
+            (&params).into(),
+            params.gas_limit,
+            params.gas_price,
+            params.nonce,
+        )
+        .await
+    }
 
-        Ok(TransactionResult {
-            transaction_hash: receipt.transaction_hash,
-            block_number: receipt.block_number,
-            gas_used: Some(U256::from(receipt.gas_used)),
-            status: receipt.status(),
-            logs: receipt.logs().to_vec(),
-        })
+    async fn send(
+        &self,
+        operation: TradeOperation,
+        gas_limit: Option<u64>,
+        gas_price: Option<u128>,
+        nonce: Option<u64>,
+    ) -> Result<TransactionResult> {
+        let router = Router::Dex(self.address);
+        send_trade(
+            self.provider.as_ref(),
+            &router,
+            operation,
+            gas_limit,
+            gas_price,
+            nonce,
+        )
+        .await
     }
 }
@@ -1,4 +1,5 @@
 use alloy::{
+    eips::BlockId,
     primitives::{Address, U256},
     providers::Provider,
     sol,
@@ -53,4 +54,38 @@ impl<P: Provider + Clone> LensContract<P> {
             .await?;
         Ok((result._0, result._1))
     }
+
+    /// [`LensContract::get_amount_in`] against the state at `block`
+    pub async fn get_amount_in_at(
+        &self,
+        token: Address,
+        amount_out: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<(Address, U256)> {
+        let contract = ILensContract::new(self.address, self.provider.as_ref());
+        let result = contract
+            .getAmountIn(token, amount_out, is_buy)
+            .block(block)
+            .call()
+            .await?;
+        Ok((result._0, result._1))
+    }
+
+    /// [`LensContract::get_amount_out`] against the state at `block`
+    pub async fn get_amount_out_at(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<(Address, U256)> {
+        let contract = ILensContract::new(self.address, self.provider.as_ref());
+        let result = contract
+            .getAmountOut(token, amount_in, is_buy)
+            .block(block)
+            .call()
+            .await?;
+        Ok((result._0, result._1))
+    }
 }
@@ -9,4 +9,44 @@ pub mod uniswap_v3_factory;
 pub use bonding_curve::BondingCurveRouter;
 pub use dex::DexRouter;
 pub use lens::LensContract;
-pub use uniswap_v3_factory::{get_pool_addresses_for_tokens, PoolDiscovery};
+pub use uniswap_v3_factory::{
+    get_pool_addresses_for_tokens, rank_by_liquidity, PoolDiscovery, PoolSummary,
+};
+
+use crate::{
+    trading::gas::TradeOperation,
+    types::{Router, TransactionResult},
+};
+use alloy::providers::Provider;
+use anyhow::Result;
+
+/// Submit a router trade and wait for its receipt
+///
+/// Calldata and value come from [`TradeOperation`], the same description used
+/// for gas estimation.
+pub(crate) async fn send_trade<P: Provider>(
+    provider: &P,
+    router: &Router,
+    operation: TradeOperation,
+    gas_limit: Option<u64>,
+    gas_price: Option<u128>,
+    nonce: Option<u64>,
+) -> Result<TransactionResult> {
+    let mut tx = operation.transaction(router);
+
+    if let Some(gas_limit) = gas_limit {
+        tx = tx.gas_limit(gas_limit);
+    }
+
+    if let Some(gas_price) = gas_price {
+        tx = tx.gas_price(gas_price);
+    }
+
+    if let Some(nonce) = nonce {
+        tx = tx.nonce(nonce);
+    }
+
+    let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+
+    Ok(TransactionResult::from(receipt))
+}
@@ -1,5 +1,12 @@
-use alloy::{primitives::Address, providers::Provider, sol};
+use crate::{stream::dex::price::sqrt_price_x96_to_price, types::UniswapV3Pool};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256, Uint},
+    providers::Provider,
+    sol,
+};
 use anyhow::Result;
+use futures_util::future::try_join_all;
 use std::sync::Arc;
 
 // Uniswap V3 Factory interface
@@ -19,6 +26,48 @@ sol! {
 // Re-export constants from the central constants module
 pub use crate::constants::{DEFAULT_FEE_TIER, UNISWAP_V3_FACTORY, WMON};
 
+/// Tokens summarized concurrently per batch by [`PoolDiscovery::get_pool_summaries`]
+pub const SUMMARY_BATCH_SIZE: usize = 16;
+
+/// Liquidity and price of a token's WMON pool, read at one block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolSummary {
+    pub token: Address,
+    pub pool: Address,
+    pub token0: Address,
+    pub token1: Address,
+    /// Fee in hundredths of a bip (10000 = 1%)
+    pub fee: u32,
+    /// In-range liquidity
+    pub liquidity: u128,
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    /// Block the pool state was read at
+    pub block: u64,
+}
+
+impl PoolSummary {
+    pub fn token_is_token0(&self) -> bool {
+        self.token0 == self.token
+    }
+
+    /// Token price in MON from `sqrt_price_x96` (WMON has 18 decimals)
+    pub fn price_in_mon(&self, token_decimals: u8) -> f64 {
+        sqrt_price_x96_to_price(
+            self.sqrt_price_x96,
+            self.token_is_token0(),
+            token_decimals,
+            18,
+        )
+    }
+}
+
+/// Sort summaries by in-range liquidity, deepest pool first
+pub fn rank_by_liquidity(mut summaries: Vec<PoolSummary>) -> Vec<PoolSummary> {
+    summaries.sort_by_key(|summary| std::cmp::Reverse(summary.liquidity));
+    summaries
+}
+
 /// Pool discovery helper for finding Uniswap V3 pools
 pub struct PoolDiscovery<P> {
     provider: Arc<P>,
@@ -48,7 +97,6 @@ impl<P: Provider + Clone> PoolDiscovery<P> {
         token_b: Address,
         fee: u32,
     ) -> Result<Option<Address>> {
-        use alloy::primitives::Uint;
         let factory = UniswapV3Factory::new(self.factory_address, &self.provider);
 
         let pool_address = factory
@@ -77,6 +125,74 @@ impl<P: Provider + Clone> PoolDiscovery<P> {
 
         Ok(pools)
     }
+
+    /// Pool address, tokens, fee, liquidity and price for each token's WMON pool
+    ///
+    /// All reads are pinned to the current block and run concurrently in
+    /// batches of [`SUMMARY_BATCH_SIZE`]; over a
+    /// [`CoalescingTransport`](crate::coalesce::CoalescingTransport) provider each
+    /// batch goes out as one JSON-RPC request. Tokens without a pool are left out.
+    pub async fn get_pool_summaries(&self, tokens: Vec<Address>) -> Result<Vec<PoolSummary>> {
+        let block = self.provider.get_block_number().await?;
+        let mut summaries = Vec::with_capacity(tokens.len());
+
+        for chunk in tokens.chunks(SUMMARY_BATCH_SIZE) {
+            let results = try_join_all(
+                chunk
+                    .iter()
+                    .map(|&token| self.pool_summary_at(token, block)),
+            )
+            .await?;
+            summaries.extend(results.into_iter().flatten());
+        }
+
+        Ok(summaries)
+    }
+
+    /// [`PoolDiscovery::get_pool_summaries`] for a single token
+    pub async fn get_pool_summary(&self, token: Address) -> Result<Option<PoolSummary>> {
+        let block = self.provider.get_block_number().await?;
+        self.pool_summary_at(token, block).await
+    }
+
+    async fn pool_summary_at(&self, token: Address, block: u64) -> Result<Option<PoolSummary>> {
+        let at = BlockId::number(block);
+        let factory = UniswapV3Factory::new(self.factory_address, &self.provider);
+        let pool = factory
+            .getPool(token, WMON.parse()?, Uint::from(DEFAULT_FEE_TIER))
+            .block(at)
+            .call()
+            .await?;
+        if pool == Address::ZERO {
+            return Ok(None);
+        }
+
+        let contract = UniswapV3Pool::new(pool, &self.provider);
+        let token0 = contract.token0().block(at);
+        let token1 = contract.token1().block(at);
+        let fee = contract.fee().block(at);
+        let liquidity = contract.liquidity().block(at);
+        let slot0 = contract.slot0().block(at);
+        let (token0, token1, fee, liquidity, slot0) = tokio::try_join!(
+            token0.call(),
+            token1.call(),
+            fee.call(),
+            liquidity.call(),
+            slot0.call(),
+        )?;
+
+        Ok(Some(PoolSummary {
+            token,
+            pool,
+            token0,
+            token1,
+            fee: fee.to::<u32>(),
+            liquidity,
+            sqrt_price_x96: U256::from(slot0.sqrtPriceX96),
+            tick: slot0.tick.try_into().unwrap_or(0),
+            block,
+        }))
+    }
 }
 
 /// Convenience function to get pool addresses for tokens paired with WMON
@@ -87,3 +203,37 @@ pub async fn get_pool_addresses_for_tokens(
     let discovery = PoolDiscovery::new(provider)?;
     discovery.get_pools_for_tokens(tokens).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_by_liquidity() {
+        let summary = |token: u8, liquidity: u128| PoolSummary {
+            token: Address::repeat_byte(token),
+            pool: Address::repeat_byte(0xee),
+            token0: Address::repeat_byte(token),
+            token1: WMON.parse().unwrap(),
+            fee: DEFAULT_FEE_TIER,
+            liquidity,
+            // Raw ratio 1: one token for one MON at equal decimals
+            sqrt_price_x96: U256::from(1u8) << 96,
+            tick: 0,
+            block: 1,
+        };
+
+        let ranked = rank_by_liquidity(vec![summary(1, 10), summary(2, 500), summary(3, 0)]);
+        let tokens: Vec<Address> = ranked.iter().map(|s| s.token).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Address::repeat_byte(2),
+                Address::repeat_byte(1),
+                Address::repeat_byte(3)
+            ]
+        );
+        assert!(ranked[0].token_is_token0());
+        assert!((ranked[0].price_in_mon(18) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,362 @@
+//! Canned log vectors for testing event pipelines
+//!
+//! Each fixture pairs a raw RPC [`Log`] with the event the SDK decodes it into,
+//! so downstream code can test its processing without an RPC connection:
+//!
+//! ```rust,ignore
+//! use nadfun_sdk::fixtures;
+//! use nadfun_sdk::types::decode_bonding_curve_event;
+//!
+//! for fixture in fixtures::curve_fixtures() {
+//!     let event = decode_bonding_curve_event(fixture.log.clone())?;
+//!     assert_eq!(event, fixture.expected);
+//! }
+//! ```
+//!
+//! The raw logs are the JSON objects an `eth_getLogs` response carries (topics,
+//! data and block/transaction metadata), embedded from `fixtures/rpc_logs.json`.
+//! Their topics and data were ABI-encoded by hand from the event signatures
+//! rather than with the SDK's `sol!` definitions, so decoding them checks the
+//! decoder against an independent encoding; the expected events are written out
+//! as literals. Only available with the `test-fixtures` feature.
+//!
+//! These vectors are not yet captures from the deployed contracts: their
+//! addresses, block and transaction hashes are placeholders. The
+//! `capture_fixtures` example fetches real logs with `eth_getLogs` and prints
+//! the block, transaction and decoded event of each; replacing the JSON with
+//! its output also means updating the literals below.
+
+use crate::types::{
+    BondingCurveEvent, BurnEvent, BuyEvent, CollectEvent, CreateEvent, DexEvent, ListedEvent,
+    LockEvent, MintEvent, SellEvent, SwapEvent, SyncEvent,
+};
+use alloy::{
+    primitives::{Address, B256, I256, U256, address, b256},
+    rpc::types::Log,
+};
+
+/// Bonding curve contract address used by the curve fixtures
+pub const FIXTURE_BONDING_CURVE: Address = address!("0x52D34d8536350Cd997bCBD0b9E9d722452f341F5");
+/// Token address shared by all fixtures
+pub const FIXTURE_TOKEN: Address = address!("0x1111111111111111111111111111111111111111");
+/// Trader / creator address
+pub const FIXTURE_SENDER: Address = address!("0x2222222222222222222222222222222222222222");
+/// Uniswap V3 pool address of the fixture token
+pub const FIXTURE_POOL: Address = address!("0x3333333333333333333333333333333333333333");
+/// Block number of every fixture log
+pub const FIXTURE_BLOCK: u64 = 1_000_000;
+/// Block hash of every fixture log
+pub const FIXTURE_BLOCK_HASH: B256 =
+    b256!("0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
+/// Transaction hash of every fixture log
+pub const FIXTURE_TX_HASH: B256 =
+    b256!("0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb");
+
+/// A raw curve log and its decoded form
+#[derive(Debug, Clone)]
+pub struct CurveFixture {
+    pub log: Log,
+    pub expected: BondingCurveEvent,
+}
+
+/// A raw Swap log and its decoded form
+#[derive(Debug, Clone)]
+pub struct SwapFixture {
+    pub log: Log,
+    pub expected: SwapEvent,
+}
+
+/// A raw pool liquidity log and its decoded form
+#[derive(Debug, Clone)]
+pub struct DexFixture {
+    pub log: Log,
+    pub expected: DexEvent,
+}
+
+/// `eth_getLogs` JSON of every fixture log, keyed by fixture name
+const RPC_LOGS: &str = include_str!("../fixtures/rpc_logs.json");
+
+fn rpc_log(name: &str) -> Log {
+    let logs: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(RPC_LOGS).expect("fixtures/rpc_logs.json is valid JSON");
+    let log = logs
+        .get(name)
+        .unwrap_or_else(|| panic!("no `{name}` log in fixtures/rpc_logs.json"));
+    serde_json::from_value(log.clone()).expect("fixture log matches the RPC log shape")
+}
+
+fn ether(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(10u64).pow(U256::from(18))
+}
+
+pub fn curve_create() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_create"),
+        expected: BondingCurveEvent::Create(CreateEvent {
+            creator: FIXTURE_SENDER,
+            token: FIXTURE_TOKEN,
+            pool: FIXTURE_POOL,
+            name: "Fixture Token".to_string(),
+            symbol: "FIX".to_string(),
+            token_uri: "ipfs://fixture".to_string(),
+            virtual_mon: ether(30_000),
+            virtual_token: ether(1_073_000_000),
+            target_token_amount: ether(279_900_191),
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 0,
+            removed: false,
+        }),
+    }
+}
+
+pub fn curve_buy() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_buy"),
+        expected: BondingCurveEvent::Buy(BuyEvent {
+            sender: FIXTURE_SENDER,
+            token: FIXTURE_TOKEN,
+            amount_in: U256::from(1_000_000_000_000_000_000u128),
+            amount_out: U256::from(35_000_000_000_000_000_000_000u128),
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 1,
+            removed: false,
+        }),
+    }
+}
+
+pub fn curve_sell() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_sell"),
+        expected: BondingCurveEvent::Sell(SellEvent {
+            sender: FIXTURE_SENDER,
+            token: FIXTURE_TOKEN,
+            amount_in: U256::from(10_000_000_000_000_000_000_000u128),
+            amount_out: U256::from(280_000_000_000_000_000u128),
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 2,
+            removed: false,
+        }),
+    }
+}
+
+pub fn curve_sync() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_sync"),
+        expected: BondingCurveEvent::Sync(SyncEvent {
+            token: FIXTURE_TOKEN,
+            real_mon_reserve: U256::from(720_000_000_000_000_000u128),
+            real_token_reserve: U256::from(775_000_000_000_000_000_000_000_000u128),
+            virtual_mon_reserve: U256::from(30_720_000_000_000_000_000u128),
+            virtual_token_reserve: U256::from(1_048_000_000_000_000_000_000_000_000u128),
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 3,
+            removed: false,
+        }),
+    }
+}
+
+pub fn curve_lock() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_lock"),
+        expected: BondingCurveEvent::Lock(LockEvent {
+            token: FIXTURE_TOKEN,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 4,
+            removed: false,
+        }),
+    }
+}
+
+pub fn curve_listed() -> CurveFixture {
+    CurveFixture {
+        log: rpc_log("curve_listed"),
+        expected: BondingCurveEvent::Listed(ListedEvent {
+            token: FIXTURE_TOKEN,
+            pool: FIXTURE_POOL,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 5,
+            removed: false,
+        }),
+    }
+}
+
+/// One fixture per bonding curve event type, in log index order
+pub fn curve_fixtures() -> Vec<CurveFixture> {
+    vec![
+        curve_create(),
+        curve_buy(),
+        curve_sell(),
+        curve_sync(),
+        curve_lock(),
+        curve_listed(),
+    ]
+}
+
+pub fn swap() -> SwapFixture {
+    SwapFixture {
+        log: rpc_log("swap"),
+        expected: SwapEvent {
+            sender: FIXTURE_SENDER,
+            recipient: FIXTURE_SENDER,
+            amount0: I256::try_from(-5_000_000_000_000_000_000_000i128).unwrap(),
+            amount1: I256::try_from(2_000_000_000_000_000_000i128).unwrap(),
+            sqrt_price_x96: U256::from(1u8) << 90,
+            liquidity: 1_000_000_000_000_000_000_000u128,
+            tick: -83_178,
+            pool_address: FIXTURE_POOL,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 6,
+            removed: false,
+        },
+    }
+}
+
+pub fn mint() -> DexFixture {
+    DexFixture {
+        log: rpc_log("mint"),
+        expected: DexEvent::Mint(MintEvent {
+            sender: FIXTURE_SENDER,
+            owner: FIXTURE_SENDER,
+            tick_lower: -887_200,
+            tick_upper: 887_200,
+            amount: 1_000_000_000_000_000_000u128,
+            amount0: U256::from(5_000_000_000_000_000_000_000u128),
+            amount1: U256::from(2_000_000_000_000_000_000u128),
+            pool_address: FIXTURE_POOL,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 7,
+            removed: false,
+        }),
+    }
+}
+
+pub fn burn() -> DexFixture {
+    DexFixture {
+        log: rpc_log("burn"),
+        expected: DexEvent::Burn(BurnEvent {
+            owner: FIXTURE_SENDER,
+            tick_lower: -887_200,
+            tick_upper: 887_200,
+            amount: 400_000_000_000_000_000u128,
+            amount0: U256::from(2_000_000_000_000_000_000_000u128),
+            amount1: U256::from(800_000_000_000_000_000u128),
+            pool_address: FIXTURE_POOL,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 8,
+            removed: false,
+        }),
+    }
+}
+
+pub fn collect() -> DexFixture {
+    DexFixture {
+        log: rpc_log("collect"),
+        expected: DexEvent::Collect(CollectEvent {
+            owner: FIXTURE_SENDER,
+            recipient: FIXTURE_SENDER,
+            tick_lower: -887_200,
+            tick_upper: 887_200,
+            amount0: 2_000_000_000_000_000_000_000u128,
+            amount1: 800_000_000_000_000_000u128,
+            pool_address: FIXTURE_POOL,
+            block_number: FIXTURE_BLOCK,
+            block_hash: FIXTURE_BLOCK_HASH,
+            transaction_hash: FIXTURE_TX_HASH,
+            transaction_index: 0,
+            log_index: 9,
+            removed: false,
+        }),
+    }
+}
+
+/// The [`swap`] event moved to `block` and `log_index`
+///
+/// Each block gets its own transaction hash, for stream tests that order, group
+/// or deduplicate events by position.
+pub fn swap_at(block: u64, log_index: u64) -> SwapEvent {
+    let mut swap = swap().expected;
+    swap.block_number = block;
+    swap.transaction_hash = B256::with_last_byte(block as u8);
+    swap.log_index = log_index;
+    swap
+}
+
+/// Mint, Burn and Collect fixtures in log order
+pub fn liquidity_fixtures() -> Vec<DexFixture> {
+    vec![mint(), burn(), collect()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        curve_log_token, decode_bonding_curve_event, decode_dex_event, decode_swap_event,
+    };
+
+    #[test]
+    fn test_curve_fixtures_decode() {
+        for fixture in curve_fixtures() {
+            let event = decode_bonding_curve_event(fixture.log).unwrap();
+            assert_eq!(event, fixture.expected);
+        }
+    }
+
+    #[test]
+    fn test_curve_log_token_reads_indexed_topic() {
+        for fixture in curve_fixtures() {
+            assert_eq!(
+                curve_log_token(&fixture.log),
+                Some(fixture.expected.token())
+            );
+        }
+        assert_eq!(curve_log_token(&swap().log), None);
+    }
+
+    #[test]
+    fn test_swap_fixture_decodes() {
+        let fixture = swap();
+        assert_eq!(decode_swap_event(fixture.log).unwrap(), fixture.expected);
+    }
+
+    #[test]
+    fn test_dex_fixtures_decode() {
+        for fixture in liquidity_fixtures() {
+            let event = decode_dex_event(fixture.log).unwrap();
+            assert_eq!(event, fixture.expected);
+        }
+
+        let fixture = swap();
+        assert_eq!(
+            decode_dex_event(fixture.log).unwrap(),
+            DexEvent::Swap(fixture.expected)
+        );
+        assert!(decode_dex_event(curve_buy().log).is_err());
+    }
+}
@@ -29,6 +29,12 @@
 //! }
 //! ```
 
+/// Single entry point sharing providers and signer across SDK components
+///
+/// `NadfunClient` connects once and exposes `trade()`, `tokens()`, `streams()`
+/// and `indexers()`.
+pub mod client;
+
 /// Constants and contract addresses for the Nad.fun ecosystem
 ///
 /// Contains all contract addresses, fee tiers, and other system constants.
@@ -63,6 +69,69 @@ pub mod stream;
 /// and response types (transaction results, token metadata).
 pub mod types;
 
+/// Warm keep-alive RPC connections with health-ranked endpoint failover
+///
+/// `EndpointPool` is an alloy transport shared by every provider built from it,
+/// cutting connection setup out of latency-critical submission paths.
+pub mod rpc;
+
+/// RPC call counters by method and SDK component
+///
+/// Enabled by the `metrics` feature.
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+/// Coalescing of concurrent view calls into JSON-RPC batch requests
+///
+/// `CoalescingTransport` collects `eth_call`/`eth_getBalance` requests sent
+/// within a short window and sends them as one batch.
+pub mod coalesce;
+
+/// Shared cache of token decimals/symbols and pool tokens
+///
+/// One `MetadataCache` can back `TokenHelper`, `PoolMetadata`, `DexPriceStream`
+/// and `PriceOracle` so immutable metadata is fetched once per process.
+pub mod metadata;
+
+/// Batches of reads pinned to a single block
+///
+/// `BatchReader` returns balances, curve state and quotes as a `Snapshot`
+/// tagged with the block they were read at.
+pub mod batch;
+
+/// Cached listed/locked state of bonding curve tokens
+///
+/// `TokenStateCache` turns `is_listed`/`is_locked` into local lookups, kept
+/// current per block or from Lock/Listed events over WebSocket.
+pub mod token_state;
+
+/// Point-in-time token prices from archive state or the nearest Sync/Swap event
+pub mod oracle;
+
+/// Validation helpers for user-supplied addresses and amounts
+///
+/// Checksum-aware address parsing, decimal amount parsing with balance/supply
+/// limits, and on-chain token existence checks with descriptive error messages.
+pub mod validation;
+
+/// Rule-based alerts for stream events via webhooks and Telegram
+///
+/// Enabled by the `alerts` feature.
+#[cfg(feature = "alerts")]
+pub mod alerts;
+
+/// Protobuf messages of curve and swap events for consumers in other languages
+///
+/// Enabled by the `proto` feature.
+#[cfg(feature = "proto")]
+pub mod proto;
+
+/// Canned raw logs with their expected decoded events for testing pipelines
+///
+/// Enabled by the `test-fixtures` feature.
+#[cfg(any(test, feature = "test-fixtures"))]
+pub mod fixtures;
+
 /// Internal contract interface definitions (not directly exposed to users)
 ///
 /// Contains low-level contract bindings and pool discovery logic. These are used
@@ -70,16 +139,59 @@ pub mod types;
 pub(crate) mod contracts;
 
 // Core API exports - only what users need
-pub use contracts::{PoolDiscovery, get_pool_addresses_for_tokens};
+pub use client::{ClientConfig, NadfunClient};
+pub use constants::ChainConfig;
+pub use rpc::{ConnectionConfig, EndpointHealth, EndpointPool, QuorumConfig, RpcEndpoints};
+pub use coalesce::{CoalesceConfig, CoalescingTransport};
+#[cfg(feature = "metrics")]
+pub use metrics::{Component, InstrumentedTransport, MethodCalls, MetricsSnapshot, RpcMetrics};
+pub use batch::{BatchReader, BlockReads, Snapshot};
+pub use metadata::{MetadataCache, PoolTokens};
+pub use token_state::{TokenState, TokenStateCache};
+pub use oracle::{HistoricalPrice, PriceOracle, PriceSource};
+pub use contracts::{PoolDiscovery, PoolSummary, get_pool_addresses_for_tokens, rank_by_liquidity};
 // Export contract interfaces for gas estimation in examples
 pub use contracts::bonding_curve::{IBondingCurveRouter};
 pub use contracts::dex::{IDexRouter};
 pub use stream::{
-    BondingCurveEvent, CurveIndexer, CurveStream, EventType, PoolMetadata, SwapEvent,
-    UniswapSwapIndexer, UniswapSwapStream,
+    BondingCurveEvent, CurveIndexer, CurveStream, EventType, PoolMetadata, Predicate, SwapEvent,
+    UniswapSwapIndexer, UniswapSwapStream, WalletActivity, WalletMonitor,
 };
-pub use token::TokenHelper;
-pub use trading::{SlippageUtils, Trade, Router, estimate_gas, GasEstimationParams};
+#[cfg(feature = "experimental")]
+pub use stream::TokenCatalog;
+#[cfg(feature = "experimental")]
+pub use stream::{AmountNormalizer, EnrichedCurveEvent, EnrichedSwapEvent};
+#[cfg(feature = "experimental")]
+pub use stream::{MonitorConfig, MonitorSet};
+pub use token::{SweepConfig, SweepReport, Sweeper, TokenHelper};
+pub use token::{detect_transfer_fee, TransferFee};
+#[cfg(feature = "offchain")]
+pub use token::{OffchainTokenMetadata, TokenUriResolver};
+#[cfg(feature = "token-list")]
+pub use token::{TokenList, TokenListEntry};
+pub use trading::{SlippageSuggestion, SlippageUtils, Trade, Router, estimate_gas, GasEstimationParams, TradeOperation};
+pub use trading::{SizingUtils, TxUtils};
+pub use trading::{GasMode, TradeBuilder, TradeOptions, TradeSide};
+pub use trading::{TokenLockGuard, TokenLocks};
+pub use trading::{SellExecution, SellPlan};
+pub use trading::{get_default_gas_limit, GasCalibration, GasConfig, GasTable, Operation};
+pub use trading::{GasUsageReport, GasUsageScanner};
+pub use trading::{ArbConfig, ArbEvent, ArbLimits, ArbOpportunity, ArbWatcher};
+pub use trading::{decode_router_calldata, encode_buy_calldata, encode_sell_calldata, encode_sell_permit_calldata, RouterCall};
+pub use trading::{safe_nonce, SafeOperation, SafeTransaction};
+pub use trading::{probe_router, RouterInfo, SelectorStatus};
+pub use trading::{DustCleanupConfig, DustReport};
+pub use trading::{FallbackConfig, FallbackOutcome};
+pub use trading::{ConfirmedReceipt, Reorged};
+pub use trading::{Replacement, ReplacementFees, ReplacementOutcome};
+pub use trading::{RiskLimits, RiskManager, RiskViolation};
+pub use trading::{MultiWalletTrader, RotationPolicy};
+pub use trading::{LadderQuote, QuoteLadder};
+pub use trading::{simulate_graduation, GraduationAssumptions, GraduationSimulation};
+#[cfg(feature = "safe-service")]
+pub use trading::SafeTxService;
+#[cfg(feature = "alerts")]
+pub use alerts::{AlertRule, AlertSink, Alerter};
 pub use types::*;
 
 /// Convenient prelude module for importing commonly used types and functions
@@ -95,8 +207,13 @@ pub use types::*;
 /// This saves you from having to import each type individually and provides
 /// a standardized way to get started with the SDK quickly.
 pub mod prelude {
+    // Facade over all components
+    pub use crate::client::{ClientConfig, NadfunClient};
+
     // Trading functionality
-    pub use crate::trading::{SlippageUtils, Trade, Router, estimate_gas, GasEstimationParams};
+    pub use crate::trading::{SlippageUtils, Trade, Router, estimate_gas, GasEstimationParams, TradeOperation};
+    pub use crate::trading::SizingUtils;
+    pub use crate::trading::{get_default_gas_limit, GasConfig, GasTable, Operation};
 
     // Token operations
     pub use crate::token::TokenHelper;
@@ -106,7 +223,7 @@ pub mod prelude {
     pub use crate::stream::{PoolMetadata, SwapEvent, UniswapSwapIndexer, UniswapSwapStream};
 
     // Pool discovery utilities
-    pub use crate::contracts::{PoolDiscovery, get_pool_addresses_for_tokens};
+    pub use crate::contracts::{PoolDiscovery, PoolSummary, get_pool_addresses_for_tokens, rank_by_liquidity};
 
     // Constants and types
     pub use crate::constants::*;
@@ -115,3 +232,36 @@ pub mod prelude {
     // Common Alloy primitives
     pub use alloy::primitives::{Address, B256, U256};
 }
+
+/// Fast-moving stream combinators and analytics, outside the semver guarantees
+///
+/// Enabled by the `experimental` feature, which also compiles the stream
+/// modules these items live in (`grouping`, `pause`, `dedup`, `redundant`,
+/// `monitor`, `normalize`, `value`, `analytics`, `catalog`, `history` and
+/// `transaction`). Without the feature none of them is part of the public API;
+/// with it they may change or move in a minor release. Pin the crate version
+/// with `=` when depending on them.
+///
+/// ```rust,ignore
+/// use nadfun_sdk::prelude::*;              // stable trading and streaming APIs
+/// use nadfun_sdk::experimental::prelude::*; // opt-in stream/analytics surfaces
+/// ```
+#[cfg(feature = "experimental")]
+pub mod experimental {
+    pub mod prelude {
+        // Stream combinators
+        pub use crate::stream::{
+            BlockEvents, EventStreamExt, PausableStream, PauseConfig, PauseHandle, TxActivity,
+            TxStreamExt, group_by_block, group_by_transaction, group_stream_by_transaction,
+            pausable,
+        };
+        pub use crate::stream::{DedupStats, Deduplicator, FeedHealth, RedundantStream};
+        pub use crate::stream::{MonitorConfig, MonitorSet};
+        pub use crate::stream::{AmountNormalizer, TradeValuer, ValuedEvent};
+
+        // Analytics and historical views
+        pub use crate::stream::{CreatorReport, LeaderboardSort, VolumeLeaderboard};
+        pub use crate::stream::TokenCatalog;
+        pub use crate::stream::{DecodedTransaction, WalletHistory, decode_transaction};
+    }
+}
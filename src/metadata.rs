@@ -0,0 +1,224 @@
+//! Token and pool metadata shared between components
+//!
+//! Decimals, symbols and pool token order never change once a contract is
+//! deployed, yet every helper used to look them up again. A [`MetadataCache`]
+//! is fetched into once and handed to each component that needs it:
+//!
+//! ```rust,ignore
+//! let cache = MetadataCache::new();
+//! cache.prefetch_tokens(&provider, &tokens).await?;
+//! cache.prefetch_pools(&provider, &pools).await?;
+//!
+//! let helper = TokenHelper::new(rpc_url.clone(), private_key).await?.with_metadata_cache(cache.clone());
+//! let prices = DexPriceStream::new(ws_url, pools).await?.with_metadata_cache(cache.clone());
+//! let oracle = PriceOracle::new(rpc_url).await?.with_metadata_cache(cache);
+//! ```
+//!
+//! Values that do change (balances, total supply, reserves) are never cached.
+
+use crate::{
+    token::token::{IToken, read_string_field},
+    types::UniswapV3Pool,
+};
+use alloy::{primitives::Address, providers::Provider, sol_types::SolCall};
+use anyhow::Result;
+use futures_util::future::try_join_all;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+/// The two tokens of a Uniswap V3 pool, in pool order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolTokens {
+    pub token0: Address,
+    pub token1: Address,
+}
+
+impl PoolTokens {
+    /// The pool token that isn't `base` (e.g. WMON), None if `base` isn't in the pool
+    pub fn other(&self, base: Address) -> Option<Address> {
+        if self.token0 == base {
+            Some(self.token1)
+        } else if self.token1 == base {
+            Some(self.token0)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    decimals: HashMap<Address, u8>,
+    symbols: HashMap<Address, String>,
+    pools: HashMap<Address, PoolTokens>,
+}
+
+/// Thread-safe cache of token decimals/symbols and pool tokens
+///
+/// Cheap to clone; all clones share the same entries. Lookups fetch on a miss
+/// and only cache successful results.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataCache {
+    entries: Arc<Mutex<Entries>>,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token decimals, fetched on a miss
+    pub async fn decimals<P: Provider>(&self, provider: &P, token: Address) -> Result<u8> {
+        if let Some(decimals) = self.cached_decimals(token) {
+            return Ok(decimals);
+        }
+        let decimals = IToken::new(token, provider).decimals().call().await?;
+        self.insert_decimals(token, decimals);
+        Ok(decimals)
+    }
+
+    /// Token symbol, fetched on a miss (`string` or `bytes32`)
+    pub async fn symbol<P: Provider>(&self, provider: &P, token: Address) -> Result<String> {
+        if let Some(symbol) = self.cached_symbol(token) {
+            return Ok(symbol);
+        }
+        let symbol = read_string_field(provider, token, IToken::symbolCall {}.abi_encode())
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Token {} has no readable symbol()", token))?;
+        self.insert_symbol(token, symbol.clone());
+        Ok(symbol)
+    }
+
+    /// Tokens of a pool, fetched on a miss
+    pub async fn pool_tokens<P: Provider>(
+        &self,
+        provider: &P,
+        pool: Address,
+    ) -> Result<PoolTokens> {
+        if let Some(tokens) = self.cached_pool(pool) {
+            return Ok(tokens);
+        }
+        let contract = UniswapV3Pool::new(pool, provider);
+        let (token0, token1) = tokio::try_join!(async { contract.token0().call().await }, async {
+            contract.token1().call().await
+        })?;
+        let tokens = PoolTokens { token0, token1 };
+        self.insert_pool(pool, tokens);
+        Ok(tokens)
+    }
+
+    /// Fetch decimals and symbols of every uncached token concurrently
+    pub async fn prefetch_tokens<P: Provider>(
+        &self,
+        provider: &P,
+        tokens: &[Address],
+    ) -> Result<()> {
+        let unique: HashSet<Address> = tokens.iter().copied().collect();
+        try_join_all(unique.into_iter().map(|token| async move {
+            tokio::try_join!(self.decimals(provider, token), self.symbol(provider, token))
+        }))
+        .await?;
+        Ok(())
+    }
+
+    /// Fetch the tokens of every uncached pool concurrently
+    pub async fn prefetch_pools<P: Provider>(&self, provider: &P, pools: &[Address]) -> Result<()> {
+        let unique: HashSet<Address> = pools.iter().copied().collect();
+        try_join_all(
+            unique
+                .into_iter()
+                .map(|pool| self.pool_tokens(provider, pool)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub fn cached_decimals(&self, token: Address) -> Option<u8> {
+        self.entries.lock().unwrap().decimals.get(&token).copied()
+    }
+
+    pub fn cached_symbol(&self, token: Address) -> Option<String> {
+        self.entries.lock().unwrap().symbols.get(&token).cloned()
+    }
+
+    pub fn cached_pool(&self, pool: Address) -> Option<PoolTokens> {
+        self.entries.lock().unwrap().pools.get(&pool).copied()
+    }
+
+    /// Seed a known value, e.g. from a database or a `Create` event
+    pub fn insert_decimals(&self, token: Address, decimals: u8) {
+        self.entries
+            .lock()
+            .unwrap()
+            .decimals
+            .insert(token, decimals);
+    }
+
+    pub fn insert_symbol(&self, token: Address, symbol: String) {
+        self.entries.lock().unwrap().symbols.insert(token, symbol);
+    }
+
+    pub fn insert_pool(&self, pool: Address, tokens: PoolTokens) {
+        self.entries.lock().unwrap().pools.insert(pool, tokens);
+    }
+
+    /// Number of cached (decimals, symbols, pools) entries
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let entries = self.entries.lock().unwrap();
+        (
+            entries.decimals.len(),
+            entries.symbols.len(),
+            entries.pools.len(),
+        )
+    }
+
+    pub fn clear(&self) {
+        *self.entries.lock().unwrap() = Entries::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::providers::ProviderBuilder;
+
+    #[tokio::test]
+    async fn test_shared_entries_skip_rpc() {
+        // Nothing listens here, so any lookup that reaches the provider fails
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:1".parse().unwrap());
+        let token = Address::repeat_byte(0x11);
+        let wmon = Address::repeat_byte(0x22);
+        let pool = Address::repeat_byte(0x33);
+
+        let cache = MetadataCache::new();
+        let shared = cache.clone();
+        cache.insert_decimals(token, 6);
+        cache.insert_symbol(token, "TKN".to_string());
+        cache.insert_pool(
+            pool,
+            PoolTokens {
+                token0: token,
+                token1: wmon,
+            },
+        );
+
+        assert_eq!(shared.decimals(&provider, token).await.unwrap(), 6);
+        assert_eq!(shared.symbol(&provider, token).await.unwrap(), "TKN");
+        let tokens = shared.pool_tokens(&provider, pool).await.unwrap();
+        assert_eq!(tokens.other(wmon), Some(token));
+        assert_eq!(tokens.other(Address::ZERO), None);
+        shared
+            .prefetch_tokens(&provider, &[token, token])
+            .await
+            .unwrap();
+
+        // Misses go to the provider and failures aren't cached
+        assert!(shared.decimals(&provider, wmon).await.is_err());
+        assert_eq!(cache.counts(), (1, 1, 1));
+
+        cache.clear();
+        assert_eq!(shared.counts(), (0, 0, 0));
+    }
+}
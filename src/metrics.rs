@@ -0,0 +1,327 @@
+//! RPC call counting per SDK component
+//!
+//! Rate limits are easier to debug once you know who is spending the request
+//! budget. [`RpcMetrics`] wraps a transport and counts every JSON-RPC request by
+//! method and by the [`Component`] that sent it:
+//!
+//! ```rust,ignore
+//! let metrics = RpcMetrics::new();
+//! let trade = Trade::new_with_metrics(rpc_url.clone(), private_key, ChainConfig::default(), &metrics)?;
+//! let indexer = CurveIndexer::new(Arc::new(metrics.http(Component::Indexer, &rpc_url)?.provider()));
+//!
+//! // ... run the bot ...
+//!
+//! let snapshot = metrics.snapshot();
+//! println!("indexer: {} calls", snapshot.calls_by_component(Component::Indexer));
+//! for entry in &snapshot.entries {
+//!     println!("{} {}: {} calls, {} errors", entry.component, entry.method, entry.calls, entry.errors);
+//! }
+//! ```
+//!
+//! Any alloy transport can be instrumented, including an [`EndpointPool`]:
+//! `metrics.instrument(Component::Trade, pool.clone()).wallet_provider(wallet)`.
+//!
+//! [`EndpointPool`]: crate::rpc::EndpointPool
+
+use alloy::{
+    network::EthereumWallet,
+    providers::{DynProvider, ProviderBuilder},
+    rpc::{
+        client::RpcClient,
+        json_rpc::{RequestPacket, ResponsePacket, ResponsePayload},
+    },
+    transports::{
+        TransportError, TransportFut,
+        http::{Http, reqwest},
+    },
+};
+use anyhow::Result;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tower::Service;
+
+/// SDK component a request is attributed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Component {
+    Trade,
+    Token,
+    Indexer,
+    Stream,
+    Other,
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Component::Trade => "trade",
+            Component::Token => "token",
+            Component::Indexer => "indexer",
+            Component::Stream => "stream",
+            Component::Other => "other",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CallStats {
+    calls: u64,
+    errors: u64,
+    total_latency: Duration,
+}
+
+/// Counters of one (component, method) pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodCalls {
+    pub component: Component,
+    pub method: String,
+    pub calls: u64,
+    /// Transport failures and JSON-RPC error responses
+    pub errors: u64,
+    /// Mean round trip; batched requests share the batch's latency
+    pub avg_latency: Duration,
+}
+
+/// Point-in-time copy of the counters, sorted by component then method
+#[derive(Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    pub entries: Vec<MethodCalls>,
+}
+
+impl MetricsSnapshot {
+    pub fn total_calls(&self) -> u64 {
+        self.entries.iter().map(|e| e.calls).sum()
+    }
+
+    pub fn total_errors(&self) -> u64 {
+        self.entries.iter().map(|e| e.errors).sum()
+    }
+
+    pub fn calls_by_component(&self, component: Component) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.component == component)
+            .map(|e| e.calls)
+            .sum()
+    }
+
+    /// Calls of `method` summed over all components
+    pub fn calls_by_method(&self, method: &str) -> u64 {
+        self.entries
+            .iter()
+            .filter(|e| e.method == method)
+            .map(|e| e.calls)
+            .sum()
+    }
+}
+
+/// Shared RPC call counters
+///
+/// Cheap to clone; all clones and every transport instrumented by them update
+/// the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct RpcMetrics {
+    stats: Arc<Mutex<HashMap<(Component, String), CallStats>>>,
+}
+
+impl RpcMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count the requests `transport` sends on behalf of `component`
+    pub fn instrument<T>(&self, component: Component, transport: T) -> InstrumentedTransport<T> {
+        InstrumentedTransport {
+            inner: transport,
+            component,
+            metrics: self.clone(),
+        }
+    }
+
+    /// Instrumented plain HTTP transport to `rpc_url`
+    pub fn http(
+        &self,
+        component: Component,
+        rpc_url: &str,
+    ) -> Result<InstrumentedTransport<Http<reqwest::Client>>> {
+        Ok(self.instrument(component, Http::new(rpc_url.parse()?)))
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let stats = self.stats.lock().unwrap();
+        let mut entries: Vec<MethodCalls> = stats
+            .iter()
+            .map(|((component, method), stats)| MethodCalls {
+                component: *component,
+                method: method.clone(),
+                calls: stats.calls,
+                errors: stats.errors,
+                avg_latency: if stats.calls == 0 {
+                    Duration::ZERO
+                } else {
+                    stats.total_latency / stats.calls as u32
+                },
+            })
+            .collect();
+        entries.sort_by(|a, b| (a.component, &a.method).cmp(&(b.component, &b.method)));
+        MetricsSnapshot { entries }
+    }
+
+    /// Clear all counters, e.g. at the start of a measurement window
+    pub fn reset(&self) {
+        self.stats.lock().unwrap().clear();
+    }
+
+    fn record(&self, component: Component, methods: &[String], latency: Duration, failed: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        for method in methods {
+            let entry = stats.entry((component, method.clone())).or_default();
+            entry.calls += 1;
+            entry.total_latency += latency;
+            if failed {
+                entry.errors += 1;
+            }
+        }
+    }
+}
+
+/// Transport wrapper that reports every request to an [`RpcMetrics`]
+#[derive(Debug, Clone)]
+pub struct InstrumentedTransport<T> {
+    inner: T,
+    component: Component,
+    metrics: RpcMetrics,
+}
+
+impl<T> InstrumentedTransport<T>
+where
+    T: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    /// Read-only provider over this transport
+    pub fn provider(self) -> DynProvider {
+        let client = RpcClient::new(self, false);
+        DynProvider::new(ProviderBuilder::new().connect_client(client))
+    }
+
+    /// Signing provider over this transport
+    pub fn wallet_provider(self, wallet: EthereumWallet) -> DynProvider {
+        let client = RpcClient::new(self, false);
+        DynProvider::new(ProviderBuilder::new().wallet(wallet).connect_client(client))
+    }
+}
+
+/// Method names of a request, one per batch entry
+fn request_methods(request: &RequestPacket) -> Vec<String> {
+    match request {
+        RequestPacket::Single(request) => vec![request.method().to_string()],
+        RequestPacket::Batch(requests) => requests.iter().map(|r| r.method().to_string()).collect(),
+    }
+}
+
+fn response_failed(response: &ResponsePacket) -> bool {
+    match response {
+        ResponsePacket::Single(response) => {
+            matches!(response.payload, ResponsePayload::Failure(_))
+        }
+        ResponsePacket::Batch(responses) => responses
+            .iter()
+            .any(|r| matches!(r.payload, ResponsePayload::Failure(_))),
+    }
+}
+
+impl<T> Service<RequestPacket> for InstrumentedTransport<T>
+where
+    T: Service<
+            RequestPacket,
+            Response = ResponsePacket,
+            Error = TransportError,
+            Future = TransportFut<'static>,
+        > + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let methods = request_methods(&request);
+        let component = self.component;
+        let metrics = self.metrics.clone();
+        let future = self.inner.call(request);
+
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = future.await;
+            let failed = match &result {
+                Ok(response) => response_failed(response),
+                Err(_) => true,
+            };
+            metrics.record(component, &methods, started.elapsed(), failed);
+            result
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::rpc::json_rpc::{Id, Request};
+
+    fn request(method: &'static str) -> RequestPacket {
+        RequestPacket::Single(Request::new(method, Id::Number(1), ()).serialize().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_counts_by_component_and_method() {
+        let ok = tower::service_fn(|_request: RequestPacket| -> TransportFut<'static> {
+            Box::pin(async {
+                Ok(serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#).unwrap())
+            })
+        });
+        let failing = tower::service_fn(|_request: RequestPacket| -> TransportFut<'static> {
+            Box::pin(async {
+                Ok(serde_json::from_str(
+                    r#"{"jsonrpc":"2.0","id":1,"error":{"code":429,"message":"rate limited"}}"#,
+                )
+                .unwrap())
+            })
+        });
+
+        let metrics = RpcMetrics::new();
+        let mut indexer = metrics.instrument(Component::Indexer, ok);
+        let mut trade = metrics.instrument(Component::Trade, failing);
+
+        indexer.call(request("eth_getLogs")).await.unwrap();
+        indexer.call(request("eth_getLogs")).await.unwrap();
+        trade.call(request("eth_call")).await.unwrap();
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.total_calls(), 3);
+        assert_eq!(snapshot.calls_by_component(Component::Indexer), 2);
+        assert_eq!(snapshot.calls_by_method("eth_call"), 1);
+        assert_eq!(snapshot.total_errors(), 1);
+        assert_eq!(snapshot.entries[0].component, Component::Trade);
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot().total_calls(), 0);
+    }
+}
@@ -0,0 +1,283 @@
+//! Point-in-time token prices
+//!
+//! [`PriceOracle::price_at`] values a token at a past block, e.g. for reports:
+//!
+//! ```rust,ignore
+//! let oracle = PriceOracle::new(rpc_url).await?;
+//! let price = oracle.price_at(token, 1_000_000).await?;
+//! println!("{} MON per token ({:?})", price.price_in_mon, price.source);
+//! ```
+//!
+//! The price is read from contract state at that block when the node serves
+//! historical state (an archive node): the curve's virtual reserves before
+//! graduation, the pool's `slot0` after. Otherwise it falls back to the latest
+//! curve Sync or pool Swap event at or before the block.
+
+use crate::{
+    constants::{ChainConfig, DEFAULT_FEE_TIER},
+    contracts::{PoolDiscovery, bonding_curve::IBondingCurve},
+    metadata::MetadataCache,
+    stream::dex::price::{PricedPool, sqrt_price_x96_to_price},
+    types::{
+        BondingCurveEvent, EventId, EventType, UniswapV3Pool, decode_bonding_curve_event,
+        decode_swap_event,
+    },
+};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Blocks searched backwards for an event when state calls fail
+pub const DEFAULT_LOOKBACK_BLOCKS: u64 = 100_000;
+/// Blocks per `eth_getLogs` call during the event search
+const EVENT_BATCH_SIZE: u64 = 1000;
+
+/// Where a historical price came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSource {
+    /// Curve virtual reserves read at the requested block
+    CurveState,
+    /// Pool `slot0` read at the requested block
+    PoolState,
+    /// Reserves of the latest curve Sync event in `block`
+    SyncEvent { block: u64 },
+    /// Price of the latest pool Swap event in `block`
+    SwapEvent { block: u64 },
+}
+
+/// Token price at a block
+#[derive(Debug, Clone)]
+pub struct HistoricalPrice {
+    pub token: Address,
+    /// The requested block
+    pub block: u64,
+    /// Price of one whole token in MON
+    pub price_in_mon: f64,
+    pub source: PriceSource,
+}
+
+/// Historical token prices from curve and pool state
+pub struct PriceOracle {
+    provider: Arc<DynProvider>,
+    chain_config: ChainConfig,
+    lookback_blocks: u64,
+    metadata: MetadataCache,
+}
+
+impl PriceOracle {
+    /// Create an oracle over HTTP (use an archive node for exact state prices)
+    pub async fn new(rpc_url: String) -> Result<Self> {
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+        Ok(Self::from_provider(Arc::new(DynProvider::new(provider))))
+    }
+
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self {
+            provider,
+            chain_config: ChainConfig::default(),
+            lookback_blocks: DEFAULT_LOOKBACK_BLOCKS,
+            metadata: MetadataCache::new(),
+        }
+    }
+
+    pub fn with_chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = chain_config;
+        self
+    }
+
+    /// How far back the event fallback searches before giving up
+    pub fn with_lookback(mut self, blocks: u64) -> Self {
+        self.lookback_blocks = blocks;
+        self
+    }
+
+    /// Share decimals lookups with other components
+    pub fn with_metadata_cache(mut self, metadata: MetadataCache) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Price of `token` in MON as of the end of `block`
+    pub async fn price_at(&self, token: Address, block: u64) -> Result<HistoricalPrice> {
+        match self.price_from_state(token, block).await {
+            Ok(Some((price_in_mon, source))) => Ok(HistoricalPrice {
+                token,
+                block,
+                price_in_mon,
+                source,
+            }),
+            Ok(None) => Err(anyhow::anyhow!(
+                "Token {} had no bonding curve at block {}",
+                token,
+                block
+            )),
+            // Typically a pruned node without state for that block
+            Err(_) => self.price_from_events(token, block).await,
+        }
+    }
+
+    async fn price_from_state(
+        &self,
+        token: Address,
+        block: u64,
+    ) -> Result<Option<(f64, PriceSource)>> {
+        let at = BlockId::number(block);
+        let curve = IBondingCurve::new(self.chain_config.bonding_curve, self.provider.as_ref());
+
+        if curve.isListed(token).block(at).call().await? {
+            let pool = self
+                .pool_for(token)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("No pool found for listed token {}", token))?;
+            let slot0 = UniswapV3Pool::new(pool, self.provider.as_ref())
+                .slot0()
+                .block(at)
+                .call()
+                .await?;
+            let pricing = self.pool_pricing(token).await?;
+            let price = sqrt_price_x96_to_price(
+                U256::from(slot0.sqrtPriceX96),
+                pricing.token_is_token0,
+                pricing.token_decimals,
+                pricing.wmon_decimals,
+            );
+            return Ok(Some((price, PriceSource::PoolState)));
+        }
+
+        let state = curve.curves(token).block(at).call().await?;
+        if state.virtualTokenReserve.is_zero() {
+            return Ok(None);
+        }
+        Ok(Some((
+            curve_price(state.virtualMonReserve, state.virtualTokenReserve),
+            PriceSource::CurveState,
+        )))
+    }
+
+    /// Search backwards from `block` for the latest Sync or Swap of `token`
+    async fn price_from_events(&self, token: Address, block: u64) -> Result<HistoricalPrice> {
+        let pool = self.pool_for(token).await.ok().flatten();
+        let pricing = match pool {
+            Some(_) => Some(self.pool_pricing(token).await?),
+            None => None,
+        };
+        let floor = block.saturating_sub(self.lookback_blocks);
+        let mut to_block = block;
+
+        loop {
+            let from_block = to_block.saturating_sub(EVENT_BATCH_SIZE - 1).max(floor);
+            let mut latest: Option<(EventId, f64, PriceSource)> = None;
+            let mut consider = |id: EventId, price: f64, source: PriceSource| {
+                if latest.as_ref().is_none_or(|(best, _, _)| id > *best) {
+                    latest = Some((id, price, source));
+                }
+            };
+
+            let sync_filter = Filter::new()
+                .address(self.chain_config.bonding_curve)
+                .event_signature(EventType::Sync.signature())
+                .topic1(token.into_word())
+                .from_block(from_block)
+                .to_block(to_block);
+            for log in self.provider.get_logs(&sync_filter).await? {
+                if let Ok(BondingCurveEvent::Sync(sync)) = decode_bonding_curve_event(log) {
+                    consider(
+                        sync.event_id(),
+                        curve_price(sync.virtual_mon_reserve, sync.virtual_token_reserve),
+                        PriceSource::SyncEvent {
+                            block: sync.block_number,
+                        },
+                    );
+                }
+            }
+
+            if let (Some(pool), Some(pricing)) = (pool, &pricing) {
+                let swap_filter = Filter::new()
+                    .address(pool)
+                    .event_signature(UniswapV3Pool::Swap::SIGNATURE_HASH)
+                    .from_block(from_block)
+                    .to_block(to_block);
+                for log in self.provider.get_logs(&swap_filter).await? {
+                    if let Ok(swap) = decode_swap_event(log) {
+                        let tick = pricing.tick(&swap);
+                        consider(
+                            swap.event_id(),
+                            tick.price_in_mon,
+                            PriceSource::SwapEvent {
+                                block: swap.block_number,
+                            },
+                        );
+                    }
+                }
+            }
+
+            if let Some((_, price_in_mon, source)) = latest {
+                return Ok(HistoricalPrice {
+                    token,
+                    block,
+                    price_in_mon,
+                    source,
+                });
+            }
+            if from_block <= floor {
+                return Err(anyhow::anyhow!(
+                    "No state or events to price {} within {} blocks of {}",
+                    token,
+                    self.lookback_blocks,
+                    block
+                ));
+            }
+            to_block = from_block - 1;
+        }
+    }
+
+    async fn pool_for(&self, token: Address) -> Result<Option<Address>> {
+        PoolDiscovery::new(self.provider.clone())?
+            .get_pool(token, self.chain_config.wmon, DEFAULT_FEE_TIER)
+            .await
+    }
+
+    async fn pool_pricing(&self, token: Address) -> Result<PricedPool> {
+        let wmon = self.chain_config.wmon;
+        let token_decimals = self
+            .metadata
+            .decimals(self.provider.as_ref(), token)
+            .await?;
+        let wmon_decimals = self.metadata.decimals(self.provider.as_ref(), wmon).await?;
+
+        Ok(PricedPool {
+            token,
+            // Uniswap orders pool tokens by address
+            token_is_token0: token < wmon,
+            token_decimals,
+            wmon_decimals,
+        })
+    }
+}
+
+/// Curve spot price in MON from virtual reserves (both 18 decimals)
+pub fn curve_price(virtual_mon_reserve: U256, virtual_token_reserve: U256) -> f64 {
+    if virtual_token_reserve.is_zero() {
+        return 0.0;
+    }
+    f64::from(virtual_mon_reserve) / f64::from(virtual_token_reserve)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve_price() {
+        let one = U256::from(10u64).pow(U256::from(18));
+        let price = curve_price(one * U256::from(30), one * U256::from(1_000_000_000u64));
+        assert!((price - 3e-8).abs() < 1e-20);
+        assert_eq!(curve_price(one, U256::ZERO), 0.0);
+    }
+}
@@ -0,0 +1,606 @@
+//! Protobuf encoding of events for cross-language pipelines
+//!
+//! The messages here are the Rust side of `proto/nadfun_events.proto`
+//! (package `nadfun.events.v1`), shipped with the crate as [`PROTO_SCHEMA`].
+//! Services in other languages generate their types from that file and read
+//! what the SDK writes. A unit test compares every field's name, number and
+//! type here with the schema, so the two cannot drift apart:
+//!
+//! ```rust,ignore
+//! use nadfun_sdk::proto;
+//!
+//! while let Some(Ok(event)) = stream.next().await {
+//!     producer.send(topic, proto::encode_curve_event(&event)).await?;
+//! }
+//!
+//! // Consumer side, also in Rust
+//! let event = proto::decode_curve_event(&payload)?;
+//!
+//! // Write the schema for the Go/Java build
+//! std::fs::write("nadfun_events.proto", proto::PROTO_SCHEMA)?;
+//! ```
+//!
+//! Addresses and hashes are 0x-prefixed hex strings and 256-bit amounts are
+//! base-10 strings, so no consumer needs a big-integer type to read them.
+
+use crate::types;
+use alloy::primitives::{Address, B256, I256, U256};
+use anyhow::{Context, Result};
+use prost::Message;
+
+/// `proto/nadfun_events.proto`, the schema these messages encode
+pub const PROTO_SCHEMA: &str = include_str!("../proto/nadfun_events.proto");
+
+/// Protobuf package of [`PROTO_SCHEMA`]
+pub const PROTO_PACKAGE: &str = "nadfun.events.v1";
+
+/// Position and reorg status of the log an event was decoded from
+#[derive(Clone, PartialEq, Message)]
+pub struct EventMeta {
+    #[prost(uint64, tag = "1")]
+    pub block_number: u64,
+    #[prost(string, tag = "2")]
+    pub block_hash: String,
+    #[prost(string, tag = "3")]
+    pub transaction_hash: String,
+    #[prost(uint64, tag = "4")]
+    pub transaction_index: u64,
+    #[prost(uint64, tag = "5")]
+    pub log_index: u64,
+    #[prost(bool, tag = "6")]
+    pub removed: bool,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveCreate {
+    #[prost(string, tag = "1")]
+    pub creator: String,
+    #[prost(string, tag = "2")]
+    pub token: String,
+    #[prost(string, tag = "3")]
+    pub pool: String,
+    #[prost(string, tag = "4")]
+    pub name: String,
+    #[prost(string, tag = "5")]
+    pub symbol: String,
+    #[prost(string, tag = "6")]
+    pub token_uri: String,
+    #[prost(string, tag = "7")]
+    pub virtual_mon: String,
+    #[prost(string, tag = "8")]
+    pub virtual_token: String,
+    #[prost(string, tag = "9")]
+    pub target_token_amount: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveBuy {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub token: String,
+    #[prost(string, tag = "3")]
+    pub amount_in: String,
+    #[prost(string, tag = "4")]
+    pub amount_out: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveSell {
+    #[prost(string, tag = "1")]
+    pub sender: String,
+    #[prost(string, tag = "2")]
+    pub token: String,
+    #[prost(string, tag = "3")]
+    pub amount_in: String,
+    #[prost(string, tag = "4")]
+    pub amount_out: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveSync {
+    #[prost(string, tag = "1")]
+    pub token: String,
+    #[prost(string, tag = "2")]
+    pub real_mon_reserve: String,
+    #[prost(string, tag = "3")]
+    pub real_token_reserve: String,
+    #[prost(string, tag = "4")]
+    pub virtual_mon_reserve: String,
+    #[prost(string, tag = "5")]
+    pub virtual_token_reserve: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveLock {
+    #[prost(string, tag = "1")]
+    pub token: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct CurveListed {
+    #[prost(string, tag = "1")]
+    pub token: String,
+    #[prost(string, tag = "2")]
+    pub pool: String,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct BondingCurveEvent {
+    #[prost(message, optional, tag = "1")]
+    pub meta: Option<EventMeta>,
+    #[prost(oneof = "bonding_curve_event::Event", tags = "2, 3, 4, 5, 6, 7")]
+    pub event: Option<bonding_curve_event::Event>,
+}
+
+pub mod bonding_curve_event {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Event {
+        #[prost(message, tag = "2")]
+        Create(super::CurveCreate),
+        #[prost(message, tag = "3")]
+        Buy(super::CurveBuy),
+        #[prost(message, tag = "4")]
+        Sell(super::CurveSell),
+        #[prost(message, tag = "5")]
+        Sync(super::CurveSync),
+        #[prost(message, tag = "6")]
+        Lock(super::CurveLock),
+        #[prost(message, tag = "7")]
+        Listed(super::CurveListed),
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SwapEvent {
+    #[prost(message, optional, tag = "1")]
+    pub meta: Option<EventMeta>,
+    #[prost(string, tag = "2")]
+    pub pool: String,
+    #[prost(string, tag = "3")]
+    pub sender: String,
+    #[prost(string, tag = "4")]
+    pub recipient: String,
+    #[prost(string, tag = "5")]
+    pub amount0: String,
+    #[prost(string, tag = "6")]
+    pub amount1: String,
+    #[prost(string, tag = "7")]
+    pub sqrt_price_x96: String,
+    #[prost(string, tag = "8")]
+    pub liquidity: String,
+    #[prost(sint32, tag = "9")]
+    pub tick: i32,
+}
+
+/// Protobuf bytes of a curve event
+pub fn encode_curve_event(event: &types::BondingCurveEvent) -> Vec<u8> {
+    BondingCurveEvent::from(event).encode_to_vec()
+}
+
+/// Curve event from protobuf bytes written by [`encode_curve_event`] or another producer
+pub fn decode_curve_event(bytes: &[u8]) -> Result<types::BondingCurveEvent> {
+    BondingCurveEvent::decode(bytes)?.try_into()
+}
+
+/// Protobuf bytes of a swap event
+pub fn encode_swap_event(event: &types::SwapEvent) -> Vec<u8> {
+    SwapEvent::from(event).encode_to_vec()
+}
+
+/// Swap event from protobuf bytes
+pub fn decode_swap_event(bytes: &[u8]) -> Result<types::SwapEvent> {
+    SwapEvent::decode(bytes)?.try_into()
+}
+
+fn hex<T: std::fmt::LowerHex>(value: T) -> String {
+    format!("{value:#x}")
+}
+
+fn address(value: &str, field: &str) -> Result<Address> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid address in `{field}`: {value:?}"))
+}
+
+fn hash(value: &str, field: &str) -> Result<B256> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid hash in `{field}`: {value:?}"))
+}
+
+fn uint(value: &str, field: &str) -> Result<U256> {
+    U256::from_str_radix(value, 10)
+        .with_context(|| format!("Invalid uint256 in `{field}`: {value:?}"))
+}
+
+fn int(value: &str, field: &str) -> Result<I256> {
+    I256::from_dec_str(value).with_context(|| format!("Invalid int256 in `{field}`: {value:?}"))
+}
+
+/// Position fields shared by every event type
+struct Position {
+    block_number: u64,
+    block_hash: B256,
+    transaction_hash: B256,
+    transaction_index: u64,
+    log_index: u64,
+    removed: bool,
+}
+
+impl From<Position> for EventMeta {
+    fn from(p: Position) -> Self {
+        Self {
+            block_number: p.block_number,
+            block_hash: hex(p.block_hash),
+            transaction_hash: hex(p.transaction_hash),
+            transaction_index: p.transaction_index,
+            log_index: p.log_index,
+            removed: p.removed,
+        }
+    }
+}
+
+impl TryFrom<Option<EventMeta>> for Position {
+    type Error = anyhow::Error;
+
+    fn try_from(meta: Option<EventMeta>) -> Result<Self> {
+        let meta = meta.context("Event has no `meta`")?;
+        Ok(Self {
+            block_number: meta.block_number,
+            block_hash: hash(&meta.block_hash, "block_hash")?,
+            transaction_hash: hash(&meta.transaction_hash, "transaction_hash")?,
+            transaction_index: meta.transaction_index,
+            log_index: meta.log_index,
+            removed: meta.removed,
+        })
+    }
+}
+
+/// Build a [`Position`] from an SDK event struct
+macro_rules! position {
+    ($e:expr) => {
+        Position {
+            block_number: $e.block_number,
+            block_hash: $e.block_hash,
+            transaction_hash: $e.transaction_hash,
+            transaction_index: $e.transaction_index,
+            log_index: $e.log_index,
+            removed: $e.removed,
+        }
+    };
+}
+
+impl From<&types::BondingCurveEvent> for BondingCurveEvent {
+    fn from(event: &types::BondingCurveEvent) -> Self {
+        use bonding_curve_event::Event;
+        use types::BondingCurveEvent as Sdk;
+
+        let (position, event) = match event {
+            Sdk::Create(e) => (
+                position!(e),
+                Event::Create(CurveCreate {
+                    creator: hex(e.creator),
+                    token: hex(e.token),
+                    pool: hex(e.pool),
+                    name: e.name.clone(),
+                    symbol: e.symbol.clone(),
+                    token_uri: e.token_uri.clone(),
+                    virtual_mon: e.virtual_mon.to_string(),
+                    virtual_token: e.virtual_token.to_string(),
+                    target_token_amount: e.target_token_amount.to_string(),
+                }),
+            ),
+            Sdk::Buy(e) => (
+                position!(e),
+                Event::Buy(CurveBuy {
+                    sender: hex(e.sender),
+                    token: hex(e.token),
+                    amount_in: e.amount_in.to_string(),
+                    amount_out: e.amount_out.to_string(),
+                }),
+            ),
+            Sdk::Sell(e) => (
+                position!(e),
+                Event::Sell(CurveSell {
+                    sender: hex(e.sender),
+                    token: hex(e.token),
+                    amount_in: e.amount_in.to_string(),
+                    amount_out: e.amount_out.to_string(),
+                }),
+            ),
+            Sdk::Sync(e) => (
+                position!(e),
+                Event::Sync(CurveSync {
+                    token: hex(e.token),
+                    real_mon_reserve: e.real_mon_reserve.to_string(),
+                    real_token_reserve: e.real_token_reserve.to_string(),
+                    virtual_mon_reserve: e.virtual_mon_reserve.to_string(),
+                    virtual_token_reserve: e.virtual_token_reserve.to_string(),
+                }),
+            ),
+            Sdk::Lock(e) => (
+                position!(e),
+                Event::Lock(CurveLock {
+                    token: hex(e.token),
+                }),
+            ),
+            Sdk::Listed(e) => (
+                position!(e),
+                Event::Listed(CurveListed {
+                    token: hex(e.token),
+                    pool: hex(e.pool),
+                }),
+            ),
+        };
+
+        Self {
+            meta: Some(position.into()),
+            event: Some(event),
+        }
+    }
+}
+
+impl TryFrom<BondingCurveEvent> for types::BondingCurveEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(message: BondingCurveEvent) -> Result<Self> {
+        use bonding_curve_event::Event;
+
+        let p = Position::try_from(message.meta)?;
+        let event = match message.event.context("Event has no payload")? {
+            Event::Create(e) => Self::Create(types::CreateEvent {
+                creator: address(&e.creator, "creator")?,
+                token: address(&e.token, "token")?,
+                pool: address(&e.pool, "pool")?,
+                name: e.name,
+                symbol: e.symbol,
+                token_uri: e.token_uri,
+                virtual_mon: uint(&e.virtual_mon, "virtual_mon")?,
+                virtual_token: uint(&e.virtual_token, "virtual_token")?,
+                target_token_amount: uint(&e.target_token_amount, "target_token_amount")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+            Event::Buy(e) => Self::Buy(types::BuyEvent {
+                sender: address(&e.sender, "sender")?,
+                token: address(&e.token, "token")?,
+                amount_in: uint(&e.amount_in, "amount_in")?,
+                amount_out: uint(&e.amount_out, "amount_out")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+            Event::Sell(e) => Self::Sell(types::SellEvent {
+                sender: address(&e.sender, "sender")?,
+                token: address(&e.token, "token")?,
+                amount_in: uint(&e.amount_in, "amount_in")?,
+                amount_out: uint(&e.amount_out, "amount_out")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+            Event::Sync(e) => Self::Sync(types::SyncEvent {
+                token: address(&e.token, "token")?,
+                real_mon_reserve: uint(&e.real_mon_reserve, "real_mon_reserve")?,
+                real_token_reserve: uint(&e.real_token_reserve, "real_token_reserve")?,
+                virtual_mon_reserve: uint(&e.virtual_mon_reserve, "virtual_mon_reserve")?,
+                virtual_token_reserve: uint(&e.virtual_token_reserve, "virtual_token_reserve")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+            Event::Lock(e) => Self::Lock(types::LockEvent {
+                token: address(&e.token, "token")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+            Event::Listed(e) => Self::Listed(types::ListedEvent {
+                token: address(&e.token, "token")?,
+                pool: address(&e.pool, "pool")?,
+                block_number: p.block_number,
+                block_hash: p.block_hash,
+                transaction_hash: p.transaction_hash,
+                transaction_index: p.transaction_index,
+                log_index: p.log_index,
+                removed: p.removed,
+            }),
+        };
+        Ok(event)
+    }
+}
+
+impl From<&types::SwapEvent> for SwapEvent {
+    fn from(e: &types::SwapEvent) -> Self {
+        Self {
+            meta: Some(position!(e).into()),
+            pool: hex(e.pool_address),
+            sender: hex(e.sender),
+            recipient: hex(e.recipient),
+            amount0: e.amount0.to_string(),
+            amount1: e.amount1.to_string(),
+            sqrt_price_x96: e.sqrt_price_x96.to_string(),
+            liquidity: e.liquidity.to_string(),
+            tick: e.tick,
+        }
+    }
+}
+
+impl TryFrom<SwapEvent> for types::SwapEvent {
+    type Error = anyhow::Error;
+
+    fn try_from(e: SwapEvent) -> Result<Self> {
+        let p = Position::try_from(e.meta)?;
+        Ok(Self {
+            sender: address(&e.sender, "sender")?,
+            recipient: address(&e.recipient, "recipient")?,
+            amount0: int(&e.amount0, "amount0")?,
+            amount1: int(&e.amount1, "amount1")?,
+            sqrt_price_x96: uint(&e.sqrt_price_x96, "sqrt_price_x96")?,
+            liquidity: e
+                .liquidity
+                .parse()
+                .with_context(|| format!("Invalid uint128 in `liquidity`: {:?}", e.liquidity))?,
+            tick: e.tick,
+            pool_address: address(&e.pool, "pool")?,
+            block_number: p.block_number,
+            block_hash: p.block_hash,
+            transaction_hash: p.transaction_hash,
+            transaction_index: p.transaction_index,
+            log_index: p.log_index,
+            removed: p.removed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::collections::{BTreeMap, BTreeSet};
+
+    #[test]
+    fn test_events_round_trip_through_protobuf() {
+        for fixture in fixtures::curve_fixtures() {
+            let bytes = encode_curve_event(&fixture.expected);
+            assert_eq!(decode_curve_event(&bytes).unwrap(), fixture.expected);
+        }
+
+        let mut swap = fixtures::swap().expected;
+        swap.amount0 = -swap.amount0.abs();
+        swap.tick = -887_272;
+        let message = SwapEvent::from(&swap);
+        assert!(message.amount0.starts_with('-'));
+        assert_eq!(message.pool, format!("{:#x}", fixtures::FIXTURE_POOL));
+        assert_eq!(decode_swap_event(&encode_swap_event(&swap)).unwrap(), swap);
+
+        let mut broken = message;
+        broken.amount1 = "0x12".to_string();
+        assert!(types::SwapEvent::try_from(broken).is_err());
+    }
+
+    /// `(field, type, tag)` of each message, with message-typed fields as their message name
+    type Fields = BTreeMap<String, BTreeSet<(String, String, u32)>>;
+
+    const SCALARS: [&str; 4] = ["string", "uint64", "bool", "sint32"];
+
+    /// Messages and fields declared in the `.proto` schema
+    fn schema_fields(schema: &str) -> Fields {
+        let mut fields = Fields::new();
+        let mut message = None;
+        for line in schema.lines().map(str::trim) {
+            if line.starts_with("//") || line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix("message ") {
+                let name = name.trim_end_matches(" {").to_string();
+                fields.entry(name.clone()).or_default();
+                message = Some(name);
+                continue;
+            }
+            let Some(message) = &message else { continue };
+            let Some(line) = line.strip_suffix(';') else {
+                continue;
+            };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if let [ty, name, "=", tag] = parts[..] {
+                fields.get_mut(message).unwrap().insert((
+                    name.to_string(),
+                    ty.to_string(),
+                    tag.parse().unwrap(),
+                ));
+            }
+        }
+        fields
+    }
+
+    /// Messages and fields declared by the `#[prost(..)]` attributes of this module
+    fn rust_fields(source: &str) -> Fields {
+        let source = &source[..source.find("#[cfg(test)]").unwrap()];
+        let mut fields = Fields::new();
+        let mut container = None;
+        let mut pending: Option<(String, u32)> = None;
+        let mut oneof_message = None;
+        for line in source.lines().map(str::trim) {
+            if let Some(module) = line.strip_prefix("pub mod ") {
+                // Oneof variants live in a module named after their message
+                let module = module.trim_end_matches(" {");
+                oneof_message = Some(
+                    module
+                        .split('_')
+                        .map(|word| word[..1].to_uppercase() + &word[1..])
+                        .collect::<String>(),
+                );
+            } else if let Some(name) = line.strip_prefix("pub struct ") {
+                container = Some(name.trim_end_matches(" {").to_string());
+            } else if line.starts_with("pub enum ") {
+                container = oneof_message.clone();
+            } else if let Some(attr) = line.strip_prefix("#[prost(") {
+                let kind = attr.split(',').next().unwrap().trim();
+                pending = attr.split("tag = \"").nth(1).map(|tag| {
+                    let tag = tag.split('"').next().unwrap().parse().unwrap();
+                    (kind.to_string(), tag)
+                });
+            } else if let Some((kind, tag)) = pending.take() {
+                let container = container.clone().unwrap();
+                let (name, ty) = match line.strip_prefix("pub ") {
+                    Some(field) => {
+                        let (name, ty) = field.trim_end_matches(',').split_once(": ").unwrap();
+                        (
+                            name.to_string(),
+                            ty.trim_start_matches("Option<").trim_end_matches('>'),
+                        )
+                    }
+                    None => {
+                        let (variant, ty) = line.trim_end_matches("),").split_once('(').unwrap();
+                        (variant.to_lowercase(), ty.trim_start_matches("super::"))
+                    }
+                };
+                let ty = if kind == "message" {
+                    ty.to_string()
+                } else {
+                    kind
+                };
+                fields.entry(container).or_default().insert((name, ty, tag));
+            }
+        }
+        fields
+    }
+
+    #[test]
+    fn test_schema_matches_message_fields() {
+        assert!(PROTO_SCHEMA.contains(&format!("package {PROTO_PACKAGE};")));
+
+        let schema = schema_fields(PROTO_SCHEMA);
+        let rust = rust_fields(include_str!("proto.rs"));
+        assert_eq!(schema, rust);
+
+        assert_eq!(schema.len(), 9);
+        for (message, fields) in &schema {
+            assert!(!fields.is_empty(), "{message}");
+            for (_, ty, _) in fields {
+                assert!(
+                    SCALARS.contains(&ty.as_str()) || schema.contains_key(ty),
+                    "{ty}"
+                );
+            }
+        }
+    }
+}
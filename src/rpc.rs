@@ -0,0 +1,724 @@
+//! Warm, health-ranked RPC connections
+//!
+//! Opening a fresh HTTP connection (TCP + TLS handshake) in the middle of a burst
+//! adds latency exactly when it hurts most. [`EndpointPool`] keeps keep-alive
+//! connections to one or more RPC endpoints, can ping them in the background so
+//! they never go idle, and sends each request to the healthiest endpoint,
+//! failing over to the next one on transport errors:
+//!
+//! ```rust,ignore
+//! let pool = EndpointPool::new(
+//!     vec![primary_url, backup_url],
+//!     ConnectionConfig::default().with_warm_interval(Duration::from_secs(15)),
+//! )?;
+//! pool.warm_up().await;
+//! let _keep_warm = pool.spawn_keep_warm();
+//!
+//! let trade = Trade::new_with_pool(&pool, private_key, ChainConfig::default())?;
+//! ```
+//!
+//! [`RpcEndpoints`] describes a primary endpoint with fallbacks, per-request
+//! timeouts and an optional quorum for critical reads, and builds the pool that
+//! `Trade`, `TokenHelper` and the indexers can share:
+//!
+//! ```rust,ignore
+//! let pool = RpcEndpoints::new(primary_url)
+//!     .with_fallback(backup_url)
+//!     .with_request_timeout(Duration::from_secs(3))
+//!     .with_quorum(QuorumConfig::new(2)) // eth_call answers must match on two endpoints
+//!     .connect()?;
+//!
+//! let trade = Trade::new_with_pool(&pool, private_key.clone(), ChainConfig::default())?;
+//! let tokens = TokenHelper::new_with_pool(&pool, private_key)?;
+//! let indexer = CurveIndexer::new(Arc::new(pool.provider()));
+//! ```
+
+use alloy::{
+    network::EthereumWallet,
+    primitives::U64,
+    providers::{DynProvider, ProviderBuilder},
+    rpc::{
+        client::RpcClient,
+        json_rpc::{
+            ErrorPayload, RequestPacket, Response, ResponsePacket, ResponsePayload,
+            SerializedRequest,
+        },
+    },
+    transports::{
+        TransportError, TransportErrorKind, TransportFut,
+        http::{Http, reqwest},
+    },
+};
+use anyhow::Result;
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+use tower::Service;
+
+/// HTTP connection settings for an [`EndpointPool`]
+#[derive(Debug, Clone)]
+pub struct ConnectionConfig {
+    /// TCP keep-alive probe interval
+    pub tcp_keepalive: Duration,
+    /// How long an idle pooled connection is kept open
+    pub pool_idle_timeout: Duration,
+    /// Idle connections kept per endpoint host
+    pub max_idle_per_host: usize,
+    /// Ping every endpoint at this interval from [`EndpointPool::spawn_keep_warm`]
+    pub warm_interval: Option<Duration>,
+    /// How long an endpoint that just failed is ranked behind healthy ones
+    pub failure_cooldown: Duration,
+    /// Give up on an endpoint after this long and fail over to the next one
+    pub request_timeout: Option<Duration>,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            tcp_keepalive: Duration::from_secs(30),
+            pool_idle_timeout: Duration::from_secs(90),
+            max_idle_per_host: 8,
+            warm_interval: None,
+            failure_cooldown: Duration::from_secs(30),
+            request_timeout: None,
+        }
+    }
+}
+
+impl ConnectionConfig {
+    pub fn with_warm_interval(mut self, interval: Duration) -> Self {
+        self.warm_interval = Some(interval);
+        self
+    }
+
+    pub fn with_failure_cooldown(mut self, cooldown: Duration) -> Self {
+        self.failure_cooldown = cooldown;
+        self
+    }
+
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.max_idle_per_host = max_idle;
+        self
+    }
+
+    /// Build a keep-alive HTTP client with these settings
+    pub fn http_client(&self) -> Result<reqwest::Client> {
+        Ok(reqwest::Client::builder()
+            .tcp_keepalive(self.tcp_keepalive)
+            .tcp_nodelay(true)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.max_idle_per_host)
+            .build()?)
+    }
+}
+
+/// Cross-check selected read methods against several endpoints
+///
+/// Matching requests are sent to the `size` best-ranked endpoints and fail unless
+/// all answers are identical. Reads at the `latest` block can disagree while
+/// endpoints are at different heights, so keep quorum to calls where that matters.
+/// In a JSON-RPC batch (e.g. from a [`CoalescingTransport`](crate::coalesce::CoalescingTransport))
+/// the matching requests are split out and checked one by one.
+#[derive(Debug, Clone)]
+pub struct QuorumConfig {
+    /// Number of endpoints that must return the same answer
+    pub size: usize,
+    /// JSON-RPC methods checked by quorum (default: `eth_call`)
+    pub methods: HashSet<String>,
+}
+
+impl QuorumConfig {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            methods: HashSet::from(["eth_call".to_string()]),
+        }
+    }
+
+    pub fn with_method(mut self, method: impl Into<String>) -> Self {
+        self.methods.insert(method.into());
+        self
+    }
+
+    fn applies_to(&self, request: &SerializedRequest) -> bool {
+        self.methods.contains(request.method())
+    }
+}
+
+/// Primary RPC endpoint with ordered fallbacks
+#[derive(Debug, Clone)]
+pub struct RpcEndpoints {
+    pub primary: String,
+    pub fallbacks: Vec<String>,
+    pub connection: ConnectionConfig,
+    pub quorum: Option<QuorumConfig>,
+}
+
+impl RpcEndpoints {
+    pub fn new(primary: impl Into<String>) -> Self {
+        Self {
+            primary: primary.into(),
+            fallbacks: Vec::new(),
+            connection: ConnectionConfig::default(),
+            quorum: None,
+        }
+    }
+
+    /// Add a fallback, tried after the primary and earlier fallbacks
+    pub fn with_fallback(mut self, url: impl Into<String>) -> Self {
+        self.fallbacks.push(url.into());
+        self
+    }
+
+    pub fn with_connection(mut self, connection: ConnectionConfig) -> Self {
+        self.connection = connection;
+        self
+    }
+
+    /// Fail over when an endpoint takes longer than `timeout`
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.connection.request_timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_quorum(mut self, quorum: QuorumConfig) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// All URLs in order of preference
+    pub fn urls(&self) -> Vec<String> {
+        std::iter::once(self.primary.clone())
+            .chain(self.fallbacks.iter().cloned())
+            .collect()
+    }
+
+    /// Build the shared connection pool
+    pub fn connect(&self) -> Result<EndpointPool> {
+        EndpointPool::new_with_quorum(self.urls(), self.connection.clone(), self.quorum.clone())
+    }
+}
+
+/// Comparable form of a single response, None for batches
+fn response_key(packet: &ResponsePacket) -> Option<String> {
+    match packet {
+        ResponsePacket::Single(response) => Some(match &response.payload {
+            ResponsePayload::Success(result) => result.get().to_string(),
+            ResponsePayload::Failure(error) => {
+                format!("error {}: {}", error.code, error.message)
+            }
+        }),
+        ResponsePacket::Batch(_) => None,
+    }
+}
+
+/// Snapshot of one endpoint's health
+#[derive(Debug, Clone)]
+pub struct EndpointHealth {
+    pub url: String,
+    /// Smoothed request latency, None until the first successful request
+    pub latency_ms: Option<f64>,
+    pub consecutive_failures: u32,
+    pub requests: u64,
+    pub failures: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct HealthState {
+    latency_ms: Option<f64>,
+    consecutive_failures: u32,
+    last_failure: Option<Instant>,
+    requests: u64,
+    failures: u64,
+}
+
+impl HealthState {
+    fn record_success(&mut self, latency: Duration) {
+        let sample = latency.as_secs_f64() * 1000.0;
+        self.latency_ms = Some(match self.latency_ms {
+            Some(current) => current * 0.8 + sample * 0.2,
+            None => sample,
+        });
+        self.consecutive_failures = 0;
+        self.requests += 1;
+    }
+
+    fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures += 1;
+        self.last_failure = Some(now);
+        self.requests += 1;
+        self.failures += 1;
+    }
+
+    fn cooling_down(&self, now: Instant, cooldown: Duration) -> bool {
+        self.consecutive_failures > 0
+            && self
+                .last_failure
+                .is_some_and(|at| now.duration_since(at) < cooldown)
+    }
+}
+
+/// Endpoint indices ordered best first: endpoints outside their failure cooldown,
+/// by smoothed latency (unmeasured last), ties in configured order
+fn rank(states: &[HealthState], now: Instant, cooldown: Duration) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..states.len()).collect();
+    order.sort_by(|&a, &b| {
+        let key = |i: usize| {
+            (
+                states[i].cooling_down(now, cooldown),
+                states[i].latency_ms.unwrap_or(f64::INFINITY),
+            )
+        };
+        let (cool_a, lat_a) = key(a);
+        let (cool_b, lat_b) = key(b);
+        cool_a.cmp(&cool_b).then(
+            lat_a
+                .partial_cmp(&lat_b)
+                .unwrap_or(std::cmp::Ordering::Equal),
+        )
+    });
+    order
+}
+
+struct Endpoint {
+    url: String,
+    transport: Http<reqwest::Client>,
+    health: Mutex<HealthState>,
+}
+
+struct PoolInner {
+    endpoints: Vec<Endpoint>,
+    config: ConnectionConfig,
+    quorum: Option<QuorumConfig>,
+}
+
+/// Keep-alive HTTP transport over one or more RPC endpoints
+///
+/// Usable as an alloy transport: every provider built from the pool shares its
+/// connections and health ranking. Requests go to the best-ranked endpoint and
+/// move on to the next one when the transport fails (connection errors, HTTP
+/// errors, rate limits, [`ConnectionConfig::request_timeout`]). JSON-RPC error
+/// responses are returned as is.
+#[derive(Clone)]
+pub struct EndpointPool {
+    inner: Arc<PoolInner>,
+}
+
+impl EndpointPool {
+    /// Create a pool over `urls`, listed in order of preference
+    pub fn new(urls: Vec<String>, config: ConnectionConfig) -> Result<Self> {
+        Self::new_with_quorum(urls, config, None)
+    }
+
+    /// Create a pool that cross-checks the quorum methods on several endpoints
+    pub fn new_with_quorum(
+        urls: Vec<String>,
+        config: ConnectionConfig,
+        quorum: Option<QuorumConfig>,
+    ) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(anyhow::anyhow!("EndpointPool needs at least one RPC URL"));
+        }
+        if let Some(quorum) = &quorum {
+            if quorum.size < 1 || quorum.size > urls.len() {
+                return Err(anyhow::anyhow!(
+                    "Quorum size {} must be between 1 and the {} configured endpoints",
+                    quorum.size,
+                    urls.len()
+                ));
+            }
+        }
+
+        let client = config.http_client()?;
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let parsed = url.parse()?;
+                Ok(Endpoint {
+                    transport: Http::with_client(client.clone(), parsed),
+                    url,
+                    health: Mutex::new(HealthState::default()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                endpoints,
+                config,
+                quorum,
+            }),
+        })
+    }
+
+    /// Pool over a single endpoint (keep-alive connections without failover)
+    pub fn single(url: String) -> Result<Self> {
+        Self::new(vec![url], ConnectionConfig::default())
+    }
+
+    pub fn config(&self) -> &ConnectionConfig {
+        &self.inner.config
+    }
+
+    /// Open a connection to every endpoint and measure its latency
+    pub async fn warm_up(&self) {
+        let pings = (0..self.inner.endpoints.len()).map(|i| self.ping(i));
+        futures_util::future::join_all(pings).await;
+    }
+
+    /// Ping all endpoints every `warm_interval` so connections never go idle
+    ///
+    /// Returns None when no interval is configured. Abort the handle to stop.
+    pub fn spawn_keep_warm(&self) -> Option<JoinHandle<()>> {
+        let interval = self.inner.config.warm_interval?;
+        let pool = self.clone();
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                pool.warm_up().await;
+            }
+        }))
+    }
+
+    /// Current health of each endpoint, in configured order
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.inner
+            .endpoints
+            .iter()
+            .map(|endpoint| {
+                let state = endpoint.health.lock().unwrap();
+                EndpointHealth {
+                    url: endpoint.url.clone(),
+                    latency_ms: state.latency_ms,
+                    consecutive_failures: state.consecutive_failures,
+                    requests: state.requests,
+                    failures: state.failures,
+                }
+            })
+            .collect()
+    }
+
+    /// Read-only provider backed by this pool
+    pub fn provider(&self) -> DynProvider {
+        let client = RpcClient::new(self.clone(), false);
+        DynProvider::new(ProviderBuilder::new().connect_client(client))
+    }
+
+    /// Signing provider backed by this pool
+    pub fn wallet_provider(&self, wallet: EthereumWallet) -> DynProvider {
+        let client = RpcClient::new(self.clone(), false);
+        DynProvider::new(ProviderBuilder::new().wallet(wallet).connect_client(client))
+    }
+
+    fn ranked(&self) -> Vec<usize> {
+        let states: Vec<HealthState> = self
+            .inner
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.health.lock().unwrap().clone())
+            .collect();
+        rank(&states, Instant::now(), self.inner.config.failure_cooldown)
+    }
+
+    async fn ping(&self, index: usize) {
+        let endpoint = &self.inner.endpoints[index];
+        let client = RpcClient::new(endpoint.transport.clone(), false);
+
+        let started = Instant::now();
+        let result: Result<U64, _> = client.request_noparams("eth_blockNumber").await;
+
+        let mut health = endpoint.health.lock().unwrap();
+        match result {
+            Ok(_) => health.record_success(started.elapsed()),
+            Err(_) => health.record_failure(Instant::now()),
+        }
+    }
+
+    /// Send to one endpoint, recording latency or failure
+    async fn call_endpoint(
+        &self,
+        index: usize,
+        request: RequestPacket,
+    ) -> Result<ResponsePacket, TransportError> {
+        let endpoint = &self.inner.endpoints[index];
+        let mut transport = endpoint.transport.clone();
+
+        let started = Instant::now();
+        let result = match self.inner.config.request_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, transport.call(request)).await {
+                Ok(result) => result,
+                Err(_) => Err(TransportErrorKind::custom_str(&format!(
+                    "Request to {} timed out after {:?}",
+                    endpoint.url, timeout
+                ))),
+            },
+            None => transport.call(request).await,
+        };
+
+        let mut health = endpoint.health.lock().unwrap();
+        match &result {
+            Ok(_) => health.record_success(started.elapsed()),
+            Err(_) => health.record_failure(Instant::now()),
+        }
+        result
+    }
+
+    async fn send(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let Some(quorum) = &self.inner.quorum else {
+            return self.send_any(request).await;
+        };
+
+        match request {
+            RequestPacket::Single(single) if quorum.applies_to(&single) => {
+                self.send_quorum(RequestPacket::Single(single), quorum.size)
+                    .await
+            }
+            RequestPacket::Batch(requests)
+                if requests.iter().any(|request| quorum.applies_to(request)) =>
+            {
+                self.send_split_batch(requests, quorum).await
+            }
+            request => self.send_any(request).await,
+        }
+    }
+
+    /// Send a batch's quorum methods one by one through quorum, the rest as a batch
+    async fn send_split_batch(
+        &self,
+        requests: Vec<SerializedRequest>,
+        quorum: &QuorumConfig,
+    ) -> Result<ResponsePacket, TransportError> {
+        let (checked, rest): (Vec<_>, Vec<_>) = requests
+            .into_iter()
+            .partition(|request| quorum.applies_to(request));
+
+        // A failed quorum becomes that request's error, not the whole batch's
+        let checked_calls = checked.into_iter().map(|request| async move {
+            let id = request.id().clone();
+            self.send_quorum(RequestPacket::Single(request), quorum.size)
+                .await
+                .unwrap_or_else(|e| {
+                    ResponsePacket::Single(Response {
+                        id,
+                        payload: ResponsePayload::Failure(ErrorPayload::internal_error_message(
+                            e.to_string().into(),
+                        )),
+                    })
+                })
+        });
+        let rest_call = async {
+            if rest.is_empty() {
+                Ok(ResponsePacket::Batch(Vec::new()))
+            } else {
+                self.send_any(RequestPacket::Batch(rest)).await
+            }
+        };
+        let (checked, rest) =
+            tokio::join!(futures_util::future::join_all(checked_calls), rest_call);
+
+        let mut responses = Vec::new();
+        for packet in checked.into_iter().chain([rest?]) {
+            match packet {
+                ResponsePacket::Single(response) => responses.push(response),
+                ResponsePacket::Batch(batch) => responses.extend(batch),
+            }
+        }
+        Ok(ResponsePacket::Batch(responses))
+    }
+
+    /// Send to the best-ranked endpoint, falling back to the others on failure
+    ///
+    /// A transaction submission that timed out is not resent: the endpoint may
+    /// have broadcast it already.
+    async fn send_any(&self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let resend_on_timeout = !is_non_idempotent(&request);
+        let mut last_error = None;
+
+        for index in self.ranked() {
+            match self.call_endpoint(index, request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) if !resend_on_timeout && is_timeout(&e) => return Err(e),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.expect("pool has at least one endpoint"))
+    }
+
+    /// Collect `size` answers from the best endpoints and require them to match
+    async fn send_quorum(
+        &self,
+        request: RequestPacket,
+        size: usize,
+    ) -> Result<ResponsePacket, TransportError> {
+        let mut candidates = self.ranked().into_iter();
+        let mut responses: Vec<(usize, ResponsePacket)> = Vec::with_capacity(size);
+        let mut last_error = None;
+
+        while responses.len() < size {
+            let batch: Vec<usize> = candidates.by_ref().take(size - responses.len()).collect();
+            if batch.is_empty() {
+                return Err(last_error.unwrap_or_else(|| {
+                    TransportErrorKind::custom_str("Not enough endpoints for quorum")
+                }));
+            }
+
+            let calls = batch
+                .iter()
+                .map(|&index| self.call_endpoint(index, request.clone()));
+            let results = futures_util::future::join_all(calls).await;
+
+            for (index, result) in batch.into_iter().zip(results) {
+                match result {
+                    Ok(response) => responses.push((index, response)),
+                    Err(e) => last_error = Some(e),
+                }
+            }
+        }
+
+        let (first_index, first) = &responses[0];
+        let expected = response_key(first);
+        for (index, response) in &responses[1..] {
+            if response_key(response) != expected {
+                return Err(TransportErrorKind::custom_str(&format!(
+                    "Quorum mismatch between {} and {}",
+                    self.inner.endpoints[*first_index].url, self.inner.endpoints[*index].url
+                )));
+            }
+        }
+
+        Ok(responses.swap_remove(0).1)
+    }
+}
+
+/// Methods with side effects that must reach the network at most once
+const NON_IDEMPOTENT_METHODS: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
+
+fn is_non_idempotent(request: &RequestPacket) -> bool {
+    request
+        .method_names()
+        .any(|method| NON_IDEMPOTENT_METHODS.contains(&method))
+}
+
+fn is_timeout(error: &TransportError) -> bool {
+    error.to_string().contains("timed out")
+}
+
+impl Service<RequestPacket> for EndpointPool {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().send(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_prefers_fast_healthy_endpoints() {
+        let now = Instant::now();
+        let cooldown = Duration::from_secs(30);
+
+        let mut fast = HealthState::default();
+        fast.record_success(Duration::from_millis(20));
+        let mut slow = HealthState::default();
+        slow.record_success(Duration::from_millis(200));
+        let unmeasured = HealthState::default();
+
+        assert_eq!(
+            rank(
+                &[unmeasured.clone(), slow.clone(), fast.clone()],
+                now,
+                cooldown
+            ),
+            vec![2, 1, 0]
+        );
+
+        let mut failed_fast = fast.clone();
+        failed_fast.record_failure(now);
+        assert_eq!(
+            rank(&[failed_fast.clone(), slow.clone()], now, cooldown),
+            vec![1, 0]
+        );
+
+        // Once the cooldown passes the endpoint competes on latency again
+        let later = now + Duration::from_secs(31);
+        assert_eq!(rank(&[failed_fast, slow], later, cooldown), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_response_key_ignores_request_id() {
+        let a: ResponsePacket =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#).unwrap();
+        let b: ResponsePacket =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":7,"result":"0x10"}"#).unwrap();
+        let c: ResponsePacket =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","id":1,"result":"0x11"}"#).unwrap();
+
+        assert_eq!(response_key(&a), response_key(&b));
+        assert_ne!(response_key(&a), response_key(&c));
+    }
+
+    #[test]
+    fn test_quorum_size_is_validated() {
+        let endpoints = RpcEndpoints::new("http://localhost:8545")
+            .with_fallback("http://localhost:8546")
+            .with_quorum(QuorumConfig::new(3));
+        assert!(endpoints.connect().is_err());
+
+        let endpoints = endpoints.with_quorum(QuorumConfig::new(2));
+        assert!(endpoints.connect().is_ok());
+    }
+
+    #[test]
+    fn test_transaction_submissions_are_not_idempotent() {
+        let request = |method: &'static str| {
+            alloy::rpc::json_rpc::Request::new(method, 1u64.into(), ())
+                .serialize()
+                .unwrap()
+        };
+
+        let call = RequestPacket::Single(request("eth_call"));
+        assert!(!is_non_idempotent(&call));
+        let send = RequestPacket::Single(request("eth_sendRawTransaction"));
+        assert!(is_non_idempotent(&send));
+        let batch = RequestPacket::Batch(vec![
+            request("eth_blockNumber"),
+            request("eth_sendTransaction"),
+        ]);
+        assert!(is_non_idempotent(&batch));
+
+        let timeout = TransportErrorKind::custom_str("Request to x timed out after 3s");
+        assert!(is_timeout(&timeout));
+        let refused = TransportErrorKind::custom_str("connection refused");
+        assert!(!is_timeout(&refused));
+    }
+
+    #[test]
+    fn test_unmeasured_endpoints_keep_configured_order() {
+        let states = vec![HealthState::default(); 3];
+        assert_eq!(
+            rank(&states, Instant::now(), Duration::from_secs(30)),
+            vec![0, 1, 2]
+        );
+    }
+}
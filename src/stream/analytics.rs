@@ -0,0 +1,471 @@
+//! Aggregations over bonding curve events
+//!
+//! [`VolumeLeaderboard`] ranks tokens by MON volume or trade count. Build it
+//! from history with an indexer, then keep it current by applying live events:
+//!
+//! ```rust,ignore
+//! let mut board = VolumeLeaderboard::build(&indexer, from_block, to_block, None).await?;
+//! for entry in board.top(10, LeaderboardSort::Volume) {
+//!     println!("{} {} MON in {} trades", entry.token, entry.volume(), entry.trades());
+//! }
+//!
+//! while let Some(Ok(event)) = stream.next().await {
+//!     board.apply(&event);
+//! }
+//! ```
+//!
+//! [`CreatorReport`] summarizes the launches of one creator address:
+//!
+//! ```rust,ignore
+//! let report = CreatorReport::build(&indexer, creator, from_block, to_block).await?;
+//! println!(
+//!     "{} launched, {} graduated, {} MON total volume",
+//!     report.launched(),
+//!     report.graduated(),
+//!     report.total_volume()
+//! );
+//! ```
+
+use crate::stream::curve::CurveIndexer;
+use crate::types::{BondingCurveEvent, CreateEvent, EventType};
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Blocks per indexer call while building from history
+const ANALYTICS_BATCH_SIZE: u64 = 1000;
+
+/// Trading activity of one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenVolume {
+    pub token: Address,
+    /// MON spent on buys
+    pub buy_volume: U256,
+    /// MON received from sells
+    pub sell_volume: U256,
+    pub buys: u64,
+    pub sells: u64,
+}
+
+impl TokenVolume {
+    fn new(token: Address) -> Self {
+        Self {
+            token,
+            buy_volume: U256::ZERO,
+            sell_volume: U256::ZERO,
+            buys: 0,
+            sells: 0,
+        }
+    }
+
+    /// Total MON volume of buys and sells
+    pub fn volume(&self) -> U256 {
+        self.buy_volume.saturating_add(self.sell_volume)
+    }
+
+    pub fn trades(&self) -> u64 {
+        self.buys + self.sells
+    }
+
+    /// Add a trade, or take it back out if it was removed by a reorg
+    fn record(&mut self, amount: U256, is_buy: bool, removed: bool) {
+        match (is_buy, removed) {
+            (true, false) => {
+                self.buy_volume = self.buy_volume.saturating_add(amount);
+                self.buys += 1;
+            }
+            (true, true) => {
+                self.buy_volume = self.buy_volume.saturating_sub(amount);
+                self.buys = self.buys.saturating_sub(1);
+            }
+            (false, false) => {
+                self.sell_volume = self.sell_volume.saturating_add(amount);
+                self.sells += 1;
+            }
+            (false, true) => {
+                self.sell_volume = self.sell_volume.saturating_sub(amount);
+                self.sells = self.sells.saturating_sub(1);
+            }
+        }
+    }
+}
+
+/// Ranking key of [`VolumeLeaderboard::top`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardSort {
+    Volume,
+    Trades,
+}
+
+/// Per-token MON volume and trade counts, updatable event by event
+#[derive(Debug, Clone, Default)]
+pub struct VolumeLeaderboard {
+    tokens: HashMap<Address, TokenVolume>,
+    last_block: Option<u64>,
+}
+
+impl VolumeLeaderboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aggregate the Buy and Sell events of `from_block..=to_block`
+    ///
+    /// Only Buy and Sell logs are requested (topic filtering), optionally for a
+    /// set of tokens.
+    pub async fn build<P: Provider + Clone>(
+        indexer: &CurveIndexer<P>,
+        from_block: u64,
+        to_block: u64,
+        token_filter: Option<Vec<Address>>,
+    ) -> Result<Self> {
+        let mut board = Self::new();
+        board
+            .fetch_range(indexer, from_block, to_block, token_filter)
+            .await?;
+        Ok(board)
+    }
+
+    /// Fetch and apply events after the last seen block up to `to_block`
+    ///
+    /// For boards kept current by polling instead of a stream.
+    pub async fn update_to<P: Provider + Clone>(
+        &mut self,
+        indexer: &CurveIndexer<P>,
+        to_block: u64,
+        token_filter: Option<Vec<Address>>,
+    ) -> Result<()> {
+        let from_block = self.last_block.map_or(0, |block| block + 1);
+        if from_block > to_block {
+            return Ok(());
+        }
+        self.fetch_range(indexer, from_block, to_block, token_filter)
+            .await
+    }
+
+    async fn fetch_range<P: Provider + Clone>(
+        &mut self,
+        indexer: &CurveIndexer<P>,
+        from_block: u64,
+        to_block: u64,
+        token_filter: Option<Vec<Address>>,
+    ) -> Result<()> {
+        let mut current = from_block;
+        while current <= to_block {
+            let batch_end = std::cmp::min(current + ANALYTICS_BATCH_SIZE - 1, to_block);
+            let events = indexer
+                .fetch_events(
+                    current,
+                    batch_end,
+                    vec![EventType::Buy, EventType::Sell],
+                    token_filter.clone(),
+                )
+                .await?;
+            self.extend(&events);
+            self.last_block = Some(self.last_block.map_or(batch_end, |b| b.max(batch_end)));
+            current = batch_end + 1;
+        }
+        Ok(())
+    }
+
+    /// Count a Buy or Sell; other events are ignored
+    ///
+    /// Events removed by a reorg (`removed() == true`) are subtracted again.
+    pub fn apply(&mut self, event: &BondingCurveEvent) {
+        let (token, amount, is_buy) = match event {
+            BondingCurveEvent::Buy(e) => (e.token, e.amount_in, true),
+            BondingCurveEvent::Sell(e) => (e.token, e.amount_out, false),
+            _ => return,
+        };
+        let block = event.block_number();
+        self.last_block = Some(self.last_block.map_or(block, |b| b.max(block)));
+
+        self.tokens
+            .entry(token)
+            .or_insert_with(|| TokenVolume::new(token))
+            .record(amount, is_buy, event.removed());
+    }
+
+    pub fn extend<'a>(&mut self, events: impl IntoIterator<Item = &'a BondingCurveEvent>) {
+        for event in events {
+            self.apply(event);
+        }
+    }
+
+    /// The `n` most active tokens, ties broken by the other key and then address
+    pub fn top(&self, n: usize, sort: LeaderboardSort) -> Vec<TokenVolume> {
+        let mut entries: Vec<&TokenVolume> = self.tokens.values().collect();
+        entries.sort_by(|a, b| {
+            let primary = match sort {
+                LeaderboardSort::Volume => b
+                    .volume()
+                    .cmp(&a.volume())
+                    .then(b.trades().cmp(&a.trades())),
+                LeaderboardSort::Trades => b
+                    .trades()
+                    .cmp(&a.trades())
+                    .then(b.volume().cmp(&a.volume())),
+            };
+            primary.then(a.token.cmp(&b.token))
+        });
+        entries.into_iter().take(n).cloned().collect()
+    }
+
+    pub fn get(&self, token: Address) -> Option<&TokenVolume> {
+        self.tokens.get(&token)
+    }
+
+    /// Number of tokens with at least one counted trade
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Highest block seen so far
+    pub fn last_block(&self) -> Option<u64> {
+        self.last_block
+    }
+}
+
+/// One token launched by a creator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CreatedToken {
+    pub token: Address,
+    pub created_block: u64,
+    /// A Lock event was seen (curve filled, awaiting listing)
+    pub locked: bool,
+    /// A Listed event was seen (graduated to Uniswap)
+    pub graduated: bool,
+    pub volume: TokenVolume,
+}
+
+/// Launches of one creator and how they played out
+///
+/// Many launches with few graduations and short-lived volume is a common rug
+/// pattern; the report gives the raw numbers, scoring is up to the caller.
+#[derive(Debug, Clone)]
+pub struct CreatorReport {
+    pub creator: Address,
+    tokens: Vec<CreatedToken>,
+    index: HashMap<Address, usize>,
+}
+
+impl CreatorReport {
+    /// Start a report from the creator's Create events
+    pub fn new(creator: Address, creates: &[CreateEvent]) -> Self {
+        let mut report = Self {
+            creator,
+            tokens: Vec::new(),
+            index: HashMap::new(),
+        };
+        for create in creates
+            .iter()
+            .filter(|c| c.creator == creator && !c.removed)
+        {
+            if report.index.contains_key(&create.token) {
+                continue;
+            }
+            report.index.insert(create.token, report.tokens.len());
+            report.tokens.push(CreatedToken {
+                token: create.token,
+                created_block: create.block_number,
+                locked: false,
+                graduated: false,
+                volume: TokenVolume::new(create.token),
+            });
+        }
+        report
+    }
+
+    /// Find the creator's launches in `from_block..=to_block` and aggregate their
+    /// Lock, Listed, Buy and Sell events up to `to_block`
+    pub async fn build<P: Provider + Clone>(
+        indexer: &CurveIndexer<P>,
+        creator: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Self> {
+        let mut creates = Vec::new();
+        let mut current = from_block;
+        while current <= to_block {
+            let batch_end = std::cmp::min(current + ANALYTICS_BATCH_SIZE - 1, to_block);
+            creates.extend(
+                indexer
+                    .fetch_creates_by_creator(creator, current, batch_end)
+                    .await?,
+            );
+            current = batch_end + 1;
+        }
+
+        let mut report = Self::new(creator, &creates);
+        let Some(first_block) = report.tokens.iter().map(|t| t.created_block).min() else {
+            return Ok(report);
+        };
+
+        let tokens: Vec<Address> = report.tokens.iter().map(|t| t.token).collect();
+        let mut current = first_block;
+        while current <= to_block {
+            let batch_end = std::cmp::min(current + ANALYTICS_BATCH_SIZE - 1, to_block);
+            let events = indexer
+                .fetch_events(
+                    current,
+                    batch_end,
+                    vec![
+                        EventType::Buy,
+                        EventType::Sell,
+                        EventType::Lock,
+                        EventType::Listed,
+                    ],
+                    Some(tokens.clone()),
+                )
+                .await?;
+            report.extend(&events);
+            current = batch_end + 1;
+        }
+        Ok(report)
+    }
+
+    /// Count an event of one of the creator's tokens; others are ignored
+    pub fn apply(&mut self, event: &BondingCurveEvent) {
+        let Some(&i) = self.index.get(&event.token()) else {
+            return;
+        };
+        let entry = &mut self.tokens[i];
+        match event {
+            BondingCurveEvent::Buy(e) => entry.volume.record(e.amount_in, true, e.removed),
+            BondingCurveEvent::Sell(e) => entry.volume.record(e.amount_out, false, e.removed),
+            BondingCurveEvent::Lock(e) => entry.locked = !e.removed,
+            BondingCurveEvent::Listed(e) => entry.graduated = !e.removed,
+            _ => {}
+        }
+    }
+
+    pub fn extend<'a>(&mut self, events: impl IntoIterator<Item = &'a BondingCurveEvent>) {
+        for event in events {
+            self.apply(event);
+        }
+    }
+
+    /// Launched tokens in creation order
+    pub fn tokens(&self) -> &[CreatedToken] {
+        &self.tokens
+    }
+
+    pub fn launched(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Tokens listed on Uniswap
+    pub fn graduated(&self) -> usize {
+        self.tokens.iter().filter(|t| t.graduated).count()
+    }
+
+    /// Tokens whose curve filled, including those since listed
+    pub fn locked(&self) -> usize {
+        self.tokens
+            .iter()
+            .filter(|t| t.locked || t.graduated)
+            .count()
+    }
+
+    /// Share of launches that graduated, 0.0 without launches
+    pub fn graduation_rate(&self) -> f64 {
+        if self.tokens.is_empty() {
+            return 0.0;
+        }
+        self.graduated() as f64 / self.tokens.len() as f64
+    }
+
+    /// MON volume across all launched tokens
+    pub fn total_volume(&self) -> U256 {
+        self.tokens
+            .iter()
+            .fold(U256::ZERO, |sum, t| sum.saturating_add(t.volume.volume()))
+    }
+
+    pub fn total_trades(&self) -> u64 {
+        self.tokens.iter().map(|t| t.volume.trades()).sum()
+    }
+
+    /// The launched token with the most MON volume
+    pub fn top_token(&self) -> Option<&CreatedToken> {
+        self.tokens
+            .iter()
+            .max_by(|a, b| a.volume.volume().cmp(&b.volume.volume()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, FIXTURE_TOKEN};
+
+    #[test]
+    fn test_leaderboard_ranking() {
+        let buy = fixtures::curve_buy().expected;
+        let sell = fixtures::curve_sell().expected;
+        let other_token = Address::repeat_byte(0x77);
+        let mut other_buy = buy.clone();
+        if let BondingCurveEvent::Buy(e) = &mut other_buy {
+            e.token = other_token;
+            e.amount_in *= U256::from(10);
+        }
+
+        let mut board = VolumeLeaderboard::new();
+        board.extend([&buy, &sell, &other_buy, &fixtures::curve_sync().expected]);
+        assert_eq!(board.len(), 2);
+
+        let by_volume = board.top(10, LeaderboardSort::Volume);
+        assert_eq!(by_volume[0].token, other_token);
+        let by_trades = board.top(1, LeaderboardSort::Trades);
+        assert_eq!(by_trades.len(), 1);
+        assert_eq!(by_trades[0].token, FIXTURE_TOKEN);
+        assert_eq!(by_trades[0].trades(), 2);
+
+        // A reorged buy is taken back out
+        let mut removed = buy.clone();
+        if let BondingCurveEvent::Buy(e) = &mut removed {
+            e.removed = true;
+        }
+        board.apply(&removed);
+        let entry = board.get(FIXTURE_TOKEN).unwrap();
+        assert_eq!((entry.buys, entry.buy_volume), (0, U256::ZERO));
+    }
+
+    #[test]
+    fn test_creator_report() {
+        let creator = Address::repeat_byte(0x55);
+        let BondingCurveEvent::Create(mut create) = fixtures::curve_create().expected else {
+            panic!("fixture is not a Create");
+        };
+        create.creator = creator;
+        create.token = FIXTURE_TOKEN;
+        let mut other = create.clone();
+        other.token = Address::repeat_byte(0x77);
+        // Launches of a different creator are not counted
+        let mut foreign = create.clone();
+        foreign.creator = Address::repeat_byte(0x66);
+        foreign.token = Address::repeat_byte(0x88);
+
+        let mut report = CreatorReport::new(creator, &[create, other, foreign]);
+        assert_eq!(report.launched(), 2);
+
+        report.extend([
+            &fixtures::curve_buy().expected,
+            &fixtures::curve_sell().expected,
+            &fixtures::curve_lock().expected,
+            &fixtures::curve_listed().expected,
+        ]);
+        assert_eq!(report.graduated(), 1);
+        assert_eq!(report.locked(), 1);
+        assert_eq!(report.graduation_rate(), 0.5);
+        assert_eq!(report.total_trades(), 2);
+        assert_eq!(report.top_token().unwrap().token, FIXTURE_TOKEN);
+    }
+}
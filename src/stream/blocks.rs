@@ -0,0 +1,83 @@
+//! New block notifications
+//!
+//! A thin wrapper over `eth_subscribe("newHeads")` for consumers that need a
+//! block clock next to their event streams (timers, staleness checks):
+//!
+//! ```rust,ignore
+//! let blocks = BlockStream::new(ws_url).await?;
+//! let mut heads = blocks.subscribe().await?;
+//! while let Some(Ok(head)) = heads.next().await {
+//!     println!("block {} at {}", head.number, head.timestamp);
+//! }
+//! ```
+
+use alloy::{
+    primitives::B256,
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+    rpc::types::Header,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::{pin::Pin, sync::Arc};
+
+/// Summary of a new block header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockHeaderInfo {
+    pub number: u64,
+    /// Unix timestamp in seconds
+    pub timestamp: u64,
+    pub hash: B256,
+}
+
+impl From<&Header> for BlockHeaderInfo {
+    fn from(header: &Header) -> Self {
+        Self {
+            number: header.number,
+            timestamp: header.timestamp,
+            hash: header.hash,
+        }
+    }
+}
+
+/// Stream of new block headers over WebSocket
+pub struct BlockStream {
+    provider: Arc<DynProvider>,
+}
+
+impl BlockStream {
+    /// Create a WebSocket-based block stream
+    pub async fn new(rpc_url: String) -> Result<BlockStream> {
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+
+        Ok(BlockStream {
+            provider: Arc::new(DynProvider::new(provider)),
+        })
+    }
+
+    /// Reuse an existing WebSocket provider (e.g. the one behind an event stream)
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self { provider }
+    }
+
+    /// Subscribe to new heads
+    ///
+    /// After a reorg the node announces the new canonical blocks again, so the
+    /// same number can appear more than once with different hashes.
+    pub async fn subscribe(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<BlockHeaderInfo>> + Send>>> {
+        let sub = self.provider.subscribe_blocks().await?;
+
+        let stream = sub
+            .into_stream()
+            .map(|header| Ok(BlockHeaderInfo::from(&header)));
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Latest block number, for comparing against stream progress
+    pub async fn latest_block_number(&self) -> Result<u64> {
+        Ok(self.provider.get_block_number().await?)
+    }
+}
@@ -0,0 +1,296 @@
+//! Searchable catalog of launched tokens
+//!
+//! Every launch emits a `CurveCreate` event with the token's name, symbol and
+//! URI. A [`TokenCatalog`] indexes them so "find the token by ticker" works
+//! without an external indexer:
+//!
+//! ```rust,ignore
+//! let indexer = CurveIndexer::new(provider.clone());
+//! let mut catalog = TokenCatalog::build(&indexer, deploy_block, 10_000).await?;
+//!
+//! for entry in catalog.search("pepe").iter().take(10) {
+//!     println!("{} {} {}", entry.symbol, entry.name, entry.token);
+//! }
+//!
+//! // Later: catch up from where the last build/refresh stopped...
+//! catalog.refresh(&indexer, 10_000).await?;
+//! // ...or keep it current from a live Create stream
+//! while let Some(Ok(event)) = creates.next().await {
+//!     catalog.apply_event(&event);
+//! }
+//! ```
+
+use crate::{
+    stream::CurveIndexer,
+    types::{BondingCurveEvent, CreateEvent, EventType},
+};
+use alloy::{primitives::Address, providers::Provider};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Launch metadata of one token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogEntry {
+    pub token: Address,
+    pub creator: Address,
+    pub pool: Address,
+    pub name: String,
+    pub symbol: String,
+    pub token_uri: String,
+    pub created_block: u64,
+}
+
+impl From<&CreateEvent> for CatalogEntry {
+    fn from(create: &CreateEvent) -> Self {
+        Self {
+            token: create.token,
+            creator: create.creator,
+            pool: create.pool,
+            name: create.name.clone(),
+            symbol: create.symbol.clone(),
+            token_uri: create.token_uri.clone(),
+            created_block: create.block_number,
+        }
+    }
+}
+
+/// How well an entry matches a search query; lower is better
+fn match_rank(entry: &CatalogEntry, query: &str) -> Option<u8> {
+    let symbol = entry.symbol.to_lowercase();
+    let name = entry.name.to_lowercase();
+    if symbol == query {
+        Some(0)
+    } else if name == query {
+        Some(1)
+    } else if symbol.starts_with(query) {
+        Some(2)
+    } else if name.starts_with(query) {
+        Some(3)
+    } else if symbol.contains(query) || name.contains(query) {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+/// Tokens launched on the bonding curve, searchable by name, symbol and creator
+#[derive(Debug, Clone, Default)]
+pub struct TokenCatalog {
+    entries: Vec<CatalogEntry>,
+    index: HashMap<Address, usize>,
+    /// Newest block covered by `build`/`refresh`
+    last_block: Option<u64>,
+}
+
+impl TokenCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Catalog of `creates`, skipping removed events
+    pub fn from_creates(creates: &[CreateEvent]) -> Self {
+        let mut catalog = Self::new();
+        for create in creates {
+            catalog.apply_create(create);
+        }
+        catalog
+    }
+
+    /// Index every Create event from `from_block` to the chain head
+    pub async fn build<P: Provider + Clone>(
+        indexer: &CurveIndexer<P>,
+        from_block: u64,
+        batch_size: u64,
+    ) -> Result<Self> {
+        let mut catalog = Self {
+            last_block: from_block.checked_sub(1),
+            ..Self::default()
+        };
+        catalog.refresh(indexer, batch_size).await?;
+        Ok(catalog)
+    }
+
+    /// Index Create events after the last covered block, up to the chain head
+    ///
+    /// Returns how many tokens were added.
+    pub async fn refresh<P: Provider + Clone>(
+        &mut self,
+        indexer: &CurveIndexer<P>,
+        batch_size: u64,
+    ) -> Result<usize> {
+        let latest = indexer.provider().get_block_number().await?;
+        let mut from_block = self.last_block.map_or(0, |block| block + 1);
+        let before = self.len();
+
+        while from_block <= latest {
+            let to_block = from_block.saturating_add(batch_size).min(latest);
+            let events = indexer
+                .fetch_events(from_block, to_block, vec![EventType::Create], None)
+                .await?;
+            for event in &events {
+                self.apply_event(event);
+            }
+            self.last_block = Some(to_block);
+            from_block = to_block + 1;
+        }
+
+        Ok(self.len() - before)
+    }
+
+    /// Add a Create event, or drop the token if the event was removed by a reorg
+    ///
+    /// Other events are ignored. Returns true if the catalog changed.
+    pub fn apply_event(&mut self, event: &BondingCurveEvent) -> bool {
+        match event {
+            BondingCurveEvent::Create(create) => self.apply_create(create),
+            _ => false,
+        }
+    }
+
+    fn apply_create(&mut self, create: &CreateEvent) -> bool {
+        if create.removed {
+            return self.remove(create.token);
+        }
+        if self.index.contains_key(&create.token) {
+            return false;
+        }
+        self.index.insert(create.token, self.entries.len());
+        self.entries.push(CatalogEntry::from(create));
+        true
+    }
+
+    fn remove(&mut self, token: Address) -> bool {
+        let Some(position) = self.index.remove(&token) else {
+            return false;
+        };
+        self.entries.remove(position);
+        for slot in self.index.values_mut() {
+            if *slot > position {
+                *slot -= 1;
+            }
+        }
+        true
+    }
+
+    pub fn get(&self, token: Address) -> Option<&CatalogEntry> {
+        self.index.get(&token).map(|&i| &self.entries[i])
+    }
+
+    /// Tokens whose symbol is `symbol`, ignoring case, newest first
+    ///
+    /// Tickers aren't unique; several launches can share one.
+    pub fn by_symbol(&self, symbol: &str) -> Vec<&CatalogEntry> {
+        let symbol = symbol.to_lowercase();
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.symbol.to_lowercase() == symbol)
+            .collect()
+    }
+
+    /// Case-insensitive substring search over symbol and name
+    ///
+    /// Exact symbol matches come first, then exact names, prefixes and other
+    /// substrings; newer tokens first within each group.
+    pub fn search(&self, query: &str) -> Vec<&CatalogEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(u8, &CatalogEntry)> = self
+            .entries
+            .iter()
+            .rev()
+            .filter_map(|entry| match_rank(entry, &query).map(|rank| (rank, entry)))
+            .collect();
+        // Stable sort keeps newest-first order within a rank
+        matches.sort_by_key(|(rank, _)| *rank);
+        matches.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    /// Tokens launched by `creator`, oldest first
+    pub fn by_creator(&self, creator: Address) -> Vec<&CatalogEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.creator == creator)
+            .collect()
+    }
+
+    /// [`TokenCatalog::search`] limited to tokens of `creator`
+    pub fn search_by_creator(&self, query: &str, creator: Address) -> Vec<&CatalogEntry> {
+        self.search(query)
+            .into_iter()
+            .filter(|entry| entry.creator == creator)
+            .collect()
+    }
+
+    /// All entries in launch order
+    pub fn entries(&self) -> &[CatalogEntry] {
+        &self.entries
+    }
+
+    /// Newest block covered by [`TokenCatalog::build`] / [`TokenCatalog::refresh`]
+    pub fn last_block(&self) -> Option<u64> {
+        self.last_block
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn create(token: u8, creator: u8, name: &str, symbol: &str, block: u64) -> CreateEvent {
+        let BondingCurveEvent::Create(mut create) = fixtures::curve_create().expected else {
+            unreachable!()
+        };
+        create.token = Address::repeat_byte(token);
+        create.creator = Address::repeat_byte(creator);
+        create.name = name.to_string();
+        create.symbol = symbol.to_string();
+        create.block_number = block;
+        create
+    }
+
+    #[test]
+    fn test_token_catalog_search() {
+        let mut catalog = TokenCatalog::from_creates(&[
+            create(1, 0xa, "Pepe Classic", "PEPE", 1),
+            create(2, 0xb, "Super Pepe", "SPEPE", 2),
+            create(3, 0xa, "Moon Cat", "CAT", 3),
+            create(4, 0xb, "Pepe Reborn", "PEPE", 4),
+        ]);
+
+        let symbols: Vec<Address> = catalog.search("pepe").iter().map(|e| e.token).collect();
+        assert_eq!(
+            symbols,
+            vec![
+                Address::repeat_byte(4),
+                Address::repeat_byte(1),
+                Address::repeat_byte(2)
+            ]
+        );
+        assert_eq!(catalog.by_symbol("pepe").len(), 2);
+        assert_eq!(catalog.by_creator(Address::repeat_byte(0xa)).len(), 2);
+        assert_eq!(
+            catalog.search_by_creator("PEPE", Address::repeat_byte(0xb))[0].token,
+            Address::repeat_byte(4)
+        );
+        assert!(catalog.search("  ").is_empty());
+
+        let mut reorged = create(1, 0xa, "Pepe Classic", "PEPE", 1);
+        reorged.removed = true;
+        assert!(catalog.apply_event(&BondingCurveEvent::Create(reorged)));
+        assert!(catalog.get(Address::repeat_byte(1)).is_none());
+        assert_eq!(catalog.get(Address::repeat_byte(3)).unwrap().symbol, "CAT");
+        assert_eq!(catalog.len(), 3);
+    }
+}
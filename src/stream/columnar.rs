@@ -0,0 +1,334 @@
+//! Column-oriented export of indexed events as Arrow record batches
+//!
+//! Turns indexer output into an Arrow [`RecordBatch`] in one call, ready for
+//! DataFusion, Polars or pyarrow (via the Arrow C data interface or an IPC file,
+//! which needs `arrow` with its `ipc` feature in your own crate):
+//!
+//! ```rust,ignore
+//! let events = indexer.fetch_events(from, to, vec![EventType::Buy, EventType::Sell], None).await?;
+//! let batch = curve_events_to_record_batch(&events)?;
+//!
+//! let mut writer = arrow::ipc::writer::FileWriter::try_new(file, &batch.schema())?;
+//! writer.write(&batch)?;
+//! writer.finish()?;
+//! ```
+//!
+//! Token and MON amounts are `Decimal256(76, 0)` in raw units, so no precision is
+//! lost; divide by `10^18` (or the token decimals) on the analytics side.
+//! Addresses and hashes are checksummed / `0x` hex strings. Curve events share
+//! one wide table: columns an event type doesn't have are null.
+//!
+//! Only available with the `arrow` feature.
+
+use crate::types::{BondingCurveEvent, EventType, SwapEvent};
+use alloy::primitives::{I256, U256};
+use anyhow::Result;
+use arrow::{
+    array::{ArrayRef, BooleanArray, Decimal256Array, Int32Array, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, i256},
+    record_batch::RecordBatch,
+};
+use std::sync::Arc;
+
+/// Precision of amount columns; the widest Arrow decimal
+const AMOUNT_PRECISION: u8 = 76;
+
+fn amount_type() -> DataType {
+    DataType::Decimal256(AMOUNT_PRECISION, 0)
+}
+
+fn event_type_name(event_type: EventType) -> &'static str {
+    match event_type {
+        EventType::Create => "create",
+        EventType::Buy => "buy",
+        EventType::Sell => "sell",
+        EventType::Sync => "sync",
+        EventType::Lock => "lock",
+        EventType::Listed => "listed",
+    }
+}
+
+fn u256_to_i256(value: U256) -> Result<i256> {
+    if value.bit(255) {
+        return Err(anyhow::anyhow!(
+            "{} does not fit a Decimal256 column",
+            value
+        ));
+    }
+    Ok(i256::from_le_bytes(value.to_le_bytes::<32>()))
+}
+
+fn i256_from_signed(value: I256) -> i256 {
+    i256::from_le_bytes(value.into_raw().to_le_bytes::<32>())
+}
+
+fn amount_column(values: Vec<Option<U256>>) -> Result<ArrayRef> {
+    let values = values
+        .into_iter()
+        .map(|value| value.map(u256_to_i256).transpose())
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(
+        Decimal256Array::from(values).with_precision_and_scale(AMOUNT_PRECISION, 0)?,
+    ))
+}
+
+fn string_column(values: Vec<Option<String>>) -> ArrayRef {
+    Arc::new(StringArray::from(values))
+}
+
+/// Columns of [`curve_events_to_record_batch`]
+pub fn curve_events_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("event_type", DataType::Utf8, false),
+        Field::new("token", DataType::Utf8, false),
+        // Creator for Create, trader for Buy/Sell
+        Field::new("sender", DataType::Utf8, true),
+        Field::new("pool", DataType::Utf8, true),
+        Field::new("amount_in", amount_type(), true),
+        Field::new("amount_out", amount_type(), true),
+        Field::new("real_mon_reserve", amount_type(), true),
+        Field::new("real_token_reserve", amount_type(), true),
+        Field::new("virtual_mon_reserve", amount_type(), true),
+        Field::new("virtual_token_reserve", amount_type(), true),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("symbol", DataType::Utf8, true),
+        Field::new("token_uri", DataType::Utf8, true),
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("block_hash", DataType::Utf8, false),
+        Field::new("transaction_hash", DataType::Utf8, false),
+        Field::new("transaction_index", DataType::UInt64, false),
+        Field::new("log_index", DataType::UInt64, false),
+        Field::new("removed", DataType::Boolean, false),
+    ])
+}
+
+/// One row per bonding curve event, in input order
+pub fn curve_events_to_record_batch(events: &[BondingCurveEvent]) -> Result<RecordBatch> {
+    let mut sender = Vec::with_capacity(events.len());
+    let mut pool = Vec::with_capacity(events.len());
+    let mut amount_in = Vec::with_capacity(events.len());
+    let mut amount_out = Vec::with_capacity(events.len());
+    let mut reserves: [Vec<Option<U256>>; 4] = Default::default();
+    let mut name = Vec::with_capacity(events.len());
+    let mut symbol = Vec::with_capacity(events.len());
+    let mut token_uri = Vec::with_capacity(events.len());
+
+    for event in events {
+        let (mut s, mut p, mut a_in, mut a_out, mut r) = (None, None, None, None, [None; 4]);
+        let (mut n, mut sym, mut uri) = (None, None, None);
+        match event {
+            BondingCurveEvent::Create(e) => {
+                s = Some(e.creator);
+                p = Some(e.pool);
+                n = Some(e.name.clone());
+                sym = Some(e.symbol.clone());
+                uri = Some(e.token_uri.clone());
+            }
+            BondingCurveEvent::Buy(e) => {
+                s = Some(e.sender);
+                a_in = Some(e.amount_in);
+                a_out = Some(e.amount_out);
+            }
+            BondingCurveEvent::Sell(e) => {
+                s = Some(e.sender);
+                a_in = Some(e.amount_in);
+                a_out = Some(e.amount_out);
+            }
+            BondingCurveEvent::Sync(e) => {
+                r = [
+                    Some(e.real_mon_reserve),
+                    Some(e.real_token_reserve),
+                    Some(e.virtual_mon_reserve),
+                    Some(e.virtual_token_reserve),
+                ];
+            }
+            BondingCurveEvent::Lock(_) => {}
+            BondingCurveEvent::Listed(e) => p = Some(e.pool),
+        }
+        sender.push(s.map(|a| a.to_string()));
+        pool.push(p.map(|a| a.to_string()));
+        amount_in.push(a_in);
+        amount_out.push(a_out);
+        for (column, value) in reserves.iter_mut().zip(r) {
+            column.push(value);
+        }
+        name.push(n);
+        symbol.push(sym);
+        token_uri.push(uri);
+    }
+
+    let [real_mon, real_token, virtual_mon, virtual_token] = reserves;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| event_type_name(e.event_type())),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.token().to_string()),
+        )),
+        string_column(sender),
+        string_column(pool),
+        amount_column(amount_in)?,
+        amount_column(amount_out)?,
+        amount_column(real_mon)?,
+        amount_column(real_token)?,
+        amount_column(virtual_mon)?,
+        amount_column(virtual_token)?,
+        string_column(name),
+        string_column(symbol),
+        string_column(token_uri),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.block_number()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.block_hash().to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.transaction_hash().to_string()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.transaction_index()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.log_index()),
+        )),
+        Arc::new(BooleanArray::from_iter(
+            events.iter().map(|e| Some(e.removed())),
+        )),
+    ];
+
+    Ok(RecordBatch::try_new(
+        Arc::new(curve_events_schema()),
+        columns,
+    )?)
+}
+
+/// Columns of [`swap_events_to_record_batch`]
+pub fn swap_events_schema() -> Schema {
+    Schema::new(vec![
+        Field::new("pool_address", DataType::Utf8, false),
+        Field::new("sender", DataType::Utf8, false),
+        Field::new("recipient", DataType::Utf8, false),
+        // Signed pool deltas: positive flows into the pool
+        Field::new("amount0", amount_type(), false),
+        Field::new("amount1", amount_type(), false),
+        Field::new("sqrt_price_x96", amount_type(), false),
+        Field::new("liquidity", amount_type(), false),
+        Field::new("tick", DataType::Int32, false),
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("block_hash", DataType::Utf8, false),
+        Field::new("transaction_hash", DataType::Utf8, false),
+        Field::new("transaction_index", DataType::UInt64, false),
+        Field::new("log_index", DataType::UInt64, false),
+        Field::new("removed", DataType::Boolean, false),
+    ])
+}
+
+/// One row per swap, in input order
+pub fn swap_events_to_record_batch(events: &[SwapEvent]) -> Result<RecordBatch> {
+    let signed = |f: fn(&SwapEvent) -> I256| -> Result<ArrayRef> {
+        Ok(Arc::new(
+            Decimal256Array::from_iter_values(events.iter().map(|e| i256_from_signed(f(e))))
+                .with_precision_and_scale(AMOUNT_PRECISION, 0)?,
+        ))
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.pool_address.to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.sender.to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.recipient.to_string()),
+        )),
+        signed(|e| e.amount0)?,
+        signed(|e| e.amount1)?,
+        amount_column(events.iter().map(|e| Some(e.sqrt_price_x96)).collect())?,
+        amount_column(
+            events
+                .iter()
+                .map(|e| Some(U256::from(e.liquidity)))
+                .collect(),
+        )?,
+        Arc::new(Int32Array::from_iter_values(events.iter().map(|e| e.tick))),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.block_number),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.block_hash.to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            events.iter().map(|e| e.transaction_hash.to_string()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.transaction_index),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            events.iter().map(|e| e.log_index),
+        )),
+        Arc::new(BooleanArray::from_iter(
+            events.iter().map(|e| Some(e.removed)),
+        )),
+    ];
+
+    Ok(RecordBatch::try_new(
+        Arc::new(swap_events_schema()),
+        columns,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use arrow::array::Array;
+
+    #[test]
+    fn test_fixtures_to_record_batches() {
+        let events: Vec<BondingCurveEvent> = fixtures::curve_fixtures()
+            .into_iter()
+            .map(|f| f.expected)
+            .collect();
+        let batch = curve_events_to_record_batch(&events).unwrap();
+        assert_eq!(batch.num_rows(), events.len());
+
+        let event_type = batch
+            .column_by_name("event_type")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(event_type.value(1), "buy");
+
+        let buy = fixtures::curve_buy();
+        let BondingCurveEvent::Buy(expected) = buy.expected else {
+            unreachable!()
+        };
+        let amount_in = batch
+            .column_by_name("amount_in")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Decimal256Array>()
+            .unwrap();
+        assert_eq!(
+            amount_in.value(1),
+            u256_to_i256(expected.amount_in).unwrap()
+        );
+        // Create has no amounts
+        assert!(amount_in.is_null(0));
+
+        let swap = fixtures::swap().expected;
+        let batch = swap_events_to_record_batch(std::slice::from_ref(&swap)).unwrap();
+        let amount0 = batch
+            .column_by_name("amount0")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Decimal256Array>()
+            .unwrap();
+        assert!(amount0.value(0) < i256::ZERO);
+        assert_eq!(amount0.value_as_string(0), swap.amount0.to_string());
+
+        assert!(u256_to_i256(U256::MAX).is_err());
+    }
+}
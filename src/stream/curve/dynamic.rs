@@ -0,0 +1,142 @@
+//! Runtime-adjustable filters for [`CurveStream::subscribe_dynamic`]
+//!
+//! ```rust,ignore
+//! let (mut events, handle) = CurveStream::new(ws_url)
+//!     .await?
+//!     .filter_tokens(vec![token_a])
+//!     .subscribe_dynamic()
+//!     .await?;
+//!
+//! // Later, from any task
+//! handle.add_tokens([token_b]);
+//! handle.remove_tokens([token_a]);
+//! handle.add_event_types([EventType::Listed]);
+//! ```
+//!
+//! [`CurveStream::subscribe_dynamic`]: super::CurveStream::subscribe_dynamic
+
+use crate::types::{BondingCurveEvent, EventType};
+use alloy::primitives::Address;
+use std::{collections::HashSet, sync::Arc};
+use tokio::sync::watch;
+
+/// Current filter of a dynamic subscription
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicFilter {
+    /// Tokens to deliver, or None for every token
+    pub tokens: Option<HashSet<Address>>,
+    pub event_types: HashSet<EventType>,
+}
+
+impl DynamicFilter {
+    pub fn allows(&self, event: &BondingCurveEvent) -> bool {
+        let token_ok = match &self.tokens {
+            Some(tokens) => tokens.contains(&event.token()),
+            None => true,
+        };
+        token_ok && self.event_types.contains(&event.event_type())
+    }
+}
+
+/// Changes the filter of a live [`CurveStream::subscribe_dynamic`] stream
+///
+/// Cheap to clone; all clones control the same subscription. Token changes and
+/// removed event types apply client-side immediately. Adding an event type the
+/// node isn't sending re-issues `eth_subscribe` behind the stream.
+///
+/// [`CurveStream::subscribe_dynamic`]: super::CurveStream::subscribe_dynamic
+#[derive(Debug, Clone)]
+pub struct FilterHandle {
+    tx: Arc<watch::Sender<DynamicFilter>>,
+}
+
+impl FilterHandle {
+    pub(crate) fn new(initial: DynamicFilter) -> (Self, watch::Receiver<DynamicFilter>) {
+        let (tx, rx) = watch::channel(initial);
+        (Self { tx: Arc::new(tx) }, rx)
+    }
+
+    /// Snapshot of the current filter
+    pub fn filter(&self) -> DynamicFilter {
+        self.tx.borrow().clone()
+    }
+
+    /// Start delivering these tokens (no-op while every token is delivered)
+    pub fn add_tokens(&self, tokens: impl IntoIterator<Item = Address>) {
+        self.tx.send_if_modified(|filter| match &mut filter.tokens {
+            Some(set) => tokens.into_iter().filter(|t| set.insert(*t)).count() > 0,
+            None => false,
+        });
+    }
+
+    /// Stop delivering these tokens (no-op while every token is delivered)
+    pub fn remove_tokens(&self, tokens: impl IntoIterator<Item = Address>) {
+        self.tx.send_if_modified(|filter| match &mut filter.tokens {
+            Some(set) => tokens.into_iter().filter(|t| set.remove(t)).count() > 0,
+            None => false,
+        });
+    }
+
+    /// Replace the token set; None delivers every token
+    pub fn set_tokens(&self, tokens: Option<Vec<Address>>) {
+        let tokens = tokens.map(|tokens| tokens.into_iter().collect());
+        self.tx.send_if_modified(|filter| {
+            let changed = filter.tokens != tokens;
+            filter.tokens = tokens;
+            changed
+        });
+    }
+
+    pub fn add_event_types(&self, event_types: impl IntoIterator<Item = EventType>) {
+        self.tx.send_if_modified(|filter| {
+            event_types
+                .into_iter()
+                .filter(|et| filter.event_types.insert(et.clone()))
+                .count()
+                > 0
+        });
+    }
+
+    pub fn remove_event_types(&self, event_types: impl IntoIterator<Item = EventType>) {
+        self.tx.send_if_modified(|filter| {
+            event_types
+                .into_iter()
+                .filter(|et| filter.event_types.remove(et))
+                .count()
+                > 0
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, FIXTURE_TOKEN};
+
+    #[test]
+    fn test_filter_handle_updates() {
+        let (handle, mut rx) = FilterHandle::new(DynamicFilter {
+            tokens: Some(HashSet::new()),
+            event_types: [EventType::Buy].into_iter().collect(),
+        });
+        let buy = fixtures::curve_buy().expected;
+        assert!(!rx.borrow_and_update().allows(&buy));
+
+        handle.add_tokens([FIXTURE_TOKEN]);
+        assert!(rx.has_changed().unwrap());
+        assert!(rx.borrow_and_update().allows(&buy));
+
+        // Re-adding an existing token doesn't wake the stream
+        handle.add_tokens([FIXTURE_TOKEN]);
+        assert!(!rx.has_changed().unwrap());
+
+        handle.remove_event_types([EventType::Buy]);
+        assert!(!rx.borrow_and_update().allows(&buy));
+
+        handle.add_event_types([EventType::Buy]);
+        handle.set_tokens(None);
+        assert!(handle.filter().allows(&buy));
+        handle.remove_tokens([FIXTURE_TOKEN]);
+        assert!(handle.filter().allows(&buy));
+    }
+}
@@ -3,9 +3,13 @@
 //! This module provides streaming and indexing functionality specifically for
 //! bonding curve events (Create, Buy, Sell, Sync, Lock, Listed).
 
+pub mod dynamic;
 pub mod indexer;
+pub mod series;
 pub mod stream;
 
 // Re-export main types
-pub use indexer::CurveIndexer;
-pub use stream::CurveStream;
+pub use dynamic::{DynamicFilter, FilterHandle};
+pub use indexer::{CurveIndexer, decode_curve_logs};
+pub use series::{CurvePoint, curve_points};
+pub use stream::{CatchUpProgress, CurveStream};
@@ -0,0 +1,172 @@
+//! Reserve and price history of a bonding curve, for charts
+//!
+//! Every trade on the curve emits a Sync event with the new reserves.
+//! [`CurveIndexer::curve_series`] turns a token's Sync events into one
+//! [`CurvePoint`] per block, optionally downsampled to fixed block buckets:
+//!
+//! ```rust,ignore
+//! let indexer = CurveIndexer::new(provider);
+//! // One point per 100 blocks: the state at the end of each bucket
+//! let points = indexer.curve_series(token, from_block, to_block, Some(100)).await?;
+//! for p in points {
+//!     println!("{} {:?} {:.10} MON", p.block, p.timestamp, p.price);
+//! }
+//! ```
+//!
+//! Blocks without a trade have no point; the curve state is unchanged since
+//! the previous one.
+
+use crate::{
+    oracle::curve_price,
+    stream::curve::CurveIndexer,
+    types::{BondingCurveEvent, EventType, SyncEvent},
+};
+use alloy::{
+    eips::BlockNumberOrTag,
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use futures_util::future::try_join_all;
+
+/// Curve state after the last trade of a block
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurvePoint {
+    pub block: u64,
+    /// Unix timestamp of `block` in seconds, if the node returned the block
+    pub timestamp: Option<u64>,
+    /// Spot price of one whole token in MON, from the virtual reserves
+    pub price: f64,
+    pub real_mon: U256,
+    pub real_token: U256,
+    pub virtual_mon: U256,
+    pub virtual_token: U256,
+}
+
+impl From<&SyncEvent> for CurvePoint {
+    fn from(sync: &SyncEvent) -> Self {
+        Self {
+            block: sync.block_number,
+            timestamp: None,
+            price: curve_price(sync.virtual_mon_reserve, sync.virtual_token_reserve),
+            real_mon: sync.real_mon_reserve,
+            real_token: sync.real_token_reserve,
+            virtual_mon: sync.virtual_mon_reserve,
+            virtual_token: sync.virtual_token_reserve,
+        }
+    }
+}
+
+/// One point per block (or per `bucket_blocks` blocks) from chronological events
+///
+/// Only Sync events count; removed ones are skipped. A point holds the state
+/// after the last Sync of its block or bucket, at that Sync's block.
+pub fn curve_points(events: &[BondingCurveEvent], bucket_blocks: Option<u64>) -> Vec<CurvePoint> {
+    let bucket_blocks = bucket_blocks.unwrap_or(1).max(1);
+    let mut points: Vec<CurvePoint> = Vec::new();
+
+    for event in events {
+        let BondingCurveEvent::Sync(sync) = event else {
+            continue;
+        };
+        if sync.removed {
+            continue;
+        }
+        let point = CurvePoint::from(sync);
+        match points.last_mut() {
+            Some(last) if last.block / bucket_blocks == point.block / bucket_blocks => {
+                *last = point
+            }
+            _ => points.push(point),
+        }
+    }
+    points
+}
+
+impl<P: Provider + Clone> CurveIndexer<P> {
+    /// Reserves and price of `token` between `from_block` and `to_block`
+    ///
+    /// With `bucket_blocks`, returns at most one point per bucket of that many
+    /// blocks. Timestamps cost one block lookup per point.
+    pub async fn curve_series(
+        &self,
+        token: Address,
+        from_block: u64,
+        to_block: u64,
+        bucket_blocks: Option<u64>,
+    ) -> Result<Vec<CurvePoint>> {
+        let events = self
+            .fetch_events(
+                from_block,
+                to_block,
+                vec![EventType::Sync],
+                Some(vec![token]),
+            )
+            .await?;
+        let mut points = curve_points(&events, bucket_blocks);
+
+        let provider = self.provider();
+        let timestamps = try_join_all(points.iter().map(|point| async move {
+            let block = provider
+                .get_block_by_number(BlockNumberOrTag::Number(point.block))
+                .await?;
+            Ok::<_, anyhow::Error>(block.map(|block| block.header.timestamp))
+        }))
+        .await?;
+        for (point, timestamp) in points.iter_mut().zip(timestamps) {
+            point.timestamp = timestamp;
+        }
+
+        Ok(points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_curve_points_keep_last_sync_per_bucket() {
+        let BondingCurveEvent::Sync(sync) = fixtures::curve_sync().expected else {
+            panic!("fixture is a sync");
+        };
+        let at = |block: u64, real_mon: u64| {
+            let mut sync = sync.clone();
+            sync.block_number = block;
+            sync.real_mon_reserve = U256::from(real_mon);
+            BondingCurveEvent::Sync(sync)
+        };
+        let mut removed = at(105, 99);
+        if let BondingCurveEvent::Sync(sync) = &mut removed {
+            sync.removed = true;
+        }
+        let events = vec![
+            at(100, 1),
+            fixtures::curve_buy().expected,
+            at(100, 2),
+            at(150, 3),
+            removed,
+            at(250, 4),
+        ];
+
+        let per_block = curve_points(&events, None);
+        let blocks: Vec<(u64, U256)> = per_block.iter().map(|p| (p.block, p.real_mon)).collect();
+        assert_eq!(
+            blocks,
+            vec![
+                (100, U256::from(2)),
+                (150, U256::from(3)),
+                (250, U256::from(4))
+            ]
+        );
+        assert_eq!(
+            per_block[0].price,
+            curve_price(sync.virtual_mon_reserve, sync.virtual_token_reserve)
+        );
+
+        let bucketed = curve_points(&events, Some(100));
+        let blocks: Vec<u64> = bucketed.iter().map(|p| p.block).collect();
+        assert_eq!(blocks, vec![150, 250]);
+    }
+}
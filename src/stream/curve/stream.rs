@@ -1,20 +1,75 @@
-use crate::constants::BONDING_CURVE;
-use crate::types::{BondingCurveEvent, EventType, decode_bonding_curve_event};
+use crate::stream::curve::dynamic::{DynamicFilter, FilterHandle};
+use crate::stream::decode_policy::{DecodeErrorPolicy, DecodeStats, DecodeTracker, Decoded};
+use crate::stream::ordering::order_by_block;
+use crate::stream::predicate::Predicate;
+use crate::types::{
+    BondingCurveEvent, EventId, EventType, creator_filter, curve_filter,
+    decode_bonding_curve_event,
+};
 
 use alloy::{
-    primitives::{Address, B256},
+    primitives::Address,
     providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
-    rpc::types::Filter,
+    rpc::types::{Filter, Log},
 };
 use anyhow::Result;
 use futures_util::{Stream, StreamExt};
-use std::{collections::HashSet, pin::Pin, sync::Arc};
+use std::{
+    collections::{HashSet, VecDeque},
+    pin::Pin,
+    sync::Arc,
+};
+use tokio::sync::watch;
+
+/// Number of blocks fetched per `eth_getLogs` call while catching up
+const CATCH_UP_BATCH_SIZE: u64 = 1000;
+
+/// Events remembered for de-duplication while two subscriptions overlap
+const OVERLAP_DEDUP_CAPACITY: usize = 4096;
+
+type LogStream = Pin<Box<dyn Stream<Item = Log> + Send>>;
+
+/// One wake-up of the dynamic subscription loop
+enum DynamicStep {
+    FilterChanged(bool),
+    Active(Option<Log>),
+    Retiring(Option<Log>),
+}
+
+/// Catch-up status of a [`CurveStream::subscribe_from`] stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatchUpProgress {
+    /// First historical block requested
+    pub from_block: u64,
+    /// Last block whose historical events have been emitted
+    pub current_block: u64,
+    /// Chain head at subscription time; live events start after this block
+    pub target_block: u64,
+    /// True once all historical events have been emitted
+    pub live: bool,
+}
+
+impl CatchUpProgress {
+    /// Fraction of the historical range already emitted (0.0 - 1.0)
+    pub fn fraction(&self) -> f64 {
+        if self.live || self.target_block <= self.from_block {
+            return 1.0;
+        }
+        let done = self.current_block.saturating_sub(self.from_block) as f64;
+        let total = (self.target_block - self.from_block) as f64;
+        (done / total).min(1.0)
+    }
+}
 
 /// Bonding curve event stream with simplified implementation
 pub struct CurveStream {
     provider: Arc<DynProvider>,
     event_types: Option<Vec<EventType>>,
     token_filter: Option<HashSet<Address>>,
+    creator_filter: Option<HashSet<Address>>,
+    predicate: Option<Predicate>,
+    order_window: Option<u64>,
+    decode: DecodeTracker,
 }
 
 impl CurveStream {
@@ -24,78 +79,442 @@ impl CurveStream {
         let provider = ProviderBuilder::new().connect_ws(ws).await?;
         let dyn_provider = Arc::new(DynProvider::new(provider));
 
-        Ok(CurveStream {
-            provider: dyn_provider,
+        Ok(CurveStream::from_provider(dyn_provider))
+    }
+
+    /// Stream over an existing WebSocket provider instead of opening a new connection
+    pub fn from_provider(provider: Arc<DynProvider>) -> CurveStream {
+        CurveStream {
+            provider,
             event_types: None,
             token_filter: None,
-        })
+            creator_filter: None,
+            predicate: None,
+            order_window: None,
+            decode: DecodeTracker::default(),
+        }
     }
 
     /// Subscribe to specific event types (network-level filtering)
     pub fn subscribe_events(mut self, event_types: Vec<EventType>) -> Self {
-        self.event_types = Some(event_types);
+        self.set_event_types(Some(event_types));
         self
     }
 
     /// Filter by specific tokens (client-level filtering)
     pub fn filter_tokens(mut self, tokens: Vec<Address>) -> Self {
-        self.token_filter = Some(tokens.into_iter().collect());
+        self.set_token_filter(Some(tokens));
         self
     }
 
+    /// Only deliver the Create events of tokens launched by `creators` (network-level filtering)
+    ///
+    /// The creator is an indexed topic of `CurveCreate`, so the node drops every
+    /// other launch. Other event types are not delivered while this is set.
+    pub fn filter_creators(mut self, creators: Vec<Address>) -> Self {
+        self.set_creator_filter(Some(creators));
+        self
+    }
+
+    /// Filter by the tokens of a [`TokenList`](crate::token::list::TokenList) (client-level filtering)
+    #[cfg(feature = "token-list")]
+    pub fn filter_token_list(self, list: &crate::token::list::TokenList) -> Self {
+        self.filter_tokens(list.addresses())
+    }
+
+    /// Only yield events matching `predicate` (client-level filtering)
+    ///
+    /// Combines with [`CurveStream::filter_tokens`]; both must pass.
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.set_predicate(Some(predicate));
+        self
+    }
+
+    /// Buffer events and yield them sorted by (block, tx_index, log_index)
+    ///
+    /// Events are held until the stream has advanced `window_blocks` blocks past
+    /// them, trading a little latency for deterministic ordering.
+    pub fn order_events(mut self, window_blocks: u64) -> Self {
+        self.set_order_window(Some(window_blocks));
+        self
+    }
+
+    /// Skip, yield or abort on logs that fail to decode (default: skip)
+    pub fn with_decode_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.set_decode_policy(policy);
+        self
+    }
+
+    /// Decoding counters of every subscription opened from this stream
+    pub fn decode_stats(&self) -> DecodeStats {
+        self.decode.stats.clone()
+    }
+
+    /// Replace the decode error policy of the next subscription
+    pub fn set_decode_policy(&mut self, policy: DecodeErrorPolicy) {
+        self.decode.policy = policy;
+    }
+
+    /// Replace the subscribed event types; None subscribes to all of them
+    ///
+    /// The setters change the configuration in place, so the next
+    /// [`CurveStream::subscribe`] uses the new filter on the same connection.
+    /// Streams already returned keep the filter they were opened with.
+    pub fn set_event_types(&mut self, event_types: Option<Vec<EventType>>) {
+        self.event_types = event_types;
+    }
+
+    /// Replace the token filter; None delivers every token
+    pub fn set_token_filter(&mut self, tokens: Option<Vec<Address>>) {
+        self.token_filter = tokens.map(|tokens| tokens.into_iter().collect());
+    }
+
+    /// Replace the creator filter; None delivers every configured event type again
+    pub fn set_creator_filter(&mut self, creators: Option<Vec<Address>>) {
+        self.creator_filter = creators.map(|creators| creators.into_iter().collect());
+    }
+
+    /// Replace the predicate; None removes it
+    pub fn set_predicate(&mut self, predicate: Option<Predicate>) {
+        self.predicate = predicate;
+    }
+
+    /// Replace the ordering window; None yields events as they arrive
+    pub fn set_order_window(&mut self, window_blocks: Option<u64>) {
+        self.order_window = window_blocks;
+    }
+
+    /// Drop every filter, predicate and ordering window
+    pub fn clear_filters(&mut self) {
+        self.event_types = None;
+        self.token_filter = None;
+        self.creator_filter = None;
+        self.predicate = None;
+        self.order_window = None;
+    }
+
     /// Create subscription and return raw stream - no transformations!
+    ///
+    /// Takes `&self`: call it again after changing the filter with the `set_*`
+    /// methods to open another subscription on the same provider.
+    ///
+    /// Logs retracted by a reorg are delivered again with `removed() == true`
+    /// so consumers can invalidate previously processed events.
     pub async fn subscribe(
         &self,
     ) -> Result<Pin<Box<dyn Stream<Item = Result<BondingCurveEvent>> + Send>>> {
-        let bonding_curve_address: Address = BONDING_CURVE
-            .parse()
-            .expect("Invalid bonding curve address");
-        let event_types = self
-            .event_types
-            .as_ref()
-            .map(|v| v.clone())
-            .unwrap_or_else(|| {
-                vec![
-                    EventType::Create,
-                    EventType::Buy,
-                    EventType::Sell,
-                    EventType::Sync,
-                    EventType::Lock,
-                    EventType::Listed,
-                ]
+        let filter = self.build_filter();
+
+        let sub = self.provider.subscribe_logs(&filter).await?;
+        let token_filter = self.token_filter.clone();
+        let creator_filter = self.creator_filter.clone();
+        let predicate = self.predicate.clone();
+
+        let stream = self
+            .decode
+            .clone()
+            .apply(sub.into_stream().map(decode_bonding_curve_event))
+            .filter(move |result| {
+                // Apply client-side token and predicate filtering if specified
+                let keep = match result {
+                    Ok(event) => event_allowed(&token_filter, &creator_filter, &predicate, event),
+                    Err(_) => true,
+                };
+                futures_util::future::ready(keep)
             });
 
-        let signatures: Vec<B256> = event_types.iter().map(|et| et.signature()).collect();
+        if let Some(window) = self.order_window {
+            return Ok(Box::pin(order_by_block(stream, window, |event| {
+                (
+                    event.block_number(),
+                    event.transaction_index(),
+                    event.log_index(),
+                )
+            })));
+        }
 
-        // Filter for bonding curve address only
-        let filter = Filter::new()
-            .address(bonding_curve_address)
-            .event_signature(signatures);
+        Ok(Box::pin(stream))
+    }
 
+    /// Emit historical events starting at `from_block`, then continue with live events
+    ///
+    /// The live subscription is opened first and the chain head is recorded, so no
+    /// event is lost between the two phases: historical logs are fetched in batches
+    /// up to the head, and live logs at or below the head are dropped as duplicates.
+    /// While catching up, a background task drains the subscription into an
+    /// unbounded buffer so a long catch-up can't overflow the provider's channel;
+    /// the buffer grows with the live log rate times the catch-up time.
+    /// The returned receiver reports catch-up progress for display purposes.
+    pub async fn subscribe_from(
+        &self,
+        from_block: u64,
+    ) -> Result<(
+        Pin<Box<dyn Stream<Item = Result<BondingCurveEvent>> + Send>>,
+        watch::Receiver<CatchUpProgress>,
+    )> {
+        let filter = self.build_filter();
         let sub = self.provider.subscribe_logs(&filter).await?;
+        let target_block = self.provider.get_block_number().await?;
+
+        // Ends once the subscription closes or the returned stream is dropped
+        let (live_tx, mut live_rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut live = sub.into_stream();
+            loop {
+                tokio::select! {
+                    _ = live_tx.closed() => break,
+                    log = live.next() => match log {
+                        Some(log) => {
+                            let _ = live_tx.send(log);
+                        }
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        let (progress_tx, progress_rx) = watch::channel(CatchUpProgress {
+            from_block,
+            current_block: from_block,
+            target_block,
+            live: from_block > target_block,
+        });
+
+        let provider = self.provider.clone();
         let token_filter = self.token_filter.clone();
+        let creator_filter = self.creator_filter.clone();
+        let predicate = self.predicate.clone();
+        let decode = self.decode.clone();
+
+        let stream = async_stream::stream! {
+            let mut current = from_block;
+
+            while current <= target_block {
+                let to_block = std::cmp::min(current + CATCH_UP_BATCH_SIZE - 1, target_block);
+                let batch_filter = filter.clone().from_block(current).to_block(to_block);
 
-        let stream = sub
-            .into_stream()
-            .map(move |log| {
-                decode_bonding_curve_event(log).and_then(|event| {
-                    // Apply client-side token filtering if specified
-                    if let Some(ref allowed_tokens) = token_filter {
-                        if !allowed_tokens.contains(&event.token()) {
-                            return Err(anyhow::anyhow!("Token not in filter"));
+                match provider.get_logs(&batch_filter).await {
+                    Ok(logs) => {
+                        for log in logs {
+                            match decode.check(decode_bonding_curve_event(log)) {
+                                Decoded::Event(event) => {
+                                    if event_allowed(&token_filter, &creator_filter, &predicate, &event) {
+                                        yield Ok(event);
+                                    }
+                                }
+                                Decoded::Skip => {}
+                                Decoded::Yield(e) => yield Err(e),
+                                Decoded::Abort(e) => {
+                                    yield Err(e);
+                                    return;
+                                }
+                            }
                         }
                     }
-                    Ok(event)
-                })
-            })
-            .filter_map(|result| async move {
-                match result {
-                    Ok(event) => Some(Ok(event)),
-                    Err(_) => None, // Skip filtered events
+                    Err(e) => {
+                        yield Err(e.into());
+                        return;
+                    }
+                }
+
+                progress_tx.send_replace(CatchUpProgress {
+                    from_block,
+                    current_block: to_block,
+                    target_block,
+                    live: to_block >= target_block,
+                });
+                current = to_block + 1;
+            }
+
+            progress_tx.send_modify(|progress| progress.live = true);
+
+            while let Some(log) = live_rx.recv().await {
+                match decode.check(decode_bonding_curve_event(log)) {
+                    Decoded::Event(event) => {
+                        if event.block_number() > target_block
+                            && event_allowed(&token_filter, &creator_filter, &predicate, &event)
+                        {
+                            yield Ok(event);
+                        }
+                    }
+                    Decoded::Skip => {}
+                    Decoded::Yield(e) => yield Err(e),
+                    Decoded::Abort(e) => {
+                        yield Err(e);
+                        return;
+                    }
                 }
+            }
+        };
+
+        if let Some(window) = self.order_window {
+            let ordered = order_by_block(stream, window, |event| {
+                (
+                    event.block_number(),
+                    event.transaction_index(),
+                    event.log_index(),
+                )
             });
+            return Ok((Box::pin(ordered), progress_rx));
+        }
 
-        Ok(Box::pin(stream))
+        Ok((Box::pin(stream), progress_rx))
+    }
+
+    /// Subscribe with a token and event type filter that can change at runtime
+    ///
+    /// Starts from the configured [`CurveStream::filter_tokens`] and
+    /// [`CurveStream::subscribe_events`] and returns a [`FilterHandle`] to change
+    /// them while the stream runs. When an added event type needs a wider network
+    /// filter, a new subscription is opened before the old one is dropped; the two
+    /// overlap until the new one delivers a block past the switch, with duplicates
+    /// removed, so the consumer sees neither a gap nor a repeat. Narrowing the
+    /// filter never resubscribes.
+    pub async fn subscribe_dynamic(
+        &self,
+    ) -> Result<(
+        Pin<Box<dyn Stream<Item = Result<BondingCurveEvent>> + Send>>,
+        FilterHandle,
+    )> {
+        let initial = DynamicFilter {
+            tokens: self.token_filter.clone(),
+            event_types: self.configured_event_types().into_iter().collect(),
+        };
+        let mut subscribed_types = initial.event_types.clone();
+        let sub = self
+            .provider
+            .subscribe_logs(&type_set_filter(&subscribed_types))
+            .await?;
+        let (handle, mut filter_rx) = FilterHandle::new(initial);
+
+        let provider = self.provider.clone();
+        let creator_filter = self.creator_filter.clone();
+        let predicate = self.predicate.clone();
+        let decode = self.decode.clone();
+
+        let stream = async_stream::stream! {
+            let mut active: LogStream = Box::pin(sub.into_stream());
+            let mut retiring: Option<LogStream> = None;
+            let mut switch_block = 0u64;
+            let mut handle_alive = true;
+            let mut seen: HashSet<(EventId, bool)> = HashSet::new();
+            let mut seen_order: VecDeque<(EventId, bool)> = VecDeque::new();
+
+            loop {
+                let step = tokio::select! {
+                    changed = filter_rx.changed(), if handle_alive => {
+                        DynamicStep::FilterChanged(changed.is_ok())
+                    }
+                    log = active.next() => DynamicStep::Active(log),
+                    log = next_log(&mut retiring), if retiring.is_some() => {
+                        DynamicStep::Retiring(log)
+                    }
+                };
+
+                let (log, from_active) = match step {
+                    DynamicStep::FilterChanged(false) => {
+                        // Every handle is gone; keep streaming with the last filter
+                        handle_alive = false;
+                        continue;
+                    }
+                    DynamicStep::FilterChanged(true) => {
+                        let wanted = filter_rx.borrow_and_update().event_types.clone();
+                        if wanted.is_subset(&subscribed_types) {
+                            continue;
+                        }
+                        let widened: HashSet<EventType> =
+                            subscribed_types.union(&wanted).cloned().collect();
+                        match provider.subscribe_logs(&type_set_filter(&widened)).await {
+                            Ok(sub) => {
+                                switch_block = provider.get_block_number().await.unwrap_or(0);
+                                retiring = Some(std::mem::replace(
+                                    &mut active,
+                                    Box::pin(sub.into_stream()),
+                                ));
+                                subscribed_types = widened;
+                            }
+                            Err(e) => yield Err(e.into()),
+                        }
+                        continue;
+                    }
+                    DynamicStep::Active(None) => break,
+                    DynamicStep::Active(Some(log)) => (log, true),
+                    DynamicStep::Retiring(None) => {
+                        retiring = None;
+                        continue;
+                    }
+                    DynamicStep::Retiring(Some(log)) => (log, false),
+                };
+
+                let event = match decode.check(decode_bonding_curve_event(log)) {
+                    Decoded::Event(event) => event,
+                    Decoded::Skip => continue,
+                    Decoded::Yield(e) => {
+                        yield Err(e);
+                        continue;
+                    }
+                    Decoded::Abort(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                };
+
+                if retiring.is_some() {
+                    if from_active && event.block_number() > switch_block {
+                        // The new subscription is past the switch; the old one is redundant
+                        retiring = None;
+                        seen.clear();
+                        seen_order.clear();
+                    } else {
+                        let key = (event.event_id(), event.removed());
+                        if !seen.insert(key) {
+                            continue;
+                        }
+                        seen_order.push_back(key);
+                        if seen_order.len() > OVERLAP_DEDUP_CAPACITY {
+                            if let Some(oldest) = seen_order.pop_front() {
+                                seen.remove(&oldest);
+                            }
+                        }
+                    }
+                }
+
+                let allowed = filter_rx.borrow().allows(&event)
+                    && creator_allowed(&creator_filter, &event)
+                    && predicate.as_ref().is_none_or(|p| p.matches(&event));
+                if allowed {
+                    yield Ok(event);
+                }
+            }
+        };
+
+        if let Some(window) = self.order_window {
+            let ordered = order_by_block(stream, window, |event| {
+                (
+                    event.block_number(),
+                    event.transaction_index(),
+                    event.log_index(),
+                )
+            });
+            return Ok((Box::pin(ordered), handle));
+        }
+
+        Ok((Box::pin(stream), handle))
+    }
+
+    /// Event types to subscribe to (all of them unless configured)
+    fn configured_event_types(&self) -> Vec<EventType> {
+        self.event_types.clone().unwrap_or_else(EventType::all)
+    }
+
+    /// Build the log filter for the configured event types or creators
+    fn build_filter(&self) -> Filter {
+        match &self.creator_filter {
+            Some(creators) => {
+                creator_filter(&creators.iter().copied().collect::<Vec<_>>(), None)
+            }
+            None => curve_filter(&self.configured_event_types(), None, None),
+        }
     }
 
     /// Get token filter for manual filtering by caller
@@ -103,3 +522,84 @@ impl CurveStream {
         self.token_filter.as_ref()
     }
 }
+
+fn event_allowed(
+    token_filter: &Option<HashSet<Address>>,
+    creator_filter: &Option<HashSet<Address>>,
+    predicate: &Option<Predicate>,
+    event: &BondingCurveEvent,
+) -> bool {
+    let token_ok = match token_filter {
+        Some(tokens) => tokens.contains(&event.token()),
+        None => true,
+    };
+    let predicate_ok = match predicate {
+        Some(predicate) => predicate.matches(event),
+        None => true,
+    };
+    token_ok && creator_allowed(creator_filter, event) && predicate_ok
+}
+
+/// With a creator filter, only Create events of those creators pass
+fn creator_allowed(creator_filter: &Option<HashSet<Address>>, event: &BondingCurveEvent) -> bool {
+    match (creator_filter, event) {
+        (None, _) => true,
+        (Some(creators), BondingCurveEvent::Create(create)) => creators.contains(&create.creator),
+        (Some(_), _) => false,
+    }
+}
+
+/// Bonding curve log filter for a set of event types
+fn type_set_filter(event_types: &HashSet<EventType>) -> Filter {
+    let event_types: Vec<EventType> = event_types.iter().cloned().collect();
+    curve_filter(&event_types, None, None)
+}
+
+async fn next_log(stream: &mut Option<LogStream>) -> Option<Log> {
+    match stream {
+        Some(stream) => stream.next().await,
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setters_change_next_subscription() {
+        let provider = ProviderBuilder::new().connect_http("http://127.0.0.1:1".parse().unwrap());
+        let mut stream = CurveStream::from_provider(Arc::new(DynProvider::new(provider)))
+            .subscribe_events(vec![EventType::Buy])
+            .filter_tokens(vec![Address::repeat_byte(1)]);
+        assert_eq!(stream.configured_event_types(), vec![EventType::Buy]);
+
+        stream.set_event_types(Some(vec![EventType::Sell, EventType::Create]));
+        stream.set_token_filter(Some(vec![Address::repeat_byte(2)]));
+        assert_eq!(
+            stream.build_filter(),
+            curve_filter(&[EventType::Sell, EventType::Create], None, None)
+        );
+        assert!(
+            stream
+                .get_token_filter()
+                .unwrap()
+                .contains(&Address::repeat_byte(2))
+        );
+
+        let creator = crate::fixtures::FIXTURE_SENDER;
+        stream.set_creator_filter(Some(vec![creator]));
+        assert_eq!(stream.build_filter(), creator_filter(&[creator], None));
+        let create = crate::fixtures::curve_create().expected;
+        assert!(creator_allowed(&stream.creator_filter, &create));
+        assert!(!creator_allowed(
+            &stream.creator_filter,
+            &crate::fixtures::curve_buy().expected
+        ));
+
+        stream.clear_filters();
+        assert!(stream.build_filter() != creator_filter(&[creator], None));
+        assert_eq!(stream.configured_event_types().len(), 6);
+        assert!(stream.get_token_filter().is_none());
+    }
+}
@@ -0,0 +1,196 @@
+//! What streams do with logs they cannot decode
+//!
+//! A log matching a stream's filter normally decodes; one that doesn't points
+//! at a contract upgrade, a misbehaving node or a filter mismatch. By default
+//! such logs are skipped, which loses events silently. [`DecodeErrorPolicy`]
+//! makes the loss visible, and [`DecodeStats`] counts it either way:
+//!
+//! ```rust,ignore
+//! let stream = CurveStream::new(ws_url)
+//!     .await?
+//!     .with_decode_policy(DecodeErrorPolicy::Yield);
+//! let stats = stream.decode_stats();
+//! let mut events = stream.subscribe().await?;
+//!
+//! while let Some(item) = events.next().await {
+//!     match item {
+//!         Ok(event) => handle(event),
+//!         Err(e) => eprintln!("gap in the feed: {e}"),
+//!     }
+//! }
+//! println!("{} decoded, {} failed", stats.decoded(), stats.failed());
+//! ```
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+/// Handling of logs that fail to decode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DecodeErrorPolicy {
+    /// Drop the log and keep streaming (counted in [`DecodeStats::failed`])
+    #[default]
+    Skip,
+    /// Yield the decoding error as an `Err` item and keep streaming
+    Yield,
+    /// Yield the decoding error, then end the stream
+    Abort,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    decoded: AtomicU64,
+    failed: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Decoding counters of a stream, shared by all its clones
+///
+/// Every subscription opened from the same stream adds to the same counters.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeStats {
+    counters: Arc<Counters>,
+}
+
+impl DecodeStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logs decoded successfully, including ones later filtered out
+    pub fn decoded(&self) -> u64 {
+        self.counters.decoded.load(Ordering::Relaxed)
+    }
+
+    /// Logs that failed to decode, whatever the policy
+    pub fn failed(&self) -> u64 {
+        self.counters.failed.load(Ordering::Relaxed)
+    }
+
+    /// Message of the most recent decoding error
+    pub fn last_error(&self) -> Option<String> {
+        self.counters
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Reset every counter to zero
+    pub fn reset(&self) {
+        self.counters.decoded.store(0, Ordering::Relaxed);
+        self.counters.failed.store(0, Ordering::Relaxed);
+        *self
+            .counters
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+/// Result of passing one decode result through a policy
+pub(crate) enum Decoded<T> {
+    Event(T),
+    Skip,
+    Yield(anyhow::Error),
+    Abort(anyhow::Error),
+}
+
+/// A policy together with the counters it reports to
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DecodeTracker {
+    pub(crate) policy: DecodeErrorPolicy,
+    pub(crate) stats: DecodeStats,
+}
+
+impl DecodeTracker {
+    pub(crate) fn check<T>(&self, result: Result<T>) -> Decoded<T> {
+        let counters = &self.stats.counters;
+        match result {
+            Ok(event) => {
+                counters.decoded.fetch_add(1, Ordering::Relaxed);
+                Decoded::Event(event)
+            }
+            Err(e) => {
+                counters.failed.fetch_add(1, Ordering::Relaxed);
+                *counters
+                    .last_error
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = Some(e.to_string());
+                match self.policy {
+                    DecodeErrorPolicy::Skip => Decoded::Skip,
+                    DecodeErrorPolicy::Yield => Decoded::Yield(e.context("Failed to decode log")),
+                    DecodeErrorPolicy::Abort => Decoded::Abort(e.context(
+                        "Failed to decode log; stream stopped by DecodeErrorPolicy::Abort",
+                    )),
+                }
+            }
+        }
+    }
+
+    /// Apply the policy to a stream of decode results
+    pub(crate) fn apply<S, T>(self, results: S) -> impl Stream<Item = Result<T>> + Send + 'static
+    where
+        S: Stream<Item = Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        async_stream::stream! {
+            futures_util::pin_mut!(results);
+            while let Some(result) = results.next().await {
+                match self.check(result) {
+                    Decoded::Event(event) => yield Ok(event),
+                    Decoded::Skip => {}
+                    Decoded::Yield(e) => yield Err(e),
+                    Decoded::Abort(e) => {
+                        yield Err(e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+
+    async fn run(policy: DecodeErrorPolicy) -> (Vec<Result<u32>>, DecodeStats) {
+        let stats = DecodeStats::new();
+        let results = stream::iter(vec![Ok(1), Err(anyhow::anyhow!("bad log")), Ok(2)]);
+        let tracker = DecodeTracker {
+            policy,
+            stats: stats.clone(),
+        };
+        let items = tracker.apply(results).collect().await;
+        (items, stats)
+    }
+
+    #[tokio::test]
+    async fn test_policies_skip_yield_or_abort() {
+        let (items, stats) = run(DecodeErrorPolicy::Skip).await;
+        assert_eq!(items.iter().filter(|i| i.is_ok()).count(), 2);
+        assert_eq!(items.len(), 2);
+        assert_eq!((stats.decoded(), stats.failed()), (2, 1));
+        assert_eq!(stats.last_error().as_deref(), Some("bad log"));
+
+        let (items, _) = run(DecodeErrorPolicy::Yield).await;
+        assert_eq!(items.len(), 3);
+        assert!(items[1].is_err());
+
+        let (items, stats) = run(DecodeErrorPolicy::Abort).await;
+        assert_eq!(items.len(), 2);
+        assert!(items[1].is_err());
+        assert_eq!((stats.decoded(), stats.failed()), (1, 1));
+
+        stats.reset();
+        assert_eq!(
+            (stats.decoded(), stats.failed(), stats.last_error()),
+            (0, 0, None)
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Idempotent ingestion across reconnects and backfills
+//!
+//! Reconnecting a subscription, or backfilling with an indexer while a stream is
+//! running, delivers some logs twice. A [`Deduplicator`] remembers the
+//! `(tx_hash, log_index)` of recent events and drops repeats:
+//!
+//! ```rust,ignore
+//! let dedup = Deduplicator::new(64); // remember the last 64 blocks
+//! loop {
+//!     let stream = dedup.wrap(curve_stream.subscribe().await?);
+//!     consume(stream).await; // returns when the connection drops
+//!     println!("skipped {} duplicates so far", dedup.stats().skipped);
+//! }
+//! ```
+//!
+//! Memory is bounded by the block window (and optionally an entry cap): keys of
+//! blocks that fall `window_blocks` behind the newest block seen are forgotten.
+//! Events older than that can't be checked and are passed through and counted as
+//! [`DedupStats::unchecked`]. Removed (reorged) logs are keyed separately from
+//! their originals, so removals always reach the consumer.
+
+use crate::types::{BondingCurveEvent, DexEvent, SwapEvent};
+use alloy::primitives::B256;
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::{
+    collections::{BTreeMap, HashSet},
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+/// Identity of a delivered log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DedupKey {
+    pub transaction_hash: B256,
+    pub log_index: u64,
+    pub removed: bool,
+}
+
+/// Events a [`Deduplicator`] can key
+pub trait DedupEvent {
+    fn dedup_key(&self) -> DedupKey;
+    fn dedup_block(&self) -> u64;
+}
+
+macro_rules! impl_dedup_event {
+    ($($event:ty),* $(,)?) => {
+        $(
+            impl DedupEvent for $event {
+                fn dedup_key(&self) -> DedupKey {
+                    DedupKey {
+                        transaction_hash: self.transaction_hash(),
+                        log_index: self.log_index(),
+                        removed: self.removed(),
+                    }
+                }
+
+                fn dedup_block(&self) -> u64 {
+                    self.block_number()
+                }
+            }
+        )*
+    };
+}
+
+impl_dedup_event!(BondingCurveEvent, DexEvent);
+
+impl DedupEvent for SwapEvent {
+    fn dedup_key(&self) -> DedupKey {
+        DedupKey {
+            transaction_hash: self.transaction_hash,
+            log_index: self.log_index,
+            removed: self.removed,
+        }
+    }
+
+    fn dedup_block(&self) -> u64 {
+        self.block_number
+    }
+}
+
+/// Counters of a [`Deduplicator`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Events passed through as new
+    pub passed: u64,
+    /// Duplicates dropped
+    pub skipped: u64,
+    /// Events older than the window, passed through without a check
+    pub unchecked: u64,
+    /// Keys currently remembered
+    pub tracked: usize,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    keys: HashSet<DedupKey>,
+    by_block: BTreeMap<u64, Vec<DedupKey>>,
+    highest_block: u64,
+    stats: DedupStats,
+}
+
+impl State {
+    fn forget_oldest_block(&mut self) {
+        if let Some((_, keys)) = self.by_block.pop_first() {
+            for key in keys {
+                self.keys.remove(&key);
+            }
+        }
+    }
+}
+
+/// Drops events already delivered within a recent block window
+///
+/// Cheap to clone; clones share the remembered keys and stats, so the same
+/// deduplicator can wrap every reconnected stream and backfill.
+#[derive(Debug, Clone)]
+pub struct Deduplicator {
+    window_blocks: u64,
+    max_entries: Option<usize>,
+    state: Arc<Mutex<State>>,
+}
+
+impl Deduplicator {
+    /// Remember events of the last `window_blocks` blocks
+    pub fn new(window_blocks: u64) -> Self {
+        Self {
+            window_blocks,
+            max_entries: None,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Also forget the oldest blocks whenever more than `max_entries` keys are remembered
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Record `event`; false if it was already seen and should be dropped
+    pub fn check<T: DedupEvent>(&self, event: &T) -> bool {
+        let block = event.dedup_block();
+        let key = event.dedup_key();
+        let mut state = self.state.lock().unwrap();
+
+        if block.saturating_add(self.window_blocks) < state.highest_block {
+            state.stats.unchecked += 1;
+            return true;
+        }
+        if !state.keys.insert(key) {
+            state.stats.skipped += 1;
+            return false;
+        }
+        state.by_block.entry(block).or_default().push(key);
+        state.stats.passed += 1;
+
+        if block > state.highest_block {
+            state.highest_block = block;
+            let floor = block.saturating_sub(self.window_blocks);
+            while state
+                .by_block
+                .first_key_value()
+                .is_some_and(|(oldest, _)| *oldest < floor)
+            {
+                state.forget_oldest_block();
+            }
+        }
+        if let Some(max_entries) = self.max_entries {
+            // Never evict the block just recorded
+            while state.keys.len() > max_entries && state.by_block.len() > 1 {
+                state.forget_oldest_block();
+            }
+        }
+
+        true
+    }
+
+    pub fn stats(&self) -> DedupStats {
+        let state = self.state.lock().unwrap();
+        DedupStats {
+            tracked: state.keys.len(),
+            ..state.stats
+        }
+    }
+
+    /// Forget all keys and reset the stats
+    pub fn clear(&self) {
+        *self.state.lock().unwrap() = State::default();
+    }
+
+    /// Drop duplicates from `stream`; errors pass through untouched
+    pub fn wrap<T, S>(&self, stream: S) -> Pin<Box<dyn Stream<Item = Result<T>> + Send>>
+    where
+        T: DedupEvent + Send + 'static,
+        S: Stream<Item = Result<T>> + Send + 'static,
+    {
+        let dedup = self.clone();
+        Box::pin(stream.filter(move |item| {
+            let keep = match item {
+                Ok(event) => dedup.check(event),
+                Err(_) => true,
+            };
+            std::future::ready(keep)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::swap_at;
+
+    #[tokio::test]
+    async fn test_deduplicator_drops_repeats_within_window() {
+        let dedup = Deduplicator::new(2);
+        let first = vec![swap_at(10, 0), swap_at(10, 1), swap_at(11, 0)];
+        // Reconnect replays block 10-11 and continues
+        let second = vec![
+            swap_at(10, 1),
+            swap_at(11, 0),
+            swap_at(12, 0),
+            swap_at(15, 0),
+        ];
+
+        for batch in [first, second] {
+            let stream = futures_util::stream::iter(batch.into_iter().map(Ok));
+            let _: Vec<_> = dedup.wrap(stream).collect().await;
+        }
+        assert_eq!(
+            dedup.stats(),
+            DedupStats {
+                passed: 5,
+                skipped: 2,
+                unchecked: 0,
+                tracked: 1,
+            }
+        );
+
+        // Block 12 is now behind the window
+        assert!(dedup.check(&swap_at(12, 0)));
+        assert_eq!(dedup.stats().unchecked, 1);
+
+        let mut removed = swap_at(15, 0);
+        removed.removed = true;
+        assert!(dedup.check(&removed));
+        assert!(!dedup.check(&removed));
+    }
+}
@@ -1,14 +1,21 @@
 //! Uniswap V3 Swap event indexing support
 //!
-//! This module provides historical indexing for Uniswap V3 Swap events.
+//! This module provides historical indexing for Uniswap V3 Swap events, and for
+//! Mint/Burn/Collect liquidity events through [`UniswapSwapIndexer::with_event_types`].
 //! All types are defined in the types::uniswap module.
 
-use crate::types::{SwapEvent, UniswapV3Pool, decode_swap_event};
+use crate::stream::{
+    predicate::Predicate,
+    progress::{IndexProgressTracker, fetch_in_batches},
+};
+use crate::types::{
+    DexEvent, DexEventType, EventId, SwapEvent, decode_dex_event, decode_swap_event, dex_filter,
+    swap_filter,
+};
 use alloy::{
     primitives::Address,
     providers::{DynProvider, Provider, ProviderBuilder},
-    rpc::types::{BlockNumberOrTag, Filter},
-    sol_types::SolEvent,
+    rpc::types::BlockNumberOrTag,
 };
 use anyhow::Result;
 use std::sync::Arc;
@@ -18,6 +25,10 @@ use std::sync::Arc;
 pub struct UniswapSwapIndexer {
     provider: Arc<DynProvider>,
     pool_addresses: Vec<Address>,
+    predicate: Option<Predicate>,
+    event_types: Vec<DexEventType>,
+    progress: Option<IndexProgressTracker>,
+    batch_retries: u32,
 }
 
 impl UniswapSwapIndexer {
@@ -29,9 +40,37 @@ impl UniswapSwapIndexer {
         Ok(Self {
             provider: dyn_provider,
             pool_addresses,
+            predicate: None,
+            event_types: vec![DexEventType::Swap],
+            progress: None,
+            batch_retries: 0,
         })
     }
 
+    /// Create an indexer on a shared [`EndpointPool`](crate::rpc::EndpointPool)
+    pub fn new_with_pool(pool: &crate::rpc::EndpointPool, pool_addresses: Vec<Address>) -> Self {
+        Self {
+            provider: Arc::new(pool.provider()),
+            pool_addresses,
+            predicate: None,
+            event_types: vec![DexEventType::Swap],
+            progress: None,
+            batch_retries: 0,
+        }
+    }
+
+    /// Reuse an existing HTTP provider
+    pub fn from_provider(provider: Arc<DynProvider>, pool_addresses: Vec<Address>) -> Self {
+        Self {
+            provider,
+            pool_addresses,
+            predicate: None,
+            event_types: vec![DexEventType::Swap],
+            progress: None,
+            batch_retries: 0,
+        }
+    }
+
     /// Create indexer by discovering pools for token addresses
     /// Uses Nad.fun standard 10_000 fee tier (1%)
     pub async fn discover_pools_for_tokens(
@@ -56,6 +95,10 @@ impl UniswapSwapIndexer {
         Ok(Self {
             provider: dyn_provider,
             pool_addresses,
+            predicate: None,
+            event_types: vec![DexEventType::Swap],
+            progress: None,
+            batch_retries: 0,
         })
     }
 
@@ -64,60 +107,129 @@ impl UniswapSwapIndexer {
         Self::discover_pools_for_tokens(rpc_url, vec![token_address]).await
     }
 
+    /// Only return events matching `predicate`
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Report the progress of the `fetch_all_*` methods to `progress`
+    pub fn with_progress(mut self, progress: IndexProgressTracker) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Retry a failed batch of the `fetch_all_*` methods up to `retries` times (default 0)
+    pub fn with_batch_retries(mut self, retries: u32) -> Self {
+        self.batch_retries = retries;
+        self
+    }
+
     /// Fetch swap events for a specific block range
     /// Returns events sorted chronologically
     pub async fn fetch_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SwapEvent>> {
-        let swap_signature = UniswapV3Pool::Swap::SIGNATURE_HASH;
+        let filter = swap_filter(&self.pool_addresses, Some(from_block..=to_block));
+
+        let logs = self.provider.get_logs(&filter).await?;
+
+        let mut events: Vec<SwapEvent> = Vec::with_capacity(logs.len());
+        events.extend(logs.into_iter().filter_map(|log| decode_swap_event(log).ok()));
+        if let Some(predicate) = &self.predicate {
+            events.retain(|event| predicate.matches(event));
+        }
+
+        // Sort events chronologically (keys are unique per log)
+        events.sort_unstable_by_key(|e| e.event_id());
+
+        Ok(events)
+    }
+
+    /// Pool events returned by [`UniswapSwapIndexer::fetch_dex_events`] (default: Swap only)
+    pub fn with_event_types(mut self, event_types: Vec<DexEventType>) -> Self {
+        self.event_types = event_types;
+        self
+    }
 
-        let filter = Filter::new()
+    pub fn event_types(&self) -> &[DexEventType] {
+        &self.event_types
+    }
+
+    /// Fetch the configured pool event types for a block range, sorted chronologically
+    pub async fn fetch_dex_events(&self, from_block: u64, to_block: u64) -> Result<Vec<DexEvent>> {
+        let filter = dex_filter(&self.pool_addresses, &self.event_types)
             .from_block(BlockNumberOrTag::Number(from_block))
-            .to_block(BlockNumberOrTag::Number(to_block))
-            .address(self.pool_addresses.clone())
-            .event_signature(swap_signature);
+            .to_block(BlockNumberOrTag::Number(to_block));
 
         let logs = self.provider.get_logs(&filter).await?;
 
-        let mut events: Vec<SwapEvent> = logs
+        let mut events: Vec<DexEvent> = logs
             .into_iter()
-            .filter_map(|log| decode_swap_event(log).ok())
+            .filter_map(|log| decode_dex_event(log).ok())
             .collect();
+        if let Some(predicate) = &self.predicate {
+            events.retain(|event| predicate.matches(event));
+        }
+        events.sort_unstable_by_key(|e| e.event_id());
 
-        // Sort events chronologically
-        events.sort_by(|a, b| {
-            a.block_number
-                .cmp(&b.block_number)
-                .then_with(|| a.transaction_index.cmp(&b.transaction_index))
-                .then_with(|| a.log_index.cmp(&b.log_index))
-        });
+        Ok(events)
+    }
 
+    /// Fetch the configured pool event types from `start_block` to the current block
+    pub async fn fetch_all_dex_events(
+        &self,
+        start_block: u64,
+        batch_size: u64,
+    ) -> Result<Vec<DexEvent>> {
+        let target_block = self.provider.get_block_number().await?;
+        fetch_in_batches(
+            start_block,
+            target_block,
+            batch_size,
+            self.batch_retries,
+            self.progress.as_ref(),
+            |from_block, to_block| self.fetch_dex_events(from_block, to_block),
+        )
+        .await
+    }
+
+    /// Fetch swap events strictly after `after`, up to `to_block`
+    ///
+    /// Resumes exactly where a previous fetch stopped, even in the middle of a block.
+    pub async fn fetch_events_after(&self, after: EventId, to_block: u64) -> Result<Vec<SwapEvent>> {
+        let mut events = self.fetch_events(after.block, to_block).await?;
+        events.retain(|e| e.event_id() > after);
         Ok(events)
     }
 
     /// Fetch all historical events from start_block to current block
-    /// This will automatically handle batching
+    /// This will automatically handle batching, retries and progress reporting
     pub async fn fetch_all_events(
         &self,
         start_block: u64,
         batch_size: u64,
     ) -> Result<Vec<SwapEvent>> {
-        let mut all_events = Vec::new();
-        let mut current_block = start_block;
         let target_block = self.provider.get_block_number().await?;
 
-        while current_block <= target_block {
-            let to_block = std::cmp::min(current_block + batch_size, target_block);
-            let events = self.fetch_events(current_block, to_block).await?;
-
-            all_events.extend(events);
-
-            if to_block >= target_block {
-                break;
-            }
-
-            current_block = to_block + 1;
-        }
+        fetch_in_batches(
+            start_block,
+            target_block,
+            batch_size,
+            self.batch_retries,
+            self.progress.as_ref(),
+            |from_block, to_block| self.fetch_events(from_block, to_block),
+        )
+        .await
+    }
 
-        Ok(all_events)
+    /// Fetch all swap events strictly after `after` up to the current block
+    pub async fn fetch_all_events_after(
+        &self,
+        after: EventId,
+        batch_size: u64,
+    ) -> Result<Vec<SwapEvent>> {
+        let mut events = self.fetch_all_events(after.block, batch_size).await?;
+        events.retain(|e| e.event_id() > after);
+        Ok(events)
     }
 
     /// Get all pool addresses being monitored
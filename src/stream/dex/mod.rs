@@ -4,8 +4,10 @@
 //! Uniswap V3 swap events across multiple pools.
 
 pub mod indexer;
+pub mod price;
 pub mod stream;
 
 // Re-export main types
 pub use indexer::UniswapSwapIndexer;
+pub use price::{DexPriceStream, PriceTick, sqrt_price_x96_to_price};
 pub use stream::UniswapSwapStream;
\ No newline at end of file
@@ -0,0 +1,276 @@
+//! Price feed for graduated tokens from Uniswap V3 Swap events
+//!
+//! Every Swap carries the pool's post-swap `sqrtPriceX96`, so the token's MON
+//! price can be derived from a single log. Pool tokens and decimals are fetched
+//! once when subscribing, through a [`MetadataCache`] that can be shared with
+//! other components.
+//!
+//! Swap logs that fail to decode go through the stream's [`DecodeErrorPolicy`].
+//! Unlike the event streams, a price feed yields them as `Err` items by default,
+//! since a silently missing tick leaves a stale price.
+
+use crate::metadata::MetadataCache;
+use crate::stream::decode_policy::{DecodeErrorPolicy, DecodeStats, DecodeTracker};
+use crate::stream::ordering::order_by_block;
+use crate::types::{SwapEvent, UniswapV3Pool, decode_swap_event};
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+    rpc::types::Filter,
+    sol_types::SolEvent,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, future};
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+
+/// Normalized price update emitted for every swap
+#[derive(Debug, Clone)]
+pub struct PriceTick {
+    /// The non-WMON token of the pool
+    pub token: Address,
+    pub pool: Address,
+    /// Price of one whole token in MON, adjusted for decimals
+    pub price_in_mon: f64,
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    pub liquidity: u128,
+    pub block: u64,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    pub log_index: u64,
+    /// True if the underlying swap was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+/// Cached metadata needed to price a pool
+#[derive(Debug, Clone, Copy)]
+pub struct PricedPool {
+    pub token: Address,
+    pub token_is_token0: bool,
+    pub token_decimals: u8,
+    pub wmon_decimals: u8,
+}
+
+impl PricedPool {
+    /// Build a price tick from a swap in this pool
+    pub fn tick(&self, swap: &SwapEvent) -> PriceTick {
+        PriceTick {
+            token: self.token,
+            pool: swap.pool_address,
+            price_in_mon: sqrt_price_x96_to_price(
+                swap.sqrt_price_x96,
+                self.token_is_token0,
+                self.token_decimals,
+                self.wmon_decimals,
+            ),
+            sqrt_price_x96: swap.sqrt_price_x96,
+            tick: swap.tick,
+            liquidity: swap.liquidity,
+            block: swap.block_number,
+            transaction_hash: swap.transaction_hash,
+            transaction_index: swap.transaction_index,
+            log_index: swap.log_index,
+            removed: swap.removed,
+        }
+    }
+}
+
+/// Convert a pool's `sqrtPriceX96` into the price of the token in MON
+///
+/// `sqrtPriceX96^2 / 2^192` is the raw token1/token0 ratio; the result is
+/// inverted when the token is token1 and scaled by the decimal difference.
+pub fn sqrt_price_x96_to_price(
+    sqrt_price_x96: U256,
+    token_is_token0: bool,
+    token_decimals: u8,
+    wmon_decimals: u8,
+) -> f64 {
+    let sqrt = f64::from(sqrt_price_x96) / 2f64.powi(96);
+    let raw = sqrt * sqrt;
+    if raw == 0.0 {
+        return 0.0;
+    }
+
+    let (decimals0, decimals1) = if token_is_token0 {
+        (token_decimals, wmon_decimals)
+    } else {
+        (wmon_decimals, token_decimals)
+    };
+    // Whole units of token1 per whole unit of token0
+    let price0_in_1 = raw * 10f64.powi(decimals0 as i32 - decimals1 as i32);
+
+    if token_is_token0 {
+        price0_in_1
+    } else {
+        1.0 / price0_in_1
+    }
+}
+
+/// Swap-driven price feed for a set of DEX pools
+pub struct DexPriceStream {
+    provider: Arc<DynProvider>,
+    pool_addresses: Vec<Address>,
+    order_window: Option<u64>,
+    metadata: MetadataCache,
+    decode: DecodeTracker,
+}
+
+impl DexPriceStream {
+    /// Create a WebSocket-based price stream for the given pools
+    pub async fn new(rpc_url: String, pool_addresses: Vec<Address>) -> Result<Self> {
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+
+        Ok(Self {
+            provider: Arc::new(DynProvider::new(provider)),
+            pool_addresses,
+            order_window: None,
+            metadata: MetadataCache::new(),
+            decode: DecodeTracker {
+                policy: DecodeErrorPolicy::Yield,
+                stats: DecodeStats::new(),
+            },
+        })
+    }
+
+    /// Create a price stream by discovering the Nad.fun pools for tokens
+    pub async fn for_tokens(rpc_url: String, token_addresses: Vec<Address>) -> Result<Self> {
+        use crate::contracts::get_pool_addresses_for_tokens;
+
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+        let provider = Arc::new(DynProvider::new(provider));
+        let pool_addresses =
+            get_pool_addresses_for_tokens(provider.clone(), token_addresses).await?;
+
+        Ok(Self {
+            provider,
+            pool_addresses,
+            order_window: None,
+            metadata: MetadataCache::new(),
+            decode: DecodeTracker {
+                policy: DecodeErrorPolicy::Yield,
+                stats: DecodeStats::new(),
+            },
+        })
+    }
+
+    /// Buffer ticks and yield them sorted by (block, tx_index, log_index)
+    pub fn order_events(mut self, window_blocks: u64) -> Self {
+        self.order_window = Some(window_blocks);
+        self
+    }
+
+    /// Skip, yield or abort on swap logs that fail to decode (default: yield)
+    pub fn with_decode_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.decode.policy = policy;
+        self
+    }
+
+    /// Decoding counters shared by every subscription of this stream
+    pub fn decode_stats(&self) -> DecodeStats {
+        self.decode.stats.clone()
+    }
+
+    /// Share pool and decimals lookups with other components
+    pub fn with_metadata_cache(mut self, metadata: MetadataCache) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Fetch token order and decimals for every monitored pool
+    pub async fn load_pools(&self) -> Result<HashMap<Address, PricedPool>> {
+        let provider = &*self.provider;
+        let wmon: Address = crate::constants::WMON.parse()?;
+        let wmon_decimals = self.metadata.decimals(provider, wmon).await?;
+        self.metadata
+            .prefetch_pools(provider, &self.pool_addresses)
+            .await?;
+        let mut pools = HashMap::new();
+
+        for &pool_address in &self.pool_addresses {
+            let tokens = self.metadata.pool_tokens(provider, pool_address).await?;
+            let token_is_token0 = tokens.token1 == wmon;
+            let token = if token_is_token0 {
+                tokens.token0
+            } else {
+                tokens.token1
+            };
+            let token_decimals = self.metadata.decimals(provider, token).await?;
+
+            pools.insert(
+                pool_address,
+                PricedPool {
+                    token,
+                    token_is_token0,
+                    token_decimals,
+                    wmon_decimals,
+                },
+            );
+        }
+
+        Ok(pools)
+    }
+
+    /// Subscribe to swaps and emit a [`PriceTick`] for each one
+    pub async fn subscribe(&self) -> Result<Pin<Box<dyn Stream<Item = Result<PriceTick>> + Send>>> {
+        let pools = self.load_pools().await?;
+
+        let filter = Filter::new()
+            .address(self.pool_addresses.clone())
+            .event_signature(UniswapV3Pool::Swap::SIGNATURE_HASH);
+        let sub = self.provider.subscribe_logs(&filter).await?;
+
+        let stream = self
+            .decode
+            .clone()
+            .apply(sub.into_stream().map(decode_swap_event))
+            .filter_map(move |item| {
+                let tick = match item {
+                    Ok(swap) => pools
+                        .get(&swap.pool_address)
+                        .map(|pool| Ok(pool.tick(&swap))),
+                    Err(e) => Some(Err(e)),
+                };
+                future::ready(tick)
+            });
+
+        if let Some(window) = self.order_window {
+            return Ok(Box::pin(order_by_block(stream, window, |tick| {
+                (tick.block, tick.transaction_index, tick.log_index)
+            })));
+        }
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqrt_price_to_price() {
+        // sqrtPriceX96 = 2^96 means a raw ratio of 1
+        let one = U256::from(1u8) << 96;
+        assert!((sqrt_price_x96_to_price(one, true, 18, 18) - 1.0).abs() < 1e-12);
+
+        // sqrt ratio of 2 => token1/token0 = 4
+        let two = U256::from(2u8) << 96;
+        assert!((sqrt_price_x96_to_price(two, true, 18, 18) - 4.0).abs() < 1e-9);
+        assert!((sqrt_price_x96_to_price(two, false, 18, 18) - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sqrt_price_to_price_with_mismatched_decimals() {
+        // 6-decimal token worth 4 MON: 4e18 raw MON per 1e6 raw token
+        let token0 = U256::from(2_000_000u64) << 96; // sqrt(4e12)
+        let price = sqrt_price_x96_to_price(token0, true, 6, 18);
+        assert!((price - 4.0).abs() < 1e-9, "token0 price {price}");
+
+        // Same price with the token as token1: 0.25e6 raw token per 1e18 raw MON
+        let token1 = (U256::from(1u8) << 96) / U256::from(2_000_000u64); // sqrt(2.5e-13)
+        let price = sqrt_price_x96_to_price(token1, false, 6, 18);
+        assert!((price - 4.0).abs() < 1e-6, "token1 price {price}");
+    }
+}
@@ -1,13 +1,16 @@
 //! Uniswap V3 Swap event streaming support
 //!
-//! This module provides real-time streaming for Uniswap V3 Swap events.
+//! This module provides real-time streaming for Uniswap V3 Swap events, and for
+//! Mint/Burn/Collect liquidity events through [`UniswapSwapStream::subscribe_events`].
 //! All types are defined in the types::uniswap module.
 
-use crate::types::SwapEvent;
+use crate::stream::decode_policy::{DecodeErrorPolicy, DecodeStats, DecodeTracker};
+use crate::stream::ordering::order_by_block;
+use crate::stream::predicate::Predicate;
+use crate::types::{DexEvent, DexEventType, SwapEvent, decode_dex_event, dex_filter};
 use alloy::{
     primitives::Address,
     providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
-    sol_types::SolEvent,
 };
 use anyhow::Result;
 use futures_util::Stream;
@@ -20,6 +23,10 @@ pub struct UniswapSwapStream {
     provider: Arc<DynProvider>,
     #[allow(dead_code)] // Will be used when real streaming is implemented
     pool_addresses: Vec<Address>,
+    predicate: Option<Predicate>,
+    order_window: Option<u64>,
+    event_types: Vec<DexEventType>,
+    decode: DecodeTracker,
 }
 
 impl UniswapSwapStream {
@@ -32,9 +39,25 @@ impl UniswapSwapStream {
         Ok(UniswapSwapStream {
             provider: dyn_provider,
             pool_addresses,
+            predicate: None,
+            order_window: None,
+            event_types: vec![DexEventType::Swap],
+            decode: DecodeTracker::default(),
         })
     }
 
+    /// Reuse an existing WebSocket provider
+    pub fn from_provider(provider: Arc<DynProvider>, pool_addresses: Vec<Address>) -> Self {
+        UniswapSwapStream {
+            provider,
+            pool_addresses,
+            predicate: None,
+            order_window: None,
+            event_types: vec![DexEventType::Swap],
+            decode: DecodeTracker::default(),
+        }
+    }
+
     /// Create stream by discovering pools for token addresses
     /// Uses Nad.fun standard 10_000 fee tier (1%)
     pub async fn discover_pools_for_tokens(
@@ -60,6 +83,10 @@ impl UniswapSwapStream {
         Ok(UniswapSwapStream {
             provider: dyn_provider,
             pool_addresses,
+            predicate: None,
+            order_window: None,
+            event_types: vec![DexEventType::Swap],
+            decode: DecodeTracker::default(),
         })
     }
 
@@ -68,34 +95,110 @@ impl UniswapSwapStream {
         Self::discover_pools_for_tokens(rpc_url, vec![token_address]).await
     }
 
+    /// Only yield swaps matching `predicate`
+    ///
+    /// Swaps only expose `sender` to predicates; see [`PredicateTarget`](crate::stream::PredicateTarget).
+    pub fn with_predicate(mut self, predicate: Predicate) -> Self {
+        self.predicate = Some(predicate);
+        self
+    }
+
+    /// Pool events yielded by [`UniswapSwapStream::subscribe_dex`] (default: Swap only)
+    pub fn subscribe_events(mut self, event_types: Vec<DexEventType>) -> Self {
+        self.event_types = event_types;
+        self
+    }
+
+    /// Skip, yield or abort on logs that fail to decode (default: skip)
+    pub fn with_decode_policy(mut self, policy: DecodeErrorPolicy) -> Self {
+        self.decode.policy = policy;
+        self
+    }
+
+    /// Decoding counters of every subscription opened from this stream
+    pub fn decode_stats(&self) -> DecodeStats {
+        self.decode.stats.clone()
+    }
+
+    /// Buffer swaps and yield them sorted by (block, tx_index, log_index)
+    ///
+    /// Logs from different pools may arrive out of order; swaps are held until the
+    /// stream has advanced `window_blocks` blocks past them.
+    pub fn order_events(mut self, window_blocks: u64) -> Self {
+        self.order_window = Some(window_blocks);
+        self
+    }
+
     /// Subscribe to swap events - provides raw swap events
+    ///
+    /// Swaps retracted by a reorg are delivered again with `removed == true`
+    /// so consumers can invalidate previously processed data.
     pub async fn subscribe(&self) -> Result<Pin<Box<dyn Stream<Item = Result<SwapEvent>> + Send>>> {
-        use crate::types::{UniswapV3Pool, decode_swap_event};
-        use alloy::rpc::types::Filter;
+        use crate::types::{decode_swap_event, swap_filter};
         use futures_util::StreamExt;
 
-        let swap_signature = UniswapV3Pool::Swap::SIGNATURE_HASH;
-
         // Create filter for all monitored pools
-        let filter = Filter::new()
-            .address(self.pool_addresses.clone())
-            .event_signature(swap_signature);
+        let filter = swap_filter(&self.pool_addresses, None);
 
         let sub = self.provider.subscribe_logs(&filter).await?;
+        let predicate = self.predicate.clone();
+
+        let stream = self
+            .decode
+            .clone()
+            .apply(sub.into_stream().map(decode_swap_event))
+            .filter(move |result| {
+                let keep = match (result, &predicate) {
+                    (Ok(event), Some(predicate)) => predicate.matches(event),
+                    _ => true,
+                };
+                futures_util::future::ready(keep)
+            });
 
-        let stream = sub
-            .into_stream()
-            .map(move |log| decode_swap_event(log))
-            .filter_map(|result| async move {
-                match result {
-                    Ok(event) => Some(Ok(event)),
-                    Err(e) => {
-                        eprintln!("Error decoding swap event: {}", e);
-                        None
-                    }
-                }
+        if let Some(window) = self.order_window {
+            return Ok(Box::pin(order_by_block(stream, window, |event| {
+                (event.block_number, event.transaction_index, event.log_index)
+            })));
+        }
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Subscribe to the configured pool event types (see [`UniswapSwapStream::subscribe_events`])
+    ///
+    /// Like [`UniswapSwapStream::subscribe`], reorged logs are delivered again
+    /// with `removed() == true`.
+    pub async fn subscribe_dex(
+        &self,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<DexEvent>> + Send>>> {
+        use futures_util::StreamExt;
+
+        let filter = dex_filter(&self.pool_addresses, &self.event_types);
+        let sub = self.provider.subscribe_logs(&filter).await?;
+        let predicate = self.predicate.clone();
+
+        let stream = self
+            .decode
+            .clone()
+            .apply(sub.into_stream().map(decode_dex_event))
+            .filter(move |result| {
+                let keep = match (result, &predicate) {
+                    (Ok(event), Some(predicate)) => predicate.matches(event),
+                    _ => true,
+                };
+                futures_util::future::ready(keep)
             });
 
+        if let Some(window) = self.order_window {
+            return Ok(Box::pin(order_by_block(stream, window, |event| {
+                (
+                    event.block_number(),
+                    event.transaction_index(),
+                    event.log_index(),
+                )
+            })));
+        }
+
         Ok(Box::pin(stream))
     }
 }
@@ -0,0 +1,279 @@
+//! Transaction origin enrichment
+//!
+//! Event `sender` fields point at whichever contract called the pool or curve
+//! (for DEX swaps this is the router). [`OriginResolver`] looks up the EOA that
+//! signed each event's transaction and caches it per transaction hash.
+//!
+//! The cache keeps the most recently used [`OriginResolver::with_capacity`]
+//! senders (10 000 by default), so a resolver on a live stream stays bounded.
+
+use crate::types::{BondingCurveEvent, SwapEvent};
+use alloy::{
+    primitives::{Address, B256},
+    providers::Provider,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, stream};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+/// Cached senders kept by default
+const DEFAULT_CAPACITY: usize = 10_000;
+/// Receipt fetches in flight at once by default
+const DEFAULT_CONCURRENCY: usize = 16;
+
+/// Events that can be attributed to the transaction that emitted them
+pub trait TransactionEvent {
+    fn transaction_hash(&self) -> B256;
+}
+
+impl TransactionEvent for BondingCurveEvent {
+    fn transaction_hash(&self) -> B256 {
+        BondingCurveEvent::transaction_hash(self)
+    }
+}
+
+impl TransactionEvent for SwapEvent {
+    fn transaction_hash(&self) -> B256 {
+        self.transaction_hash
+    }
+}
+
+/// An event together with the address that signed its transaction
+#[derive(Debug, Clone)]
+pub struct Enriched<T> {
+    pub event: T,
+    pub origin_from: Address,
+}
+
+/// Least recently used transaction senders, up to a capacity
+struct OriginCache {
+    capacity: usize,
+    /// Sender and last-use tick of each cached hash
+    entries: HashMap<B256, (Address, u64)>,
+    /// Cached hashes by last-use tick, oldest first
+    by_use: BTreeMap<u64, B256>,
+    tick: u64,
+}
+
+impl OriginCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            by_use: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    fn get(&mut self, tx_hash: &B256) -> Option<Address> {
+        self.tick += 1;
+        let (from, used) = self.entries.get_mut(tx_hash)?;
+        self.by_use.remove(used);
+        *used = self.tick;
+        self.by_use.insert(self.tick, *tx_hash);
+        Some(*from)
+    }
+
+    fn insert(&mut self, tx_hash: B256, from: Address) {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(tx_hash, (from, self.tick)) {
+            self.by_use.remove(&used);
+        }
+        self.by_use.insert(self.tick, tx_hash);
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.by_use.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.by_use.clear();
+    }
+}
+
+/// Resolves and caches `tx.from` for event transaction hashes
+pub struct OriginResolver<P> {
+    provider: Arc<P>,
+    cache: Mutex<OriginCache>,
+    concurrency: usize,
+}
+
+impl<P: Provider + Clone> OriginResolver<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self {
+            provider,
+            cache: Mutex::new(OriginCache::new(DEFAULT_CAPACITY)),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Keep at most `capacity` senders, evicting the least recently used
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(OriginCache::new(capacity.max(1))),
+            ..self
+        }
+    }
+
+    /// Fetch at most `concurrency` receipts at once in [`origins_of`](Self::origins_of)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Get the sender of a transaction, using the cache when possible
+    pub async fn origin_of(&self, tx_hash: B256) -> Result<Address> {
+        let cached = self.cache.lock().unwrap().get(&tx_hash);
+        if let Some(from) = cached {
+            return Ok(from);
+        }
+
+        let receipt = self
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction receipt not found: {}", tx_hash))?;
+
+        self.cache.lock().unwrap().insert(tx_hash, receipt.from);
+        Ok(receipt.from)
+    }
+
+    /// Resolve many transaction hashes concurrently
+    ///
+    /// Each distinct uncached hash is fetched once, with at most
+    /// [`with_concurrency`](Self::with_concurrency) receipt requests in flight.
+    pub async fn origins_of(&self, tx_hashes: &[B256]) -> Result<HashMap<B256, Address>> {
+        let unique: HashSet<B256> = tx_hashes.iter().copied().collect();
+        let results: Vec<Result<(B256, Address)>> = stream::iter(unique)
+            .map(|hash| async move { self.origin_of(hash).await.map(|from| (hash, from)) })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        results.into_iter().collect()
+    }
+
+    /// Attach `origin_from` to a batch of indexed events, preserving order
+    pub async fn enrich<T: TransactionEvent>(&self, events: Vec<T>) -> Result<Vec<Enriched<T>>> {
+        let hashes: Vec<B256> = events.iter().map(|e| e.transaction_hash()).collect();
+        let origins = self.origins_of(&hashes).await?;
+
+        Ok(events
+            .into_iter()
+            .map(|event| {
+                let origin_from = origins[&event.transaction_hash()];
+                Enriched { event, origin_from }
+            })
+            .collect())
+    }
+
+    /// Attach `origin_from` to every event of a live stream
+    pub fn enrich_stream<T, S>(
+        self: Arc<Self>,
+        inner: S,
+    ) -> impl Stream<Item = Result<Enriched<T>>> + Send
+    where
+        T: TransactionEvent + Send + 'static,
+        S: Stream<Item = Result<T>> + Send + 'static,
+        P: Send + Sync + 'static,
+    {
+        inner.then(move |item| {
+            let resolver = self.clone();
+            async move {
+                let event = item?;
+                let origin_from = resolver.origin_of(event.transaction_hash()).await?;
+                Ok(Enriched { event, origin_from })
+            }
+        })
+    }
+
+    /// Number of cached transaction senders
+    pub fn cache_len(&self) -> usize {
+        self.cache.lock().unwrap().entries.len()
+    }
+
+    /// Drop all cached transaction senders
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, swap_at};
+    use alloy::providers::{ProviderBuilder, RootProvider, mock::Asserter};
+
+    fn receipt(tx_hash: B256, from: Address) -> serde_json::Value {
+        serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "transactionHash": tx_hash,
+            "transactionIndex": "0x0",
+            "blockHash": fixtures::FIXTURE_BLOCK_HASH,
+            "blockNumber": "0x1",
+            "from": from,
+            "to": fixtures::FIXTURE_POOL,
+            "contractAddress": null,
+            "gasUsed": "0x5208",
+            "cumulativeGasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+        })
+    }
+
+    fn resolver(asserter: &Asserter) -> OriginResolver<RootProvider> {
+        let provider = ProviderBuilder::default().connect_mocked_client(asserter.clone());
+        OriginResolver::new(Arc::new(provider))
+    }
+
+    #[tokio::test]
+    async fn test_enrich_fetches_each_hash_once_and_caches() {
+        let asserter = Asserter::new();
+        let resolver = resolver(&asserter);
+        let events = vec![swap_at(1, 0), swap_at(1, 1), swap_at(2, 0)];
+        let origin = fixtures::FIXTURE_SENDER;
+        for event in [&events[0], &events[2]] {
+            asserter.push_success(&receipt(event.transaction_hash, origin));
+        }
+
+        let enriched = resolver.enrich(events.clone()).await.unwrap();
+        assert_eq!(enriched.len(), 3);
+        assert!(enriched.iter().all(|e| e.origin_from == origin));
+        assert!(asserter.read_q().is_empty());
+        assert_eq!(resolver.cache_len(), 2);
+
+        // Served from the cache: the mock has no responses left
+        let again = resolver.enrich(events).await.unwrap();
+        assert_eq!(again[1].event.log_index, 1);
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let asserter = Asserter::new();
+        let resolver = resolver(&asserter).with_capacity(2);
+        let hashes: Vec<B256> = (1..=3).map(B256::with_last_byte).collect();
+        let origin = fixtures::FIXTURE_SENDER;
+
+        for hash in &hashes[..2] {
+            asserter.push_success(&receipt(*hash, origin));
+            resolver.origin_of(*hash).await.unwrap();
+        }
+        // Touch the first hash so the second becomes the oldest
+        resolver.origin_of(hashes[0]).await.unwrap();
+        asserter.push_success(&receipt(hashes[2], origin));
+        resolver.origin_of(hashes[2]).await.unwrap();
+
+        assert_eq!(resolver.cache_len(), 2);
+        assert!(resolver.origin_of(hashes[0]).await.is_ok());
+        assert!(resolver.origin_of(hashes[1]).await.is_err());
+    }
+}
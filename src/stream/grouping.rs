@@ -0,0 +1,415 @@
+//! Per-block batching of event streams
+//!
+//! Many consumers handle a block at a time (one DB transaction per block, one
+//! price update per block). [`EventStreamExt::by_block`] turns a stream of events
+//! into a stream of [`BlockEvents`]:
+//!
+//! ```rust,ignore
+//! let mut blocks = curve_stream.subscribe().await?.by_block();
+//! while let Some(Ok(batch)) = blocks.next().await {
+//!     println!("block {}: {} events", batch.block, batch.events.len());
+//! }
+//! ```
+//!
+//! A block is emitted once the first event of a later block arrives, so the last
+//! block waits for the next trade. [`EventStreamExt::by_block_with_heads`] also
+//! emits it as soon as a newer block header arrives. Events must arrive in block
+//! order; combine with `order_events` for sources that may reorder.
+//!
+//! [`TxStreamExt::by_transaction`] and [`group_by_transaction`] do the same per
+//! transaction for curve events, summarizing each as a [`TxActivity`] with the
+//! trader's net token and MON flows (a buy emits Buy + Sync, for example).
+
+use crate::{
+    stream::{
+        blocks::BlockHeaderInfo,
+        ordering::BlockNumbered,
+        pause::{PausableStream, PauseConfig, pausable},
+    },
+    types::BondingCurveEvent,
+};
+use alloy::primitives::{Address, B256, I256, U256};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{collections::HashMap, pin::Pin};
+
+/// All events of one block, in arrival order
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEvents<T> {
+    pub block: u64,
+    pub events: Vec<T>,
+}
+
+/// Group consecutive events of the same block
+///
+/// Errors are passed through immediately without flushing the pending block.
+pub fn group_by_block<T, S>(inner: S) -> impl Stream<Item = Result<BlockEvents<T>>>
+where
+    T: BlockNumbered,
+    S: Stream<Item = Result<T>>,
+{
+    async_stream::stream! {
+        let mut pending: Option<BlockEvents<T>> = None;
+        futures_util::pin_mut!(inner);
+
+        while let Some(item) = inner.next().await {
+            let event = match item {
+                Ok(event) => event,
+                Err(e) => {
+                    yield Err(e);
+                    continue;
+                }
+            };
+            if let Some(done) = push_event(&mut pending, event) {
+                yield Ok(done);
+            }
+        }
+
+        if let Some(done) = pending {
+            yield Ok(done);
+        }
+    }
+}
+
+/// One wake-up of [`group_by_block_with_heads`]
+enum Step<T> {
+    Event(Option<Result<T>>),
+    Head(Option<Result<BlockHeaderInfo>>),
+}
+
+/// Like [`group_by_block`], but also emit the pending block when a newer head arrives
+///
+/// Events already delivered are handled before a head that arrives at the same
+/// time. If `heads` ends, grouping continues on events alone.
+pub fn group_by_block_with_heads<T, S, H>(
+    inner: S,
+    heads: H,
+) -> impl Stream<Item = Result<BlockEvents<T>>>
+where
+    T: BlockNumbered,
+    S: Stream<Item = Result<T>>,
+    H: Stream<Item = Result<BlockHeaderInfo>>,
+{
+    async_stream::stream! {
+        let mut pending: Option<BlockEvents<T>> = None;
+        let mut heads_alive = true;
+        futures_util::pin_mut!(inner);
+        futures_util::pin_mut!(heads);
+
+        loop {
+            let step = tokio::select! {
+                biased;
+                item = inner.next() => Step::Event(item),
+                head = heads.next(), if heads_alive => Step::Head(head),
+            };
+
+            match step {
+                Step::Event(Some(Ok(event))) => {
+                    if let Some(done) = push_event(&mut pending, event) {
+                        yield Ok(done);
+                    }
+                }
+                Step::Event(Some(Err(e))) | Step::Head(Some(Err(e))) => yield Err(e),
+                Step::Event(None) => break,
+                Step::Head(Some(Ok(head))) => {
+                    if pending.as_ref().is_some_and(|p| head.number > p.block) {
+                        if let Some(done) = pending.take() {
+                            yield Ok(done);
+                        }
+                    }
+                }
+                Step::Head(None) => heads_alive = false,
+            }
+        }
+
+        if let Some(done) = pending {
+            yield Ok(done);
+        }
+    }
+}
+
+/// Add `event` to the pending block, returning the previous block if it is complete
+fn push_event<T: BlockNumbered>(
+    pending: &mut Option<BlockEvents<T>>,
+    event: T,
+) -> Option<BlockEvents<T>> {
+    let block = event.block_number();
+    match pending {
+        Some(batch) if batch.block == block => {
+            batch.events.push(event);
+            None
+        }
+        _ => pending.replace(BlockEvents {
+            block,
+            events: vec![event],
+        }),
+    }
+}
+
+/// Curve events of one transaction with the trader's net flows
+///
+/// Deltas are from the trader's side: buys add tokens and spend MON, sells the
+/// reverse. They are summed over every token traded in the transaction; check
+/// [`TxActivity::tokens`] before reading them for multi-token transactions.
+/// Removed (reorged) events count negatively, undoing their originals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxActivity {
+    pub tx_hash: B256,
+    pub block: u64,
+    pub events: Vec<BondingCurveEvent>,
+    /// Tokens bought minus tokens sold
+    pub net_token_delta: I256,
+    /// MON received from sells minus MON paid for buys
+    pub net_mon_delta: I256,
+}
+
+impl TxActivity {
+    fn new(event: BondingCurveEvent) -> Self {
+        let mut activity = Self {
+            tx_hash: event.transaction_hash(),
+            block: event.block_number(),
+            events: Vec::new(),
+            net_token_delta: I256::ZERO,
+            net_mon_delta: I256::ZERO,
+        };
+        activity.push(event);
+        activity
+    }
+
+    fn push(&mut self, event: BondingCurveEvent) {
+        let (tokens, mon) = match &event {
+            BondingCurveEvent::Buy(buy) => (signed(buy.amount_out), -signed(buy.amount_in)),
+            BondingCurveEvent::Sell(sell) => (-signed(sell.amount_in), signed(sell.amount_out)),
+            _ => (I256::ZERO, I256::ZERO),
+        };
+        let (tokens, mon) = if event.removed() {
+            (-tokens, -mon)
+        } else {
+            (tokens, mon)
+        };
+        self.net_token_delta = self.net_token_delta.saturating_add(tokens);
+        self.net_mon_delta = self.net_mon_delta.saturating_add(mon);
+        self.events.push(event);
+    }
+
+    /// Distinct tokens of the transaction's events, in order of appearance
+    pub fn tokens(&self) -> Vec<Address> {
+        let mut tokens = Vec::new();
+        for event in &self.events {
+            if !tokens.contains(&event.token()) {
+                tokens.push(event.token());
+            }
+        }
+        tokens
+    }
+
+    /// Number of Buy and Sell events
+    pub fn trade_count(&self) -> usize {
+        self.events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    BondingCurveEvent::Buy(_) | BondingCurveEvent::Sell(_)
+                )
+            })
+            .count()
+    }
+}
+
+fn signed(value: U256) -> I256 {
+    I256::try_from(value).unwrap_or(I256::MAX)
+}
+
+/// Group an indexed batch of curve events by transaction
+///
+/// Transactions are returned in order of their first event; events keep their
+/// order within a transaction, so the batch doesn't need to be sorted.
+pub fn group_by_transaction(
+    events: impl IntoIterator<Item = BondingCurveEvent>,
+) -> Vec<TxActivity> {
+    let mut activities: Vec<TxActivity> = Vec::new();
+    let mut index: HashMap<B256, usize> = HashMap::new();
+    for event in events {
+        match index.get(&event.transaction_hash()) {
+            Some(&i) => activities[i].push(event),
+            None => {
+                index.insert(event.transaction_hash(), activities.len());
+                activities.push(TxActivity::new(event));
+            }
+        }
+    }
+    activities
+}
+
+/// Group consecutive curve events of the same transaction
+///
+/// A transaction is emitted once an event of another transaction arrives (or the
+/// stream ends). Errors are passed through without flushing the pending one.
+pub fn group_stream_by_transaction<S>(inner: S) -> impl Stream<Item = Result<TxActivity>>
+where
+    S: Stream<Item = Result<BondingCurveEvent>>,
+{
+    async_stream::stream! {
+        let mut pending: Option<TxActivity> = None;
+        futures_util::pin_mut!(inner);
+
+        while let Some(item) = inner.next().await {
+            let event = match item {
+                Ok(event) => event,
+                Err(e) => {
+                    yield Err(e);
+                    continue;
+                }
+            };
+            match &mut pending {
+                Some(activity) if activity.tx_hash == event.transaction_hash() => {
+                    activity.push(event);
+                }
+                _ => {
+                    if let Some(done) = pending.replace(TxActivity::new(event)) {
+                        yield Ok(done);
+                    }
+                }
+            }
+        }
+
+        if let Some(done) = pending {
+            yield Ok(done);
+        }
+    }
+}
+
+/// Per-block batching and pausing for event streams
+pub trait EventStreamExt<T>: Stream<Item = Result<T>> + Sized {
+    /// Emit all events of a block at once, when the next block's first event arrives
+    fn by_block(self) -> Pin<Box<dyn Stream<Item = Result<BlockEvents<T>>> + Send>>
+    where
+        Self: Send + 'static,
+        T: BlockNumbered + Send + 'static,
+    {
+        Box::pin(group_by_block(self))
+    }
+
+    /// Emit all events of a block at once, when a later event or block header arrives
+    fn by_block_with_heads<H>(
+        self,
+        heads: H,
+    ) -> Pin<Box<dyn Stream<Item = Result<BlockEvents<T>>> + Send>>
+    where
+        Self: Send + 'static,
+        T: BlockNumbered + Send + 'static,
+        H: Stream<Item = Result<BlockHeaderInfo>> + Send + 'static,
+    {
+        Box::pin(group_by_block_with_heads(self, heads))
+    }
+
+    /// Read on a background task so consumption can be paused without losing events
+    fn pausable(self, config: PauseConfig) -> PausableStream<T>
+    where
+        Self: Send + 'static,
+        T: Serialize + DeserializeOwned + Send + 'static,
+    {
+        pausable(self, config)
+    }
+}
+
+impl<T, S: Stream<Item = Result<T>>> EventStreamExt<T> for S {}
+
+/// Per-transaction grouping for curve event streams
+pub trait TxStreamExt: Stream<Item = Result<BondingCurveEvent>> + Sized {
+    /// Emit the curve events of each transaction at once, as a [`TxActivity`]
+    fn by_transaction(self) -> Pin<Box<dyn Stream<Item = Result<TxActivity>> + Send>>
+    where
+        Self: Send + 'static,
+    {
+        Box::pin(group_stream_by_transaction(self))
+    }
+}
+
+impl<S: Stream<Item = Result<BondingCurveEvent>>> TxStreamExt for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, swap_at};
+    use alloy::primitives::B256;
+
+    #[tokio::test]
+    async fn test_by_block_groups_consecutive_events() {
+        let input = vec![
+            swap_at(1, 0),
+            swap_at(1, 1),
+            swap_at(2, 0),
+            swap_at(4, 0),
+            swap_at(4, 1),
+        ];
+        let batches: Vec<(u64, usize)> =
+            futures_util::stream::iter(input.into_iter().map(Ok::<_, anyhow::Error>))
+                .by_block()
+                .map(|batch| {
+                    let batch = batch.unwrap();
+                    (batch.block, batch.events.len())
+                })
+                .collect()
+                .await;
+        assert_eq!(batches, vec![(1, 2), (2, 1), (4, 2)]);
+    }
+
+    #[tokio::test]
+    async fn test_by_transaction_nets_trader_flows() {
+        let buy = fixtures::curve_buy().expected;
+        let sync = fixtures::curve_sync().expected;
+        let mut other = buy.clone();
+        if let BondingCurveEvent::Buy(event) = &mut other {
+            event.transaction_hash = B256::repeat_byte(0x42);
+        }
+
+        let input = vec![buy.clone(), sync.clone(), other];
+        let batch = group_by_transaction(input.clone());
+        let streamed: Vec<TxActivity> =
+            futures_util::stream::iter(input.into_iter().map(Ok::<_, anyhow::Error>))
+                .by_transaction()
+                .map(|activity| activity.unwrap())
+                .collect()
+                .await;
+        assert_eq!(batch, streamed);
+
+        let BondingCurveEvent::Buy(bought) = &buy else {
+            panic!("fixture is a buy");
+        };
+        // The fixture's Buy and Sync share one transaction
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0].events, vec![buy.clone(), sync]);
+        assert_eq!(batch[0].trade_count(), 1);
+        assert_eq!(batch[0].net_token_delta, signed(bought.amount_out));
+        assert_eq!(batch[0].net_mon_delta, -signed(bought.amount_in));
+    }
+
+    #[tokio::test]
+    async fn test_by_block_with_heads_flushes_on_new_head() {
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (head_tx, mut head_rx) = tokio::sync::mpsc::unbounded_channel();
+        let events = async_stream::stream! {
+            while let Some(event) = event_rx.recv().await {
+                yield Ok::<_, anyhow::Error>(event);
+            }
+        };
+        let heads = async_stream::stream! {
+            while let Some(number) = head_rx.recv().await {
+                yield Ok::<_, anyhow::Error>(BlockHeaderInfo { number, timestamp: 0, hash: B256::ZERO });
+            }
+        };
+        let mut batches = events.by_block_with_heads(heads);
+
+        event_tx.send(swap_at(7, 0)).unwrap();
+        head_tx.send(7).unwrap();
+        head_tx.send(8).unwrap();
+        let batch = batches.next().await.unwrap().unwrap();
+        assert_eq!((batch.block, batch.events.len()), (7, 1));
+
+        drop(event_tx);
+        assert!(batches.next().await.is_none());
+    }
+}
@@ -0,0 +1,305 @@
+//! Callback-based event handling
+//!
+//! For integrators that prefer registering handlers over polling streams.
+//! [`EventHandlers`] holds one async handler per event type and drives a
+//! [`CurveStream`] or [`UniswapSwapStream`] subscription on a background task:
+//!
+//! ```rust,ignore
+//! let (handle, mut errors) = EventHandlers::new()
+//!     .on_buy(|event| async move {
+//!         println!("buy {} MON -> {}", event.amount_in, event.token);
+//!         Ok(())
+//!     })
+//!     .spawn_curve(&curve_stream)
+//!     .await?;
+//! tokio::spawn(async move {
+//!     while let Some(err) = errors.recv().await {
+//!         eprintln!("handler failed: {err}");
+//!     }
+//! });
+//!
+//! // ... later
+//! handle.shutdown().await?;
+//! ```
+
+use crate::stream::{CurveStream, UniswapSwapStream};
+use crate::types::{
+    BondingCurveEvent, BuyEvent, CreateEvent, ListedEvent, LockEvent, SellEvent, SwapEvent,
+    SyncEvent,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, future::BoxFuture};
+use std::{future::Future, sync::Arc};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+type Handler<T> = Arc<dyn Fn(T) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+fn boxed<T, F, Fut>(handler: F) -> Handler<T>
+where
+    F: Fn(T) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    Arc::new(move |event| Box::pin(handler(event)))
+}
+
+/// Async handlers per event type
+///
+/// Events without a registered handler are ignored. Handlers run one at a time
+/// in stream order; errors from handlers and from the stream itself do not stop
+/// the task and are sent to the receiver returned with its [`SubscriptionHandle`]
+/// (drop it to ignore them).
+#[derive(Clone, Default)]
+pub struct EventHandlers {
+    on_create: Option<Handler<CreateEvent>>,
+    on_buy: Option<Handler<BuyEvent>>,
+    on_sell: Option<Handler<SellEvent>>,
+    on_sync: Option<Handler<SyncEvent>>,
+    on_lock: Option<Handler<LockEvent>>,
+    on_listed: Option<Handler<ListedEvent>>,
+    on_swap: Option<Handler<SwapEvent>>,
+}
+
+impl EventHandlers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn on_create<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(CreateEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_create = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_buy<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(BuyEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_buy = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_sell<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(SellEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_sell = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_sync<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(SyncEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_sync = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_lock<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(LockEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_lock = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_listed<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(ListedEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_listed = Some(boxed(handler));
+        self
+    }
+
+    pub fn on_swap<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(SwapEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.on_swap = Some(boxed(handler));
+        self
+    }
+
+    /// Dispatch a single bonding curve event to its handler
+    pub async fn handle_curve_event(&self, event: BondingCurveEvent) -> Result<()> {
+        match event {
+            BondingCurveEvent::Create(e) => dispatch(&self.on_create, e).await,
+            BondingCurveEvent::Buy(e) => dispatch(&self.on_buy, e).await,
+            BondingCurveEvent::Sell(e) => dispatch(&self.on_sell, e).await,
+            BondingCurveEvent::Sync(e) => dispatch(&self.on_sync, e).await,
+            BondingCurveEvent::Lock(e) => dispatch(&self.on_lock, e).await,
+            BondingCurveEvent::Listed(e) => dispatch(&self.on_listed, e).await,
+        }
+    }
+
+    /// Dispatch a single swap event to its handler
+    pub async fn handle_swap_event(&self, event: SwapEvent) -> Result<()> {
+        dispatch(&self.on_swap, event).await
+    }
+
+    /// Subscribe to a curve stream and run the handlers on a background task
+    pub async fn spawn_curve(
+        &self,
+        stream: &CurveStream,
+    ) -> Result<(SubscriptionHandle, HandlerErrors)> {
+        let events = stream.subscribe().await?;
+        let handlers = self.clone();
+        Ok(self.spawn(events, move |event| {
+            let handlers = handlers.clone();
+            async move { handlers.handle_curve_event(event).await }
+        }))
+    }
+
+    /// Subscribe to a swap stream and run the handlers on a background task
+    pub async fn spawn_dex(
+        &self,
+        stream: &UniswapSwapStream,
+    ) -> Result<(SubscriptionHandle, HandlerErrors)> {
+        let events = stream.subscribe().await?;
+        let handlers = self.clone();
+        Ok(self.spawn(events, move |event| {
+            let handlers = handlers.clone();
+            async move { handlers.handle_swap_event(event).await }
+        }))
+    }
+
+    fn spawn<T, S, F, Fut>(&self, events: S, handle: F) -> (SubscriptionHandle, HandlerErrors)
+    where
+        T: Send + 'static,
+        S: Stream<Item = Result<T>> + Send + Unpin + 'static,
+        F: Fn(T) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send,
+    {
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let (error_tx, error_rx) = mpsc::unbounded_channel();
+        let report = move |err: anyhow::Error| {
+            let _ = error_tx.send(err);
+        };
+
+        let task = tokio::spawn(async move {
+            let mut events = events;
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    next = events.next() => match next {
+                        Some(Ok(event)) => {
+                            if let Err(e) = handle(event).await {
+                                report(e);
+                            }
+                        }
+                        Some(Err(e)) => report(e),
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        (SubscriptionHandle::new(shutdown_tx, task), error_rx)
+    }
+}
+
+/// Stream and handler errors of a task started by [`EventHandlers`]
+pub type HandlerErrors = mpsc::UnboundedReceiver<anyhow::Error>;
+
+async fn dispatch<T>(handler: &Option<Handler<T>>, event: T) -> Result<()> {
+    match handler {
+        Some(handler) => handler(event).await,
+        None => Ok(()),
+    }
+}
+
+/// Handle to a background subscription started by [`EventHandlers`] or
+/// [`SharedWsClient`](crate::stream::SharedWsClient)
+pub struct SubscriptionHandle {
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+impl SubscriptionHandle {
+    pub(crate) fn new(shutdown: oneshot::Sender<()>, task: JoinHandle<()>) -> Self {
+        Self {
+            shutdown: Some(shutdown),
+            task,
+        }
+    }
+
+    /// True once the underlying stream ended or the task was shut down
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Stop driving the stream and wait for the in-flight handler to finish
+    pub async fn shutdown(mut self) -> Result<()> {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        self.task.await?;
+        Ok(())
+    }
+
+    /// Wait until the underlying stream ends
+    pub async fn join(self) -> Result<()> {
+        self.task.await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn test_dispatch_routes_by_event_type() {
+        let buys = Arc::new(AtomicUsize::new(0));
+        let counter = buys.clone();
+        let handlers = EventHandlers::new().on_buy(move |_| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+
+        for fixture in fixtures::curve_fixtures() {
+            handlers.handle_curve_event(fixture.expected).await.unwrap();
+        }
+
+        assert_eq!(buys.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_reports_stream_and_handler_errors() {
+        let handlers =
+            EventHandlers::new().on_buy(|_| async { Err(anyhow::anyhow!("handler failed")) });
+        let events = futures_util::stream::iter(vec![
+            Err(anyhow::anyhow!("subscription dropped")),
+            Ok(fixtures::curve_buy().expected),
+            Ok(fixtures::curve_sell().expected),
+        ]);
+        let dispatcher = handlers.clone();
+        let (handle, mut errors) = handlers.spawn(events, move |event| {
+            let handlers = dispatcher.clone();
+            async move { handlers.handle_curve_event(event).await }
+        });
+        handle.join().await.unwrap();
+
+        assert_eq!(
+            errors.recv().await.unwrap().to_string(),
+            "subscription dropped"
+        );
+        assert_eq!(errors.recv().await.unwrap().to_string(), "handler failed");
+        assert!(errors.recv().await.is_none());
+    }
+}
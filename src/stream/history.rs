@@ -0,0 +1,328 @@
+//! Historical Nad.fun interactions of a wallet
+//!
+//! [`WalletHistory`] answers "what did this wallet do on Nad.fun?" from plain
+//! `eth_getLogs` queries, without a block explorer: curve trades, token
+//! launches and router approvals, as typed [`HistoryRecord`]s in chain order:
+//!
+//! ```rust,ignore
+//! let history = WalletHistory::new(http_url)?.with_max_block_range(10_000);
+//! for record in history.fetch(wallet, from_block, to_block).await? {
+//!     match &record.kind {
+//!         HistoryRecordKind::Trade(event) => println!("{} traded {}", record.block, event.token()),
+//!         HistoryRecordKind::Create(create) => println!("launched {}", create.symbol),
+//!         HistoryRecordKind::Approve { token, spender, value } => println!("{token} -> {spender}: {value}"),
+//!         HistoryRecordKind::RouterCall { call, .. } => println!("called router for {}", call.token()),
+//!     }
+//! }
+//! ```
+//!
+//! Logs only show what succeeded. With [`WalletHistory::with_traces`] the
+//! router calls themselves are added from `trace_filter`, which needs a node
+//! with the trace API enabled.
+
+use crate::{
+    constants::ChainConfig,
+    token::token::IToken,
+    trading::calldata::{RouterCall, decode_router_calldata},
+    types::{BondingCurveEvent, CreateEvent, EventType, curve_filter, decode_bonding_curve_event},
+};
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider, ProviderBuilder, ext::TraceApi},
+    rpc::types::{
+        Filter, Log,
+        trace::{filter::TraceFilter, parity::Action},
+    },
+    sol_types::SolEvent,
+};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// What a wallet did in one history record
+#[derive(Debug, Clone)]
+pub enum HistoryRecordKind {
+    /// A buy or sell on the bonding curve
+    Trade(BondingCurveEvent),
+    /// A token launched by the wallet
+    Create(CreateEvent),
+    /// An ERC-20 approval of one of the Nad.fun routers
+    Approve {
+        token: Address,
+        spender: Address,
+        value: U256,
+    },
+    /// A router call found by tracing (only with [`WalletHistory::with_traces`])
+    RouterCall { router: Address, call: RouterCall },
+}
+
+/// One interaction of a wallet with the Nad.fun contracts
+#[derive(Debug, Clone)]
+pub struct HistoryRecord {
+    pub block: u64,
+    pub transaction_hash: B256,
+    /// Log index, or `None` for traced calls, which sort before the logs
+    /// of their transaction
+    pub log_index: Option<u64>,
+    pub kind: HistoryRecordKind,
+}
+
+impl HistoryRecord {
+    fn from_log(log: &Log, kind: HistoryRecordKind) -> Self {
+        Self {
+            block: log.block_number.unwrap_or_default(),
+            transaction_hash: log.transaction_hash.unwrap_or_default(),
+            log_index: log.log_index,
+            kind,
+        }
+    }
+
+    /// Token the record is about
+    pub fn token(&self) -> Address {
+        match &self.kind {
+            HistoryRecordKind::Trade(event) => event.token(),
+            HistoryRecordKind::Create(create) => create.token,
+            HistoryRecordKind::Approve { token, .. } => *token,
+            HistoryRecordKind::RouterCall { call, .. } => call.token(),
+        }
+    }
+}
+
+/// `Approval` logs of any token where `wallet` approved one of `routers`
+fn approval_filter(wallet: Address, routers: &[Address], from: u64, to: u64) -> Filter {
+    let routers: Vec<B256> = routers.iter().map(|router| router.into_word()).collect();
+    Filter::new()
+        .event_signature(IToken::Approval::SIGNATURE_HASH)
+        .topic1(wallet.into_word())
+        .topic2(routers)
+        .from_block(from)
+        .to_block(to)
+}
+
+/// Decode an `Approval` log of `owner` into a record
+///
+/// Returns `None` if the log is not an approval by `owner`.
+fn decode_approval(log: &Log, owner: Address) -> Option<HistoryRecord> {
+    if log.topic0() != Some(&IToken::Approval::SIGNATURE_HASH) {
+        return None;
+    }
+    let approval = IToken::Approval::decode_log(&log.inner).ok()?.data;
+    if approval.owner != owner {
+        return None;
+    }
+    Some(HistoryRecord::from_log(
+        log,
+        HistoryRecordKind::Approve {
+            token: log.address(),
+            spender: approval.spender,
+            value: approval.value,
+        },
+    ))
+}
+
+/// Fetches the Nad.fun history of wallets over an HTTP provider
+pub struct WalletHistory {
+    provider: Arc<DynProvider>,
+    config: ChainConfig,
+    max_block_range: Option<u64>,
+    traces: bool,
+}
+
+impl WalletHistory {
+    /// Create an HTTP-based history reader for the default chain config
+    pub fn new(rpc_url: String) -> Result<WalletHistory> {
+        let provider = ProviderBuilder::new().connect_http(rpc_url.parse()?);
+        Ok(WalletHistory::from_provider(Arc::new(DynProvider::new(
+            provider,
+        ))))
+    }
+
+    /// Reuse an existing provider
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self {
+            provider,
+            config: ChainConfig::default(),
+            max_block_range: None,
+            traces: false,
+        }
+    }
+
+    /// Use the contract addresses of another deployment
+    pub fn with_config(mut self, config: ChainConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Split queries into ranges of at most `blocks` blocks
+    ///
+    /// Most public RPCs cap the range of `eth_getLogs`; by default the whole
+    /// range is queried at once.
+    pub fn with_max_block_range(mut self, blocks: u64) -> Self {
+        self.max_block_range = Some(blocks.max(1));
+        self
+    }
+
+    /// Also list router calls from `trace_filter` (off by default)
+    pub fn with_traces(mut self, enabled: bool) -> Self {
+        self.traces = enabled;
+        self
+    }
+
+    fn routers(&self) -> Vec<Address> {
+        vec![self.config.bonding_curve_router, self.config.dex_router]
+    }
+
+    /// All records of `wallet` between `from_block` and `to_block` (inclusive),
+    /// in chain order
+    pub async fn fetch(
+        &self,
+        wallet: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<HistoryRecord>> {
+        let mut records = Vec::new();
+        let step = self.max_block_range.unwrap_or(u64::MAX);
+        let mut start = from_block;
+        while start <= to_block {
+            let end = start.saturating_add(step - 1).min(to_block);
+            records.extend(self.fetch_range(wallet, start, end).await?);
+            if end == u64::MAX {
+                break;
+            }
+            start = end + 1;
+        }
+
+        records.sort_by_key(|r| (r.block, r.transaction_hash, r.log_index));
+        Ok(records)
+    }
+
+    async fn fetch_range(&self, wallet: Address, from: u64, to: u64) -> Result<Vec<HistoryRecord>> {
+        let mut records = self.curve_records(wallet, from, to).await?;
+        records.extend(self.approvals(wallet, from, to).await?);
+        if self.traces {
+            records.extend(self.router_calls(wallet, from, to).await?);
+        }
+        Ok(records)
+    }
+
+    /// Trades and creates: the wallet is the first indexed topic of both
+    async fn curve_records(
+        &self,
+        wallet: Address,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<HistoryRecord>> {
+        let filter = curve_filter(
+            &[EventType::Create, EventType::Buy, EventType::Sell],
+            None,
+            Some(from..=to),
+        )
+        .address(self.config.bonding_curve)
+        .topic1(wallet.into_word());
+
+        let logs = self.provider.get_logs(&filter).await?;
+        logs.into_iter()
+            .map(|log| {
+                let event = decode_bonding_curve_event(log.clone())?;
+                let kind = match event {
+                    BondingCurveEvent::Create(create) => HistoryRecordKind::Create(create),
+                    trade => HistoryRecordKind::Trade(trade),
+                };
+                Ok(HistoryRecord::from_log(&log, kind))
+            })
+            .collect()
+    }
+
+    async fn approvals(&self, wallet: Address, from: u64, to: u64) -> Result<Vec<HistoryRecord>> {
+        let filter = approval_filter(wallet, &self.routers(), from, to);
+        let logs = self.provider.get_logs(&filter).await?;
+        Ok(logs
+            .iter()
+            .filter_map(|log| decode_approval(log, wallet))
+            .collect())
+    }
+
+    async fn router_calls(
+        &self,
+        wallet: Address,
+        from: u64,
+        to: u64,
+    ) -> Result<Vec<HistoryRecord>> {
+        let filter = TraceFilter::default()
+            .from_block(from)
+            .to_block(to)
+            .from_address(vec![wallet])
+            .to_address(self.routers());
+
+        let traces = self.provider.trace_filter(&filter).await?;
+        Ok(traces
+            .into_iter()
+            .filter(|trace| trace.trace.error.is_none())
+            .filter_map(|trace| {
+                let Action::Call(action) = &trace.trace.action else {
+                    return None;
+                };
+                let call = decode_router_calldata(&action.input).ok()?;
+                Some(HistoryRecord {
+                    block: trace.block_number.unwrap_or_default(),
+                    transaction_hash: trace.transaction_hash.unwrap_or_default(),
+                    log_index: None,
+                    kind: HistoryRecordKind::RouterCall {
+                        router: action.to,
+                        call,
+                    },
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_approval_filter_matches_router_spenders() {
+        let wallet = fixtures::FIXTURE_SENDER;
+        let routers = [Address::repeat_byte(0x11), Address::repeat_byte(0x22)];
+        let filter = approval_filter(wallet, &routers, 10, 20);
+
+        assert!(filter.topics[0].matches(&IToken::Approval::SIGNATURE_HASH));
+        assert!(filter.topics[1].matches(&wallet.into_word()));
+        for router in routers {
+            assert!(filter.topics[2].matches(&router.into_word()));
+        }
+        assert!(!filter.topics[2].matches(&Address::repeat_byte(0x33).into_word()));
+        assert_eq!(filter.get_from_block(), Some(10));
+        assert_eq!(filter.get_to_block(), Some(20));
+    }
+
+    #[test]
+    fn test_decode_approval_of_owner() {
+        let owner = fixtures::FIXTURE_SENDER;
+        let spender = Address::repeat_byte(0x55);
+        let approval = IToken::Approval {
+            owner,
+            spender,
+            value: U256::from(7u64),
+        };
+        let mut log = fixtures::curve_buy().log;
+        log.inner.address = fixtures::FIXTURE_TOKEN;
+        log.inner.data = approval.encode_log_data();
+
+        let record = decode_approval(&log, owner).unwrap();
+        assert_eq!(record.block, fixtures::FIXTURE_BLOCK);
+        assert_eq!(record.transaction_hash, fixtures::FIXTURE_TX_HASH);
+        assert_eq!(record.token(), fixtures::FIXTURE_TOKEN);
+        let HistoryRecordKind::Approve {
+            spender: s, value, ..
+        } = record.kind
+        else {
+            panic!("expected an approval");
+        };
+        assert_eq!((s, value), (spender, U256::from(7u64)));
+
+        assert!(decode_approval(&log, spender).is_none());
+        assert!(decode_approval(&fixtures::curve_buy().log, owner).is_none());
+    }
+}
@@ -3,16 +3,128 @@
 //! This module is organized by functionality:
 //! - `curve`: Bonding curve event streaming and indexing
 //! - `dex`: DEX (Uniswap V3) event streaming and indexing
+//! - `handlers`: Callback-based event handling driven by a background task
+//! - `enrich`: Transaction origin (`tx.from`) enrichment for events
+//! - `normalize`: Decimal-adjusted amounts for curve and swap events (feature `experimental`)
+//! - `value`: MON and optional USD value of trades and swaps (feature `experimental`)
+//! - `predicate`: Composable event filters shared by streams and indexers
+//! - `progress`: Progress, ETA and batch retries of long indexer backfills
+//! - `blocks`: New block header notifications
+//! - `analytics`: Volume leaderboards, creator reports and other event aggregations (feature `experimental`)
+//! - `catalog`: Searchable catalog of launched tokens built from Create events (feature `experimental`)
+//! - `shared`: Several streams multiplexed over one WebSocket connection
+//! - `dedup`: Drops events delivered twice across reconnects and backfills (feature `experimental`)
+//! - `redundant`: One deduplicated feed from the same subscription on several endpoints (feature `experimental`)
+//! - `decode_policy`: Skip, yield or abort on undecodable logs, with counters
+//! - `grouping`: Per-block and per-transaction batching of event streams (`by_block()`, feature `experimental`)
+//! - `pause`: Pausing a stream while buffering its events (`pausable()`, feature `experimental`)
+//! - `replay`: Deterministic replay of recorded events for consumer tests
+//! - `wallet`: Watch-only monitoring of trades, transfers and balances of wallets
+//! - `history`: Historical trades, launches and router approvals of a wallet (feature `experimental`)
+//! - `transaction`: Every curve, pool and transfer event of one transaction by hash (feature `experimental`)
+//! - `monitor`: Many per-token monitoring tasks over one subscription (`MonitorSet`, feature `experimental`)
+//! - `queue`: Disk-backed at-least-once delivery (feature `persistent-queue`)
+//! - `columnar`: Arrow record batch export of indexed events (feature `arrow`)
 //!
 //! Both support real-time streaming and historical indexing with
 //! 2-stage filtering capabilities.
 
+#[cfg(feature = "experimental")]
+pub mod analytics;
+pub mod blocks;
+#[cfg(feature = "experimental")]
+pub mod catalog;
+#[cfg(feature = "arrow")]
+pub mod columnar;
 pub mod curve;
+pub mod decode_policy;
+#[cfg(feature = "experimental")]
+pub mod dedup;
 pub mod dex;
+pub mod enrich;
+#[cfg(feature = "experimental")]
+pub mod grouping;
+pub mod handlers;
+#[cfg(feature = "experimental")]
+pub mod history;
+#[cfg(feature = "experimental")]
+pub mod monitor;
+#[cfg(feature = "experimental")]
+pub mod normalize;
+pub mod ordering;
+#[cfg(feature = "experimental")]
+pub mod pause;
+pub mod predicate;
+pub mod progress;
+#[cfg(feature = "persistent-queue")]
+pub mod queue;
+#[cfg(feature = "experimental")]
+pub mod redundant;
+pub mod replay;
+pub mod shared;
+#[cfg(feature = "experimental")]
+pub mod transaction;
+#[cfg(feature = "experimental")]
+pub mod value;
+pub mod wallet;
 
 // Re-export main functionality
-pub use curve::{CurveIndexer, CurveStream};
-pub use dex::{UniswapSwapIndexer, UniswapSwapStream};
+#[cfg(feature = "experimental")]
+pub use analytics::{
+    CreatedToken, CreatorReport, LeaderboardSort, TokenVolume, VolumeLeaderboard,
+};
+pub use blocks::{BlockHeaderInfo, BlockStream};
+#[cfg(feature = "experimental")]
+pub use catalog::{CatalogEntry, TokenCatalog};
+#[cfg(feature = "arrow")]
+pub use columnar::{
+    curve_events_schema, curve_events_to_record_batch, swap_events_schema,
+    swap_events_to_record_batch,
+};
+pub use curve::{
+    CatchUpProgress, CurveIndexer, CurvePoint, CurveStream, DynamicFilter, FilterHandle,
+    curve_points,
+};
+pub use decode_policy::{DecodeErrorPolicy, DecodeStats};
+#[cfg(feature = "experimental")]
+pub use dedup::{DedupEvent, DedupKey, DedupStats, Deduplicator};
+pub use dex::{DexPriceStream, PriceTick, UniswapSwapIndexer, UniswapSwapStream};
+pub use enrich::{Enriched, OriginResolver, TransactionEvent};
+#[cfg(feature = "experimental")]
+pub use grouping::{
+    BlockEvents, EventStreamExt, TxActivity, TxStreamExt, group_by_block,
+    group_by_block_with_heads, group_by_transaction, group_stream_by_transaction,
+};
+pub use handlers::{EventHandlers, HandlerErrors, SubscriptionHandle};
+#[cfg(feature = "experimental")]
+pub use history::{HistoryRecord, HistoryRecordKind, WalletHistory};
+#[cfg(feature = "experimental")]
+pub use monitor::{MonitorConfig, MonitorError, MonitorSet, MonitorStats};
+#[cfg(feature = "experimental")]
+pub use normalize::{
+    AmountNormalizer, EnrichedCurveEvent, EnrichedSwapEvent, format_amount, to_decimal,
+    to_decimal_signed,
+};
+pub use ordering::{BlockNumbered, order_by_block};
+#[cfg(feature = "experimental")]
+pub use pause::{PausableStream, PauseConfig, PauseHandle, pausable};
+pub use predicate::{Predicate, PredicateTarget};
+pub use progress::{IndexProgress, IndexProgressTracker, estimate_eta};
+#[cfg(feature = "persistent-queue")]
+pub use queue::{PersistentQueue, QueueConfig, QueuedEvent};
+#[cfg(feature = "experimental")]
+pub use redundant::{DEFAULT_REDUNDANT_DEDUP_WINDOW, FeedHealth, RedundantStream};
+pub use replay::{ReplayEvent, ReplayStream, ReplayTiming};
+pub use shared::SharedWsClient;
+#[cfg(feature = "experimental")]
+pub use transaction::{
+    DecodedEvent, DecodedTransaction, decode_transaction, decode_transaction_logs,
+};
+#[cfg(feature = "experimental")]
+pub use value::{TradeValuer, ValuedEvent, swap_mon_value};
+pub use wallet::{
+    TransferEvent, WalletActivity, WalletActivityKind, WalletMonitor, decode_transfer_event,
+};
 
 // Re-export types from the types module
 pub use crate::types::{
@@ -28,9 +140,19 @@ pub use crate::types::{
     SellEvent,
     // Uniswap types
     SwapEvent,
+    DexEvent,
+    DexEventType,
+    MintEvent,
+    BurnEvent,
+    CollectEvent,
     SyncEvent,
     decode_bonding_curve_event,
     decode_swap_event,
+    decode_dex_event,
+    // Log filters matching the decoders above
+    curve_filter,
+    swap_filter,
+    dex_filter,
 };
 
 /// Usage Examples:
@@ -62,7 +184,6 @@ pub use crate::types::{
 ///     Ok(())
 /// }
 /// ```
-
 #[cfg(test)]
 mod tests {
     use crate::types::*;
@@ -100,14 +221,17 @@ mod tests {
             virtual_token: U256::from(1000000),
             target_token_amount: U256::from(1000000),
             block_number: 100,
+            block_hash: B256::ZERO,
             transaction_hash: B256::ZERO,
             transaction_index: 0,
             log_index: 0,
+            removed: false,
         };
 
         let event = BondingCurveEvent::Create(create_event);
         assert_eq!(event.block_number(), 100);
         assert_eq!(event.token(), Address::ZERO);
         assert_eq!(event.event_type(), EventType::Create);
+        assert!(!event.removed());
     }
 }
@@ -0,0 +1,498 @@
+//! Many per-token monitors over one subscription
+//!
+//! A [`MonitorSet`] runs one task per watched token. A single WebSocket
+//! subscription to the bonding curve feeds all of them; each task applies its
+//! token's events to an optional [`TokenStateCache`], alert rules (feature
+//! `alerts`) and the event handler, in order. Tokens can be added and removed
+//! while it runs:
+//!
+//! ```rust,ignore
+//! let mut monitors = MonitorSet::new(ws_url)
+//!     .await?
+//!     .with_config(MonitorConfig::default().with_max_monitors(500))
+//!     .with_state_cache(cache.clone())
+//!     .on_event(|event| async move {
+//!         println!("{event}");
+//!         Ok(())
+//!     });
+//! monitors.start().await?;
+//!
+//! monitors.add(token_a)?;
+//! monitors.add(token_b)?;
+//! // ... later
+//! monitors.remove(token_a);
+//! for failure in monitors.take_errors() {
+//!     eprintln!("{:?}: {}", failure.token, failure.error);
+//! }
+//! monitors.shutdown().await?;
+//! ```
+//!
+//! Resources are bounded: at most [`MonitorConfig::max_monitors`] tasks, each
+//! with a queue of [`MonitorConfig::channel_capacity`] events. A monitor whose
+//! queue is full drops new events and counts them in [`MonitorStats::dropped`]
+//! instead of slowing down the others.
+
+#[cfg(feature = "alerts")]
+use crate::alerts::Alerter;
+use crate::{
+    stream::handlers::SubscriptionHandle,
+    token_state::TokenStateCache,
+    types::{BondingCurveEvent, EventType, curve_filter, decode_bonding_curve_event},
+};
+use alloy::{
+    primitives::Address,
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+};
+use anyhow::Result;
+use futures_util::{StreamExt, future::BoxFuture};
+use std::{
+    collections::{HashMap, VecDeque},
+    future::Future,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+
+type EventHandler = Arc<dyn Fn(BondingCurveEvent) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+/// Limits of a [`MonitorSet`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorConfig {
+    /// Maximum number of monitored tokens (default 256)
+    pub max_monitors: usize,
+    /// Events queued per monitor before new ones are dropped (default 1024)
+    pub channel_capacity: usize,
+    /// Errors kept for [`MonitorSet::take_errors`]; older ones are discarded
+    /// (default 100)
+    pub max_errors: usize,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            max_monitors: 256,
+            channel_capacity: 1024,
+            max_errors: 100,
+        }
+    }
+}
+
+impl MonitorConfig {
+    pub fn with_max_monitors(mut self, max_monitors: usize) -> Self {
+        self.max_monitors = max_monitors;
+        self
+    }
+
+    pub fn with_channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn with_max_errors(mut self, max_errors: usize) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+}
+
+/// An error of one monitor, or of the shared subscription (`token` is `None`)
+#[derive(Debug)]
+pub struct MonitorError {
+    pub token: Option<Address>,
+    pub error: anyhow::Error,
+}
+
+/// Counters of one monitor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MonitorStats {
+    /// Events processed by the monitor task
+    pub events: u64,
+    /// Events dropped because the monitor's queue was full
+    pub dropped: u64,
+    /// Failed event handlings
+    pub errors: u64,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    events: AtomicU64,
+    dropped: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Counters {
+    fn snapshot(&self) -> MonitorStats {
+        MonitorStats {
+            events: self.events.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+struct Monitor {
+    tx: mpsc::Sender<BondingCurveEvent>,
+    counters: Arc<Counters>,
+    task: JoinHandle<()>,
+}
+
+/// State shared by the dispatcher and the monitor tasks
+#[derive(Default)]
+struct Shared {
+    monitors: Mutex<HashMap<Address, Monitor>>,
+    errors: Mutex<VecDeque<MonitorError>>,
+    /// Errors reported since creation, including discarded ones
+    error_count: AtomicU64,
+    max_errors: usize,
+}
+
+impl Shared {
+    fn report(&self, token: Option<Address>, error: anyhow::Error) {
+        self.error_count.fetch_add(1, Ordering::Relaxed);
+        let mut errors = self.errors.lock().unwrap_or_else(|e| e.into_inner());
+        errors.push_back(MonitorError { token, error });
+        while errors.len() > self.max_errors {
+            errors.pop_front();
+        }
+    }
+
+    /// Queue `event` for its token's monitor, if there is one
+    fn route(&self, event: BondingCurveEvent) {
+        let monitors = self.monitors.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(monitor) = monitors.get(&event.token()) else {
+            return;
+        };
+        if let Err(mpsc::error::TrySendError::Full(_)) = monitor.tx.try_send(event) {
+            monitor.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// What each monitor task does with its token's events
+#[derive(Clone, Default)]
+struct Pipeline {
+    state: Option<TokenStateCache>,
+    #[cfg(feature = "alerts")]
+    alerter: Option<Arc<Alerter>>,
+    handler: Option<EventHandler>,
+}
+
+impl Pipeline {
+    async fn process(&self, event: BondingCurveEvent) -> Result<()> {
+        if let Some(state) = &self.state {
+            state.apply_event(&event);
+        }
+        #[cfg(feature = "alerts")]
+        if let Some(alerter) = &self.alerter {
+            alerter.handle(&event).await?;
+        }
+        match &self.handler {
+            Some(handler) => handler(event).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Per-token monitoring tasks sharing one bonding curve subscription
+pub struct MonitorSet {
+    provider: Arc<DynProvider>,
+    config: MonitorConfig,
+    pipeline: Pipeline,
+    shared: Arc<Shared>,
+    subscription: Option<SubscriptionHandle>,
+}
+
+impl MonitorSet {
+    /// Open the WebSocket connection
+    pub async fn new(rpc_url: String) -> Result<MonitorSet> {
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+
+        Ok(MonitorSet::from_provider(Arc::new(DynProvider::new(
+            provider,
+        ))))
+    }
+
+    /// Reuse an existing WebSocket provider
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        let config = MonitorConfig::default();
+        Self {
+            provider,
+            config,
+            pipeline: Pipeline::default(),
+            shared: Arc::new(Shared {
+                max_errors: config.max_errors,
+                ..Shared::default()
+            }),
+            subscription: None,
+        }
+    }
+
+    /// Set the limits; call before adding tokens
+    pub fn with_config(mut self, config: MonitorConfig) -> Self {
+        self.config = config;
+        self.shared = Arc::new(Shared {
+            max_errors: config.max_errors,
+            ..Shared::default()
+        });
+        self
+    }
+
+    /// Apply Lock/Listed events of monitored tokens to `cache`
+    pub fn with_state_cache(mut self, cache: TokenStateCache) -> Self {
+        self.pipeline.state = Some(cache);
+        self
+    }
+
+    /// Evaluate and deliver alerts for every event of a monitored token
+    #[cfg(feature = "alerts")]
+    pub fn with_alerter(mut self, alerter: Alerter) -> Self {
+        self.pipeline.alerter = Some(Arc::new(alerter));
+        self
+    }
+
+    /// Call `handler` for every event of a monitored token
+    ///
+    /// Events of one token are handled one at a time, in order; different
+    /// tokens are handled concurrently.
+    pub fn on_event<F, Fut>(mut self, handler: F) -> Self
+    where
+        F: Fn(BondingCurveEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.pipeline.handler = Some(Arc::new(move |event| Box::pin(handler(event))));
+        self
+    }
+
+    /// Open the shared subscription
+    ///
+    /// Monitors added before or after receive events from now on. Subscription
+    /// and decoding errors are reported through [`MonitorSet::take_errors`].
+    pub async fn start(&mut self) -> Result<()> {
+        if self.subscription.is_some() {
+            return Err(anyhow::anyhow!("MonitorSet is already started"));
+        }
+
+        let filter = curve_filter(&EventType::all(), None, None);
+        let mut logs = self.provider.subscribe_logs(&filter).await?.into_stream();
+        let shared = self.shared.clone();
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    next = logs.next() => match next {
+                        Some(log) => match decode_bonding_curve_event(log) {
+                            Ok(event) => shared.route(event),
+                            Err(e) => shared.report(None, e),
+                        },
+                        None => {
+                            shared.report(None, anyhow::anyhow!("Curve subscription ended"));
+                            break;
+                        }
+                    },
+                }
+            }
+        });
+
+        self.subscription = Some(SubscriptionHandle::new(shutdown_tx, task));
+        Ok(())
+    }
+
+    /// Start monitoring `token`
+    ///
+    /// Adding a token that is already monitored does nothing. Fails once
+    /// [`MonitorConfig::max_monitors`] tokens are monitored.
+    pub fn add(&self, token: Address) -> Result<()> {
+        let mut monitors = self
+            .shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if monitors.contains_key(&token) {
+            return Ok(());
+        }
+        if monitors.len() >= self.config.max_monitors {
+            return Err(anyhow::anyhow!(
+                "Cannot monitor {}: limit of {} monitors reached",
+                token,
+                self.config.max_monitors
+            ));
+        }
+
+        let (tx, mut rx) = mpsc::channel(self.config.channel_capacity);
+        let counters = Arc::new(Counters::default());
+        let pipeline = self.pipeline.clone();
+        let shared = Arc::downgrade(&self.shared);
+        let task_counters = counters.clone();
+        let task = tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                task_counters.events.fetch_add(1, Ordering::Relaxed);
+                if let Err(e) = pipeline.process(event).await {
+                    task_counters.errors.fetch_add(1, Ordering::Relaxed);
+                    match shared.upgrade() {
+                        Some(shared) => shared.report(Some(token), e),
+                        None => break,
+                    }
+                }
+            }
+        });
+
+        monitors.insert(token, Monitor { tx, counters, task });
+        Ok(())
+    }
+
+    /// Stop monitoring `token`
+    ///
+    /// Events already queued for it are still processed. Returns false if the
+    /// token was not monitored.
+    pub fn remove(&self, token: Address) -> bool {
+        self.shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&token)
+            .is_some()
+    }
+
+    /// True if `token` is monitored
+    pub fn contains(&self, token: Address) -> bool {
+        self.shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(&token)
+    }
+
+    /// Monitored tokens
+    pub fn tokens(&self) -> Vec<Address> {
+        self.shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .copied()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counters of a monitored token
+    pub fn stats(&self, token: Address) -> Option<MonitorStats> {
+        self.shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&token)
+            .map(|monitor| monitor.counters.snapshot())
+    }
+
+    /// Drain the errors collected since the last call, oldest first
+    pub fn take_errors(&self) -> Vec<MonitorError> {
+        self.shared
+            .errors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain(..)
+            .collect()
+    }
+
+    /// Errors reported since creation, including ones no longer kept
+    pub fn error_count(&self) -> u64 {
+        self.shared.error_count.load(Ordering::Relaxed)
+    }
+
+    /// True while the shared subscription is running
+    pub fn is_running(&self) -> bool {
+        self.subscription
+            .as_ref()
+            .is_some_and(|subscription| !subscription.is_finished())
+    }
+
+    /// Close the subscription and wait for every monitor to finish its queue
+    pub async fn shutdown(self) -> Result<()> {
+        if let Some(subscription) = self.subscription {
+            subscription.shutdown().await?;
+        }
+        let monitors: Vec<Monitor> = self
+            .shared
+            .monitors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .map(|(_, monitor)| monitor)
+            .collect();
+        for monitor in monitors {
+            drop(monitor.tx);
+            monitor.task.await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use std::time::Duration;
+
+    fn monitor_set() -> MonitorSet {
+        MonitorSet::from_provider(Arc::new(DynProvider::new(
+            ProviderBuilder::new().connect_http("http://localhost:8545".parse().unwrap()),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_routes_events_to_token_monitors() {
+        let seen = Arc::new(AtomicU64::new(0));
+        let counter = seen.clone();
+        let monitors = monitor_set()
+            .with_config(MonitorConfig::default().with_max_monitors(2))
+            .on_event(move |event| {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    if matches!(event, BondingCurveEvent::Sell(_)) {
+                        return Err(anyhow::anyhow!("sell failed"));
+                    }
+                    Ok(())
+                }
+            });
+
+        monitors.add(fixtures::FIXTURE_TOKEN).unwrap();
+        monitors.add(Address::repeat_byte(0x44)).unwrap();
+        assert!(monitors.add(Address::repeat_byte(0x55)).is_err());
+        assert_eq!(monitors.len(), 2);
+
+        monitors.shared.route(fixtures::curve_buy().expected);
+        monitors.shared.route(fixtures::curve_sell().expected);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        assert_eq!(seen.load(Ordering::Relaxed), 2);
+        let stats = monitors.stats(fixtures::FIXTURE_TOKEN).unwrap();
+        assert_eq!((stats.events, stats.errors, stats.dropped), (2, 1, 0));
+        let errors = monitors.take_errors();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].token, Some(fixtures::FIXTURE_TOKEN));
+
+        assert!(monitors.remove(fixtures::FIXTURE_TOKEN));
+        monitors.shared.route(fixtures::curve_buy().expected);
+        monitors.shutdown().await.unwrap();
+        assert_eq!(seen.load(Ordering::Relaxed), 2);
+    }
+}
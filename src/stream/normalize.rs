@@ -0,0 +1,266 @@
+//! Decimal-adjusted amounts for curve and swap events
+//!
+//! Event amounts are raw integer units. Showing or aggregating them means
+//! looking up each token's decimals and scaling, which every consumer ends up
+//! repeating. An [`AmountNormalizer`] does both once, with decimals from a
+//! shared [`MetadataCache`]:
+//!
+//! ```rust,ignore
+//! let normalizer = Arc::new(AmountNormalizer::new(provider.clone()).with_cache(cache.clone()));
+//! let events = normalizer.normalize_curve_stream(curve_stream.subscribe().await?);
+//! pin_mut!(events);
+//!
+//! while let Some(Ok(event)) = events.next().await {
+//!     if let (Some(mon), Some(tokens)) = (event.mon_amount, event.token_amount) {
+//!         println!("{:?}: {} tokens for {} MON", event.event.event_type(), tokens, mon);
+//!     }
+//! }
+//! ```
+//!
+//! Values are `f64`, fine for display and statistics; use [`format_amount`]
+//! for an exact decimal string.
+
+use crate::{
+    metadata::MetadataCache,
+    types::{BondingCurveEvent, SwapEvent},
+};
+use alloy::{
+    primitives::{Address, I256, U256, utils::format_units},
+    providers::Provider,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::sync::Arc;
+
+/// Decimals of MON (and WMON)
+pub(crate) const MON_DECIMALS: u8 = 18;
+
+/// `value` in whole units of a token with `decimals`
+pub fn to_decimal(value: U256, decimals: u8) -> f64 {
+    f64::from(value) / 10f64.powi(decimals as i32)
+}
+
+/// Signed [`to_decimal`], for swap deltas
+pub fn to_decimal_signed(value: I256, decimals: u8) -> f64 {
+    let magnitude = to_decimal(value.unsigned_abs(), decimals);
+    if value.is_negative() {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Exact decimal string of `value` (e.g. `"1.500000000000000000"`)
+pub fn format_amount(value: U256, decimals: u8) -> String {
+    format_units(value, decimals).unwrap_or_else(|_| value.to_string())
+}
+
+/// A bonding curve event with decimal-adjusted amounts
+///
+/// Amount fields are None for events without them (Create, Sync, Lock, Listed).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichedCurveEvent {
+    pub event: BondingCurveEvent,
+    pub token_decimals: u8,
+    /// `amount_in` in whole units: MON for buys, tokens for sells
+    pub amount_in: Option<f64>,
+    /// `amount_out` in whole units: tokens for buys, MON for sells
+    pub amount_out: Option<f64>,
+    /// MON side of a trade
+    pub mon_amount: Option<f64>,
+    /// Token side of a trade
+    pub token_amount: Option<f64>,
+    /// Execution price in MON per token
+    pub price: Option<f64>,
+}
+
+impl EnrichedCurveEvent {
+    pub fn new(event: BondingCurveEvent, token_decimals: u8) -> Self {
+        let (mon, tokens, mon_is_input) = match &event {
+            BondingCurveEvent::Buy(e) => (Some(e.amount_in), Some(e.amount_out), true),
+            BondingCurveEvent::Sell(e) => (Some(e.amount_out), Some(e.amount_in), false),
+            _ => (None, None, false),
+        };
+        let mon_amount = mon.map(|v| to_decimal(v, MON_DECIMALS));
+        let token_amount = tokens.map(|v| to_decimal(v, token_decimals));
+        let price = match (mon_amount, token_amount) {
+            (Some(mon), Some(tokens)) if tokens > 0.0 => Some(mon / tokens),
+            _ => None,
+        };
+        let (amount_in, amount_out) = if mon_is_input {
+            (mon_amount, token_amount)
+        } else {
+            (token_amount, mon_amount)
+        };
+
+        Self {
+            event,
+            token_decimals,
+            amount_in,
+            amount_out,
+            mon_amount,
+            token_amount,
+            price,
+        }
+    }
+}
+
+/// A swap with decimal-adjusted token deltas
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnrichedSwapEvent {
+    pub event: SwapEvent,
+    pub token0: Address,
+    pub token1: Address,
+    pub token0_decimals: u8,
+    pub token1_decimals: u8,
+    /// Pool's token0 delta in whole units (positive: into the pool)
+    pub amount0: f64,
+    /// Pool's token1 delta in whole units (positive: into the pool)
+    pub amount1: f64,
+}
+
+impl EnrichedSwapEvent {
+    pub fn new(
+        event: SwapEvent,
+        (token0, token0_decimals): (Address, u8),
+        (token1, token1_decimals): (Address, u8),
+    ) -> Self {
+        Self {
+            amount0: to_decimal_signed(event.amount0, token0_decimals),
+            amount1: to_decimal_signed(event.amount1, token1_decimals),
+            event,
+            token0,
+            token1,
+            token0_decimals,
+            token1_decimals,
+        }
+    }
+
+    /// Whole units of `token` exchanged, absolute; None if it isn't in the pool
+    pub fn abs_amount_of(&self, token: Address) -> Option<f64> {
+        if token == self.token0 {
+            Some(self.amount0.abs())
+        } else if token == self.token1 {
+            Some(self.amount1.abs())
+        } else {
+            None
+        }
+    }
+}
+
+/// Converts event amounts to whole units using cached token decimals
+pub struct AmountNormalizer<P> {
+    provider: Arc<P>,
+    cache: MetadataCache,
+}
+
+impl<P: Provider + Clone> AmountNormalizer<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self {
+            provider,
+            cache: MetadataCache::new(),
+        }
+    }
+
+    /// Share decimal and pool lookups with other components
+    pub fn with_cache(mut self, cache: MetadataCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn cache(&self) -> &MetadataCache {
+        &self.cache
+    }
+
+    pub async fn normalize_curve(&self, event: BondingCurveEvent) -> Result<EnrichedCurveEvent> {
+        let decimals = self
+            .cache
+            .decimals(self.provider.as_ref(), event.token())
+            .await?;
+        Ok(EnrichedCurveEvent::new(event, decimals))
+    }
+
+    pub async fn normalize_swap(&self, event: SwapEvent) -> Result<EnrichedSwapEvent> {
+        let provider = self.provider.as_ref();
+        let tokens = self.cache.pool_tokens(provider, event.pool_address).await?;
+        let decimals0 = self.cache.decimals(provider, tokens.token0).await?;
+        let decimals1 = self.cache.decimals(provider, tokens.token1).await?;
+        Ok(EnrichedSwapEvent::new(
+            event,
+            (tokens.token0, decimals0),
+            (tokens.token1, decimals1),
+        ))
+    }
+
+    /// Normalize a batch of indexed curve events, preserving order
+    pub async fn normalize_curve_events(
+        &self,
+        events: Vec<BondingCurveEvent>,
+    ) -> Result<Vec<EnrichedCurveEvent>> {
+        let mut normalized = Vec::with_capacity(events.len());
+        for event in events {
+            normalized.push(self.normalize_curve(event).await?);
+        }
+        Ok(normalized)
+    }
+
+    /// Normalize every event of a live curve stream
+    pub fn normalize_curve_stream<S>(
+        self: Arc<Self>,
+        inner: S,
+    ) -> impl Stream<Item = Result<EnrichedCurveEvent>> + Send
+    where
+        S: Stream<Item = Result<BondingCurveEvent>> + Send + 'static,
+        P: Send + Sync + 'static,
+    {
+        inner.then(move |item| {
+            let normalizer = self.clone();
+            async move { normalizer.normalize_curve(item?).await }
+        })
+    }
+
+    /// Normalize every event of a live swap stream
+    pub fn normalize_swap_stream<S>(
+        self: Arc<Self>,
+        inner: S,
+    ) -> impl Stream<Item = Result<EnrichedSwapEvent>> + Send
+    where
+        S: Stream<Item = Result<SwapEvent>> + Send + 'static,
+        P: Send + Sync + 'static,
+    {
+        inner.then(move |item| {
+            let normalizer = self.clone();
+            async move { normalizer.normalize_swap(item?).await }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_normalized_amounts() {
+        let BondingCurveEvent::Buy(mut buy) = fixtures::curve_buy().expected else {
+            unreachable!()
+        };
+        buy.amount_in = U256::from(15u64) * U256::from(10u64).pow(U256::from(17));
+        buy.amount_out = U256::from(3_000_000u64);
+        let enriched = EnrichedCurveEvent::new(BondingCurveEvent::Buy(buy), 6);
+
+        assert_eq!(enriched.amount_in, Some(1.5));
+        assert_eq!(enriched.amount_out, Some(3.0));
+        assert_eq!(enriched.price, Some(0.5));
+
+        let swap = fixtures::swap().expected;
+        let (token0, token1) = (Address::repeat_byte(1), Address::repeat_byte(2));
+        let enriched = EnrichedSwapEvent::new(swap.clone(), (token0, 18), (token1, 18));
+        assert_eq!(enriched.amount0, to_decimal_signed(swap.amount0, 18));
+        assert_eq!(enriched.abs_amount_of(token1), Some(enriched.amount1.abs()));
+        assert!(enriched.abs_amount_of(Address::ZERO).is_none());
+
+        assert_eq!(to_decimal_signed(I256::try_from(-2_500).unwrap(), 3), -2.5);
+        assert_eq!(format_amount(U256::from(1_500u64), 3), "1.500");
+    }
+}
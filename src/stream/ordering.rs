@@ -0,0 +1,120 @@
+//! Block-window ordering buffer for subscription streams
+//!
+//! WebSocket subscriptions can deliver logs slightly out of order (especially across
+//! multiple pools). The buffer holds events until the stream has advanced `window`
+//! blocks past them, then releases them sorted by (block, tx_index, log_index).
+
+use crate::types::{BondingCurveEvent, DexEvent, SwapEvent};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::collections::BTreeMap;
+
+/// Events that know the block they were emitted in
+pub trait BlockNumbered {
+    fn block_number(&self) -> u64;
+}
+
+impl BlockNumbered for BondingCurveEvent {
+    fn block_number(&self) -> u64 {
+        BondingCurveEvent::block_number(self)
+    }
+}
+
+impl BlockNumbered for SwapEvent {
+    fn block_number(&self) -> u64 {
+        self.block_number
+    }
+}
+
+impl BlockNumbered for DexEvent {
+    fn block_number(&self) -> u64 {
+        DexEvent::block_number(self)
+    }
+}
+
+/// Sort key used for deterministic event ordering
+pub type EventOrderKey = (u64, u64, u64);
+
+/// Reorder a stream of events within a window of `window` blocks
+///
+/// Events from block `n` are released once an event from a block greater than
+/// `n + window` has been seen, or when the inner stream ends. Errors are passed
+/// through immediately. Events arriving after their block was already released
+/// are yielded as soon as they arrive.
+pub fn order_by_block<T, S, K>(inner: S, window: u64, key: K) -> impl Stream<Item = Result<T>>
+where
+    S: Stream<Item = Result<T>>,
+    K: Fn(&T) -> EventOrderKey,
+{
+    async_stream::stream! {
+        let mut buffer: BTreeMap<EventOrderKey, Vec<T>> = BTreeMap::new();
+        let mut highest_block = 0u64;
+
+        futures_util::pin_mut!(inner);
+
+        while let Some(item) = inner.next().await {
+            let event = match item {
+                Ok(event) => event,
+                Err(e) => {
+                    yield Err(e);
+                    continue;
+                }
+            };
+
+            let event_key = key(&event);
+            highest_block = highest_block.max(event_key.0);
+            buffer.entry(event_key).or_default().push(event);
+
+            // Release everything that is now outside the reorder window
+            while let Some(entry) = buffer.first_entry() {
+                if entry.key().0.saturating_add(window) >= highest_block {
+                    break;
+                }
+                for event in entry.remove() {
+                    yield Ok(event);
+                }
+            }
+        }
+
+        for (_, events) in buffer {
+            for event in events {
+                yield Ok(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+
+    #[tokio::test]
+    async fn test_order_by_block_sorts_within_window() {
+        let input: Vec<Result<EventOrderKey>> = vec![
+            Ok((10, 1, 0)),
+            Ok((10, 0, 3)),
+            Ok((11, 0, 0)),
+            Ok((10, 0, 1)),
+            Ok((13, 0, 0)),
+            Ok((12, 2, 0)),
+        ];
+
+        let ordered: Vec<EventOrderKey> = order_by_block(stream::iter(input), 1, |k| *k)
+            .map(|r| r.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(
+            ordered,
+            vec![
+                (10, 0, 1),
+                (10, 0, 3),
+                (10, 1, 0),
+                (11, 0, 0),
+                (12, 2, 0),
+                (13, 0, 0),
+            ]
+        );
+    }
+}
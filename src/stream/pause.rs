@@ -0,0 +1,503 @@
+//! Pausing consumption of an event stream without losing events
+//!
+//! [`pausable`] (or [`EventStreamExt::pausable`](crate::stream::EventStreamExt::pausable))
+//! wraps a subscription in a [`PausableStream`]. A background task keeps reading the
+//! subscription while the consumer is paused, so the connection doesn't lag behind;
+//! events are buffered and replayed in order once the stream is resumed:
+//!
+//! ```rust,ignore
+//! let mut events = curve_stream.subscribe().await?.pausable(PauseConfig::default());
+//! let control = events.handle();
+//!
+//! // From any task, e.g. around a database migration
+//! control.pause();
+//! migrate().await?;
+//! control.resume();
+//!
+//! while let Some(event) = events.next().await { /* ... */ }
+//! ```
+//!
+//! At most `max_buffered` events are held in memory. Once that is reached while
+//! paused, further events go to a spill file (feature `persistent-queue`, with
+//! [`PauseConfig::with_spill_dir`]); without a spill directory the reader stops
+//! reading until the consumer resumes, and the subscription's own buffer applies.
+//! The spill file is temporary: it does not survive a restart of the process.
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+#[cfg(feature = "persistent-queue")]
+use serde::Deserialize;
+#[cfg(feature = "persistent-queue")]
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::atomic::AtomicU64,
+};
+
+/// Buffering settings of a [`PausableStream`]
+#[derive(Debug, Clone)]
+pub struct PauseConfig {
+    /// Events held in memory, paused or not, before spilling or backpressure
+    pub max_buffered: usize,
+    /// Directory for the spill file used once the memory buffer is full
+    #[cfg(feature = "persistent-queue")]
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for PauseConfig {
+    fn default() -> Self {
+        Self {
+            max_buffered: 10_000,
+            #[cfg(feature = "persistent-queue")]
+            spill_dir: None,
+        }
+    }
+}
+
+impl PauseConfig {
+    pub fn with_max_buffered(mut self, max_buffered: usize) -> Self {
+        self.max_buffered = max_buffered.max(1);
+        self
+    }
+
+    /// Spill events beyond `max_buffered` to a temporary file in `dir` while paused
+    #[cfg(feature = "persistent-queue")]
+    pub fn with_spill_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.spill_dir = Some(dir.into());
+        self
+    }
+}
+
+/// State shared by [`PauseHandle`]s and the reader task
+struct PauseControl {
+    paused: AtomicBool,
+    buffered: AtomicUsize,
+    /// Wakes the consumer: new event, end of stream or resume
+    consumer: Notify,
+    /// Wakes the reader: buffer space freed or pause
+    reader: Notify,
+}
+
+/// Pauses and resumes a [`PausableStream`] from any task
+///
+/// Cheap to clone; all clones control the same stream.
+#[derive(Clone)]
+pub struct PauseHandle {
+    control: Arc<PauseControl>,
+}
+
+impl PauseHandle {
+    /// Stop handing events to the consumer; they are buffered until `resume`
+    pub fn pause(&self) {
+        self.control.paused.store(true, Ordering::SeqCst);
+        // A reader waiting for memory space may now spill instead
+        self.control.reader.notify_one();
+    }
+
+    /// Replay buffered events in order, then continue with live events
+    pub fn resume(&self) {
+        self.control.paused.store(false, Ordering::SeqCst);
+        self.control.consumer.notify_one();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.control.paused.load(Ordering::SeqCst)
+    }
+
+    /// Events read from the subscription but not yet handed to the consumer
+    pub fn buffered(&self) -> usize {
+        self.control.buffered.load(Ordering::SeqCst)
+    }
+}
+
+struct Buffer<T> {
+    memory: VecDeque<Result<T>>,
+    #[cfg(feature = "persistent-queue")]
+    spill: Option<SpillFile>,
+    /// Error that ended the reader, delivered after everything buffered
+    failure: Option<anyhow::Error>,
+    ended: bool,
+}
+
+impl<T> Buffer<T> {
+    fn spilled(&self) -> usize {
+        #[cfg(feature = "persistent-queue")]
+        {
+            self.spill.as_ref().map_or(0, |spill| spill.pending)
+        }
+        #[cfg(not(feature = "persistent-queue"))]
+        {
+            0
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.memory.len() + self.spilled()
+    }
+}
+
+/// One wake-up of the consumer
+enum Pop<T> {
+    Item(Result<T>),
+    Wait,
+    End,
+}
+
+struct Shared<T> {
+    control: Arc<PauseControl>,
+    buffer: Mutex<Buffer<T>>,
+    config: PauseConfig,
+}
+
+impl<T: Serialize + DeserializeOwned> Shared<T> {
+    /// Buffer `item`, or hand it back if there is no room for it yet
+    fn push(&self, item: Result<T>) -> Option<Result<T>> {
+        let mut buffer = match self.buffer.lock() {
+            Ok(buffer) => buffer,
+            Err(_) => return None,
+        };
+        let full = buffer.memory.len() >= self.config.max_buffered;
+
+        #[cfg(feature = "persistent-queue")]
+        if let Some(dir) = &self.config.spill_dir {
+            // Once anything is on disk, later events follow it there to keep order
+            let paused = self.control.paused.load(Ordering::SeqCst);
+            if buffer.spilled() > 0 || (paused && full) {
+                if buffer.spill.is_none() {
+                    match SpillFile::create(dir) {
+                        Ok(spill) => buffer.spill = Some(spill),
+                        Err(e) => fail(&mut buffer, e),
+                    }
+                }
+                if let Some(spill) = buffer.spill.as_mut() {
+                    if let Err(e) = spill.append(&item) {
+                        fail(&mut buffer, e);
+                    }
+                }
+                self.stored(&buffer);
+                return None;
+            }
+        }
+
+        if full {
+            return Some(item);
+        }
+        buffer.memory.push_back(item);
+        self.stored(&buffer);
+        None
+    }
+
+    fn pop(&self) -> Pop<T> {
+        if self.control.paused.load(Ordering::SeqCst) {
+            return Pop::Wait;
+        }
+        let mut buffer = match self.buffer.lock() {
+            Ok(buffer) => buffer,
+            Err(_) => return Pop::Item(Err(anyhow::anyhow!("Pause buffer poisoned"))),
+        };
+
+        if let Some(item) = buffer.memory.pop_front() {
+            self.taken(&buffer);
+            return Pop::Item(item);
+        }
+
+        #[cfg(feature = "persistent-queue")]
+        if let Some(spill) = buffer.spill.as_mut().filter(|spill| spill.pending > 0) {
+            let item = spill.read_next().and_then(|item| item);
+            self.taken(&buffer);
+            return Pop::Item(item);
+        }
+
+        if buffer.ended {
+            return match buffer.failure.take() {
+                Some(e) => Pop::Item(Err(e)),
+                None => Pop::End,
+            };
+        }
+        Pop::Wait
+    }
+
+    fn ended(&self) -> bool {
+        match self.buffer.lock() {
+            Ok(buffer) => buffer.ended,
+            Err(_) => true,
+        }
+    }
+
+    fn finish(&self) {
+        if let Ok(mut buffer) = self.buffer.lock() {
+            buffer.ended = true;
+        }
+        self.control.consumer.notify_one();
+    }
+
+    fn stored(&self, buffer: &Buffer<T>) {
+        self.control.buffered.store(buffer.len(), Ordering::SeqCst);
+        self.control.consumer.notify_one();
+    }
+
+    fn taken(&self, buffer: &Buffer<T>) {
+        self.control.buffered.store(buffer.len(), Ordering::SeqCst);
+        self.control.reader.notify_one();
+    }
+}
+
+/// Event stream that can be paused and resumed through a [`PauseHandle`]
+///
+/// Dropping the stream stops the background reader.
+pub struct PausableStream<T> {
+    events: Pin<Box<dyn Stream<Item = Result<T>> + Send>>,
+    handle: PauseHandle,
+    reader: JoinHandle<()>,
+}
+
+impl<T> PausableStream<T> {
+    /// Handle to pause and resume this stream from other tasks
+    pub fn handle(&self) -> PauseHandle {
+        self.handle.clone()
+    }
+
+    pub fn pause(&self) {
+        self.handle.pause();
+    }
+
+    pub fn resume(&self) {
+        self.handle.resume();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.handle.is_paused()
+    }
+}
+
+impl<T> Stream for PausableStream<T> {
+    type Item = Result<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.as_mut().poll_next(cx)
+    }
+}
+
+impl<T> Drop for PausableStream<T> {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Read `inner` on a background task so the consumer can pause without losing events
+///
+/// Errors of `inner` are buffered and delivered in order like events.
+pub fn pausable<T, S>(inner: S, config: PauseConfig) -> PausableStream<T>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+    S: Stream<Item = Result<T>> + Send + 'static,
+{
+    let control = Arc::new(PauseControl {
+        paused: AtomicBool::new(false),
+        buffered: AtomicUsize::new(0),
+        consumer: Notify::new(),
+        reader: Notify::new(),
+    });
+    let shared = Arc::new(Shared {
+        control: control.clone(),
+        buffer: Mutex::new(Buffer {
+            memory: VecDeque::new(),
+            #[cfg(feature = "persistent-queue")]
+            spill: None,
+            failure: None,
+            ended: false,
+        }),
+        config,
+    });
+
+    let reader = {
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let mut inner = Box::pin(inner);
+            while let Some(item) = inner.next().await {
+                let mut item = item;
+                while let Some(back) = shared.push(item) {
+                    item = back;
+                    shared.control.reader.notified().await;
+                }
+                if shared.ended() {
+                    break;
+                }
+            }
+            shared.finish();
+        })
+    };
+
+    let events = async_stream::stream! {
+        loop {
+            match shared.pop() {
+                Pop::Item(item) => yield item,
+                Pop::Wait => shared.control.consumer.notified().await,
+                Pop::End => break,
+            }
+        }
+    };
+
+    PausableStream {
+        events: Box::pin(events),
+        handle: PauseHandle { control },
+        reader,
+    }
+}
+
+/// Stop reading after a spill failure; the error is delivered after the buffer
+#[cfg(feature = "persistent-queue")]
+fn fail<T>(buffer: &mut Buffer<T>, e: anyhow::Error) {
+    if buffer.failure.is_none() {
+        buffer.failure = Some(e.context("Failed to spill paused events"));
+    }
+    buffer.ended = true;
+}
+
+/// A buffered item as written to the spill file
+#[cfg(feature = "persistent-queue")]
+#[derive(Serialize, Deserialize)]
+enum Spilled<E> {
+    Event(E),
+    Error(String),
+}
+
+#[cfg(feature = "persistent-queue")]
+static NEXT_SPILL_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Temporary JSON-lines file of events, read back front to back
+#[cfg(feature = "persistent-queue")]
+struct SpillFile {
+    path: PathBuf,
+    writer: File,
+    reader: BufReader<File>,
+    pending: usize,
+}
+
+#[cfg(feature = "persistent-queue")]
+impl SpillFile {
+    fn create(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let id = NEXT_SPILL_ID.fetch_add(1, Ordering::Relaxed);
+        let path = dir.join(format!("pause-spill-{}-{}.jsonl", std::process::id(), id));
+        let writer = OpenOptions::new().create(true).append(true).open(&path)?;
+        writer.set_len(0)?;
+        let reader = BufReader::new(File::open(&path)?);
+        Ok(Self {
+            path,
+            writer,
+            reader,
+            pending: 0,
+        })
+    }
+
+    fn append<T: Serialize>(&mut self, item: &Result<T>) -> Result<()> {
+        let entry = match item {
+            Ok(event) => Spilled::Event(event),
+            Err(e) => Spilled::Error(e.to_string()),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.pending += 1;
+        Ok(())
+    }
+
+    /// Oldest spilled item; the file is truncated once everything was read back
+    fn read_next<T: DeserializeOwned>(&mut self) -> Result<Result<T>> {
+        let mut line = String::new();
+        let read = self.reader.read_line(&mut line);
+        self.pending -= 1;
+        if self.pending == 0 {
+            self.writer.set_len(0)?;
+            self.reader.seek(SeekFrom::Start(0))?;
+        }
+        if read? == 0 {
+            anyhow::bail!("Spill file {} ended early", self.path.display());
+        }
+
+        Ok(match serde_json::from_str(&line)? {
+            Spilled::Event(event) => Ok(event),
+            Spilled::Error(message) => Err(anyhow::anyhow!(message)),
+        })
+    }
+}
+
+#[cfg(feature = "persistent-queue")]
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::swap_at;
+    use std::time::Duration;
+
+    async fn wait_buffered(handle: &PauseHandle, count: usize) {
+        while handle.buffered() < count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    async fn paused_roundtrip(config: PauseConfig) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let inner = async_stream::stream! {
+            while let Some(event) = rx.recv().await {
+                yield Ok::<_, anyhow::Error>(event);
+            }
+        };
+        let mut events = pausable(inner, config);
+        let handle = events.handle();
+
+        handle.pause();
+        for block in 1..=5 {
+            tx.send(swap_at(block, 0)).unwrap();
+        }
+        wait_buffered(&handle, 5).await;
+        let blocked = tokio::time::timeout(Duration::from_millis(50), events.next()).await;
+        assert!(blocked.is_err(), "paused stream must not yield");
+
+        handle.resume();
+        tx.send(swap_at(6, 0)).unwrap();
+        drop(tx);
+        let blocks: Vec<u64> = events
+            .map(|event| event.unwrap().block_number)
+            .collect()
+            .await;
+        assert_eq!(blocks, vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[tokio::test]
+    async fn test_pause_buffers_and_replays_in_order() {
+        paused_roundtrip(PauseConfig::default()).await;
+    }
+
+    #[cfg(feature = "persistent-queue")]
+    #[tokio::test]
+    async fn test_pause_spills_beyond_memory_limit() {
+        let dir = std::env::temp_dir().join(format!("nadfun-pause-{}", std::process::id()));
+        paused_roundtrip(
+            PauseConfig::default()
+                .with_max_buffered(2)
+                .with_spill_dir(&dir),
+        )
+        .await;
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,248 @@
+//! Composable event predicates
+//!
+//! A [`Predicate`] describes which events a monitor cares about. The same value
+//! can be attached to streams (`with_predicate`) and indexers, or loaded from a
+//! config file through serde:
+//!
+//! ```rust,ignore
+//! let predicate = Predicate::all(vec![
+//!     Predicate::event_type_in([EventType::Buy, EventType::Sell]),
+//!     Predicate::min_amount(parse_ether("10")?),
+//!     Predicate::not(Predicate::sender_in([my_wallet])),
+//! ]);
+//!
+//! let stream = CurveStream::new(ws_url).await?.with_predicate(predicate);
+//! ```
+//!
+//! Serialized form (JSON shown; any serde format works):
+//!
+//! ```json
+//! { "all": [
+//!     { "event_type_in": ["buy", "sell"] },
+//!     { "min_amount": "0x8ac7230489e80000" },
+//!     { "not": { "sender_in": ["0x..."] } }
+//! ] }
+//! ```
+
+use crate::types::{BondingCurveEvent, DexEvent, EventType, SwapEvent};
+use alloy::primitives::{Address, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Event fields that predicates can test
+///
+/// Fields an event doesn't have return `None`, and leaf predicates on a missing
+/// field never match.
+pub trait PredicateTarget {
+    fn token(&self) -> Option<Address>;
+    fn event_type(&self) -> Option<EventType>;
+    fn sender(&self) -> Option<Address>;
+    /// MON side of a trade (buy input, sell output)
+    fn mon_amount(&self) -> Option<U256>;
+}
+
+impl PredicateTarget for BondingCurveEvent {
+    fn token(&self) -> Option<Address> {
+        Some(BondingCurveEvent::token(self))
+    }
+
+    fn event_type(&self) -> Option<EventType> {
+        Some(BondingCurveEvent::event_type(self))
+    }
+
+    fn sender(&self) -> Option<Address> {
+        match self {
+            BondingCurveEvent::Create(e) => Some(e.creator),
+            BondingCurveEvent::Buy(e) => Some(e.sender),
+            BondingCurveEvent::Sell(e) => Some(e.sender),
+            _ => None,
+        }
+    }
+
+    fn mon_amount(&self) -> Option<U256> {
+        match self {
+            BondingCurveEvent::Buy(e) => Some(e.amount_in),
+            BondingCurveEvent::Sell(e) => Some(e.amount_out),
+            _ => None,
+        }
+    }
+}
+
+/// Swaps only expose `sender`: the token and MON side depend on pool orientation,
+/// which the event alone doesn't carry.
+impl PredicateTarget for SwapEvent {
+    fn token(&self) -> Option<Address> {
+        None
+    }
+
+    fn event_type(&self) -> Option<EventType> {
+        None
+    }
+
+    fn sender(&self) -> Option<Address> {
+        Some(self.sender)
+    }
+
+    fn mon_amount(&self) -> Option<U256> {
+        None
+    }
+}
+
+/// Pool events expose the address that called the pool as `sender` (the
+/// position owner for Burn and Collect); other fields are `None` as for swaps.
+impl PredicateTarget for DexEvent {
+    fn token(&self) -> Option<Address> {
+        None
+    }
+
+    fn event_type(&self) -> Option<EventType> {
+        None
+    }
+
+    fn sender(&self) -> Option<Address> {
+        match self {
+            DexEvent::Swap(e) => Some(e.sender),
+            DexEvent::Mint(e) => Some(e.sender),
+            DexEvent::Burn(e) => Some(e.owner),
+            DexEvent::Collect(e) => Some(e.owner),
+        }
+    }
+
+    fn mon_amount(&self) -> Option<U256> {
+        None
+    }
+}
+
+/// Boolean expression over event fields
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Predicate {
+    /// Matches when every inner predicate matches (an empty list always matches)
+    All(Vec<Predicate>),
+    /// Matches when any inner predicate matches (an empty list never matches)
+    Any(Vec<Predicate>),
+    Not(Box<Predicate>),
+    TokenIn(HashSet<Address>),
+    EventTypeIn(HashSet<EventType>),
+    /// MON amount of a buy or sell is at least this many wei
+    MinAmount(U256),
+    SenderIn(HashSet<Address>),
+}
+
+impl Predicate {
+    pub fn all(predicates: Vec<Predicate>) -> Self {
+        Predicate::All(predicates)
+    }
+
+    pub fn any(predicates: Vec<Predicate>) -> Self {
+        Predicate::Any(predicates)
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(predicate: Predicate) -> Self {
+        Predicate::Not(Box::new(predicate))
+    }
+
+    pub fn token_in(tokens: impl IntoIterator<Item = Address>) -> Self {
+        Predicate::TokenIn(tokens.into_iter().collect())
+    }
+
+    pub fn event_type_in(event_types: impl IntoIterator<Item = EventType>) -> Self {
+        Predicate::EventTypeIn(event_types.into_iter().collect())
+    }
+
+    pub fn min_amount(amount: U256) -> Self {
+        Predicate::MinAmount(amount)
+    }
+
+    pub fn sender_in(senders: impl IntoIterator<Item = Address>) -> Self {
+        Predicate::SenderIn(senders.into_iter().collect())
+    }
+
+    /// Combine with another predicate; both must match
+    pub fn and(self, other: Predicate) -> Self {
+        match self {
+            Predicate::All(mut predicates) => {
+                predicates.push(other);
+                Predicate::All(predicates)
+            }
+            predicate => Predicate::All(vec![predicate, other]),
+        }
+    }
+
+    /// Combine with another predicate; either may match
+    pub fn or(self, other: Predicate) -> Self {
+        match self {
+            Predicate::Any(mut predicates) => {
+                predicates.push(other);
+                Predicate::Any(predicates)
+            }
+            predicate => Predicate::Any(vec![predicate, other]),
+        }
+    }
+
+    pub fn matches<E: PredicateTarget>(&self, event: &E) -> bool {
+        match self {
+            Predicate::All(predicates) => predicates.iter().all(|p| p.matches(event)),
+            Predicate::Any(predicates) => predicates.iter().any(|p| p.matches(event)),
+            Predicate::Not(predicate) => !predicate.matches(event),
+            Predicate::TokenIn(tokens) => event.token().is_some_and(|t| tokens.contains(&t)),
+            Predicate::EventTypeIn(types) => event.event_type().is_some_and(|t| types.contains(&t)),
+            Predicate::MinAmount(min) => event.mon_amount().is_some_and(|a| a >= *min),
+            Predicate::SenderIn(senders) => event.sender().is_some_and(|s| senders.contains(&s)),
+        }
+    }
+
+    /// Keep only the matching events, preserving order
+    pub fn filter_events<E: PredicateTarget>(&self, mut events: Vec<E>) -> Vec<E> {
+        events.retain(|event| self.matches(event));
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, FIXTURE_SENDER, FIXTURE_TOKEN};
+
+    #[test]
+    fn test_predicate_matching() {
+        let buy = fixtures::curve_buy().expected;
+        let sync = fixtures::curve_sync().expected;
+
+        let large_trades = Predicate::event_type_in([EventType::Buy, EventType::Sell])
+            .and(Predicate::min_amount(U256::from(10u64).pow(U256::from(17))));
+        assert!(large_trades.matches(&buy));
+        assert!(!large_trades.matches(&sync));
+
+        let not_me = Predicate::not(Predicate::sender_in([FIXTURE_SENDER]));
+        assert!(!not_me.matches(&buy));
+        // Sync has no sender, so sender_in doesn't match and its negation does
+        assert!(not_me.matches(&sync));
+
+        assert!(Predicate::token_in([FIXTURE_TOKEN]).matches(&sync));
+        assert!(!Predicate::token_in([FIXTURE_TOKEN]).matches(&fixtures::swap().expected));
+        assert!(Predicate::all(vec![]).matches(&buy));
+        assert!(!Predicate::any(vec![]).matches(&buy));
+    }
+
+    #[test]
+    fn test_predicate_serde_roundtrip() {
+        let json = r#"{"all":[{"event_type_in":["buy"]},{"not":{"sender_in":["0x2222222222222222222222222222222222222222"]}}]}"#;
+        let predicate: Predicate = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            predicate,
+            Predicate::all(vec![
+                Predicate::event_type_in([EventType::Buy]),
+                Predicate::not(Predicate::sender_in([FIXTURE_SENDER])),
+            ])
+        );
+
+        let encoded = serde_json::to_string(&predicate).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Predicate>(&encoded).unwrap(),
+            predicate
+        );
+    }
+}
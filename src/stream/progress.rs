@@ -0,0 +1,308 @@
+//! Progress reporting for long historical backfills
+//!
+//! `fetch_all_events` on [`CurveIndexer`](crate::stream::CurveIndexer) and
+//! [`UniswapSwapIndexer`](crate::stream::UniswapSwapIndexer) can run for hours.
+//! Hand the indexer an [`IndexProgressTracker`] to follow it, either with a
+//! callback after every batch or by polling from another task:
+//!
+//! ```rust,ignore
+//! let progress = IndexProgressTracker::new().on_update(|p| {
+//!     println!("{:.1}% ({} events, ETA {:?})", p.fraction() * 100.0, p.events, p.eta);
+//! });
+//! let indexer = CurveIndexer::new(provider)
+//!     .with_progress(progress.clone())
+//!     .with_batch_retries(3);
+//!
+//! let watcher = progress.clone();
+//! tokio::spawn(async move {
+//!     loop {
+//!         tokio::time::sleep(Duration::from_secs(30)).await;
+//!         report_to_dashboard(watcher.snapshot());
+//!     }
+//! });
+//! let events = indexer.fetch_all_events(start, 1000, EventType::all(), None).await?;
+//! ```
+//!
+//! The ETA extrapolates the average speed so far over the remaining blocks.
+
+use anyhow::Result;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+type ProgressCallback = Arc<dyn Fn(&IndexProgress) + Send + Sync>;
+
+/// Delay before the first retry of a failed batch; doubles with every attempt
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// State of a backfill after its latest batch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IndexProgress {
+    pub from_block: u64,
+    /// Chain head when the backfill started; the last block fetched
+    pub target_block: u64,
+    /// Blocks fetched so far
+    pub blocks_done: u64,
+    /// Blocks in the whole range
+    pub blocks_total: u64,
+    /// Events found so far (after token and predicate filtering)
+    pub events: u64,
+    /// Batches completed
+    pub batches: u64,
+    /// Failed batch requests that were retried
+    pub retries: u64,
+    pub elapsed: Duration,
+    /// Estimated time until the last batch, once a batch has completed
+    pub eta: Option<Duration>,
+    /// True once every batch has completed
+    pub done: bool,
+}
+
+impl IndexProgress {
+    /// Fraction of the range already fetched (0.0 - 1.0)
+    pub fn fraction(&self) -> f64 {
+        if self.done || self.blocks_total == 0 {
+            return 1.0;
+        }
+        (self.blocks_done as f64 / self.blocks_total as f64).min(1.0)
+    }
+}
+
+/// Remaining time at the average speed so far
+pub fn estimate_eta(blocks_done: u64, blocks_total: u64, elapsed: Duration) -> Option<Duration> {
+    if blocks_done == 0 {
+        return None;
+    }
+    let remaining = blocks_total.saturating_sub(blocks_done);
+    Some(elapsed.mul_f64(remaining as f64 / blocks_done as f64))
+}
+
+#[derive(Default)]
+struct TrackerState {
+    progress: IndexProgress,
+    started: Option<Instant>,
+}
+
+/// Shared progress of an indexer's backfills
+///
+/// Clones share the same state. A new `fetch_all_*` call on the indexer
+/// restarts the progress from zero.
+#[derive(Clone, Default)]
+pub struct IndexProgressTracker {
+    state: Arc<Mutex<TrackerState>>,
+    callback: Option<ProgressCallback>,
+}
+
+impl IndexProgressTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` after every completed batch, and once more when done
+    ///
+    /// Runs on the indexing task, so it should return quickly.
+    pub fn on_update<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&IndexProgress) + Send + Sync + 'static,
+    {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Current progress, with `elapsed` and `eta` as of now
+    pub fn snapshot(&self) -> IndexProgress {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut progress = state.progress.clone();
+        if let (Some(started), false) = (state.started, progress.done) {
+            progress.elapsed = started.elapsed();
+            progress.eta = estimate_eta(
+                progress.blocks_done,
+                progress.blocks_total,
+                progress.elapsed,
+            );
+        }
+        progress
+    }
+
+    pub(crate) fn start(&self, from_block: u64, target_block: u64) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.started = Some(Instant::now());
+            state.progress = IndexProgress {
+                from_block,
+                target_block,
+                blocks_total: (target_block + 1).saturating_sub(from_block),
+                ..IndexProgress::default()
+            };
+        }
+        self.notify();
+    }
+
+    pub(crate) fn batch_done(&self, from_block: u64, to_block: u64, events: usize) {
+        self.update(|progress| {
+            progress.blocks_done += to_block + 1 - from_block;
+            progress.events += events as u64;
+            progress.batches += 1;
+        });
+        self.notify();
+    }
+
+    pub(crate) fn retried(&self) {
+        self.update(|progress| progress.retries += 1);
+    }
+
+    pub(crate) fn finish(&self) {
+        {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.progress.elapsed = state.started.map(|s| s.elapsed()).unwrap_or_default();
+            state.progress.eta = Some(Duration::ZERO);
+            state.progress.done = true;
+        }
+        self.notify();
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut IndexProgress)) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        apply(&mut state.progress);
+    }
+
+    fn notify(&self) {
+        if let Some(callback) = &self.callback {
+            callback(&self.snapshot());
+        }
+    }
+}
+
+/// Fetch `start_block..=target_block` in batches of `batch_size + 1` blocks
+///
+/// Each failed batch is retried up to `retries` times with exponential backoff
+/// before the whole fetch fails.
+pub(crate) async fn fetch_in_batches<T, F, Fut>(
+    start_block: u64,
+    target_block: u64,
+    batch_size: u64,
+    retries: u32,
+    progress: Option<&IndexProgressTracker>,
+    mut fetch: F,
+) -> Result<Vec<T>>
+where
+    F: FnMut(u64, u64) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    if let Some(progress) = progress {
+        progress.start(start_block, target_block);
+    }
+
+    let mut all_events = Vec::new();
+    let mut current_block = start_block;
+
+    while current_block <= target_block {
+        let to_block = std::cmp::min(current_block + batch_size, target_block);
+
+        let mut attempt = 0;
+        let events = loop {
+            match fetch(current_block, to_block).await {
+                Ok(events) => break events,
+                Err(_) if attempt < retries => {
+                    if let Some(progress) = progress {
+                        progress.retried();
+                    }
+                    tokio::time::sleep(RETRY_BASE_DELAY * 2u32.pow(attempt.min(6))).await;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(e.context(format!(
+                        "Failed to fetch blocks {current_block}..={to_block}"
+                    )));
+                }
+            }
+        };
+
+        if let Some(progress) = progress {
+            progress.batch_done(current_block, to_block, events.len());
+        }
+        all_events.extend(events);
+
+        if to_block >= target_block {
+            break;
+        }
+
+        current_block = to_block + 1;
+    }
+
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+    Ok(all_events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_estimate_eta() {
+        assert_eq!(estimate_eta(0, 100, Duration::from_secs(5)), None);
+        assert_eq!(
+            estimate_eta(25, 100, Duration::from_secs(10)),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            estimate_eta(100, 100, Duration::from_secs(10)),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_in_batches_reports_progress_and_retries() {
+        let updates = Arc::new(Mutex::new(Vec::new()));
+        let seen = updates.clone();
+        let tracker = IndexProgressTracker::new().on_update(move |p| {
+            seen.lock()
+                .unwrap()
+                .push((p.blocks_done, p.batches, p.done));
+        });
+
+        // The second batch fails once before succeeding
+        let calls = AtomicU32::new(0);
+        let events = fetch_in_batches(10, 34, 9, 2, Some(&tracker), |from, to| {
+            let call = calls.fetch_add(1, Ordering::Relaxed);
+            async move {
+                if call == 1 {
+                    return Err(anyhow::anyhow!("rate limited"));
+                }
+                Ok(vec![(from, to)])
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(events, vec![(10, 19), (20, 29), (30, 34)]);
+        let progress = tracker.snapshot();
+        assert_eq!((progress.blocks_done, progress.blocks_total), (25, 25));
+        assert_eq!(
+            (progress.events, progress.batches, progress.retries),
+            (3, 3, 1)
+        );
+        assert!(progress.done);
+        assert_eq!(
+            *updates.lock().unwrap(),
+            vec![
+                (0, 0, false),
+                (10, 1, false),
+                (20, 2, false),
+                (25, 3, false),
+                (25, 3, true)
+            ]
+        );
+
+        let failing = fetch_in_batches(0, 5, 10, 1, None, |_, _| async {
+            Err::<Vec<()>, _>(anyhow::anyhow!("down"))
+        })
+        .await;
+        assert!(failing.is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! Disk-backed at-least-once delivery for event streams
+//!
+//! [`PersistentQueue`] sits between a subscription and its consumer. Every event
+//! is appended to a segment log on disk before the consumer sees it, and stays
+//! there until the consumer acknowledges it. After a crash or restart, reopening
+//! the queue delivers every unacknowledged event again:
+//!
+//! ```rust,ignore
+//! let queue = Arc::new(PersistentQueue::open("./curve-queue", QueueConfig::default())?);
+//! queue.spawn_writer(curve_stream.subscribe().await?);
+//!
+//! while let Some(item) = queue.recv().await? {
+//!     process(&item.event).await?;
+//!     queue.ack(item.seq)?;
+//! }
+//! ```
+//!
+//! Events are stored as JSON lines in segment files named after the sequence
+//! number of their first event; the `ack` file records the next unacknowledged
+//! sequence number. Segments are deleted once all their events are acknowledged.
+//! Consumers must tolerate redelivery of events processed but not yet acked.
+
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use serde::{Serialize, de::DeserializeOwned};
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+const SEGMENT_EXTENSION: &str = "log";
+const ACK_FILE: &str = "ack";
+
+/// Storage settings of a [`PersistentQueue`]
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    /// Start a new segment once the current one reaches this size
+    pub segment_max_bytes: u64,
+    /// fsync appends and acks before returning (slower, survives power loss)
+    pub sync_writes: bool,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            segment_max_bytes: 64 * 1024 * 1024,
+            sync_writes: true,
+        }
+    }
+}
+
+impl QueueConfig {
+    pub fn with_segment_max_bytes(mut self, bytes: u64) -> Self {
+        self.segment_max_bytes = bytes;
+        self
+    }
+
+    pub fn with_sync_writes(mut self, sync: bool) -> Self {
+        self.sync_writes = sync;
+        self
+    }
+}
+
+/// An event read from the queue, to be acknowledged by `seq`
+#[derive(Debug, Clone)]
+pub struct QueuedEvent<T> {
+    pub seq: u64,
+    pub event: T,
+}
+
+struct Segment {
+    first_seq: u64,
+    path: PathBuf,
+}
+
+struct QueueState<T> {
+    segments: VecDeque<Segment>,
+    writer: File,
+    writer_bytes: u64,
+    next_seq: u64,
+    acked: u64,
+    /// Persisted but not yet handed to the consumer
+    undelivered: VecDeque<QueuedEvent<T>>,
+    closed: bool,
+}
+
+/// Write-ahead queue giving at-least-once delivery of `T`
+///
+/// One writer task and one consumer per queue. Unacknowledged events are kept
+/// on disk; events not yet received are also held in memory.
+pub struct PersistentQueue<T> {
+    dir: PathBuf,
+    config: QueueConfig,
+    state: Mutex<QueueState<T>>,
+    notify: Notify,
+    _event: PhantomData<fn() -> T>,
+}
+
+impl<T: Serialize + DeserializeOwned + Send + 'static> PersistentQueue<T> {
+    /// Open (or create) a queue in `dir`, recovering unacknowledged events
+    ///
+    /// A partially written last line, left by a crash during an append, is cut off.
+    pub fn open(dir: impl AsRef<Path>, config: QueueConfig) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let acked = match fs::read_to_string(dir.join(ACK_FILE)) {
+            Ok(contents) => contents
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| anyhow::anyhow!("Corrupt ack file in {}: {}", dir.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut segments: Vec<Segment> = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SEGMENT_EXTENSION) {
+                continue;
+            }
+            let first_seq = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+                .ok_or_else(|| anyhow::anyhow!("Unexpected segment file {}", path.display()))?;
+            segments.push(Segment { first_seq, path });
+        }
+        segments.sort_by_key(|segment| segment.first_seq);
+
+        let mut undelivered = VecDeque::new();
+        let mut next_seq = acked;
+        let mut writer_bytes = 0;
+        for segment in &segments {
+            let (events, valid_bytes) = read_segment::<T>(&segment.path)?;
+            let file_bytes = fs::metadata(&segment.path)?.len();
+            if valid_bytes < file_bytes {
+                OpenOptions::new()
+                    .write(true)
+                    .open(&segment.path)?
+                    .set_len(valid_bytes)?;
+            }
+
+            for (offset, event) in events.into_iter().enumerate() {
+                let seq = segment.first_seq + offset as u64;
+                if seq >= acked {
+                    undelivered.push_back(QueuedEvent { seq, event });
+                }
+                next_seq = next_seq.max(seq + 1);
+            }
+            writer_bytes = valid_bytes;
+        }
+
+        if segments.is_empty() {
+            segments.push(Segment {
+                first_seq: next_seq,
+                path: segment_path(&dir, next_seq),
+            });
+        }
+        let writer = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&segments.last().expect("at least one segment").path)?;
+
+        Ok(Self {
+            dir,
+            config,
+            state: Mutex::new(QueueState {
+                segments: segments.into(),
+                writer,
+                writer_bytes,
+                next_seq,
+                acked,
+                undelivered,
+                closed: false,
+            }),
+            notify: Notify::new(),
+            _event: PhantomData,
+        })
+    }
+
+    /// Persist `event` and queue it for the consumer, returning its sequence number
+    pub fn push(&self, event: T) -> Result<u64> {
+        let mut line = serde_json::to_vec(&event)?;
+        line.push(b'\n');
+
+        let mut state = self.lock()?;
+        if state.writer_bytes > 0 && state.writer_bytes >= self.config.segment_max_bytes {
+            let first_seq = state.next_seq;
+            let path = segment_path(&self.dir, first_seq);
+            state.writer = OpenOptions::new().create(true).append(true).open(&path)?;
+            state.writer_bytes = 0;
+            state.segments.push_back(Segment { first_seq, path });
+        }
+
+        state.writer.write_all(&line)?;
+        if self.config.sync_writes {
+            state.writer.sync_data()?;
+        }
+        state.writer_bytes += line.len() as u64;
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.undelivered.push_back(QueuedEvent { seq, event });
+        drop(state);
+
+        self.notify.notify_one();
+        Ok(seq)
+    }
+
+    /// Next event, waiting for one to arrive
+    ///
+    /// Returns `None` once the queue is closed and every event has been received.
+    pub async fn recv(&self) -> Result<Option<QueuedEvent<T>>> {
+        loop {
+            {
+                let mut state = self.lock()?;
+                if let Some(event) = state.undelivered.pop_front() {
+                    return Ok(Some(event));
+                }
+                if state.closed {
+                    return Ok(None);
+                }
+            }
+            self.notify.notified().await;
+        }
+    }
+
+    /// Acknowledge every event up to and including `seq`
+    ///
+    /// Acks are cumulative: acknowledging a later event also acknowledges all
+    /// earlier ones. Fully acknowledged segments are deleted.
+    pub fn ack(&self, seq: u64) -> Result<()> {
+        let mut state = self.lock()?;
+        let acked = (seq + 1).min(state.next_seq);
+        if acked <= state.acked {
+            return Ok(());
+        }
+
+        let tmp = self.dir.join(format!("{ACK_FILE}.tmp"));
+        {
+            let mut file = File::create(&tmp)?;
+            file.write_all(acked.to_string().as_bytes())?;
+            if self.config.sync_writes {
+                file.sync_all()?;
+            }
+        }
+        fs::rename(&tmp, self.dir.join(ACK_FILE))?;
+        state.acked = acked;
+
+        // A segment is done when the next one starts at or before the ack point;
+        // the last segment is still being written and is always kept
+        while state.segments.len() > 1 && state.segments[1].first_seq <= acked {
+            let segment = state.segments.pop_front().expect("checked length");
+            fs::remove_file(&segment.path)?;
+        }
+        Ok(())
+    }
+
+    /// Number of persisted events not yet acknowledged
+    pub fn unacked(&self) -> Result<u64> {
+        let state = self.lock()?;
+        Ok(state.next_seq - state.acked)
+    }
+
+    /// Stop accepting events; `recv` returns `None` after draining
+    pub fn close(&self) -> Result<()> {
+        self.lock()?.closed = true;
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// Persist every event of `stream` on a background task
+    ///
+    /// The queue is closed when the stream ends. The task stops at the first
+    /// stream or disk error and returns it.
+    pub fn spawn_writer<S>(self: &Arc<Self>, stream: S) -> JoinHandle<Result<()>>
+    where
+        S: Stream<Item = Result<T>> + Send + 'static,
+    {
+        let queue = self.clone();
+        tokio::spawn(async move {
+            let mut stream = Box::pin(stream);
+            let result = async {
+                while let Some(event) = stream.next().await {
+                    queue.push(event?)?;
+                }
+                Ok::<(), anyhow::Error>(())
+            }
+            .await;
+            queue.close()?;
+            result
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, QueueState<T>>> {
+        self.state
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Queue state poisoned"))
+    }
+}
+
+fn segment_path(dir: &Path, first_seq: u64) -> PathBuf {
+    dir.join(format!("{first_seq:020}.{SEGMENT_EXTENSION}"))
+}
+
+/// Decode a segment, returning its events and the length of the intact prefix
+fn read_segment<T: DeserializeOwned>(path: &Path) -> Result<(Vec<T>, u64)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+    let mut valid_bytes = 0u64;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        match serde_json::from_slice(&line) {
+            Ok(event) => events.push(event),
+            Err(_) => break,
+        }
+        valid_bytes += read as u64;
+    }
+
+    Ok((events, valid_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::types::BondingCurveEvent;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("nadfun-queue-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_unacked_events_are_redelivered() {
+        let dir = temp_dir("redeliver");
+        let buy = fixtures::curve_buy().expected;
+        let sync = fixtures::curve_sync().expected;
+
+        {
+            let queue =
+                PersistentQueue::<BondingCurveEvent>::open(&dir, QueueConfig::default()).unwrap();
+            queue.push(buy.clone()).unwrap();
+            queue.push(sync.clone()).unwrap();
+
+            let first = queue.recv().await.unwrap().unwrap();
+            assert_eq!(first.event, buy);
+            queue.ack(first.seq).unwrap();
+            // The second event is received but the consumer "crashes" before acking
+            queue.recv().await.unwrap().unwrap();
+        }
+
+        let queue =
+            PersistentQueue::<BondingCurveEvent>::open(&dir, QueueConfig::default()).unwrap();
+        assert_eq!(queue.unacked().unwrap(), 1);
+        let redelivered = queue.recv().await.unwrap().unwrap();
+        assert_eq!((redelivered.seq, redelivered.event), (1, sync));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_torn_write_and_segment_cleanup() {
+        let dir = temp_dir("segments");
+        let config = QueueConfig::default()
+            .with_segment_max_bytes(1)
+            .with_sync_writes(false);
+        let buy = fixtures::curve_buy().expected;
+
+        {
+            let queue = PersistentQueue::<BondingCurveEvent>::open(&dir, config.clone()).unwrap();
+            for _ in 0..3 {
+                queue.push(buy.clone()).unwrap();
+            }
+            queue.ack(1).unwrap();
+        }
+        // Seqs 0 and 1 lived in their own segments and are gone
+        let segments = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().path().extension() == Some("log".as_ref()))
+            .count();
+        assert_eq!(segments, 1);
+
+        // Simulate a crash halfway through an append
+        let last = segment_path(&dir, 2);
+        let mut file = OpenOptions::new().append(true).open(&last).unwrap();
+        file.write_all(b"{\"Buy\":{\"sen").unwrap();
+        drop(file);
+
+        let queue = PersistentQueue::<BondingCurveEvent>::open(&dir, config).unwrap();
+        assert_eq!(queue.unacked().unwrap(), 1);
+        assert_eq!(queue.recv().await.unwrap().unwrap().seq, 2);
+        assert_eq!(queue.push(buy).unwrap(), 3);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
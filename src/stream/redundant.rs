@@ -0,0 +1,302 @@
+//! One event feed from several WebSocket endpoints
+//!
+//! A single WebSocket connection misses events while it reconnects or when its
+//! node falls behind. A [`RedundantStream`] subscribes the same filter on every
+//! endpoint, merges the feeds and drops the copies, so an event arrives as soon
+//! as the fastest endpoint has it and still arrives while one endpoint is down:
+//!
+//! ```rust,ignore
+//! let redundant = RedundantStream::new(vec![primary_ws, backup_ws]);
+//! let mut events = redundant.curve_events(vec![EventType::Buy, EventType::Sell], None).await?;
+//!
+//! while let Some(Ok(event)) = events.next().await {
+//!     handle(event);
+//! }
+//! for feed in redundant.health() {
+//!     println!("{}: connected {}, {} events, first {} times", feed.url, feed.connected, feed.events, feed.first_deliveries);
+//! }
+//! ```
+//!
+//! Copies are recognized by `(tx_hash, log_index)` (see
+//! [`Deduplicator`](crate::stream::Deduplicator)) within the last
+//! [`RedundantStream::with_dedup_window`] blocks. The merged stream ends once
+//! every feed has ended; ended feeds are not reconnected.
+
+use crate::{
+    stream::dedup::{DedupEvent, Deduplicator},
+    types::{
+        BondingCurveEvent, EventType, SwapEvent, curve_filter, decode_bonding_curve_event,
+        decode_swap_event, swap_filter,
+    },
+};
+use alloy::{
+    primitives::Address,
+    providers::{Provider, ProviderBuilder, WsConnect},
+    rpc::types::{Filter, Log},
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, future::join_all, stream::select_all};
+use std::{
+    collections::HashSet,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send>>;
+type FeedStream = Pin<Box<dyn Stream<Item = FeedItem> + Send>>;
+
+/// Blocks of events remembered to recognize copies from slower feeds
+pub const DEFAULT_REDUNDANT_DEDUP_WINDOW: u64 = 64;
+
+/// Health of one endpoint's feed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeedHealth {
+    pub url: String,
+    /// Subscribed and not ended
+    pub connected: bool,
+    /// Logs received, including copies of other feeds' events
+    pub events: u64,
+    /// Events this feed delivered before any other feed
+    pub first_deliveries: u64,
+    /// Logs this feed could not decode (not yielded; another feed may decode them)
+    pub decode_errors: u64,
+    /// Newest block seen on this feed
+    pub last_block: Option<u64>,
+    /// Why the feed failed to connect or ended
+    pub last_error: Option<String>,
+}
+
+/// A log from feed `index`, or the end of that feed
+enum FeedItem {
+    Log(usize, Box<Log>),
+    Ended(usize),
+}
+
+/// The same subscription on several WebSocket endpoints, deduplicated
+pub struct RedundantStream {
+    urls: Vec<String>,
+    dedup_window: u64,
+    health: Arc<Mutex<Vec<FeedHealth>>>,
+}
+
+impl RedundantStream {
+    pub fn new(urls: Vec<String>) -> Self {
+        let health = urls
+            .iter()
+            .map(|url| FeedHealth {
+                url: url.clone(),
+                ..FeedHealth::default()
+            })
+            .collect();
+        Self {
+            urls,
+            dedup_window: DEFAULT_REDUNDANT_DEDUP_WINDOW,
+            health: Arc::new(Mutex::new(health)),
+        }
+    }
+
+    /// Remember events of the last `blocks` blocks to drop copies (default 64)
+    ///
+    /// Must cover how far the slowest feed lags behind the fastest one.
+    pub fn with_dedup_window(mut self, blocks: u64) -> Self {
+        self.dedup_window = blocks;
+        self
+    }
+
+    /// Per-endpoint health; counters add up over every subscription
+    pub fn health(&self) -> Vec<FeedHealth> {
+        self.health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Bonding curve events of `event_types`, optionally for a set of tokens
+    pub async fn curve_events(
+        &self,
+        event_types: Vec<EventType>,
+        token_filter: Option<Vec<Address>>,
+    ) -> Result<EventStream<BondingCurveEvent>> {
+        let filter = curve_filter(&event_types, None, None);
+        let events = self.subscribe(filter, decode_bonding_curve_event).await?;
+        let Some(tokens) = token_filter else {
+            return Ok(events);
+        };
+
+        let tokens: HashSet<Address> = tokens.into_iter().collect();
+        Ok(Box::pin(events.filter(move |item| {
+            let keep = match item {
+                Ok(event) => tokens.contains(&event.token()),
+                Err(_) => true,
+            };
+            futures_util::future::ready(keep)
+        })))
+    }
+
+    /// Swap events of `pool_addresses`
+    pub async fn swap_events(
+        &self,
+        pool_addresses: Vec<Address>,
+    ) -> Result<EventStream<SwapEvent>> {
+        self.subscribe(swap_filter(&pool_addresses, None), decode_swap_event)
+            .await
+    }
+
+    /// Subscribe `filter` on every endpoint and merge the decoded feeds
+    ///
+    /// Endpoints that fail to connect are reported in [`RedundantStream::health`];
+    /// fails only if none of them connects.
+    pub async fn subscribe<T, D>(&self, filter: Filter, decode: D) -> Result<EventStream<T>>
+    where
+        T: DedupEvent + Send + 'static,
+        D: Fn(Log) -> Result<T> + Send + 'static,
+    {
+        let connections = join_all(self.urls.iter().enumerate().map(|(index, url)| {
+            let filter = filter.clone();
+            async move { (index, connect_feed(url.clone(), index, &filter).await) }
+        }))
+        .await;
+
+        let mut feeds = Vec::new();
+        let mut last_error = None;
+        {
+            let mut health = self.health.lock().unwrap_or_else(|e| e.into_inner());
+            for (index, connection) in connections {
+                match connection {
+                    Ok(feed) => {
+                        health[index].connected = true;
+                        feeds.push(feed);
+                    }
+                    Err(e) => {
+                        health[index].connected = false;
+                        health[index].last_error = Some(e.to_string());
+                        last_error = Some(e);
+                    }
+                }
+            }
+        }
+        if feeds.is_empty() {
+            return Err(last_error
+                .unwrap_or_else(|| anyhow::anyhow!("No endpoints configured"))
+                .context("No endpoint of the redundant stream connected"));
+        }
+
+        Ok(merge_feeds(
+            feeds,
+            decode,
+            Deduplicator::new(self.dedup_window),
+            self.health.clone(),
+        ))
+    }
+}
+
+/// Open `url` and subscribe `filter`; the feed keeps its connection open
+async fn connect_feed(url: String, index: usize, filter: &Filter) -> Result<FeedStream> {
+    let provider = ProviderBuilder::new()
+        .connect_ws(WsConnect::new(url))
+        .await?;
+    let sub = provider.subscribe_logs(filter).await?;
+
+    Ok(Box::pin(async_stream::stream! {
+        let _provider = provider;
+        let mut logs = sub.into_stream();
+        while let Some(log) = logs.next().await {
+            yield FeedItem::Log(index, Box::new(log));
+        }
+        yield FeedItem::Ended(index);
+    }))
+}
+
+fn merge_feeds<T, D>(
+    feeds: Vec<FeedStream>,
+    decode: D,
+    dedup: Deduplicator,
+    health: Arc<Mutex<Vec<FeedHealth>>>,
+) -> EventStream<T>
+where
+    T: DedupEvent + Send + 'static,
+    D: Fn(Log) -> Result<T> + Send + 'static,
+{
+    Box::pin(async_stream::stream! {
+        let mut merged = select_all(feeds);
+        while let Some(item) = merged.next().await {
+            let (index, log) = match item {
+                FeedItem::Log(index, log) => (index, log),
+                FeedItem::Ended(index) => {
+                    let mut health = health.lock().unwrap_or_else(|e| e.into_inner());
+                    health[index].connected = false;
+                    health[index].last_error = Some("Subscription ended".to_string());
+                    continue;
+                }
+            };
+
+            let decoded = decode(*log);
+            let fresh = decoded.as_ref().is_ok_and(|event| dedup.check(event));
+            {
+                let mut health = health.lock().unwrap_or_else(|e| e.into_inner());
+                let feed = &mut health[index];
+                feed.events += 1;
+                match &decoded {
+                    Ok(event) => {
+                        let block = event.dedup_block();
+                        feed.last_block = Some(feed.last_block.map_or(block, |b| b.max(block)));
+                        if fresh {
+                            feed.first_deliveries += 1;
+                        }
+                    }
+                    Err(_) => feed.decode_errors += 1,
+                }
+            }
+
+            if let (true, Ok(event)) = (fresh, decoded) {
+                yield Ok(event);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn feed(index: usize, logs: Vec<Log>) -> FeedStream {
+        let mut items: Vec<FeedItem> = logs
+            .into_iter()
+            .map(|log| FeedItem::Log(index, Box::new(log)))
+            .collect();
+        items.push(FeedItem::Ended(index));
+        Box::pin(futures_util::stream::iter(items))
+    }
+
+    #[tokio::test]
+    async fn test_merge_feeds_drops_copies_and_tracks_health() {
+        let health = Arc::new(Mutex::new(vec![FeedHealth::default(); 2]));
+        let mut garbage = fixtures::swap().log;
+        garbage.inner.data = alloy::primitives::LogData::empty();
+
+        let feeds = vec![
+            feed(0, vec![fixtures::swap().log]),
+            feed(1, vec![fixtures::swap().log, garbage]),
+        ];
+        let events: Vec<_> = merge_feeds(
+            feeds,
+            decode_swap_event,
+            Deduplicator::new(DEFAULT_REDUNDANT_DEDUP_WINDOW),
+            health.clone(),
+        )
+        .collect()
+        .await;
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].as_ref().unwrap(), &fixtures::swap().expected);
+
+        let health = health.lock().unwrap();
+        let total_first: u64 = health.iter().map(|f| f.first_deliveries).sum();
+        assert_eq!(total_first, 1);
+        assert_eq!((health[0].events, health[1].events), (1, 2));
+        assert_eq!(health[1].decode_errors, 1);
+        assert_eq!(health[0].last_block, Some(fixtures::FIXTURE_BLOCK));
+        assert!(health.iter().all(|f| !f.connected));
+    }
+}
@@ -0,0 +1,178 @@
+//! Deterministic replay of recorded events
+//!
+//! A [`ReplayStream`] yields a fixed list of events through the same boxed
+//! `Stream<Item = Result<T>>` the live streams return, so consumer logic can be
+//! tested without a node:
+//!
+//! ```rust,ignore
+//! let events = indexer.fetch_events(from, to, EventType::all(), None).await?;
+//! let replay = ReplayStream::new(events)
+//!     .with_timing(ReplayTiming::PerBlock(Duration::from_millis(10)))
+//!     .with_error_at(3, "subscription dropped");
+//!
+//! let stream = replay.subscribe().await?; // same type as CurveStream::subscribe
+//! run_consumer(stream).await;
+//! ```
+//!
+//! Events are yielded in the order given; sort them by `event_id()` first to
+//! replay chain order.
+
+use crate::stream::ordering::BlockNumbered;
+use anyhow::Result;
+use futures_util::Stream;
+use std::{pin::Pin, time::Duration};
+
+/// Events a [`ReplayStream`] can pace by block
+pub trait ReplayEvent: BlockNumbered + Clone + Send + 'static {}
+
+impl<T: BlockNumbered + Clone + Send + 'static> ReplayEvent for T {}
+
+/// How a [`ReplayStream`] spaces its events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayTiming {
+    /// Yield every event as soon as it is polled
+    #[default]
+    Immediate,
+    /// Wait this long before every event after the first
+    Interval(Duration),
+    /// Wait this long per block between consecutive events; events in the same
+    /// block are yielded back to back
+    PerBlock(Duration),
+}
+
+impl ReplayTiming {
+    /// Delay before an event in `block`, after one in `previous_block`
+    pub fn delay(&self, previous_block: Option<u64>, block: u64) -> Duration {
+        let Some(previous_block) = previous_block else {
+            return Duration::ZERO;
+        };
+        match *self {
+            ReplayTiming::Immediate => Duration::ZERO,
+            ReplayTiming::Interval(interval) => interval,
+            ReplayTiming::PerBlock(per_block) => {
+                let blocks = block.saturating_sub(previous_block);
+                per_block.saturating_mul(u32::try_from(blocks).unwrap_or(u32::MAX))
+            }
+        }
+    }
+}
+
+/// Replays a fixed list of events as a stream
+#[derive(Debug, Clone)]
+pub struct ReplayStream<T> {
+    events: Vec<T>,
+    timing: ReplayTiming,
+    /// (position, message) pairs; errors are yielded before the event at that position
+    errors: Vec<(usize, String)>,
+}
+
+impl<T: ReplayEvent> ReplayStream<T> {
+    pub fn new(events: Vec<T>) -> Self {
+        Self {
+            events,
+            timing: ReplayTiming::Immediate,
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn with_timing(mut self, timing: ReplayTiming) -> Self {
+        self.timing = timing;
+        self
+    }
+
+    /// Yield `Err(message)` before the event at `position` (or at the end if past it)
+    ///
+    /// Exercises the consumer's error path the way a dropped subscription would.
+    pub fn with_error_at(mut self, position: usize, message: impl Into<String>) -> Self {
+        self.errors.push((position, message.into()));
+        self.errors.sort_by_key(|(position, _)| *position);
+        self
+    }
+
+    pub fn events(&self) -> &[T] {
+        &self.events
+    }
+
+    /// Start a replay, with the same signature as the live streams' `subscribe`
+    ///
+    /// Every call replays from the beginning.
+    pub async fn subscribe(&self) -> Result<Pin<Box<dyn Stream<Item = Result<T>> + Send>>> {
+        Ok(self.clone().into_stream())
+    }
+
+    /// Consume the replay into a stream
+    pub fn into_stream(self) -> Pin<Box<dyn Stream<Item = Result<T>> + Send>> {
+        let Self {
+            events,
+            timing,
+            errors,
+        } = self;
+
+        Box::pin(async_stream::stream! {
+            let mut errors = errors.into_iter().peekable();
+            let mut previous_block = None;
+
+            for (position, event) in events.into_iter().enumerate() {
+                while let Some((_, message)) = errors.next_if(|(at, _)| *at <= position) {
+                    yield Err(anyhow::anyhow!(message));
+                }
+
+                let block = event.block_number();
+                let delay = timing.delay(previous_block, block);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                previous_block = Some(block);
+
+                yield Ok(event);
+            }
+
+            for (_, message) in errors {
+                yield Err(anyhow::anyhow!(message));
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures, types::BondingCurveEvent};
+    use futures_util::StreamExt;
+
+    #[test]
+    fn test_replay_timing_delay() {
+        let per_block = ReplayTiming::PerBlock(Duration::from_millis(10));
+        assert_eq!(per_block.delay(None, 100), Duration::ZERO);
+        assert_eq!(per_block.delay(Some(100), 100), Duration::ZERO);
+        assert_eq!(per_block.delay(Some(100), 103), Duration::from_millis(30));
+
+        let interval = ReplayTiming::Interval(Duration::from_millis(5));
+        assert_eq!(interval.delay(Some(100), 100), Duration::from_millis(5));
+        assert_eq!(ReplayTiming::Immediate.delay(Some(1), 9), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_replay_yields_events_and_errors_in_order() {
+        let events: Vec<BondingCurveEvent> = fixtures::curve_fixtures()
+            .into_iter()
+            .map(|fixture| fixture.expected)
+            .collect();
+        let replay = ReplayStream::new(events.clone())
+            .with_error_at(1, "dropped")
+            .with_error_at(events.len() + 5, "closed");
+
+        for _ in 0..2 {
+            let items: Vec<_> = replay.subscribe().await.unwrap().collect().await;
+            assert_eq!(items.len(), events.len() + 2);
+            assert!(items[1].is_err());
+            assert_eq!(
+                items.last().unwrap().as_ref().unwrap_err().to_string(),
+                "closed"
+            );
+
+            let replayed: Vec<_> = items.into_iter().filter_map(Result::ok).collect();
+            assert_eq!(replayed, events);
+        }
+    }
+}
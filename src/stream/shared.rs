@@ -0,0 +1,269 @@
+//! Several event streams over one WebSocket connection
+//!
+//! [`CurveStream`] and [`UniswapSwapStream`] each open their own connection, so
+//! a monitor watching both holds several sockets and subscriptions. A
+//! [`SharedWsClient`] collects the streams first, then opens a single
+//! `eth_subscribe` with the union of their filters and routes every log to the
+//! streams it belongs to:
+//!
+//! ```rust,ignore
+//! let mut client = SharedWsClient::new(ws_url).await?;
+//! let mut curve = client.curve_events(vec![EventType::Buy, EventType::Sell], None);
+//! let mut swaps = client.swap_events(pool_addresses);
+//! let handle = client.start().await?;
+//!
+//! loop {
+//!     tokio::select! {
+//!         Some(Ok(event)) = curve.next() => println!("curve: {:?}", event.event_type()),
+//!         Some(Ok(swap)) = swaps.next() => println!("swap in {}", swap.pool_address),
+//!     }
+//! }
+//! ```
+//!
+//! Streams must be registered before [`SharedWsClient::start`]. They end when
+//! the subscription ends or the returned handle is shut down.
+//!
+//! [`CurveStream`]: crate::stream::CurveStream
+//! [`UniswapSwapStream`]: crate::stream::UniswapSwapStream
+
+use crate::constants::BONDING_CURVE;
+use crate::stream::handlers::SubscriptionHandle;
+use crate::types::{
+    BondingCurveEvent, EventType, SwapEvent, UniswapV3Pool, decode_bonding_curve_event,
+    decode_swap_event, signatures_for,
+};
+use alloy::{
+    primitives::{Address, B256},
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt};
+use std::{collections::HashSet, pin::Pin, sync::Arc};
+use tokio::sync::{mpsc, oneshot};
+
+type EventStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send>>;
+
+/// Logs of one registered stream
+struct Route {
+    /// Emitting contracts, empty for any
+    addresses: HashSet<Address>,
+    /// Accepted topic0 values, empty for any
+    signatures: HashSet<B256>,
+    tx: mpsc::UnboundedSender<Log>,
+}
+
+impl Route {
+    fn matches(&self, log: &Log) -> bool {
+        let address_ok = self.addresses.is_empty() || self.addresses.contains(&log.address());
+        let signature_ok = self.signatures.is_empty()
+            || log
+                .topic0()
+                .is_some_and(|topic| self.signatures.contains(topic));
+        address_ok && signature_ok
+    }
+}
+
+/// One WebSocket connection shared by several typed event streams
+pub struct SharedWsClient {
+    provider: Arc<DynProvider>,
+    routes: Vec<Route>,
+}
+
+impl SharedWsClient {
+    /// Open the WebSocket connection
+    pub async fn new(rpc_url: String) -> Result<SharedWsClient> {
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+
+        Ok(SharedWsClient::from_provider(Arc::new(DynProvider::new(
+            provider,
+        ))))
+    }
+
+    /// Reuse an existing WebSocket provider
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self {
+            provider,
+            routes: Vec::new(),
+        }
+    }
+
+    /// The shared provider, e.g. for a [`BlockStream`](crate::stream::BlockStream)
+    /// on the same connection
+    pub fn provider(&self) -> Arc<DynProvider> {
+        self.provider.clone()
+    }
+
+    /// Bonding curve events of `event_types`, optionally for a set of tokens
+    pub fn curve_events(
+        &mut self,
+        event_types: Vec<EventType>,
+        token_filter: Option<Vec<Address>>,
+    ) -> EventStream<BondingCurveEvent> {
+        let bonding_curve: Address = BONDING_CURVE
+            .parse()
+            .expect("Invalid bonding curve address");
+        let token_filter: Option<HashSet<Address>> =
+            token_filter.map(|tokens| tokens.into_iter().collect());
+
+        let logs = self.route(vec![bonding_curve], signatures_for(&event_types));
+        Box::pin(logs.filter_map(move |log| {
+            let result = match decode_bonding_curve_event(log) {
+                Ok(event)
+                    if token_filter
+                        .as_ref()
+                        .is_some_and(|tokens| !tokens.contains(&event.token())) =>
+                {
+                    None
+                }
+                result => Some(result),
+            };
+            futures_util::future::ready(result)
+        }))
+    }
+
+    /// Uniswap V3 Swap events of `pool_addresses`
+    pub fn swap_events(&mut self, pool_addresses: Vec<Address>) -> EventStream<SwapEvent> {
+        let logs = self.route(pool_addresses, vec![UniswapV3Pool::Swap::SIGNATURE_HASH]);
+        Box::pin(logs.map(decode_swap_event))
+    }
+
+    /// Undecoded logs of `addresses` with one of `event_signatures`
+    ///
+    /// An empty list matches anything, e.g. every event of a contract.
+    pub fn logs(
+        &mut self,
+        addresses: Vec<Address>,
+        event_signatures: Vec<B256>,
+    ) -> Pin<Box<dyn Stream<Item = Log> + Send>> {
+        Box::pin(self.route(addresses, event_signatures))
+    }
+
+    fn route(
+        &mut self,
+        addresses: Vec<Address>,
+        signatures: Vec<B256>,
+    ) -> impl Stream<Item = Log> + Send + 'static {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        self.routes.push(Route {
+            addresses: addresses.into_iter().collect(),
+            signatures: signatures.into_iter().collect(),
+            tx,
+        });
+        async_stream::stream! {
+            while let Some(log) = rx.recv().await {
+                yield log;
+            }
+        }
+    }
+
+    /// Open the combined subscription and start routing logs
+    ///
+    /// The subscription ends once every registered stream has been dropped.
+    pub async fn start(self) -> Result<SubscriptionHandle> {
+        if self.routes.is_empty() {
+            return Err(anyhow::anyhow!("No streams registered on SharedWsClient"));
+        }
+
+        let sub = self
+            .provider
+            .subscribe_logs(&combined_filter(&self.routes))
+            .await?;
+        let mut logs = sub.into_stream();
+        let mut routes = self.routes;
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    next = logs.next() => match next {
+                        Some(log) => {
+                            dispatch(&mut routes, &log);
+                            if routes.is_empty() {
+                                break;
+                            }
+                        }
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        Ok(SubscriptionHandle::new(shutdown_tx, task))
+    }
+}
+
+/// One filter matching every route
+///
+/// Addresses and signatures are unioned separately, so the node may also send
+/// address/signature pairs no route asked for; [`dispatch`] drops those.
+fn combined_filter(routes: &[Route]) -> Filter {
+    let mut filter = Filter::new();
+    if routes.iter().all(|route| !route.addresses.is_empty()) {
+        let addresses: HashSet<Address> = routes
+            .iter()
+            .flat_map(|route| route.addresses.iter().copied())
+            .collect();
+        filter = filter.address(addresses.into_iter().collect::<Vec<_>>());
+    }
+    if routes.iter().all(|route| !route.signatures.is_empty()) {
+        let signatures: HashSet<B256> = routes
+            .iter()
+            .flat_map(|route| route.signatures.iter().copied())
+            .collect();
+        filter = filter.event_signature(signatures.into_iter().collect::<Vec<_>>());
+    }
+    filter
+}
+
+/// Send `log` to every matching route, forgetting routes whose stream was dropped
+fn dispatch(routes: &mut Vec<Route>, log: &Log) {
+    routes.retain(|route| {
+        if route.tx.is_closed() {
+            return false;
+        }
+        if route.matches(log) {
+            return route.tx.send(log.clone()).is_ok();
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, FIXTURE_BONDING_CURVE, FIXTURE_POOL};
+
+    #[tokio::test]
+    async fn test_dispatch_routes_by_address_and_signature() {
+        let mut client = SharedWsClient::from_provider(Arc::new(DynProvider::new(
+            ProviderBuilder::new().connect_http("http://localhost:8545".parse().unwrap()),
+        )));
+        let buys = client.curve_events(vec![EventType::Buy], None);
+        let swaps = client.swap_events(vec![FIXTURE_POOL]);
+        let raw = client.logs(vec![FIXTURE_BONDING_CURVE], vec![]);
+
+        let buy = fixtures::curve_buy();
+        let sell = fixtures::curve_sell();
+        let swap = fixtures::swap();
+        for log in [&buy.log, &sell.log, &swap.log] {
+            dispatch(&mut client.routes, log);
+        }
+        // Closing the senders ends the streams after the routed logs
+        client.routes.clear();
+
+        let buys: Vec<_> = buys.collect().await;
+        assert_eq!(buys.len(), 1);
+        assert_eq!(buys[0].as_ref().unwrap(), &buy.expected);
+
+        let swaps: Vec<_> = swaps.collect().await;
+        assert_eq!(swaps.len(), 1);
+        assert_eq!(swaps[0].as_ref().unwrap(), &swap.expected);
+
+        // The raw route takes every bonding curve event
+        assert_eq!(raw.count().await, 2);
+    }
+}
@@ -0,0 +1,176 @@
+//! Every Nad.fun event of one transaction
+//!
+//! Given a transaction hash, [`decode_transaction`] fetches the transaction and
+//! its receipt and decodes everything the SDK understands: bonding curve
+//! events, Uniswap V3 pool events, ERC-20 transfers and the router call that
+//! started it. Useful for support tooling and debugging:
+//!
+//! ```rust,ignore
+//! let tx = decode_transaction(&provider, tx_hash).await?;
+//! println!("{} from {} (status {})", tx.transaction_hash, tx.from, tx.status);
+//! if let Some(call) = &tx.router_call {
+//!     println!("router call: {call:?}");
+//! }
+//! for event in &tx.events {
+//!     println!("  {event:?}");
+//! }
+//! println!("{} logs not understood", tx.unknown_logs);
+//! ```
+//!
+//! Logs are recognized by their signature, not their emitter, so a curve event
+//! from a redeployed bonding curve decodes as well.
+
+use crate::{
+    stream::wallet::{TransferEvent, decode_transfer_event},
+    trading::calldata::{RouterCall, decode_router_calldata},
+    types::{BondingCurveEvent, DexEvent, decode_bonding_curve_event_ref, decode_dex_event},
+};
+use alloy::{
+    consensus::Transaction as _,
+    primitives::{Address, B256},
+    providers::Provider,
+    rpc::types::Log,
+};
+use anyhow::Result;
+
+/// One decoded log of a transaction
+#[derive(Debug, Clone)]
+pub enum DecodedEvent {
+    Curve(BondingCurveEvent),
+    Dex(DexEvent),
+    Transfer(TransferEvent),
+}
+
+impl DecodedEvent {
+    pub fn log_index(&self) -> u64 {
+        match self {
+            DecodedEvent::Curve(event) => event.log_index(),
+            DecodedEvent::Dex(event) => event.log_index(),
+            DecodedEvent::Transfer(event) => event.log_index,
+        }
+    }
+}
+
+/// A transaction with everything the SDK could decode from it
+#[derive(Debug, Clone)]
+pub struct DecodedTransaction {
+    pub transaction_hash: B256,
+    pub block_number: Option<u64>,
+    pub from: Address,
+    /// None for contract creations
+    pub to: Option<Address>,
+    /// False if the transaction reverted
+    pub status: bool,
+    /// The router `buy`/`sell`/`sellPermit` call, if the calldata is one
+    pub router_call: Option<RouterCall>,
+    /// Decoded logs in log order
+    pub events: Vec<DecodedEvent>,
+    /// Logs that are none of the above (approvals, other protocols...)
+    pub unknown_logs: usize,
+}
+
+impl DecodedTransaction {
+    pub fn curve_events(&self) -> impl Iterator<Item = &BondingCurveEvent> {
+        self.events.iter().filter_map(|event| match event {
+            DecodedEvent::Curve(event) => Some(event),
+            _ => None,
+        })
+    }
+
+    pub fn dex_events(&self) -> impl Iterator<Item = &DexEvent> {
+        self.events.iter().filter_map(|event| match event {
+            DecodedEvent::Dex(event) => Some(event),
+            _ => None,
+        })
+    }
+
+    pub fn transfers(&self) -> impl Iterator<Item = &TransferEvent> {
+        self.events.iter().filter_map(|event| match event {
+            DecodedEvent::Transfer(event) => Some(event),
+            _ => None,
+        })
+    }
+}
+
+/// Decode curve, pool and transfer logs; returns the events and the number of unknown logs
+pub fn decode_transaction_logs(logs: &[Log]) -> (Vec<DecodedEvent>, usize) {
+    let mut events = Vec::with_capacity(logs.len());
+    let mut unknown = 0;
+
+    for log in logs {
+        if let Ok(event) = decode_bonding_curve_event_ref(log) {
+            events.push(DecodedEvent::Curve(event.into_owned()));
+        } else if let Ok(event) = decode_dex_event(log.clone()) {
+            events.push(DecodedEvent::Dex(event));
+        } else if let Ok(event) = decode_transfer_event(log.clone()) {
+            events.push(DecodedEvent::Transfer(event));
+        } else {
+            unknown += 1;
+        }
+    }
+    (events, unknown)
+}
+
+/// Fetch `tx_hash` and its receipt and decode every event the SDK understands
+///
+/// Fails if the transaction is unknown or not mined yet.
+pub async fn decode_transaction<P: Provider>(
+    provider: &P,
+    tx_hash: B256,
+) -> Result<DecodedTransaction> {
+    let (tx, receipt) = tokio::try_join!(
+        provider.get_transaction_by_hash(tx_hash),
+        provider.get_transaction_receipt(tx_hash)
+    )?;
+    let tx = tx.ok_or_else(|| anyhow::anyhow!("Transaction {} not found", tx_hash))?;
+    let receipt =
+        receipt.ok_or_else(|| anyhow::anyhow!("Transaction {} is not mined yet", tx_hash))?;
+
+    let (events, unknown_logs) = decode_transaction_logs(receipt.logs());
+    Ok(DecodedTransaction {
+        transaction_hash: tx_hash,
+        block_number: receipt.block_number,
+        from: receipt.from,
+        to: receipt.to,
+        status: receipt.status(),
+        router_call: decode_router_calldata(tx.input()).ok(),
+        events,
+        unknown_logs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_decode_transaction_logs() {
+        let mut unknown = fixtures::swap().log;
+        unknown.inner.data =
+            alloy::primitives::LogData::new_unchecked(vec![B256::ZERO], Default::default());
+
+        let logs = vec![
+            fixtures::curve_buy().log,
+            fixtures::swap().log,
+            unknown,
+            fixtures::curve_sync().log,
+        ];
+        let (events, unknown_logs) = decode_transaction_logs(&logs);
+
+        assert_eq!(unknown_logs, 1);
+        assert_eq!(events.len(), 3);
+        assert!(matches!(
+            &events[0],
+            DecodedEvent::Curve(e) if *e == fixtures::curve_buy().expected
+        ));
+        assert!(matches!(
+            &events[1],
+            DecodedEvent::Dex(DexEvent::Swap(e)) if *e == fixtures::swap().expected
+        ));
+        assert!(matches!(
+            &events[2],
+            DecodedEvent::Curve(BondingCurveEvent::Sync(_))
+        ));
+    }
+}
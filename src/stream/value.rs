@@ -0,0 +1,238 @@
+//! MON and USD value of trades
+//!
+//! Alert thresholds like "buys over 100 MON" or "swaps over $5k" need the MON
+//! side of every trade, which for swaps depends on the pool's token order. A
+//! [`TradeValuer`] attaches it to Buy, Sell and Swap events, and converts it
+//! to USD with a price source you provide:
+//!
+//! ```rust,ignore
+//! let valuer = Arc::new(
+//!     TradeValuer::new(provider.clone())
+//!         .with_usd_price(|_block| async { fetch_mon_usd_from_my_api().await }),
+//! );
+//! let events = valuer.value_curve_stream(curve_stream.subscribe().await?);
+//! pin_mut!(events);
+//!
+//! while let Some(Ok(valued)) = events.next().await {
+//!     if valued.usd.is_some_and(|usd| usd >= 5_000.0) {
+//!         alert(&valued.event);
+//!     }
+//! }
+//! ```
+//!
+//! The price hook is called at most once per block and its result reused for
+//! the block's other events. If it fails, `usd` is `None` and the event is
+//! still delivered.
+
+use crate::{
+    constants::WMON,
+    metadata::MetadataCache,
+    stream::{
+        normalize::{MON_DECIMALS, to_decimal},
+        predicate::PredicateTarget,
+    },
+    types::{BondingCurveEvent, SwapEvent},
+};
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, future::BoxFuture};
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+type UsdPriceHook = Arc<dyn Fn(u64) -> BoxFuture<'static, Result<f64>> + Send + Sync>;
+
+/// An event with the MON value of its trade
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValuedEvent<E> {
+    pub event: E,
+    /// MON side of the trade in wei; None for events that aren't trades and
+    /// swaps in pools without WMON
+    pub mon_value: Option<U256>,
+    /// `mon_value` in whole MON
+    pub mon: Option<f64>,
+    /// `mon` in USD, if a price hook is set and answered
+    pub usd: Option<f64>,
+}
+
+impl<E> ValuedEvent<E> {
+    pub fn new(event: E, mon_value: Option<U256>, mon_usd_price: Option<f64>) -> Self {
+        let mon = mon_value.map(|value| to_decimal(value, MON_DECIMALS));
+        Self {
+            event,
+            mon_value,
+            mon,
+            usd: mon.zip(mon_usd_price).map(|(mon, price)| mon * price),
+        }
+    }
+}
+
+/// MON moved by `swap`: the absolute WMON delta of the pool
+///
+/// None if neither pool token is `wmon`.
+pub fn swap_mon_value(
+    swap: &SwapEvent,
+    token0: Address,
+    token1: Address,
+    wmon: Address,
+) -> Option<U256> {
+    if token0 != wmon && token1 != wmon {
+        return None;
+    }
+    Some(swap.abs_wmon_amount(token0 == wmon))
+}
+
+/// Attaches MON and optional USD values to trade events
+pub struct TradeValuer<P> {
+    provider: Arc<P>,
+    cache: MetadataCache,
+    wmon: Address,
+    usd_price: Option<UsdPriceHook>,
+    /// Last answered block and price
+    last_price: Mutex<Option<(u64, f64)>>,
+}
+
+impl<P: Provider + Clone> TradeValuer<P> {
+    pub fn new(provider: Arc<P>) -> Self {
+        Self {
+            provider,
+            cache: MetadataCache::new(),
+            wmon: WMON.parse().expect("Invalid WMON address"),
+            usd_price: None,
+            last_price: Mutex::new(None),
+        }
+    }
+
+    /// Share pool token lookups with other components
+    pub fn with_cache(mut self, cache: MetadataCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// WMON address of a custom deployment
+    pub fn with_wmon(mut self, wmon: Address) -> Self {
+        self.wmon = wmon;
+        self
+    }
+
+    /// Price source for USD values: MON/USD at the given block
+    pub fn with_usd_price<F, Fut>(mut self, hook: F) -> Self
+    where
+        F: Fn(u64) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<f64>> + Send + 'static,
+    {
+        self.usd_price = Some(Arc::new(move |block| Box::pin(hook(block))));
+        self
+    }
+
+    /// MON/USD at `block` from the hook, reusing the last answer for the same block
+    pub async fn mon_usd_price(&self, block: u64) -> Option<f64> {
+        let hook = self.usd_price.as_ref()?;
+        if let Some((cached_block, price)) =
+            *self.last_price.lock().unwrap_or_else(|e| e.into_inner())
+        {
+            if cached_block == block {
+                return Some(price);
+            }
+        }
+        let price = hook(block).await.ok()?;
+        *self.last_price.lock().unwrap_or_else(|e| e.into_inner()) = Some((block, price));
+        Some(price)
+    }
+
+    /// Value a curve event; only Buy and Sell have a MON value
+    pub async fn value_curve(&self, event: BondingCurveEvent) -> ValuedEvent<BondingCurveEvent> {
+        let mon_value = event.mon_amount();
+        let price = match mon_value {
+            Some(_) => self.mon_usd_price(event.block_number()).await,
+            None => None,
+        };
+        ValuedEvent::new(event, mon_value, price)
+    }
+
+    /// Value a swap from the WMON side of its pool
+    pub async fn value_swap(&self, event: SwapEvent) -> Result<ValuedEvent<SwapEvent>> {
+        let tokens = self
+            .cache
+            .pool_tokens(self.provider.as_ref(), event.pool_address)
+            .await?;
+        let mon_value = swap_mon_value(&event, tokens.token0, tokens.token1, self.wmon);
+        let price = match mon_value {
+            Some(_) => self.mon_usd_price(event.block_number).await,
+            None => None,
+        };
+        Ok(ValuedEvent::new(event, mon_value, price))
+    }
+
+    /// Value every event of a live curve stream
+    pub fn value_curve_stream<S>(
+        self: Arc<Self>,
+        inner: S,
+    ) -> impl Stream<Item = Result<ValuedEvent<BondingCurveEvent>>> + Send
+    where
+        S: Stream<Item = Result<BondingCurveEvent>> + Send + 'static,
+        P: Send + Sync + 'static,
+    {
+        inner.then(move |item| {
+            let valuer = self.clone();
+            async move { Ok(valuer.value_curve(item?).await) }
+        })
+    }
+
+    /// Value every event of a live swap stream
+    pub fn value_swap_stream<S>(
+        self: Arc<Self>,
+        inner: S,
+    ) -> impl Stream<Item = Result<ValuedEvent<SwapEvent>>> + Send
+    where
+        S: Stream<Item = Result<SwapEvent>> + Send + 'static,
+        P: Send + Sync + 'static,
+    {
+        inner.then(move |item| {
+            let valuer = self.clone();
+            async move { valuer.value_swap(item?).await }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use alloy::primitives::I256;
+
+    #[test]
+    fn test_mon_and_usd_values() {
+        let BondingCurveEvent::Buy(mut buy) = fixtures::curve_buy().expected else {
+            unreachable!()
+        };
+        buy.amount_in = U256::from(15u64) * U256::from(10u64).pow(U256::from(17));
+        let event = BondingCurveEvent::Buy(buy);
+
+        let valued = ValuedEvent::new(event.clone(), event.mon_amount(), Some(2.0));
+        assert_eq!(valued.mon, Some(1.5));
+        assert_eq!(valued.usd, Some(3.0));
+
+        let unpriced = ValuedEvent::new(event, Some(U256::ZERO), None);
+        assert_eq!((unpriced.mon, unpriced.usd), (Some(0.0), None));
+
+        let mut swap = fixtures::swap().expected;
+        swap.amount0 = I256::try_from(-500).unwrap();
+        swap.amount1 = I256::try_from(700).unwrap();
+        let wmon = Address::repeat_byte(0xee);
+        let token = Address::repeat_byte(0x11);
+        assert_eq!(
+            swap_mon_value(&swap, wmon, token, wmon),
+            Some(U256::from(500))
+        );
+        assert_eq!(
+            swap_mon_value(&swap, token, wmon, wmon),
+            Some(U256::from(700))
+        );
+        assert_eq!(swap_mon_value(&swap, token, token, wmon), None);
+    }
+}
@@ -0,0 +1,437 @@
+//! Watch-only wallet monitoring
+//!
+//! A [`WalletMonitor`] follows a set of addresses and merges everything that
+//! happens to them into one feed of [`WalletActivity`]: bonding curve trades,
+//! DEX swaps, ERC-20 transfers and native balance changes, each tagged with the
+//! wallet's label:
+//!
+//! ```rust,ignore
+//! let monitor = WalletMonitor::new(ws_url)
+//!     .await?
+//!     .with_wallet(treasury, "treasury")
+//!     .with_wallet(dev_wallet, "dev")
+//!     .with_pools(pool_addresses);
+//!
+//! let mut feed = monitor.subscribe().await?;
+//! while let Some(Ok(activity)) = feed.next().await {
+//!     match &activity.kind {
+//!         WalletActivityKind::CurveTrade(event) => println!("{} traded {}", activity.name(), event.token()),
+//!         WalletActivityKind::NativeBalance { previous, current } => println!("{} -> {}", previous, current),
+//!         _ => {}
+//!     }
+//! }
+//! ```
+//!
+//! A curve trade or swap also moves tokens, so it is usually followed by the
+//! matching [`WalletActivityKind::Transfer`]. Swaps are only watched on the
+//! pools given to [`WalletMonitor::with_pools`] and match on the pool's
+//! `sender`/`recipient`; swaps routed through a router are attributed to the
+//! router, not the wallet (see [`OriginResolver`](crate::stream::OriginResolver)).
+
+use crate::{
+    constants::BONDING_CURVE,
+    stream::ordering::BlockNumbered,
+    token::token::IToken,
+    types::{
+        BondingCurveEvent, SwapEvent, TRADE_EVENT_SIGNATURES, UniswapV3Pool,
+        decode_bonding_curve_event, decode_swap_event,
+    },
+};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider, ProviderBuilder, WsConnect},
+    rpc::types::{Filter, Log},
+    sol_types::SolEvent,
+};
+use anyhow::Result;
+use futures_util::{Stream, StreamExt, future::try_join_all, stream::select_all};
+use std::{collections::HashMap, pin::Pin, sync::Arc};
+
+type ActivityStream = Pin<Box<dyn Stream<Item = Result<WalletActivity>> + Send>>;
+
+/// An ERC-20 `Transfer` log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferEvent {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub block_number: u64,
+    pub transaction_hash: B256,
+    pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+/// Decode a log into a TransferEvent
+pub fn decode_transfer_event(log: Log) -> Result<TransferEvent> {
+    if log.topic0() != Some(&IToken::Transfer::SIGNATURE_HASH) {
+        return Err(anyhow::anyhow!("Not a Transfer event"));
+    }
+
+    let IToken::Transfer { from, to, value } = log.log_decode()?.inner.data;
+
+    Ok(TransferEvent {
+        token: log.address(),
+        from,
+        to,
+        value,
+        block_number: log.block_number.unwrap_or_default(),
+        transaction_hash: log.transaction_hash.unwrap_or_default(),
+        log_index: log.log_index.unwrap_or_default(),
+        removed: log.removed,
+    })
+}
+
+/// What happened to a watched wallet
+#[derive(Debug, Clone)]
+pub enum WalletActivityKind {
+    /// The wallet bought or sold on the bonding curve
+    CurveTrade(BondingCurveEvent),
+    /// The wallet is the sender or recipient of a swap on a watched pool
+    DexSwap(SwapEvent),
+    /// The wallet sent or received an ERC-20 token
+    Transfer(TransferEvent),
+    /// The wallet's MON balance changed at `block`
+    NativeBalance { previous: U256, current: U256 },
+}
+
+/// One event of a watched wallet
+#[derive(Debug, Clone)]
+pub struct WalletActivity {
+    pub wallet: Address,
+    pub label: Option<String>,
+    pub block: u64,
+    pub kind: WalletActivityKind,
+}
+
+impl WalletActivity {
+    /// The label if the wallet has one, its address otherwise
+    pub fn name(&self) -> String {
+        self.label
+            .clone()
+            .unwrap_or_else(|| self.wallet.to_string())
+    }
+}
+
+impl BlockNumbered for WalletActivity {
+    fn block_number(&self) -> u64 {
+        self.block
+    }
+}
+
+/// Watched addresses and their labels
+type Wallets = Arc<HashMap<Address, Option<String>>>;
+
+fn activity(
+    wallets: &Wallets,
+    wallet: Address,
+    block: u64,
+    kind: WalletActivityKind,
+) -> WalletActivity {
+    WalletActivity {
+        wallet,
+        label: wallets.get(&wallet).cloned().flatten(),
+        block,
+        kind,
+    }
+}
+
+/// Activities of `swap` for every watched wallet that is its sender or recipient
+fn swap_activities(wallets: &Wallets, swap: &SwapEvent) -> Vec<WalletActivity> {
+    let mut involved = vec![swap.sender];
+    if swap.recipient != swap.sender {
+        involved.push(swap.recipient);
+    }
+    involved
+        .into_iter()
+        .filter(|wallet| wallets.contains_key(wallet))
+        .map(|wallet| {
+            activity(
+                wallets,
+                wallet,
+                swap.block_number,
+                WalletActivityKind::DexSwap(swap.clone()),
+            )
+        })
+        .collect()
+}
+
+/// Streams curve trades, swaps, transfers and balance changes of a set of wallets
+pub struct WalletMonitor {
+    provider: Arc<DynProvider>,
+    wallets: HashMap<Address, Option<String>>,
+    pools: Vec<Address>,
+    transfer_tokens: Option<Vec<Address>>,
+    native_balances: bool,
+}
+
+impl WalletMonitor {
+    /// Create a WebSocket-based wallet monitor
+    pub async fn new(rpc_url: String) -> Result<WalletMonitor> {
+        let ws = WsConnect::new(rpc_url);
+        let provider = ProviderBuilder::new().connect_ws(ws).await?;
+
+        Ok(WalletMonitor::from_provider(Arc::new(DynProvider::new(
+            provider,
+        ))))
+    }
+
+    /// Reuse an existing WebSocket provider
+    pub fn from_provider(provider: Arc<DynProvider>) -> Self {
+        Self {
+            provider,
+            wallets: HashMap::new(),
+            pools: Vec::new(),
+            transfer_tokens: None,
+            native_balances: true,
+        }
+    }
+
+    /// Watch `wallet`, tagging its activity with `label`
+    pub fn with_wallet(mut self, wallet: Address, label: impl Into<String>) -> Self {
+        self.wallets.insert(wallet, Some(label.into()));
+        self
+    }
+
+    /// Watch `wallets` without labels
+    pub fn with_wallets(mut self, wallets: impl IntoIterator<Item = Address>) -> Self {
+        for wallet in wallets {
+            self.wallets.entry(wallet).or_insert(None);
+        }
+        self
+    }
+
+    /// Watch swaps on these Uniswap V3 pools (none by default)
+    pub fn with_pools(mut self, pools: Vec<Address>) -> Self {
+        self.pools = pools;
+        self
+    }
+
+    /// Only report transfers of these tokens (all ERC-20 tokens by default)
+    pub fn with_transfer_tokens(mut self, tokens: Vec<Address>) -> Self {
+        self.transfer_tokens = Some(tokens);
+        self
+    }
+
+    /// Report native balance changes, polled once per block (on by default)
+    pub fn with_native_balances(mut self, enabled: bool) -> Self {
+        self.native_balances = enabled;
+        self
+    }
+
+    /// Label of a watched wallet
+    pub fn label(&self, wallet: Address) -> Option<&str> {
+        self.wallets.get(&wallet).and_then(|label| label.as_deref())
+    }
+
+    pub fn wallets(&self) -> Vec<Address> {
+        self.wallets.keys().copied().collect()
+    }
+
+    /// Subscribe to the merged activity feed
+    ///
+    /// Each source has its own subscription on the shared connection; the feed
+    /// interleaves them in arrival order and ends once all of them have ended.
+    pub async fn subscribe(&self) -> Result<ActivityStream> {
+        if self.wallets.is_empty() {
+            return Err(anyhow::anyhow!("No wallets to monitor"));
+        }
+
+        let wallets: Wallets = Arc::new(self.wallets.clone());
+        let mut sources = vec![
+            self.curve_trades(wallets.clone()).await?,
+            self.transfers(wallets.clone(), true).await?,
+            self.transfers(wallets.clone(), false).await?,
+        ];
+        if !self.pools.is_empty() {
+            sources.push(self.swaps(wallets.clone()).await?);
+        }
+        if self.native_balances {
+            sources.push(self.balances(wallets).await?);
+        }
+
+        Ok(Box::pin(select_all(sources)))
+    }
+
+    async fn logs(&self, filter: Filter) -> Result<impl Stream<Item = Log> + Send + 'static> {
+        Ok(self.provider.subscribe_logs(&filter).await?.into_stream())
+    }
+
+    async fn curve_trades(&self, wallets: Wallets) -> Result<ActivityStream> {
+        let bonding_curve: Address = BONDING_CURVE
+            .parse()
+            .expect("Invalid bonding curve address");
+        let filter = Filter::new()
+            .address(bonding_curve)
+            .event_signature(TRADE_EVENT_SIGNATURES.to_vec())
+            .topic1(self.wallet_topics());
+
+        Ok(Box::pin(self.logs(filter).await?.map(move |log| {
+            let event = decode_bonding_curve_event(log)?;
+            let sender = match &event {
+                BondingCurveEvent::Buy(e) => e.sender,
+                BondingCurveEvent::Sell(e) => e.sender,
+                _ => {
+                    return Err(anyhow::anyhow!(
+                        "Unexpected curve event {:?}",
+                        event.event_type()
+                    ));
+                }
+            };
+            Ok(activity(
+                &wallets,
+                sender,
+                event.block_number(),
+                WalletActivityKind::CurveTrade(event),
+            ))
+        })))
+    }
+
+    /// Transfers sent by (`outgoing`) or to the watched wallets
+    async fn transfers(&self, wallets: Wallets, outgoing: bool) -> Result<ActivityStream> {
+        let mut filter = Filter::new().event_signature(IToken::Transfer::SIGNATURE_HASH);
+        filter = if outgoing {
+            filter.topic1(self.wallet_topics())
+        } else {
+            filter.topic2(self.wallet_topics())
+        };
+        if let Some(tokens) = &self.transfer_tokens {
+            filter = filter.address(tokens.clone());
+        }
+
+        let logs = self.logs(filter).await?;
+        Ok(Box::pin(logs.filter_map(move |log| {
+            let result = match decode_transfer_event(log) {
+                // A self-transfer is reported once, as outgoing
+                Ok(transfer) if !outgoing && transfer.from == transfer.to => None,
+                Ok(transfer) => {
+                    let wallet = if outgoing { transfer.from } else { transfer.to };
+                    Some(Ok(activity(
+                        &wallets,
+                        wallet,
+                        transfer.block_number,
+                        WalletActivityKind::Transfer(transfer),
+                    )))
+                }
+                Err(e) => Some(Err(e)),
+            };
+            futures_util::future::ready(result)
+        })))
+    }
+
+    async fn swaps(&self, wallets: Wallets) -> Result<ActivityStream> {
+        let filter = Filter::new()
+            .address(self.pools.clone())
+            .event_signature(UniswapV3Pool::Swap::SIGNATURE_HASH);
+
+        let logs = self.logs(filter).await?;
+        Ok(Box::pin(logs.flat_map(move |log| {
+            let items: Vec<Result<WalletActivity>> = match decode_swap_event(log) {
+                Ok(swap) => swap_activities(&wallets, &swap)
+                    .into_iter()
+                    .map(Ok)
+                    .collect(),
+                Err(e) => vec![Err(e)],
+            };
+            futures_util::stream::iter(items)
+        })))
+    }
+
+    /// Balance of every wallet re-read at each new head, reported when it changed
+    async fn balances(&self, wallets: Wallets) -> Result<ActivityStream> {
+        let provider = self.provider.clone();
+        let addresses: Vec<Address> = wallets.keys().copied().collect();
+        let mut last: HashMap<Address, U256> = addresses
+            .iter()
+            .copied()
+            .zip(read_balances(&provider, &addresses, BlockId::latest()).await?)
+            .collect();
+        let heads = provider.subscribe_blocks().await?.into_stream();
+
+        Ok(Box::pin(async_stream::stream! {
+            futures_util::pin_mut!(heads);
+            while let Some(head) = heads.next().await {
+                let balances =
+                    match read_balances(&provider, &addresses, BlockId::number(head.number)).await {
+                        Ok(balances) => balances,
+                        Err(e) => {
+                            yield Err(e);
+                            continue;
+                        }
+                    };
+
+                for (wallet, current) in addresses.iter().copied().zip(balances) {
+                    let previous = last.insert(wallet, current).unwrap_or(current);
+                    if previous != current {
+                        yield Ok(activity(
+                            &wallets,
+                            wallet,
+                            head.number,
+                            WalletActivityKind::NativeBalance { previous, current },
+                        ));
+                    }
+                }
+            }
+        }))
+    }
+
+    fn wallet_topics(&self) -> Vec<B256> {
+        self.wallets
+            .keys()
+            .map(|wallet| wallet.into_word())
+            .collect()
+    }
+}
+
+async fn read_balances(
+    provider: &DynProvider,
+    wallets: &[Address],
+    at: BlockId,
+) -> Result<Vec<U256>> {
+    try_join_all(wallets.iter().map(|wallet| async move {
+        Ok::<_, anyhow::Error>(provider.get_balance(*wallet).block_id(at).await?)
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_wallet_activity_matching() {
+        let watched = fixtures::FIXTURE_SENDER;
+        let other = Address::repeat_byte(0x44);
+        let wallets: Wallets = Arc::new(HashMap::from([(watched, Some("whale".to_string()))]));
+
+        // Sender and recipient are the same watched wallet: one activity
+        let swap = fixtures::swap().expected;
+        let activities = swap_activities(&wallets, &swap);
+        assert_eq!(activities.len(), 1);
+        assert_eq!(activities[0].label.as_deref(), Some("whale"));
+        assert_eq!(activities[0].block, fixtures::FIXTURE_BLOCK);
+
+        let mut unrelated = swap.clone();
+        unrelated.sender = other;
+        unrelated.recipient = other;
+        assert!(swap_activities(&wallets, &unrelated).is_empty());
+
+        let transfer = IToken::Transfer {
+            from: watched,
+            to: other,
+            value: U256::from(42u64),
+        };
+        let mut log = fixtures::curve_buy().log;
+        log.inner.address = fixtures::FIXTURE_TOKEN;
+        log.inner.data = transfer.encode_log_data();
+        let decoded = decode_transfer_event(log.clone()).unwrap();
+        assert_eq!((decoded.from, decoded.to), (watched, other));
+        assert_eq!(decoded.token, fixtures::FIXTURE_TOKEN);
+        assert_eq!(decoded.value, U256::from(42u64));
+
+        assert!(decode_transfer_event(fixtures::curve_buy().log).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+//! Token lists in the Uniswap token list JSON format
+//!
+//! Teams share watchlists as files: a [`TokenList`] loads and saves the
+//! [token list](https://tokenlists.org) format, fills in missing metadata from
+//! chain, and turns into stream filters:
+//!
+//! ```rust,ignore
+//! let mut list = TokenList::load("watchlist.json")?;
+//! token_helper.hydrate_token_list(&mut list, chain_id).await?; // fills symbol/name/decimals
+//! list.save("watchlist.json")?;
+//!
+//! let stream = CurveStream::new(ws_url).await?.filter_token_list(&list.tagged("whales"));
+//! let indexer = CurveIndexer::new(provider).with_predicate(list.predicate());
+//! ```
+//!
+//! Entries only need an `address` to load; `name`, `symbol` and `decimals` may be
+//! left out and hydrated later.
+
+use crate::{stream::Predicate, token::TokenHelper};
+use alloy::primitives::Address;
+use anyhow::Result;
+use futures_util::future::try_join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Semantic version of a token list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TokenListVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Default for TokenListVersion {
+    fn default() -> Self {
+        Self {
+            major: 1,
+            minor: 0,
+            patch: 0,
+        }
+    }
+}
+
+/// Description of a tag used by list entries
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenListTag {
+    pub name: String,
+    pub description: String,
+}
+
+/// One token of a [`TokenList`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListEntry {
+    pub chain_id: u64,
+    pub address: Address,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub decimals: u8,
+    #[serde(rename = "logoURI", default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl TokenListEntry {
+    pub fn new(chain_id: u64, address: Address) -> Self {
+        Self {
+            chain_id,
+            address,
+            name: String::new(),
+            symbol: String::new(),
+            decimals: 0,
+            logo_uri: None,
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// True when symbol or decimals still need to be read from chain
+    pub fn needs_hydration(&self) -> bool {
+        self.symbol.is_empty() || self.decimals == 0
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+/// A named, versioned list of tokens
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenList {
+    pub name: String,
+    /// ISO 8601 timestamp of the last change
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default)]
+    pub version: TokenListVersion,
+    pub tokens: Vec<TokenListEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, TokenListTag>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+    #[serde(rename = "logoURI", default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+}
+
+impl TokenList {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            version: TokenListVersion::default(),
+            tokens: Vec::new(),
+            tags: BTreeMap::new(),
+            keywords: Vec::new(),
+            logo_uri: None,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, address: Address) -> Option<&TokenListEntry> {
+        self.tokens.iter().find(|entry| entry.address == address)
+    }
+
+    /// Add `entry`, replacing an entry with the same chain and address
+    pub fn insert(&mut self, entry: TokenListEntry) {
+        match self
+            .tokens
+            .iter_mut()
+            .find(|e| e.chain_id == entry.chain_id && e.address == entry.address)
+        {
+            Some(existing) => *existing = entry,
+            None => self.tokens.push(entry),
+        }
+        self.timestamp = chrono::Utc::now().to_rfc3339();
+    }
+
+    /// Remove every entry for `address`; true if any was removed
+    pub fn remove(&mut self, address: Address) -> bool {
+        let before = self.tokens.len();
+        self.tokens.retain(|entry| entry.address != address);
+        let removed = self.tokens.len() != before;
+        if removed {
+            self.timestamp = chrono::Utc::now().to_rfc3339();
+        }
+        removed
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.tokens.iter().map(|entry| entry.address).collect()
+    }
+
+    /// The entries carrying `tag`, as a list of their own
+    pub fn tagged(&self, tag: &str) -> TokenList {
+        TokenList {
+            tokens: self
+                .tokens
+                .iter()
+                .filter(|entry| entry.has_tag(tag))
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// The entries of one chain, as a list of their own
+    pub fn for_chain(&self, chain_id: u64) -> TokenList {
+        TokenList {
+            tokens: self
+                .tokens
+                .iter()
+                .filter(|entry| entry.chain_id == chain_id)
+                .cloned()
+                .collect(),
+            ..self.clone()
+        }
+    }
+
+    /// Predicate matching events of the listed tokens
+    pub fn predicate(&self) -> Predicate {
+        Predicate::token_in(self.addresses())
+    }
+}
+
+impl TokenHelper {
+    /// Fill in name, symbol and decimals of entries that lack them
+    ///
+    /// Only entries of `chain_id` are read; returns how many were hydrated.
+    pub async fn hydrate_token_list(&self, list: &mut TokenList, chain_id: u64) -> Result<usize> {
+        let pending: Vec<usize> = list
+            .tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.chain_id == chain_id && entry.needs_hydration())
+            .map(|(index, _)| index)
+            .collect();
+
+        let metadata = try_join_all(
+            pending
+                .iter()
+                .map(|&index| self.get_token_metadata(list.tokens[index].address)),
+        )
+        .await?;
+
+        for (index, metadata) in pending.iter().zip(metadata) {
+            let entry = &mut list.tokens[*index];
+            entry.name = metadata.name;
+            entry.symbol = metadata.symbol;
+            entry.decimals = metadata.decimals;
+        }
+        if !pending.is_empty() {
+            list.timestamp = chrono::Utc::now().to_rfc3339();
+        }
+        Ok(pending.len())
+    }
+
+    /// A hydrated entry for `token`
+    pub async fn token_list_entry(&self, token: Address, chain_id: u64) -> Result<TokenListEntry> {
+        let metadata = self.get_token_metadata(token).await?;
+        Ok(TokenListEntry {
+            name: metadata.name,
+            symbol: metadata.symbol,
+            decimals: metadata.decimals,
+            ..TokenListEntry::new(chain_id, token)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_list_json_roundtrip() {
+        let json = r#"{
+            "name": "Watchlist",
+            "timestamp": "2025-01-01T00:00:00Z",
+            "version": { "major": 1, "minor": 2, "patch": 0 },
+            "tokens": [
+                {
+                    "chainId": 10143,
+                    "address": "0x1111111111111111111111111111111111111111",
+                    "symbol": "AAA",
+                    "name": "Token A",
+                    "decimals": 18,
+                    "logoURI": "ipfs://logo",
+                    "tags": ["whales"]
+                },
+                { "chainId": 10143, "address": "0x2222222222222222222222222222222222222222" }
+            ]
+        }"#;
+
+        let mut list = TokenList::from_json(json).unwrap();
+        assert_eq!(list.version.minor, 2);
+        assert_eq!(list.tokens[0].logo_uri.as_deref(), Some("ipfs://logo"));
+        assert!(!list.tokens[0].needs_hydration());
+        assert!(list.tokens[1].needs_hydration());
+        assert_eq!(
+            list.tagged("whales").addresses(),
+            vec![Address::repeat_byte(0x11)]
+        );
+
+        let reloaded = TokenList::from_json(&list.to_json().unwrap()).unwrap();
+        assert_eq!(reloaded, list);
+        assert!(list.to_json().unwrap().contains("\"chainId\": 10143"));
+
+        list.insert(TokenListEntry::new(10143, Address::repeat_byte(0x22)).with_tags(["new"]));
+        assert_eq!(list.tokens.len(), 2);
+        assert!(list.remove(Address::repeat_byte(0x11)));
+        assert_eq!(list.addresses(), vec![Address::repeat_byte(0x22)]);
+    }
+}
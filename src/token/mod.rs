@@ -59,6 +59,64 @@
 //! ).await?;
 //! ```
 //!
+//! ## Allowance Storage Slots
+//!
+//! [`storage`] computes where an ERC-20 keeps `allowance(owner, spender)`, for
+//! `eth_call`/`eth_estimateGas` state overrides, and probes tokens whose layout
+//! isn't known up front:
+//!
+//! ```rust,ignore
+//! if let Some(mapping_slot) = probe_allowance_slot(provider, token, owner, spender).await? {
+//!     let overrides = allowance_override(token, owner, spender, mapping_slot, U256::MAX);
+//! }
+//! ```
+//!
+//! ## Fee-on-Transfer Tokens
+//!
+//! [`detect_transfer_fee`] simulates a transfer and reports how much of it the
+//! recipient actually receives; [`Trade::plan_sell`](crate::trading::Trade::plan_sell)
+//! uses it to quote sales on the post-fee amount:
+//!
+//! ```rust,ignore
+//! let fee = token_helper.detect_transfer_fee(token, router.address(), amount).await?;
+//! println!("{} bps fee, {} arrives", fee.fee_bps, fee.received);
+//! ```
+//!
+//! ## Sweeping Multiple Wallets
+//!
+//! [`Sweeper`] consolidates token balances (and optionally native MON) from many
+//! wallets into one address, with a dry-run mode that only reports the plan:
+//!
+//! ```rust,ignore
+//! let sweeper = Sweeper::new(rpc_url, private_keys)?;
+//! let mut config = SweepConfig::new(treasury, vec![token]);
+//! config.dry_run = true;
+//! let report = sweeper.sweep(&config).await;
+//! println!("Would sweep {} tokens", report.total(SweepAsset::Token(token)));
+//! ```
+//!
+//! ## Off-chain Metadata
+//!
+//! With the `offchain` feature, `TokenUriResolver` fetches the JSON document behind
+//! a token's URI through configurable IPFS/Arweave gateways:
+//!
+//! ```rust,ignore
+//! let resolver = TokenUriResolver::new().with_timeout(Duration::from_secs(5));
+//! let metadata = resolver.resolve(&create_event.token_uri).await?;
+//! println!("{:?} {:?}", metadata.description, resolver.image_url(&metadata));
+//! ```
+//!
+//! ## Token Lists
+//!
+//! With the `token-list` feature, [`TokenList`](list::TokenList) reads and writes
+//! watchlists in the Uniswap token list JSON format and hydrates missing metadata:
+//!
+//! ```rust,ignore
+//! let mut list = TokenList::load("watchlist.json")?;
+//! token_helper.hydrate_token_list(&mut list, chain_id).await?;
+//! let stream = CurveStream::new(ws_url).await?.filter_token_list(&list);
+//! ```
+//!
 //! ## Error Handling
 //!
 //! The module provides detailed error handling for common scenarios:
@@ -70,11 +128,39 @@
 //! ## Performance Optimization
 //!
 //! - **Batch Calls**: Multiple token operations in single RPC call where possible
-//! - **Caching**: Metadata caching to reduce redundant network calls
+//! - **Caching**: Decimals and symbols are cached, and can share a [`MetadataCache`](crate::metadata::MetadataCache) with other components
 //! - **Gas Estimation**: Automatic gas estimation with safety margins
 
 /// ERC-20 token interaction utilities and helpers
+#[allow(clippy::module_inception)]
 pub mod token;
 
+/// ERC-20 storage slots for state-override simulations
+pub mod storage;
+
+/// Fee-on-transfer detection by simulated transfer
+pub mod transfer_fee;
+
+/// Multi-wallet balance sweeping and consolidation
+pub mod sweep;
+
+/// Off-chain token URI metadata resolution
+#[cfg(feature = "offchain")]
+pub mod offchain;
+
+/// Token list files (Uniswap token list format)
+#[cfg(feature = "token-list")]
+pub mod list;
+
 // Re-export main types for convenience
-pub use token::TokenHelper;
\ No newline at end of file
+pub use storage::{
+    OZ_ALLOWANCES_SLOT, OZ_UPGRADEABLE_ALLOWANCES_SLOT, allowance_override, allowance_slot,
+    oz_allowance_slot, probe_allowance_slot,
+};
+pub use sweep::{SweepAsset, SweepConfig, SweepReport, SweepTransfer, Sweeper};
+pub use token::TokenHelper;
+pub use transfer_fee::{TransferFee, detect_transfer_fee, transfer_fee_bps};
+#[cfg(feature = "offchain")]
+pub use offchain::{OffchainTokenMetadata, TokenUriResolver};
+#[cfg(feature = "token-list")]
+pub use list::{TokenList, TokenListEntry, TokenListTag, TokenListVersion};
@@ -0,0 +1,191 @@
+//! Off-chain token metadata resolution
+//!
+//! `CreateEvent::token_uri` points at a JSON document (usually on IPFS) with the
+//! token's description, image and socials. [`TokenUriResolver`] maps `ipfs://`
+//! and `ar://` URIs onto HTTP gateways, tries each gateway in order with a
+//! per-request timeout, and parses the document into [`OffchainTokenMetadata`].
+
+use anyhow::Result;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Default IPFS gateways, tried in order
+pub const DEFAULT_IPFS_GATEWAYS: [&str; 3] = [
+    "https://ipfs.io/ipfs/",
+    "https://cloudflare-ipfs.com/ipfs/",
+    "https://gateway.pinata.cloud/ipfs/",
+];
+
+/// Default Arweave gateway
+pub const DEFAULT_ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+
+/// Token metadata document referenced by `token_uri`
+///
+/// Unknown fields are kept in `extra` so new keys don't break parsing.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct OffchainTokenMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+    #[serde(alias = "image_uri", alias = "imageUri")]
+    pub image: Option<String>,
+    #[serde(alias = "home_page", alias = "homepage", alias = "website_url")]
+    pub website: Option<String>,
+    #[serde(alias = "x", alias = "twitter_url")]
+    pub twitter: Option<String>,
+    #[serde(alias = "telegram_url")]
+    pub telegram: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Fetches and parses token URI documents
+#[derive(Debug, Clone)]
+pub struct TokenUriResolver {
+    client: reqwest::Client,
+    ipfs_gateways: Vec<String>,
+    arweave_gateway: String,
+    timeout: Duration,
+}
+
+impl Default for TokenUriResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenUriResolver {
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            ipfs_gateways: DEFAULT_IPFS_GATEWAYS
+                .iter()
+                .map(|g| g.to_string())
+                .collect(),
+            arweave_gateway: DEFAULT_ARWEAVE_GATEWAY.to_string(),
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Replace the IPFS gateways (each should end with `/ipfs/`)
+    pub fn with_ipfs_gateways(mut self, gateways: Vec<String>) -> Self {
+        self.ipfs_gateways = gateways;
+        self
+    }
+
+    pub fn with_arweave_gateway(mut self, gateway: String) -> Self {
+        self.arweave_gateway = gateway;
+        self
+    }
+
+    /// Timeout for each individual gateway request
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Use a preconfigured HTTP client (proxies, headers, TLS settings)
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// HTTP URLs to try for a URI, in order
+    pub fn candidate_urls(&self, uri: &str) -> Vec<String> {
+        let uri = uri.trim();
+
+        if let Some(path) = uri.strip_prefix("ipfs://") {
+            let path = path.trim_start_matches("ipfs/");
+            return self
+                .ipfs_gateways
+                .iter()
+                .map(|gateway| format!("{}{}", gateway, path))
+                .collect();
+        }
+
+        if let Some(path) = uri.strip_prefix("ar://") {
+            return vec![format!("{}{}", self.arweave_gateway, path)];
+        }
+
+        if uri.starts_with("http://") || uri.starts_with("https://") {
+            return vec![uri.to_string()];
+        }
+
+        Vec::new()
+    }
+
+    /// Fetch and parse the metadata document behind a token URI
+    pub async fn resolve(&self, uri: &str) -> Result<OffchainTokenMetadata> {
+        let urls = self.candidate_urls(uri);
+        if urls.is_empty() {
+            return Err(anyhow::anyhow!("Unsupported token URI scheme: {}", uri));
+        }
+
+        let mut last_error = None;
+        for url in urls {
+            match self.fetch(&url).await {
+                Ok(metadata) => return Ok(metadata),
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error
+            .unwrap_or_else(|| anyhow::anyhow!("No gateway returned metadata"))
+            .context(format!("Failed to resolve token URI {}", uri)))
+    }
+
+    /// HTTP URL for the metadata image, using the first configured gateway
+    pub fn image_url(&self, metadata: &OffchainTokenMetadata) -> Option<String> {
+        let image = metadata.image.as_deref()?;
+        self.candidate_urls(image).into_iter().next()
+    }
+
+    async fn fetch(&self, url: &str) -> Result<OffchainTokenMetadata> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(self.timeout)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json::<OffchainTokenMetadata>().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candidate_urls() {
+        let resolver = TokenUriResolver::new()
+            .with_ipfs_gateways(vec!["https://gw.example/ipfs/".to_string()]);
+
+        assert_eq!(
+            resolver.candidate_urls("ipfs://QmHash/meta.json"),
+            vec!["https://gw.example/ipfs/QmHash/meta.json"]
+        );
+        assert_eq!(
+            resolver.candidate_urls("ipfs://ipfs/QmHash"),
+            vec!["https://gw.example/ipfs/QmHash"]
+        );
+        assert_eq!(
+            resolver.candidate_urls("https://storage.example/token.json"),
+            vec!["https://storage.example/token.json"]
+        );
+        assert!(resolver.candidate_urls("unknown:thing").is_empty());
+    }
+
+    #[test]
+    fn test_metadata_aliases_and_extra_fields() {
+        let json = r#"{"name":"Cat","image_uri":"ipfs://QmImg","x":"https://x.com/cat","rank":1}"#;
+        let metadata: OffchainTokenMetadata = serde_json::from_str(json).unwrap();
+
+        assert_eq!(metadata.name.as_deref(), Some("Cat"));
+        assert_eq!(metadata.image.as_deref(), Some("ipfs://QmImg"));
+        assert_eq!(metadata.twitter.as_deref(), Some("https://x.com/cat"));
+        assert!(metadata.extra.contains_key("rank"));
+    }
+}
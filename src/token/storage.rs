@@ -0,0 +1,140 @@
+//! ERC-20 storage slots for state overrides
+//!
+//! Simulations that need an allowance which doesn't exist yet (e.g. estimating a
+//! sell before approving) override the token's storage directly. Solidity stores
+//! `mapping(address => mapping(address => uint256)) _allowances` at
+//! `keccak256(spender . keccak256(owner . mapping_slot))`, so only the mapping's
+//! base slot depends on the token's layout:
+//!
+//! ```rust,ignore
+//! let mapping_slot = probe_allowance_slot(provider.as_ref(), token, owner, router)
+//!     .await?
+//!     .ok_or_else(|| anyhow::anyhow!("unknown allowance layout"))?;
+//! let overrides = allowance_override(token, owner, router, mapping_slot, U256::MAX);
+//! let gas = provider.estimate_gas(tx).overrides(overrides).await?;
+//! ```
+
+use super::token::IToken;
+use alloy::{
+    primitives::{Address, B256, U256, b256, keccak256},
+    providers::Provider,
+    rpc::types::{
+        TransactionRequest,
+        state::{AccountOverride, StateOverride},
+    },
+    sol_types::{SolCall, SolValue},
+};
+use anyhow::Result;
+
+/// `_allowances` mapping slot of OpenZeppelin's `ERC20` (`_balances` is slot 0)
+pub const OZ_ALLOWANCES_SLOT: U256 = U256::from_limbs([1, 0, 0, 0]);
+
+/// `_allowances` mapping slot of OpenZeppelin's `ERC20Upgradeable`
+///
+/// ERC-7201 namespace `openzeppelin.storage.ERC20`, second field of the struct.
+pub const OZ_UPGRADEABLE_ALLOWANCES_SLOT: B256 =
+    b256!("0x52c63247e1f47db19d5ce0460030c497f067ca4cebf71ba98eeadabe20bace01");
+
+/// Plain mapping slots tried after the OpenZeppelin layouts (0..=this)
+const MAX_PROBED_SLOT: u64 = 10;
+
+/// Storage slot holding `allowance(owner, spender)` for a Solidity mapping at `mapping_slot`
+pub fn allowance_slot(owner: Address, spender: Address, mapping_slot: U256) -> B256 {
+    let inner = keccak256((owner, mapping_slot).abi_encode());
+    keccak256((spender, inner).abi_encode())
+}
+
+/// Storage slot holding `allowance(owner, spender)` in an OpenZeppelin `ERC20`
+pub fn oz_allowance_slot(owner: Address, spender: Address) -> B256 {
+    allowance_slot(owner, spender, OZ_ALLOWANCES_SLOT)
+}
+
+/// State override setting `allowance(owner, spender)` of `token` to `value`
+pub fn allowance_override(
+    token: Address,
+    owner: Address,
+    spender: Address,
+    mapping_slot: U256,
+    value: U256,
+) -> StateOverride {
+    let slot = allowance_slot(owner, spender, mapping_slot);
+    let mut overrides = StateOverride::default();
+    overrides.insert(
+        token,
+        AccountOverride::default().with_state_diff([(slot, B256::from(value))]),
+    );
+    overrides
+}
+
+/// Find the `_allowances` mapping slot of `token` by trial
+///
+/// Tries the OpenZeppelin layouts first, then plain slots 0 to 10: each candidate
+/// overrides the computed slot with a marker value and checks whether
+/// `allowance(owner, spender)` returns it. Best effort: returns `Ok(None)` for
+/// layouts that aren't a Solidity mapping (e.g. Solady, Vyper), and an error if
+/// the node rejects state overrides.
+pub async fn probe_allowance_slot<P: Provider>(
+    provider: &P,
+    token: Address,
+    owner: Address,
+    spender: Address,
+) -> Result<Option<U256>> {
+    // Unlikely to be a real allowance, so a match means the override took
+    let marker = U256::from_be_bytes(keccak256("nadfun.allowance.probe").0);
+    let calldata = IToken::allowanceCall { owner, spender }.abi_encode();
+
+    let namespaced = U256::from_be_bytes(OZ_UPGRADEABLE_ALLOWANCES_SLOT.0);
+    let plain = (0..=MAX_PROBED_SLOT)
+        .map(U256::from)
+        .filter(|slot| *slot != OZ_ALLOWANCES_SLOT);
+    let candidates = [OZ_ALLOWANCES_SLOT, namespaced].into_iter().chain(plain);
+
+    for mapping_slot in candidates {
+        let overrides = allowance_override(token, owner, spender, mapping_slot, marker);
+        let output = provider
+            .call(
+                TransactionRequest::default()
+                    .to(token)
+                    .input(calldata.clone().into()),
+            )
+            .overrides(overrides)
+            .await?;
+        if IToken::allowanceCall::abi_decode_returns(&output)? == marker {
+            return Ok(Some(mapping_slot));
+        }
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oz_upgradeable_namespace() {
+        // keccak256(abi.encode(uint256(keccak256("openzeppelin.storage.ERC20")) - 1)) & ~0xff
+        let id = U256::from_be_bytes(keccak256("openzeppelin.storage.ERC20").0) - U256::from(1);
+        let mut base = keccak256(id.abi_encode());
+        base.0[31] = 0;
+
+        let allowances = U256::from_be_bytes(base.0) + U256::from(1);
+        assert_eq!(B256::from(allowances), OZ_UPGRADEABLE_ALLOWANCES_SLOT);
+    }
+
+    #[test]
+    fn test_allowance_slot_is_directional() {
+        let owner = Address::repeat_byte(0x11);
+        let spender = Address::repeat_byte(0x22);
+
+        let slot = oz_allowance_slot(owner, spender);
+        assert_eq!(slot, allowance_slot(owner, spender, U256::from(1)));
+        assert_ne!(slot, oz_allowance_slot(spender, owner));
+        assert_ne!(slot, allowance_slot(owner, spender, U256::ZERO));
+
+        let overrides =
+            allowance_override(Address::ZERO, owner, spender, OZ_ALLOWANCES_SLOT, U256::MAX);
+        let diff = overrides[&Address::ZERO].state_diff.as_ref().unwrap();
+        assert_eq!(diff[&slot], B256::from(U256::MAX));
+    }
+}
@@ -0,0 +1,326 @@
+//! Multi-wallet sweep / consolidation
+//!
+//! Moves all balances of a set of tokens (and optionally native MON minus gas)
+//! from many wallets into a single target address.
+
+use crate::{token::token::IToken, trading::TxUtils};
+use alloy::{
+    eips::BlockId,
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, B256, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+};
+use anyhow::Result;
+use futures_util::future::join_all;
+use std::time::Duration;
+
+/// Gas limit for a plain native transfer
+const NATIVE_TRANSFER_GAS: u64 = 21_000;
+/// Gas assumed for a dry-run token transfer whose estimate fails
+const TOKEN_TRANSFER_GAS: u64 = 65_000;
+
+/// Asset moved by a sweep transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SweepAsset {
+    Token(Address),
+    Native,
+}
+
+/// Sweep settings
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    /// Address receiving all swept funds
+    pub target: Address,
+    /// Tokens whose full balances are swept
+    pub tokens: Vec<Address>,
+    /// Also sweep native MON, leaving exactly enough for the transfer's gas
+    pub include_native: bool,
+    /// Plan and report without sending any transactions
+    pub dry_run: bool,
+    /// How long to wait for each wallet's token transfers before sweeping native funds
+    pub receipt_timeout: Duration,
+}
+
+impl SweepConfig {
+    pub fn new(target: Address, tokens: Vec<Address>) -> Self {
+        Self {
+            target,
+            tokens,
+            include_native: false,
+            dry_run: false,
+            receipt_timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A single planned or executed transfer
+#[derive(Debug, Clone)]
+pub struct SweepTransfer {
+    pub wallet: Address,
+    pub asset: SweepAsset,
+    pub amount: U256,
+    pub nonce: u64,
+    /// Set once the transfer was submitted (None in dry-run mode)
+    pub tx_hash: Option<B256>,
+    /// Set if the transfer could not be submitted or reverted
+    pub error: Option<String>,
+}
+
+/// Result of a sweep run
+#[derive(Debug, Clone, Default)]
+pub struct SweepReport {
+    pub dry_run: bool,
+    pub transfers: Vec<SweepTransfer>,
+    /// Wallets that failed before any transfer could be planned
+    pub wallet_errors: Vec<(Address, String)>,
+    /// (wallet, asset, error) of balances that couldn't be read; the rest of the
+    /// wallet is still swept
+    pub skipped: Vec<(Address, SweepAsset, String)>,
+}
+
+impl SweepReport {
+    /// Total amount of an asset moved (or planned) without errors
+    pub fn total(&self, asset: SweepAsset) -> U256 {
+        self.transfers
+            .iter()
+            .filter(|t| t.asset == asset && t.error.is_none())
+            .fold(U256::ZERO, |acc, t| acc + t.amount)
+    }
+
+    /// Transfers that failed
+    pub fn failed(&self) -> impl Iterator<Item = &SweepTransfer> {
+        self.transfers.iter().filter(|t| t.error.is_some())
+    }
+}
+
+/// Consolidates funds from many wallets into one address
+pub struct Sweeper {
+    wallets: Vec<(Address, DynProvider)>,
+}
+
+impl Sweeper {
+    /// Create a sweeper from private key strings, one provider per wallet
+    pub fn new(rpc_url: String, private_keys: Vec<String>) -> Result<Self> {
+        let mut wallets = Vec::with_capacity(private_keys.len());
+
+        for private_key in private_keys {
+            let signer: PrivateKeySigner = private_key.parse()?;
+            let address = signer.address();
+            let provider = ProviderBuilder::new()
+                .wallet(EthereumWallet::from(signer))
+                .connect_http(rpc_url.parse()?);
+            wallets.push((address, DynProvider::new(provider)));
+        }
+
+        Ok(Self { wallets })
+    }
+
+    /// Addresses of all managed wallets
+    pub fn wallet_addresses(&self) -> Vec<Address> {
+        self.wallets.iter().map(|(address, _)| *address).collect()
+    }
+
+    /// Sweep every wallet into `config.target`
+    ///
+    /// Wallets are processed concurrently. Within a wallet, nonces are assigned
+    /// sequentially starting from the pending nonce, so token transfers are
+    /// submitted back to back without waiting on each other.
+    pub async fn sweep(&self, config: &SweepConfig) -> SweepReport {
+        let results = join_all(
+            self.wallets
+                .iter()
+                .map(|(address, provider)| sweep_wallet(*address, provider, config)),
+        )
+        .await;
+
+        let mut report = SweepReport {
+            dry_run: config.dry_run,
+            ..Default::default()
+        };
+
+        for ((address, _), result) in self.wallets.iter().zip(results) {
+            match result {
+                Ok((transfers, skipped)) => {
+                    report.transfers.extend(transfers);
+                    report.skipped.extend(
+                        skipped
+                            .into_iter()
+                            .map(|(token, error)| (*address, SweepAsset::Token(token), error)),
+                    );
+                }
+                Err(e) => report.wallet_errors.push((*address, e.to_string())),
+            }
+        }
+
+        report
+    }
+}
+
+/// Tokens whose balance couldn't be read, with the error
+type SkippedTokens = Vec<(Address, String)>;
+
+/// Nonzero token balances to sweep, and the tokens whose balance read failed
+fn split_balances<E: std::fmt::Display>(
+    balances: Vec<(Address, std::result::Result<U256, E>)>,
+) -> (Vec<(Address, U256)>, SkippedTokens) {
+    let mut amounts = Vec::new();
+    let mut failed = Vec::new();
+    for (token, balance) in balances {
+        match balance {
+            Ok(amount) if amount.is_zero() => {}
+            Ok(amount) => amounts.push((token, amount)),
+            Err(e) => failed.push((token, e.to_string())),
+        }
+    }
+    (amounts, failed)
+}
+
+/// Native amount left to sweep once `pending_gas` (token transfers not yet
+/// mined) and the native transfer itself are paid for
+fn native_sweep_amount(balance: U256, gas_price: u128, pending_gas: u64) -> Option<U256> {
+    let gas = U256::from(pending_gas) + U256::from(NATIVE_TRANSFER_GAS);
+    let gas_cost = U256::from(gas_price).saturating_mul(gas);
+    balance
+        .checked_sub(gas_cost)
+        .filter(|amount| !amount.is_zero())
+}
+
+async fn sweep_wallet(
+    wallet: Address,
+    provider: &DynProvider,
+    config: &SweepConfig,
+) -> Result<(Vec<SweepTransfer>, SkippedTokens)> {
+    // Batched balance discovery
+    let balances = join_all(config.tokens.iter().map(|token| async move {
+        let balance = IToken::new(*token, provider).balanceOf(wallet).call().await;
+        (*token, balance)
+    }))
+    .await;
+    let (amounts, skipped) = split_balances(balances);
+
+    let mut nonce = provider
+        .get_transaction_count(wallet)
+        .block_id(BlockId::pending())
+        .await?;
+    let mut transfers = Vec::new();
+    // Gas the dry-run token transfers would spend before the native sweep
+    let mut pending_gas = 0u64;
+
+    for (token, amount) in amounts {
+        let mut transfer = SweepTransfer {
+            wallet,
+            asset: SweepAsset::Token(token),
+            amount,
+            nonce,
+            tx_hash: None,
+            error: None,
+        };
+
+        let contract = IToken::new(token, provider);
+        let call = contract.transfer(config.target, amount);
+        if config.dry_run {
+            let gas = call.from(wallet).estimate_gas().await;
+            pending_gas = pending_gas.saturating_add(gas.unwrap_or(TOKEN_TRANSFER_GAS));
+        } else {
+            match call.nonce(nonce).send().await {
+                Ok(pending) => transfer.tx_hash = Some(*pending.tx_hash()),
+                Err(e) => transfer.error = Some(e.to_string()),
+            }
+        }
+
+        if transfer.error.is_none() {
+            nonce += 1;
+        }
+        transfers.push(transfer);
+    }
+
+    if config.include_native {
+        // Token transfers must be mined first so the remaining balance is final
+        if !config.dry_run {
+            for transfer in transfers.iter_mut() {
+                if let Some(tx_hash) = transfer.tx_hash {
+                    match TxUtils::wait_for_receipt(provider, tx_hash, config.receipt_timeout).await
+                    {
+                        Ok(result) if !result.status => {
+                            transfer.error = Some("transaction reverted".to_string())
+                        }
+                        Ok(_) => {}
+                        Err(e) => transfer.error = Some(e.to_string()),
+                    }
+                }
+            }
+        }
+
+        let gas_price = provider.get_gas_price().await?;
+        let balance = provider.get_balance(wallet).await?;
+
+        if let Some(amount) = native_sweep_amount(balance, gas_price, pending_gas) {
+            let mut transfer = SweepTransfer {
+                wallet,
+                asset: SweepAsset::Native,
+                amount,
+                nonce,
+                tx_hash: None,
+                error: None,
+            };
+
+            if !config.dry_run {
+                let tx = TransactionRequest::default()
+                    .with_to(config.target)
+                    .with_value(amount)
+                    .with_nonce(nonce)
+                    .with_gas_limit(NATIVE_TRANSFER_GAS)
+                    .with_gas_price(gas_price);
+
+                match provider.send_transaction(tx).await {
+                    Ok(pending) => transfer.tx_hash = Some(*pending.tx_hash()),
+                    Err(e) => transfer.error = Some(e.to_string()),
+                }
+            }
+
+            transfers.push(transfer);
+        }
+    }
+
+    Ok((transfers, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_balances_skips_zero_and_records_failures() {
+        let (a, b, c) = (
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(3),
+        );
+        let (amounts, failed) = split_balances(vec![
+            (a, Ok(U256::from(5u64))),
+            (b, Ok(U256::ZERO)),
+            (c, Err("rate limited")),
+        ]);
+        assert_eq!(amounts, vec![(a, U256::from(5u64))]);
+        assert_eq!(failed, vec![(c, "rate limited".to_string())]);
+    }
+
+    #[test]
+    fn test_native_sweep_amount_reserves_pending_token_gas() {
+        let balance = U256::from(2_000_000u64);
+        // Native transfer only: 21_000 gas at 10 wei
+        assert_eq!(
+            native_sweep_amount(balance, 10, 0),
+            Some(U256::from(1_790_000u64))
+        );
+        // Two dry-run token transfers of 50_000 gas still to pay for
+        assert_eq!(
+            native_sweep_amount(balance, 10, 2 * 50_000),
+            Some(U256::from(790_000u64))
+        );
+        assert_eq!(native_sweep_amount(balance, 100, 0), None);
+        assert_eq!(native_sweep_amount(U256::from(210_000u64), 10, 0), None);
+    }
+}
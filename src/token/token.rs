@@ -1,16 +1,83 @@
-use crate::types::TokenMetadata;
+use super::storage::{allowance_override, probe_allowance_slot};
+use super::transfer_fee::{TransferFee, detect_transfer_fee};
+use crate::{
+    metadata::MetadataCache,
+    rpc::EndpointPool,
+    trading::{estimate_gas_from, get_default_gas_limit, TradeOperation, Operation, TxUtils},
+    types::{
+        MetadataField, NativeTransferParams, Router, SellPathCost, TokenMetadata,
+        TransactionResult,
+    },
+};
 use alloy::{
-    network::EthereumWallet,
+    network::{EthereumWallet, TransactionBuilder},
     primitives::{keccak256, Address, B256, U256},
-    providers::{DynProvider, ProviderBuilder},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
     signers::{Signer, local::PrivateKeySigner},
     sol,
+    sol_types::{SolCall, SolValue},
+    transports::RpcError,
 };
 use anyhow::Result;
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// Decimals assumed for tokens whose `decimals()` can't be read
+pub const FALLBACK_DECIMALS: u8 = 18;
+
+/// Largest `decimals()` accepted as genuine; `10^decimals` must fit in a U256
+pub const MAX_TOKEN_DECIMALS: u8 = 77;
+
+/// Decode a `name()`/`symbol()` return value as `string` or zero-padded `bytes32`
+///
+/// Older tokens (MKR-style) declare these getters as `bytes32`. A `string`
+/// return is at least two words long, so exactly one word means `bytes32`.
+pub fn decode_string_or_bytes32(data: &[u8]) -> Option<String> {
+    if data.len() == 32 {
+        let end = data.iter().rposition(|&byte| byte != 0).map_or(0, |i| i + 1);
+        return Some(String::from_utf8_lossy(&data[..end]).into_owned());
+    }
+    String::abi_decode(data).ok()
+}
+
+/// Read a string getter with a raw `eth_call`, accepting `bytes32` returns
+///
+/// `None` when the token reverts or returns neither encoding.
+pub(crate) async fn read_string_field<P: Provider>(
+    provider: &P,
+    token: Address,
+    calldata: Vec<u8>,
+) -> Result<Option<String>> {
+    let tx = TransactionRequest::default().to(token).input(calldata.into());
+    match provider.call(tx).await {
+        Ok(data) => Ok(decode_string_or_bytes32(&data)),
+        Err(RpcError::Transport(e)) => Err(e.into()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Result of an optional getter: `None` when the token can't answer it
+///
+/// Reverts, error responses and undecodable return data mean the token doesn't
+/// provide the value; only failures to reach the node are returned as errors.
+async fn optional_field<T>(
+    call: impl Future<Output = std::result::Result<T, alloy::contract::Error>>,
+) -> Result<Option<T>> {
+    match call.await {
+        Ok(value) => Ok(Some(value)),
+        Err(alloy::contract::Error::TransportError(RpcError::Transport(e))) => Err(e.into()),
+        Err(_) => Ok(None),
+    }
+}
 
 // Complete ERC20 + ERC20Permit + ERC20Burnable interface
 sol! {
+    #[allow(clippy::too_many_arguments)]
     #[sol(rpc)]
     interface IToken {
         // ERC20 Standard Functions
@@ -33,6 +100,9 @@ sol! {
         function nonces(address owner) external view returns (uint256);
         function DOMAIN_SEPARATOR() external view returns (bytes32);
 
+        // ERC-5267 EIP-712 domain retrieval
+        function eip712Domain() external view returns (bytes1 fields, string name, string version, uint256 chainId, address verifyingContract, bytes32 salt, uint256[] extensions);
+
         // ERC20Burnable Functions
         function burn(uint256 amount) external;
         function burnFrom(address account, uint256 amount) external;
@@ -42,6 +112,8 @@ sol! {
 pub struct TokenHelper {
     provider: Arc<DynProvider>,
     signer: PrivateKeySigner,
+    permit_support: Mutex<HashMap<Address, bool>>,
+    metadata: MetadataCache,
 }
 
 impl TokenHelper {
@@ -55,32 +127,112 @@ impl TokenHelper {
         Ok(Self {
             provider: dyn_provider,
             signer,
+            permit_support: Mutex::new(HashMap::new()),
+            metadata: MetadataCache::new(),
+        })
+    }
+
+    /// Create a TokenHelper on an existing provider that signs with `signer`'s wallet
+    pub fn from_provider(provider: Arc<DynProvider>, signer: PrivateKeySigner) -> Self {
+        Self {
+            provider,
+            signer,
+            permit_support: Mutex::new(HashMap::new()),
+            metadata: MetadataCache::new(),
+        }
+    }
+
+    /// Create a TokenHelper on a shared [`EndpointPool`] (keep-alive, failover, quorum)
+    pub fn new_with_pool(pool: &EndpointPool, private_key: String) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+
+        Ok(Self {
+            provider: Arc::new(pool.wallet_provider(wallet)),
+            signer,
+            permit_support: Mutex::new(HashMap::new()),
+            metadata: MetadataCache::new(),
+        })
+    }
+
+    /// Create a TokenHelper whose RPC calls are counted under [`Component::Token`]
+    ///
+    /// [`Component::Token`]: crate::metrics::Component::Token
+    #[cfg(feature = "metrics")]
+    pub fn new_with_metrics(
+        rpc_url: String,
+        private_key: String,
+        metrics: &crate::metrics::RpcMetrics,
+    ) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = metrics
+            .http(crate::metrics::Component::Token, &rpc_url)?
+            .wallet_provider(wallet);
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            signer,
+            permit_support: Mutex::new(HashMap::new()),
+            metadata: MetadataCache::new(),
         })
     }
 
+    /// Create a TokenHelper whose concurrent reads share JSON-RPC batches
+    /// (see [`CoalescingTransport`](crate::coalesce::CoalescingTransport))
+    pub fn new_with_batching(
+        rpc_url: String,
+        private_key: String,
+        config: crate::coalesce::CoalesceConfig,
+    ) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = crate::coalesce::CoalescingTransport::http(&rpc_url, config)?
+            .wallet_provider(wallet);
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            signer,
+            permit_support: Mutex::new(HashMap::new()),
+            metadata: MetadataCache::new(),
+        })
+    }
+
+    /// Share decimals/symbol lookups with other components
+    pub fn with_metadata_cache(mut self, metadata: MetadataCache) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    pub fn metadata_cache(&self) -> &MetadataCache {
+        &self.metadata
+    }
+
     // =================
     // ERC20 Functions
     // =================
 
     /// Get token name
+    ///
+    /// Accepts tokens returning `bytes32` instead of `string`.
     pub async fn name(&self, token: Address) -> Result<String> {
-        let contract = IToken::new(token, self.provider.as_ref());
-        let name = contract.name().call().await?;
-        Ok(name)
+        read_string_field(
+            self.provider.as_ref(),
+            token,
+            IToken::nameCall {}.abi_encode(),
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Token {} has no readable name()", token))
     }
 
-    /// Get token symbol
+    /// Get token symbol (cached)
     pub async fn symbol(&self, token: Address) -> Result<String> {
-        let contract = IToken::new(token, self.provider.as_ref());
-        let symbol = contract.symbol().call().await?;
-        Ok(symbol)
+        self.metadata.symbol(self.provider.as_ref(), token).await
     }
 
-    /// Get token decimals
+    /// Get token decimals (cached)
     pub async fn decimals(&self, token: Address) -> Result<u8> {
-        let contract = IToken::new(token, self.provider.as_ref());
-        let decimals = contract.decimals().call().await?;
-        Ok(decimals)
+        self.metadata.decimals(self.provider.as_ref(), token).await
     }
 
     /// Get total supply
@@ -97,6 +249,25 @@ impl TokenHelper {
         Ok(balance)
     }
 
+    /// Simulate sending `amount` of `token` to `to` and measure what arrives
+    ///
+    /// See [`detect_transfer_fee`]; the wallet must hold `amount`.
+    pub async fn detect_transfer_fee(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<TransferFee> {
+        detect_transfer_fee(
+            self.provider.as_ref(),
+            token,
+            self.wallet_address(),
+            to,
+            amount,
+        )
+        .await
+    }
+
     /// Get allowance between owner and spender
     pub async fn allowance(
         &self,
@@ -110,11 +281,18 @@ impl TokenHelper {
     }
 
     /// Transfer tokens (requires wallet with this token)
-    pub async fn transfer(&self, token: Address, to: Address, value: U256) -> Result<B256> {
+    ///
+    /// Waits for the receipt; the returned logs include the `Transfer` event.
+    pub async fn transfer(
+        &self,
+        token: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<TransactionResult> {
         let contract = IToken::new(token, self.provider.as_ref());
         let tx = contract.transfer(to, value).send().await?;
         let receipt = tx.get_receipt().await?;
-        Ok(receipt.transaction_hash)
+        Ok(TransactionResult::from(receipt))
     }
 
     /// Transfer tokens from one address to another (requires allowance)
@@ -124,19 +302,184 @@ impl TokenHelper {
         from: Address,
         to: Address,
         value: U256,
-    ) -> Result<B256> {
+    ) -> Result<TransactionResult> {
         let contract = IToken::new(token, self.provider.as_ref());
         let tx = contract.transferFrom(from, to, value).send().await?;
         let receipt = tx.get_receipt().await?;
-        Ok(receipt.transaction_hash)
+        Ok(TransactionResult::from(receipt))
     }
 
     /// Approve spender to spend tokens
-    pub async fn approve(&self, token: Address, spender: Address, value: U256) -> Result<B256> {
+    ///
+    /// Waits for the receipt; the returned logs include the `Approval` event.
+    pub async fn approve(
+        &self,
+        token: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<TransactionResult> {
         let contract = IToken::new(token, self.provider.as_ref());
         let tx = contract.approve(spender, value).send().await?;
         let receipt = tx.get_receipt().await?;
-        Ok(receipt.transaction_hash)
+        Ok(TransactionResult::from(receipt))
+    }
+
+    /// Submit a transfer without waiting for the receipt
+    ///
+    /// Returns the pending transaction hash; use [`TokenHelper::wait_for_transaction`]
+    /// to wait for it later.
+    pub async fn transfer_submit(&self, token: Address, to: Address, value: U256) -> Result<B256> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        let tx = contract.transfer(to, value).send().await?;
+        Ok(*tx.tx_hash())
+    }
+
+    /// Submit an approval without waiting for the receipt
+    ///
+    /// Returns the pending transaction hash; use [`TokenHelper::wait_for_transaction`]
+    /// to wait for it later.
+    pub async fn approve_submit(
+        &self,
+        token: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<B256> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        let tx = contract.approve(spender, value).send().await?;
+        Ok(*tx.tx_hash())
+    }
+
+    /// Wait for a previously submitted transaction to be mined
+    pub async fn wait_for_transaction(
+        &self,
+        tx_hash: B256,
+        timeout: Duration,
+    ) -> Result<TransactionResult> {
+        TxUtils::wait_for_receipt(self.provider.as_ref(), tx_hash, timeout).await
+    }
+
+    // =================
+    // Gas Estimation
+    // =================
+
+    /// Estimate gas for approving `spender` from the internal wallet
+    pub async fn estimate_approve_gas(
+        &self,
+        token: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<u64> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        let gas = contract
+            .approve(spender, value)
+            .from(self.wallet_address())
+            .estimate_gas()
+            .await?;
+        Ok(gas)
+    }
+
+    /// Estimate gas for transferring tokens from the internal wallet
+    pub async fn estimate_transfer_gas(
+        &self,
+        token: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<u64> {
+        let contract = IToken::new(token, self.provider.as_ref());
+        let gas = contract
+            .transfer(to, value)
+            .from(self.wallet_address())
+            .estimate_gas()
+            .await?;
+        Ok(gas)
+    }
+
+    /// Estimate selling `amount_in` before the router is approved
+    ///
+    /// See [`estimate_sell_gas_unapproved`].
+    pub async fn estimate_sell_gas_unapproved(
+        &self,
+        router: &Router,
+        token: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+        deadline: U256,
+    ) -> Result<u64> {
+        let owner = self.wallet_address();
+        let params = TradeOperation::Sell {
+            token,
+            amount_in,
+            amount_out_min,
+            to: owner,
+            deadline,
+        };
+        estimate_sell_gas_unapproved(self.provider.as_ref(), router, owner, params).await
+    }
+
+    /// Compare the gas cost of approve + sell against sellPermit for selling `amount_in`
+    ///
+    /// When the router's allowance already covers the amount, the sale is estimated
+    /// live and no approval is counted. Otherwise the sale is simulated with the
+    /// allowance overridden (see [`estimate_sell_gas_unapproved`]); nodes or tokens
+    /// where that doesn't work fall back to the default gas table. The
+    /// sellPermit estimate uses a throwaway signature that is never broadcast; it is
+    /// `None` if signing or estimation fails, e.g. for tokens without permit support.
+    ///
+    /// ```rust,ignore
+    /// let cost = token_helper
+    ///     .estimate_sell_path_cost(&router, token, amount, min_out)
+    ///     .await?;
+    /// println!("{:?} is cheapest at {} gas", cost.cheapest(), cost.cheapest_gas());
+    /// ```
+    pub async fn estimate_sell_path_cost(
+        &self,
+        router: &Router,
+        token: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+    ) -> Result<SellPathCost> {
+        estimate_sell_path_cost(
+            self.provider.clone(),
+            &self.signer,
+            router,
+            token,
+            amount_in,
+            amount_out_min,
+        )
+        .await
+    }
+
+    // =================
+    // Native MON Functions
+    // =================
+
+    /// Get the native MON balance of an address
+    pub async fn native_balance(&self, owner: Address) -> Result<U256> {
+        let balance = self.provider.get_balance(owner).await?;
+        Ok(balance)
+    }
+
+    /// Send native MON from the internal wallet
+    pub async fn send_native(&self, params: NativeTransferParams) -> Result<TransactionResult> {
+        let mut tx = TransactionRequest::default()
+            .with_to(params.to)
+            .with_value(params.amount);
+
+        if let Some(gas_limit) = params.gas_limit {
+            tx = tx.with_gas_limit(gas_limit);
+        }
+
+        if let Some(gas_price) = params.gas_price {
+            tx = tx.with_gas_price(gas_price);
+        }
+
+        if let Some(nonce) = params.nonce {
+            tx = tx.with_nonce(nonce);
+        }
+
+        let pending = self.provider.send_transaction(tx).await?;
+        let receipt = pending.get_receipt().await?;
+        Ok(TransactionResult::from(receipt))
     }
 
     // =================
@@ -157,6 +500,34 @@ impl TokenHelper {
         Ok(domain_separator)
     }
 
+    /// Whether `token` supports EIP-2612 permits
+    ///
+    /// Probes `nonces()`, `DOMAIN_SEPARATOR()` and `eip712Domain()` with `eth_call`
+    /// and caches the answer per token. Fails if the token has no contract code,
+    /// so a typo'd address isn't cached as "no permit support", and on RPC errors,
+    /// which are not cached either.
+    pub async fn supports_permit(&self, token: Address) -> Result<bool> {
+        let cached = self.permit_support.lock().unwrap().get(&token).copied();
+        if let Some(supported) = cached {
+            return Ok(supported);
+        }
+
+        let code = self.provider.get_code_at(token).await?;
+        if code.is_empty() {
+            return Err(anyhow::anyhow!("No contract code at token {}", token));
+        }
+
+        let supported =
+            probe_permit_support(self.provider.as_ref(), token, self.wallet_address()).await?;
+        self.permit_support.lock().unwrap().insert(token, supported);
+        Ok(supported)
+    }
+
+    /// Forget cached [`TokenHelper::supports_permit`] results
+    pub fn clear_permit_support_cache(&self) {
+        self.permit_support.lock().unwrap().clear();
+    }
+
     // Note: Direct permit() function is intentionally not provided
     //
     // Why? Because it's inefficient:
@@ -166,25 +537,32 @@ impl TokenHelper {
     // Instead, use:
     // 1. generate_permit_signature() to create signature
     // 2. sell_permit() to execute trade with permit in one transaction
+    //
+    // estimate_sell_path_cost() compares both paths for a concrete sale.
 
     // =================
     // ERC20Burnable Functions
     // =================
 
     /// Burn tokens from caller's account
-    pub async fn burn(&self, token: Address, amount: U256) -> Result<B256> {
+    pub async fn burn(&self, token: Address, amount: U256) -> Result<TransactionResult> {
         let contract = IToken::new(token, self.provider.as_ref());
         let tx = contract.burn(amount).send().await?;
         let receipt = tx.get_receipt().await?;
-        Ok(receipt.transaction_hash)
+        Ok(TransactionResult::from(receipt))
     }
 
     /// Burn tokens from another account (requires allowance)
-    pub async fn burn_from(&self, token: Address, account: Address, amount: U256) -> Result<B256> {
+    pub async fn burn_from(
+        &self,
+        token: Address,
+        account: Address,
+        amount: U256,
+    ) -> Result<TransactionResult> {
         let contract = IToken::new(token, self.provider.as_ref());
         let tx = contract.burnFrom(account, amount).send().await?;
         let receipt = tx.get_receipt().await?;
-        Ok(receipt.transaction_hash)
+        Ok(TransactionResult::from(receipt))
     }
 
     // =================
@@ -192,30 +570,72 @@ impl TokenHelper {
     // =================
 
     /// Get complete token metadata
+    ///
+    /// Name and symbol may be `string` or `bytes32`. Tokens that revert on (or
+    /// don't implement) a getter, or return a value that can't be decoded, don't
+    /// fail the call: the field gets a fallback and
+    /// is listed in [`TokenMetadata::missing`]. Decimals above
+    /// [`MAX_TOKEN_DECIMALS`] count as missing too. Transport errors still fail.
     pub async fn get_token_metadata(&self, token: Address) -> Result<TokenMetadata> {
-        // 병렬 호출로 네트워크 지연 최적화
-        let (name_result, symbol_result, decimals_result, total_supply_result) = tokio::join!(
-            self.name(token),
-            self.symbol(token),
-            self.decimals(token),
-            self.total_supply(token)
-        );
-
-        let name = name_result?;
-        let symbol = symbol_result?;
-        let decimals = decimals_result?;
-        let total_supply = total_supply_result?;
+        let provider = self.provider.as_ref();
+        let contract = IToken::new(token, provider);
+        let cached_symbol = self.metadata.cached_symbol(token);
+        let cached_decimals = self.metadata.cached_decimals(token);
+
+        let (name, symbol, decimals, total_supply) = tokio::try_join!(
+            read_string_field(provider, token, IToken::nameCall {}.abi_encode()),
+            async {
+                match cached_symbol {
+                    Some(symbol) => Ok(Some(symbol)),
+                    None => {
+                        read_string_field(provider, token, IToken::symbolCall {}.abi_encode())
+                            .await
+                    }
+                }
+            },
+            async {
+                match cached_decimals {
+                    Some(decimals) => Ok(Some(decimals)),
+                    None => optional_field(async { contract.decimals().call().await }).await,
+                }
+            },
+            optional_field(async { contract.totalSupply().call().await }),
+        )?;
+
+        let decimals = decimals.filter(|&decimals| decimals <= MAX_TOKEN_DECIMALS);
+        if let Some(symbol) = &symbol {
+            self.metadata.insert_symbol(token, symbol.clone());
+        }
+        if let Some(decimals) = decimals {
+            self.metadata.insert_decimals(token, decimals);
+        }
+
+        let mut missing = Vec::new();
+        for (field, present) in [
+            (MetadataField::Name, name.is_some()),
+            (MetadataField::Symbol, symbol.is_some()),
+            (MetadataField::Decimals, decimals.is_some()),
+            (MetadataField::TotalSupply, total_supply.is_some()),
+        ] {
+            if !present {
+                missing.push(field);
+            }
+        }
 
         Ok(TokenMetadata {
             address: token,
-            name,
-            symbol,
-            decimals,
-            total_supply,
+            name: name.unwrap_or_default(),
+            symbol: symbol.unwrap_or_default(),
+            decimals: decimals.unwrap_or(FALLBACK_DECIMALS),
+            total_supply: total_supply.unwrap_or_default(),
+            missing,
         })
     }
 
     /// Generates an EIP-2612 permit signature using the internal wallet
+    ///
+    /// Check [`TokenHelper::supports_permit`] first to avoid failing on tokens
+    /// without permit.
     pub async fn generate_permit_signature(
         &self,
         token: Address,
@@ -224,49 +644,16 @@ impl TokenHelper {
         value: U256,
         deadline: U256,
     ) -> Result<(u8, B256, B256)> {
-        // 병렬 호출로 네트워크 지연 최적화
-        let (nonce_result, domain_separator_result) = tokio::join!(
-            self.get_nonce(token, owner),
-            self.get_domain_separator(token)
-        );
-
-        let nonce = nonce_result?;
-        let domain_separator = domain_separator_result?;
-
-        // Create the permit message hash according to EIP-2612
-        let permit_typehash = keccak256(
-            "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
-        );
-
-        let mut data = Vec::new();
-        data.extend_from_slice(permit_typehash.as_slice());
-        data.extend_from_slice(&[0u8; 12]); // padding for address
-        data.extend_from_slice(owner.as_slice());
-        data.extend_from_slice(&[0u8; 12]); // padding for address
-        data.extend_from_slice(spender.as_slice());
-        data.extend_from_slice(&value.to_be_bytes::<32>());
-        data.extend_from_slice(&nonce.to_be_bytes::<32>());
-        data.extend_from_slice(&deadline.to_be_bytes::<32>());
-
-        let struct_hash = keccak256(&data);
-
-        // Create the EIP-712 message hash
-        let mut message_data = Vec::new();
-        message_data.extend_from_slice(b"\x19\x01");
-        message_data.extend_from_slice(domain_separator.as_slice());
-        message_data.extend_from_slice(struct_hash.as_slice());
-
-        let message_hash = keccak256(&message_data);
-
-        // Sign the message hash using the internal signer
-        let signature = self.signer.sign_hash(&message_hash).await?;
-
-        // Extract v, r, s from signature
-        let v = if signature.v() { 28u8 } else { 27u8 };
-        let r = B256::from_slice(&signature.r().to_be_bytes::<32>());
-        let s = B256::from_slice(&signature.s().to_be_bytes::<32>());
-
-        Ok((v, r, s))
+        sign_permit(
+            self.provider.as_ref(),
+            &self.signer,
+            token,
+            owner,
+            spender,
+            value,
+            deadline,
+        )
+        .await
     }
 
     /// Builds a domain separator manually (alternative method)
@@ -305,9 +692,276 @@ impl TokenHelper {
     }
 }
 
+/// Compare approve + sell against sellPermit for `signer` selling `amount_in`
+///
+/// See [`TokenHelper::estimate_sell_path_cost`].
+pub async fn estimate_sell_path_cost<P: Provider>(
+    provider: Arc<P>,
+    signer: &PrivateKeySigner,
+    router: &Router,
+    token: Address,
+    amount_in: U256,
+    amount_out_min: U256,
+) -> Result<SellPathCost> {
+    let owner = signer.address();
+    let spender = router.address();
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+    let deadline = U256::from(now.as_secs() + 3600);
+
+    let contract = IToken::new(token, provider.as_ref());
+    let allowance = contract.allowance(owner, spender).call().await?;
+
+    let (approve_gas, sell_gas, sell_gas_estimated) = if allowance >= amount_in {
+        let params = TradeOperation::Sell {
+            token,
+            amount_in,
+            amount_out_min,
+            to: owner,
+            deadline,
+        };
+        let gas = estimate_gas_from(provider.clone(), router, owner, params).await?;
+        (None, gas, true)
+    } else {
+        let approve_gas = contract
+            .approve(spender, amount_in)
+            .from(owner)
+            .estimate_gas()
+            .await?;
+        let params = TradeOperation::Sell {
+            token,
+            amount_in,
+            amount_out_min,
+            to: owner,
+            deadline,
+        };
+        match estimate_sell_gas_unapproved(provider.as_ref(), router, owner, params).await {
+            Ok(sell_gas) => (Some(approve_gas), sell_gas, true),
+            Err(_) => (
+                Some(approve_gas),
+                get_default_gas_limit(router, Operation::Sell),
+                false,
+            ),
+        }
+    };
+
+    let sell_permit_gas = match sign_permit(
+        provider.as_ref(),
+        signer,
+        token,
+        owner,
+        spender,
+        amount_in,
+        deadline,
+    )
+    .await
+    {
+        Ok((v, r, s)) => {
+            let params = TradeOperation::SellPermit {
+                token,
+                amount_in,
+                amount_out_min,
+                to: owner,
+                deadline,
+                amount_allowance: amount_in,
+                v,
+                r: r.0,
+                s: s.0,
+            };
+            estimate_gas_from(provider.clone(), router, owner, params)
+                .await
+                .ok()
+        }
+        Err(_) => None,
+    };
+
+    Ok(SellPathCost {
+        allowance,
+        approve_gas,
+        sell_gas,
+        sell_gas_estimated,
+        sell_permit_gas,
+    })
+}
+
+/// Estimate a sell as if `from` had already approved the router
+///
+/// Runs `eth_estimateGas` with a state override that sets the router's allowance
+/// to `U256::MAX` in the token's storage, so approve + sell can be costed before
+/// the approval is sent. The allowance slot is located with
+/// [`probe_allowance_slot`]; tokens with an unrecognized layout, and nodes without
+/// state override support, return an error.
+///
+/// `params` must be [`TradeOperation::Sell`].
+pub async fn estimate_sell_gas_unapproved<P: Provider>(
+    provider: &P,
+    router: &Router,
+    from: Address,
+    params: TradeOperation,
+) -> Result<u64> {
+    let TradeOperation::Sell { token, .. } = params else {
+        return Err(anyhow::anyhow!(
+            "Allowance override only applies to sell estimates"
+        ));
+    };
+    let spender = router.address();
+
+    // A wrong slot would silently estimate a reverting sale, so only use a probed one
+    let mapping_slot = probe_allowance_slot(provider, token, from, spender)
+        .await?
+        .ok_or_else(|| {
+            anyhow::anyhow!("Could not locate the allowance storage slot of {}", token)
+        })?;
+    let overrides = allowance_override(token, from, spender, mapping_slot, U256::MAX);
+
+    let gas = provider
+        .estimate_gas(params.transaction(router).from(from))
+        .overrides(overrides)
+        .await?;
+    Ok(gas)
+}
+
+/// Check whether `token` answers the EIP-2612 permit view calls
+///
+/// A token counts as permit-capable when `nonces()` succeeds and its EIP-712
+/// domain is readable through `DOMAIN_SEPARATOR()` or ERC-5267 `eip712Domain()`.
+/// A call that reverts or returns no data counts as a missing function; any other
+/// failure (timeout, rate limit, ...) is returned as an error.
+pub async fn probe_permit_support<P: Provider>(
+    provider: &P,
+    token: Address,
+    owner: Address,
+) -> Result<bool> {
+    let contract = IToken::new(token, provider);
+    let nonces = contract.nonces(owner);
+    let domain = contract.DOMAIN_SEPARATOR();
+    let eip712_domain = contract.eip712Domain();
+    let (nonce_result, domain_result, eip712_result) =
+        tokio::join!(nonces.call(), domain.call(), eip712_domain.call());
+
+    if !answers(nonce_result)? {
+        return Ok(false);
+    }
+    Ok(answers(domain_result)? || answers(eip712_result)?)
+}
+
+/// Whether a probe call went through; Err when the node couldn't tell
+fn answers<T>(result: std::result::Result<T, alloy::contract::Error>) -> Result<bool> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(e) if is_missing_function(&e) => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// A revert or an empty/undecodable return, as from a token without the function
+fn is_missing_function(error: &alloy::contract::Error) -> bool {
+    match error {
+        alloy::contract::Error::ZeroData(..) | alloy::contract::Error::AbiError(_) => true,
+        alloy::contract::Error::TransportError(e) => e
+            .as_error_resp()
+            .is_some_and(|resp| resp.code == 3 || resp.message.to_lowercase().contains("revert")),
+        _ => false,
+    }
+}
+
+/// Sign an EIP-2612 permit for `token` with `signer`
+///
+/// Reads the owner's current permit nonce and the token's domain separator.
+/// `owner` must be the signer's address for the permit to verify on chain.
+pub async fn sign_permit<P: Provider>(
+    provider: &P,
+    signer: &PrivateKeySigner,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    value: U256,
+    deadline: U256,
+) -> Result<(u8, B256, B256)> {
+    // 병렬 호출로 네트워크 지연 최적화
+    let contract = IToken::new(token, provider);
+    let nonces = contract.nonces(owner);
+    let domain = contract.DOMAIN_SEPARATOR();
+    let (nonce_result, domain_separator_result) = tokio::join!(nonces.call(), domain.call());
+
+    let nonce = nonce_result?;
+    let domain_separator = domain_separator_result?;
+
+    // Create the permit message hash according to EIP-2612
+    let permit_typehash = keccak256(
+        "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)",
+    );
+
+    let mut data = Vec::new();
+    data.extend_from_slice(permit_typehash.as_slice());
+    data.extend_from_slice(&[0u8; 12]); // padding for address
+    data.extend_from_slice(owner.as_slice());
+    data.extend_from_slice(&[0u8; 12]); // padding for address
+    data.extend_from_slice(spender.as_slice());
+    data.extend_from_slice(&value.to_be_bytes::<32>());
+    data.extend_from_slice(&nonce.to_be_bytes::<32>());
+    data.extend_from_slice(&deadline.to_be_bytes::<32>());
+
+    let struct_hash = keccak256(&data);
+
+    // Create the EIP-712 message hash
+    let mut message_data = Vec::new();
+    message_data.extend_from_slice(b"\x19\x01");
+    message_data.extend_from_slice(domain_separator.as_slice());
+    message_data.extend_from_slice(struct_hash.as_slice());
+
+    let message_hash = keccak256(&message_data);
+
+    // Sign the message hash
+    let signature = signer.sign_hash(&message_hash).await?;
+
+    // Extract v, r, s from signature
+    let v = if signature.v() { 28u8 } else { 27u8 };
+    let r = B256::from_slice(&signature.r().to_be_bytes::<32>());
+    let s = B256::from_slice(&signature.s().to_be_bytes::<32>());
+
+    Ok((v, r, s))
+}
+
 #[cfg(test)]
 mod tests {
+    use super::TokenHelper;
     use alloy::primitives::{keccak256, Address, B256, U256};
+    use alloy::{
+        providers::{DynProvider, ProviderBuilder, mock::Asserter},
+        rpc::json_rpc::ErrorPayload,
+        signers::local::PrivateKeySigner,
+    };
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_supports_permit_caches_only_conclusive_answers() {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::default().connect_mocked_client(asserter.clone());
+        let helper = TokenHelper::from_provider(
+            Arc::new(DynProvider::new(provider)),
+            PrivateKeySigner::random(),
+        );
+        let token = Address::repeat_byte(0x11);
+        let code = "0x6080";
+
+        // The probe calls get no answer: an RPC failure, not "no permit support"
+        asserter.push_success(&code);
+        assert!(helper.supports_permit(token).await.is_err());
+
+        asserter.push_success(&code);
+        for _ in 0..3 {
+            asserter.push_failure(ErrorPayload {
+                code: 3,
+                message: "execution reverted".into(),
+                data: None,
+            });
+        }
+        assert!(!helper.supports_permit(token).await.unwrap());
+
+        // Served from the cache: the mock has no responses left
+        assert!(asserter.read_q().is_empty());
+        assert!(!helper.supports_permit(token).await.unwrap());
+    }
 
     #[test]
     fn test_domain_separator_calculation() {
@@ -351,6 +1005,21 @@ mod tests {
         assert_ne!(domain_separator, domain_separator2);
     }
 
+    #[test]
+    fn test_decode_string_or_bytes32() {
+        use super::decode_string_or_bytes32;
+        use alloy::sol_types::SolValue;
+
+        let mut word = [0u8; 32];
+        word[..3].copy_from_slice(b"MKR");
+        assert_eq!(decode_string_or_bytes32(&word).as_deref(), Some("MKR"));
+
+        let encoded = "Nad Token".to_string().abi_encode();
+        assert_eq!(decode_string_or_bytes32(&encoded).as_deref(), Some("Nad Token"));
+
+        assert_eq!(decode_string_or_bytes32(&[]), None);
+    }
+
     #[test]
     fn test_permit_type_hash() {
         // This should match the Solidity version
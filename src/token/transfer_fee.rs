@@ -0,0 +1,153 @@
+//! Fee-on-transfer detection
+//!
+//! Some tokens keep part of every transfer (tax, burn, reflection), so the
+//! router receives less than the `amount_in` it pulls from the seller. A sale
+//! quoted on the gross amount then expects too much MON and reverts on its
+//! minimum output, or needs a much wider slippage than it should.
+//!
+//! [`detect_transfer_fee`] simulates a transfer with `eth_simulateV1` and
+//! compares the recipient's balance before and after it:
+//!
+//! ```rust,ignore
+//! let fee = detect_transfer_fee(provider.as_ref(), token, wallet, router.address(), amount).await?;
+//! if fee.is_fee_on_transfer() {
+//!     println!("{} bps kept per transfer, router gets {}", fee.fee_bps, fee.received);
+//! }
+//! ```
+//!
+//! Nodes without `eth_simulateV1` return an error. Tokens that exempt some
+//! senders or recipients can charge other transfers than the one simulated.
+
+use super::token::IToken;
+use alloy::{
+    primitives::{Address, U256},
+    providers::Provider,
+    rpc::types::{
+        TransactionRequest,
+        simulate::{SimBlock, SimulatePayload},
+    },
+    sol_types::SolCall,
+};
+use anyhow::Result;
+
+const MAX_BPS: u64 = 10_000;
+
+/// Result of a simulated transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFee {
+    pub token: Address,
+    /// Amount sent
+    pub amount: U256,
+    /// Amount the recipient's balance grew by
+    pub received: U256,
+    /// Share of `amount` that didn't arrive, in basis points (rounded up)
+    pub fee_bps: u64,
+}
+
+impl TransferFee {
+    pub fn is_fee_on_transfer(&self) -> bool {
+        self.received < self.amount
+    }
+
+    /// What the recipient would get from sending `amount`, at the simulated rate
+    pub fn net_of(&self, amount: U256) -> U256 {
+        if self.amount.is_zero() {
+            return amount;
+        }
+        amount * self.received / self.amount
+    }
+}
+
+/// Share of `amount` missing from `received`, in basis points, rounded up
+pub fn transfer_fee_bps(amount: U256, received: U256) -> u64 {
+    if amount.is_zero() || received >= amount {
+        return 0;
+    }
+    let missing = amount - received;
+    let bps = (missing * U256::from(MAX_BPS)).div_ceil(amount);
+    bps.saturating_to::<u64>().min(MAX_BPS)
+}
+
+/// Simulate `from` transferring `amount` of `token` to `to`
+///
+/// `from` must hold at least `amount`. The balance of `to` is read before and
+/// after the transfer within one simulated block.
+pub async fn detect_transfer_fee<P: Provider>(
+    provider: &P,
+    token: Address,
+    from: Address,
+    to: Address,
+    amount: U256,
+) -> Result<TransferFee> {
+    let balance_call = || {
+        TransactionRequest::default()
+            .to(token)
+            .input(IToken::balanceOfCall { owner: to }.abi_encode().into())
+    };
+    let transfer_call = TransactionRequest::default().from(from).to(token).input(
+        IToken::transferCall { to, value: amount }
+            .abi_encode()
+            .into(),
+    );
+
+    let payload = SimulatePayload {
+        block_state_calls: vec![SimBlock {
+            calls: vec![balance_call(), transfer_call, balance_call()],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let blocks = provider.simulate(&payload).await?;
+    let calls = blocks
+        .first()
+        .map(|block| &block.calls[..])
+        .unwrap_or_default();
+    let [before, transfer, after] = calls else {
+        return Err(anyhow::anyhow!(
+            "Transfer simulation of {} returned {} calls",
+            token,
+            calls.len()
+        ));
+    };
+    if !transfer.status {
+        return Err(anyhow::anyhow!(
+            "Simulated transfer of {} reverted: {:?}",
+            token,
+            transfer.error
+        ));
+    }
+
+    let before = IToken::balanceOfCall::abi_decode_returns(&before.return_data)?;
+    let after = IToken::balanceOfCall::abi_decode_returns(&after.return_data)?;
+    let received = after.saturating_sub(before);
+
+    Ok(TransferFee {
+        token,
+        amount,
+        received,
+        fee_bps: transfer_fee_bps(amount, received),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_fee() {
+        assert_eq!(transfer_fee_bps(U256::from(1_000), U256::from(1_000)), 0);
+        assert_eq!(transfer_fee_bps(U256::from(1_000), U256::from(950)), 500);
+        assert_eq!(transfer_fee_bps(U256::from(3), U256::from(2)), 3_334);
+        assert_eq!(transfer_fee_bps(U256::ZERO, U256::ZERO), 0);
+
+        let fee = TransferFee {
+            token: Address::ZERO,
+            amount: U256::from(1_000),
+            received: U256::from(950),
+            fee_bps: 500,
+        };
+        assert!(fee.is_fee_on_transfer());
+        assert_eq!(fee.net_of(U256::from(1_000)), U256::from(950));
+        assert_eq!(fee.net_of(U256::from(2_000)), U256::from(1_900));
+    }
+}
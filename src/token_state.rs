@@ -0,0 +1,261 @@
+//! Cached listed/locked state of bonding curve tokens
+//!
+//! `isListed`/`isLocked` decide between the curve and DEX routers, so routing
+//! code asks them before every trade. A [`TokenStateCache`] answers from memory:
+//!
+//! ```rust,ignore
+//! let cache = TokenStateCache::new(trade.provider().clone(), trade.chain_config().bonding_curve);
+//! // Keep entries current from Lock/Listed events (optional)
+//! let watcher = cache.watch(ws_url).await?;
+//! let trade = trade.with_token_state_cache(cache.clone());
+//!
+//! if trade.is_listed(token).await? { /* DEX */ }
+//! ```
+//!
+//! Without a WebSocket watcher, entries are valid for the block they were read
+//! at: feed new block numbers to [`TokenStateCache::on_block`] (e.g. from a
+//! [`BlockStream`](crate::stream::BlockStream)) and older entries are re-read on
+//! their next lookup. While a watcher is running, entries stay valid until a
+//! Lock/Listed event for the token updates them.
+
+use crate::{
+    contracts::bonding_curve::IBondingCurve,
+    stream::CurveStream,
+    types::{BondingCurveEvent, EventType},
+};
+use alloy::{eips::BlockId, primitives::Address, providers::DynProvider};
+use anyhow::Result;
+use futures_util::StreamExt;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::task::JoinHandle;
+
+/// Listed/locked flags of a token, as of `block`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenState {
+    pub listed: bool,
+    pub locked: bool,
+    /// Block the flags were read or last updated at (0 if unknown)
+    pub block: u64,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    entries: HashMap<Address, TokenState>,
+    /// Newest block passed to `on_block`
+    block: u64,
+    /// A watcher is applying Lock/Listed events
+    live: bool,
+    /// Bumped on every applied event, so a read that raced an event isn't cached
+    epoch: u64,
+}
+
+impl Inner {
+    fn fresh(&self, token: Address) -> Option<TokenState> {
+        self.entries
+            .get(&token)
+            .filter(|state| self.live || state.block >= self.block)
+            .copied()
+    }
+}
+
+/// Thread-safe cache of `isListed`/`isLocked`
+///
+/// Cheap to clone; all clones share the same entries.
+#[derive(Debug, Clone)]
+pub struct TokenStateCache {
+    provider: Arc<DynProvider>,
+    bonding_curve: Address,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl TokenStateCache {
+    pub fn new(provider: Arc<DynProvider>, bonding_curve: Address) -> Self {
+        Self {
+            provider,
+            bonding_curve,
+            inner: Arc::new(Mutex::new(Inner::default())),
+        }
+    }
+
+    /// Listed/locked state of `token`, read from the bonding curve on a miss
+    pub async fn state(&self, token: Address) -> Result<TokenState> {
+        let (block, epoch) = {
+            let inner = self.inner.lock().unwrap();
+            if let Some(state) = inner.fresh(token) {
+                return Ok(state);
+            }
+            (inner.block, inner.epoch)
+        };
+
+        // Pin both reads to the block the entry will be tagged with
+        let at = if block == 0 {
+            BlockId::latest()
+        } else {
+            BlockId::number(block)
+        };
+        let curve = IBondingCurve::new(self.bonding_curve, self.provider.as_ref());
+        let (listed, locked) = tokio::try_join!(
+            async { Ok::<_, anyhow::Error>(curve.isListed(token).block(at).call().await?) },
+            async { Ok::<_, anyhow::Error>(curve.isLocked(token).block(at).call().await?) },
+        )?;
+        let state = TokenState {
+            listed,
+            locked,
+            block,
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.epoch == epoch {
+            inner.entries.insert(token, state);
+        }
+        Ok(state)
+    }
+
+    pub async fn is_listed(&self, token: Address) -> Result<bool> {
+        Ok(self.state(token).await?.listed)
+    }
+
+    pub async fn is_locked(&self, token: Address) -> Result<bool> {
+        Ok(self.state(token).await?.locked)
+    }
+
+    /// Cached state without a lookup, None if missing or stale
+    pub fn cached(&self, token: Address) -> Option<TokenState> {
+        self.inner.lock().unwrap().fresh(token)
+    }
+
+    pub fn insert(&self, token: Address, state: TokenState) {
+        self.inner.lock().unwrap().entries.insert(token, state);
+    }
+
+    /// Advance to block `number`; entries read at older blocks become stale
+    pub fn on_block(&self, number: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.block = inner.block.max(number);
+    }
+
+    /// Update the cache from a bonding curve event
+    ///
+    /// Lock and Listed set the flag on a cached token; a removed (reorged) Lock or
+    /// Listed drops the token so its next lookup re-reads it. Other events are ignored.
+    pub fn apply_event(&self, event: &BondingCurveEvent) {
+        let (token, block, listed) = match event {
+            BondingCurveEvent::Lock(e) => (e.token, e.block_number, false),
+            BondingCurveEvent::Listed(e) => (e.token, e.block_number, true),
+            _ => return,
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        inner.epoch += 1;
+        if event.removed() {
+            inner.entries.remove(&token);
+            return;
+        }
+        if let Some(state) = inner.entries.get_mut(&token) {
+            if listed {
+                state.listed = true;
+            } else {
+                state.locked = true;
+            }
+            state.block = state.block.max(block);
+        }
+    }
+
+    /// Keep the cache current from Lock/Listed events over WebSocket
+    ///
+    /// Entries stay valid while the returned task runs. If the subscription ends
+    /// the cache is cleared and falls back to per-block invalidation; the task
+    /// then returns the stream error that ended it, or `Ok(())` if it closed.
+    pub async fn watch(&self, ws_url: String) -> Result<JoinHandle<Result<()>>> {
+        let stream = CurveStream::new(ws_url)
+            .await?
+            .subscribe_events(vec![EventType::Lock, EventType::Listed])
+            .subscribe()
+            .await?;
+
+        {
+            // Entries read before the subscription started may have missed events
+            let mut inner = self.inner.lock().unwrap();
+            inner.entries.clear();
+            inner.epoch += 1;
+            inner.live = true;
+        }
+
+        let cache = self.clone();
+        Ok(tokio::spawn(async move {
+            let mut stream = stream;
+            let mut result = Ok(());
+            while let Some(item) = stream.next().await {
+                match item {
+                    Ok(event) => cache.apply_event(&event),
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+
+            let mut inner = cache.inner.lock().unwrap();
+            inner.live = false;
+            inner.entries.clear();
+            inner.epoch += 1;
+            result
+        }))
+    }
+
+    /// True while a [`TokenStateCache::watch`] task is applying events
+    pub fn is_live(&self) -> bool {
+        self.inner.lock().unwrap().live
+    }
+
+    pub fn clear(&self) {
+        self.inner.lock().unwrap().entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use alloy::providers::ProviderBuilder;
+
+    #[test]
+    fn test_token_state_cache_invalidation() {
+        let provider =
+            ProviderBuilder::new().connect_http("http://localhost:8545".parse().unwrap());
+        let cache = TokenStateCache::new(Arc::new(DynProvider::new(provider)), Address::ZERO);
+
+        let lock = fixtures::curve_lock().expected;
+        let token = match &lock {
+            BondingCurveEvent::Lock(e) => e.token,
+            _ => unreachable!(),
+        };
+        let state = TokenState {
+            listed: false,
+            locked: false,
+            block: 5,
+        };
+
+        cache.insert(token, state);
+        cache.on_block(5);
+        assert_eq!(cache.cached(token), Some(state));
+        cache.on_block(6);
+        assert_eq!(cache.cached(token), None);
+
+        // Events update entries regardless of age
+        cache.inner.lock().unwrap().live = true;
+        cache.apply_event(&lock);
+        let updated = cache.cached(token).unwrap();
+        assert!(updated.locked && !updated.listed);
+
+        let mut removed = lock.clone();
+        if let BondingCurveEvent::Lock(e) = &mut removed {
+            e.removed = true;
+        }
+        cache.apply_event(&removed);
+        assert_eq!(cache.cached(token), None);
+    }
+}
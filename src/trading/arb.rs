@@ -0,0 +1,365 @@
+//! Arbitrage detection between bonding curve and DEX pool prices
+//!
+//! Around graduation a token can be tradable on both the bonding curve and its
+//! Uniswap V3 pool, and the two prices can diverge. [`ArbWatcher`] quotes a round
+//! trip through both venues for a fixed probe amount of MON and reports any spread
+//! that remains after router fees and estimated gas.
+
+use crate::{trading::Trade, types::*};
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+use futures_util::Stream;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Which venue is bought on and which is sold on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArbDirection {
+    /// Buy on the bonding curve, sell into the DEX pool
+    CurveToDex,
+    /// Buy from the DEX pool, sell into the bonding curve
+    DexToCurve,
+}
+
+/// Configuration for an [`ArbWatcher`]
+#[derive(Debug, Clone)]
+pub struct ArbConfig {
+    /// Tokens to monitor
+    pub tokens: Vec<Address>,
+    /// MON amount used to quote both legs of the round trip
+    pub probe_amount: U256,
+    /// Estimated gas cost of both legs combined, in MON wei
+    pub gas_cost: U256,
+    /// Minimum net profit (after fees and gas) for an opportunity to be emitted
+    pub min_profit: U256,
+    /// Delay between polling rounds in [`ArbWatcher::watch`]
+    pub poll_interval: Duration,
+}
+
+/// Strict limits applied when opportunities are executed automatically
+#[derive(Debug, Clone)]
+pub struct ArbLimits {
+    /// Largest MON amount a single execution may spend
+    pub max_amount_in: U256,
+    /// Minimum net profit required before executing
+    pub min_profit: U256,
+    /// Stop auto-executing after this many executions
+    pub max_executions: usize,
+    /// Slippage applied to the first leg's token output (1.0 = 1%)
+    pub slippage_percent: f64,
+}
+
+/// A detected price divergence between the bonding curve and the DEX pool
+///
+/// All amounts come from router quotes, so router and pool fees are already
+/// accounted for. `net_profit` additionally subtracts the configured gas cost.
+#[derive(Debug, Clone)]
+pub struct ArbOpportunity {
+    pub token: Address,
+    pub direction: ArbDirection,
+    /// MON spent on the first leg
+    pub amount_in: U256,
+    /// Tokens received from the first leg
+    pub token_amount: U256,
+    /// MON received from selling `token_amount` on the second leg
+    pub amount_out: U256,
+    /// Gas cost subtracted from the gross spread
+    pub gas_cost: U256,
+    /// `amount_out - amount_in - gas_cost`
+    pub net_profit: U256,
+    /// Net profit relative to `amount_in`, in basis points
+    pub spread_bps: u64,
+}
+
+impl ArbOpportunity {
+    /// Build an opportunity from round-trip quotes
+    /// Returns None if the round trip doesn't cover `amount_in + gas_cost`
+    pub fn from_quotes(
+        token: Address,
+        direction: ArbDirection,
+        amount_in: U256,
+        token_amount: U256,
+        amount_out: U256,
+        gas_cost: U256,
+    ) -> Option<Self> {
+        let cost = amount_in.checked_add(gas_cost)?;
+        if amount_in.is_zero() || amount_out <= cost {
+            return None;
+        }
+
+        let net_profit = amount_out - cost;
+        let spread_bps = net_profit * U256::from(10000) / amount_in;
+
+        Some(Self {
+            token,
+            direction,
+            amount_in,
+            token_amount,
+            amount_out,
+            gas_cost,
+            net_profit,
+            spread_bps: spread_bps.try_into().unwrap_or(u64::MAX),
+        })
+    }
+
+    /// Router to buy on for the first leg
+    pub fn buy_router(&self, trade: &Trade) -> Router {
+        match self.direction {
+            ArbDirection::CurveToDex => Router::BondingCurve(trade.bonding_curve_router().address),
+            ArbDirection::DexToCurve => Router::Dex(trade.dex_router().address),
+        }
+    }
+
+    /// Router to sell on for the second leg
+    pub fn sell_router(&self, trade: &Trade) -> Router {
+        match self.direction {
+            ArbDirection::CurveToDex => Router::Dex(trade.dex_router().address),
+            ArbDirection::DexToCurve => Router::BondingCurve(trade.bonding_curve_router().address),
+        }
+    }
+}
+
+/// Result of executing both legs of an opportunity
+#[derive(Debug)]
+pub struct ArbExecution {
+    pub opportunity: ArbOpportunity,
+    pub buy: TransactionResult,
+    pub sell: TransactionResult,
+}
+
+/// Item of [`ArbWatcher::watch`]
+#[derive(Debug)]
+pub enum ArbEvent {
+    /// A profitable opportunity, yielded whether or not it is executed
+    Opportunity(ArbOpportunity),
+    /// Both legs of an auto-executed opportunity succeeded
+    Executed(Box<ArbExecution>),
+}
+
+/// Monitors curve and DEX prices for a set of tokens and emits arbitrage opportunities
+pub struct ArbWatcher {
+    trade: Arc<Trade>,
+    config: ArbConfig,
+    limits: Option<ArbLimits>,
+}
+
+impl ArbWatcher {
+    pub fn new(trade: Arc<Trade>, config: ArbConfig) -> Self {
+        Self {
+            trade,
+            config,
+            limits: None,
+        }
+    }
+
+    /// Automatically execute opportunities that satisfy the given limits
+    ///
+    /// The wallet must already have approved both routers for the monitored tokens,
+    /// since the second leg is a regular sell.
+    pub fn auto_execute(mut self, limits: ArbLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Quote both directions for a token and return the more profitable opportunity
+    pub async fn check(&self, token: Address) -> Result<Option<ArbOpportunity>> {
+        let amount_in = self.config.probe_amount;
+        let curve = self.trade.bonding_curve_router();
+        let dex = self.trade.dex_router();
+
+        // A venue that can't quote (not yet listed, already graduated) simply yields no opportunity
+        let curve_to_dex = match curve.get_amount_out(token, amount_in, true).await {
+            Ok(tokens) => match dex.get_amount_out(token, tokens, false).await {
+                Ok(mon_out) => ArbOpportunity::from_quotes(
+                    token,
+                    ArbDirection::CurveToDex,
+                    amount_in,
+                    tokens,
+                    mon_out,
+                    self.config.gas_cost,
+                ),
+                Err(_) => None,
+            },
+            Err(_) => None,
+        };
+
+        let dex_to_curve = match dex.get_amount_out(token, amount_in, true).await {
+            Ok(tokens) => match curve.get_amount_out(token, tokens, false).await {
+                Ok(mon_out) => ArbOpportunity::from_quotes(
+                    token,
+                    ArbDirection::DexToCurve,
+                    amount_in,
+                    tokens,
+                    mon_out,
+                    self.config.gas_cost,
+                ),
+                Err(_) => None,
+            },
+            Err(_) => None,
+        };
+
+        let best = match (curve_to_dex, dex_to_curve) {
+            (Some(a), Some(b)) => Some(if a.net_profit >= b.net_profit { a } else { b }),
+            (a, b) => a.or(b),
+        };
+
+        Ok(best.filter(|opp| opp.net_profit >= self.config.min_profit))
+    }
+
+    /// Execute both legs of an opportunity
+    ///
+    /// The buy leg is protected by `slippage_percent`; the sell leg only sells the
+    /// guaranteed minimum token amount and must return at least the MON spent.
+    pub async fn execute(
+        &self,
+        opportunity: &ArbOpportunity,
+        slippage_percent: f64,
+    ) -> Result<ArbExecution> {
+        let trade = &self.trade;
+        let wallet = trade.wallet_address();
+        let deadline = U256::from(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 60);
+        let token_amount_min = crate::trading::SlippageUtils::calculate_amount_out_min(
+            opportunity.token_amount,
+            slippage_percent,
+        );
+
+        let buy = trade
+            .buy(
+                BuyParams {
+                    token: opportunity.token,
+                    amount_in: opportunity.amount_in,
+                    amount_out_min: token_amount_min,
+                    to: wallet,
+                    deadline,
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                },
+                opportunity.buy_router(trade),
+            )
+            .await?;
+
+        if !buy.status {
+            return Err(anyhow::anyhow!(
+                "Arbitrage buy leg reverted: {}",
+                buy.transaction_hash
+            ));
+        }
+
+        let sell = trade
+            .sell(
+                SellParams {
+                    amount_in: token_amount_min,
+                    amount_out_min: opportunity.amount_in,
+                    token: opportunity.token,
+                    to: wallet,
+                    deadline,
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                },
+                opportunity.sell_router(trade),
+            )
+            .await?;
+
+        if !sell.status {
+            return Err(anyhow::anyhow!(
+                "Arbitrage sell leg reverted: {}",
+                sell.transaction_hash
+            ));
+        }
+
+        Ok(ArbExecution {
+            opportunity: opportunity.clone(),
+            buy,
+            sell,
+        })
+    }
+
+    /// Poll all configured tokens and yield opportunities as they appear
+    ///
+    /// When auto-execution is enabled, qualifying opportunities are executed before
+    /// being yielded, followed by an [`ArbEvent::Executed`] on success; execution
+    /// errors are yielded as stream errors. Only successful executions count
+    /// towards `max_executions`.
+    pub fn watch(self) -> Pin<Box<dyn Stream<Item = Result<ArbEvent>> + Send>> {
+        let stream = async_stream::stream! {
+            let mut executions = 0usize;
+
+            loop {
+                for token in self.config.tokens.iter().copied() {
+                    let opportunity = match self.check(token).await {
+                        Ok(Some(opportunity)) => opportunity,
+                        Ok(None) => continue,
+                        Err(e) => {
+                            yield Err(e);
+                            continue;
+                        }
+                    };
+
+                    if let Some(ref limits) = self.limits {
+                        if executions < limits.max_executions
+                            && opportunity.amount_in <= limits.max_amount_in
+                            && opportunity.net_profit >= limits.min_profit
+                        {
+                            match self.execute(&opportunity, limits.slippage_percent).await {
+                                Ok(execution) => {
+                                    executions += 1;
+                                    yield Ok(ArbEvent::Opportunity(opportunity));
+                                    yield Ok(ArbEvent::Executed(Box::new(execution)));
+                                    continue;
+                                }
+                                Err(e) => yield Err(e),
+                            }
+                        }
+                    }
+
+                    yield Ok(ArbEvent::Opportunity(opportunity));
+                }
+
+                tokio::time::sleep(self.config.poll_interval).await;
+            }
+        };
+
+        Box::pin(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opportunity_requires_profit_after_gas() {
+        let amount_in = U256::from(1_000_000u64);
+        let gas = U256::from(10_000u64);
+
+        // 1% gross spread minus 1% gas = break-even, not an opportunity
+        assert!(
+            ArbOpportunity::from_quotes(
+                Address::ZERO,
+                ArbDirection::CurveToDex,
+                amount_in,
+                U256::from(1),
+                U256::from(1_010_000u64),
+                gas,
+            )
+            .is_none()
+        );
+
+        let opp = ArbOpportunity::from_quotes(
+            Address::ZERO,
+            ArbDirection::DexToCurve,
+            amount_in,
+            U256::from(1),
+            U256::from(1_030_000u64),
+            gas,
+        )
+        .unwrap();
+        assert_eq!(opp.net_profit, U256::from(20_000u64));
+        assert_eq!(opp.spread_bps, 200);
+    }
+}
@@ -0,0 +1,373 @@
+//! Fluent order builder on top of [`Trade`]
+//!
+//! ```rust,ignore
+//! let result = trade
+//!     .buy_order(token)
+//!     .amount(parse_ether("0.1")?)
+//!     .slippage_bps(500)
+//!     .deadline_in(60)
+//!     .gas_auto()
+//!     .send()
+//!     .await?;
+//! ```
+//!
+//! The builder quotes through the lens, derives the minimum output from the
+//! slippage, and fills deadline, gas limit and nonce. Use [`TradeBuilder::build`]
+//! to inspect the resulting low-level params before sending.
+
+use crate::{
+    trading::{SlippageUtils, Trade, TradeOperation, recipient::check_recipient},
+    types::{BuyParams, Router, SellParams, TransactionResult},
+};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Default slippage tolerance (5%)
+pub const DEFAULT_SLIPPAGE_BPS: u64 = 500;
+/// Default deadline offset in seconds
+pub const DEFAULT_DEADLINE_SECS: u64 = 300;
+
+/// Trade direction of a [`TradeBuilder`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// How the builder picks a gas limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasMode {
+    /// Leave it to the trade's [`GasConfig`](crate::trading::GasConfig) (or the router default)
+    Default,
+    /// Estimate against the network and add the default buffer
+    Auto,
+    /// Use a fixed gas limit
+    Limit(u64),
+}
+
+/// Params produced by [`TradeBuilder::build`]
+#[derive(Debug, Clone)]
+pub enum BuiltOrder {
+    Buy(BuyParams, Router),
+    Sell(SellParams, Router),
+}
+
+/// Fluent builder for a single buy or sell
+pub struct TradeBuilder<'a> {
+    trade: &'a Trade,
+    side: TradeSide,
+    token: Address,
+    amount: Option<U256>,
+    slippage_bps: u64,
+    deadline_secs: u64,
+    to: Option<Address>,
+    allow_third_party_recipient: bool,
+    gas: GasMode,
+    gas_price: Option<u128>,
+    nonce: Option<u64>,
+}
+
+impl<'a> TradeBuilder<'a> {
+    pub fn new(trade: &'a Trade, side: TradeSide, token: Address) -> Self {
+        Self {
+            trade,
+            side,
+            token,
+            amount: None,
+            slippage_bps: trade.options().default_slippage_bps,
+            deadline_secs: trade.options().default_deadline_secs,
+            to: None,
+            allow_third_party_recipient: false,
+            gas: trade.options().default_gas_strategy,
+            gas_price: None,
+            nonce: None,
+        }
+    }
+
+    /// Amount to spend: MON for buys, tokens for sells
+    pub fn amount(mut self, amount: U256) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Slippage tolerance in basis points (100 = 1%)
+    pub fn slippage_bps(mut self, bps: u64) -> Self {
+        self.slippage_bps = bps;
+        self
+    }
+
+    /// Deadline relative to now, in seconds
+    pub fn deadline_in(mut self, seconds: u64) -> Self {
+        self.deadline_secs = seconds;
+        self
+    }
+
+    /// Recipient of the output (defaults to the trading wallet)
+    ///
+    /// Buys into another address also need
+    /// [`allow_third_party_recipient`](Self::allow_third_party_recipient).
+    pub fn to(mut self, recipient: Address) -> Self {
+        self.to = Some(recipient);
+        self
+    }
+
+    /// Confirm that a buy may deliver tokens to a `to` other than the trading wallet
+    ///
+    /// The recipient is still checked with [`Trade::verify_recipient`].
+    pub fn allow_third_party_recipient(mut self) -> Self {
+        self.allow_third_party_recipient = true;
+        self
+    }
+
+    /// Estimate the gas limit against the network
+    pub fn gas_auto(mut self) -> Self {
+        self.gas = GasMode::Auto;
+        self
+    }
+
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas = GasMode::Limit(gas_limit);
+        self
+    }
+
+    pub fn gas_price(mut self, gas_price: u128) -> Self {
+        self.gas_price = Some(gas_price);
+        self
+    }
+
+    /// Use an explicit nonce instead of the wallet's pending nonce
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Quote the trade and produce fully populated low-level params
+    pub async fn build(&self) -> Result<BuiltOrder> {
+        let amount_in = self
+            .amount
+            .ok_or_else(|| anyhow::anyhow!("Trade amount not set"))?;
+        if self.slippage_bps >= 10_000 {
+            return Err(anyhow::anyhow!(
+                "Slippage must be below 10000 bps, got {}",
+                self.slippage_bps
+            ));
+        }
+
+        let to = self.to.unwrap_or(self.trade.wallet_address());
+        let is_buy = self.side == TradeSide::Buy;
+        if is_buy {
+            check_recipient(
+                self.trade.wallet_address(),
+                to,
+                self.allow_third_party_recipient || self.trade.allows_third_party_recipient(),
+            )?;
+            if to != self.trade.wallet_address() {
+                self.trade.verify_recipient(self.token, to)?;
+            }
+        }
+
+        let (router, expected_out) = self
+            .trade
+            .get_amount_out(self.token, amount_in, is_buy)
+            .await?;
+        let amount_out_min =
+            SlippageUtils::calculate_amount_out_min(expected_out, self.slippage_bps as f64 / 100.0);
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let deadline = U256::from(now + self.deadline_secs);
+
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                self.trade
+                    .provider()
+                    .get_transaction_count(self.trade.wallet_address())
+                    .block_id(BlockId::pending())
+                    .await?
+            }
+        };
+
+        let mut order = match self.side {
+            TradeSide::Buy => BuiltOrder::Buy(
+                BuyParams {
+                    token: self.token,
+                    amount_in,
+                    amount_out_min,
+                    to,
+                    deadline,
+                    gas_limit: None,
+                    gas_price: self.gas_price,
+                    nonce: Some(nonce),
+                },
+                router,
+            ),
+            TradeSide::Sell => BuiltOrder::Sell(
+                SellParams {
+                    amount_in,
+                    amount_out_min,
+                    token: self.token,
+                    to,
+                    deadline,
+                    gas_limit: None,
+                    gas_price: self.gas_price,
+                    nonce: Some(nonce),
+                },
+                router,
+            ),
+        };
+
+        let gas_limit = match self.gas {
+            GasMode::Default => None,
+            GasMode::Limit(limit) => Some(limit),
+            GasMode::Auto => {
+                let (router, params): (&Router, TradeOperation) = match &order {
+                    BuiltOrder::Buy(params, router) => (router, params.into()),
+                    BuiltOrder::Sell(params, router) => (router, params.into()),
+                };
+                let estimated = self.trade.estimate_gas(router, params).await?;
+                let buffer = self.trade.gas_config().cloned().unwrap_or_default();
+                Some(buffer.apply_buffer(estimated))
+            }
+        };
+
+        match &mut order {
+            BuiltOrder::Buy(params, _) => params.gas_limit = gas_limit,
+            BuiltOrder::Sell(params, _) => params.gas_limit = gas_limit,
+        }
+
+        Ok(order)
+    }
+
+    /// Build and submit the trade
+    pub async fn send(self) -> Result<TransactionResult> {
+        match self.build().await? {
+            // build() already checked the recipient
+            BuiltOrder::Buy(params, router) => self.trade.submit_buy(params, router).await,
+            BuiltOrder::Sell(params, router) => self.trade.sell(params, router).await,
+        }
+    }
+}
+
+impl Trade {
+    /// Start a fluent buy order for `token`
+    pub fn buy_order(&self, token: Address) -> TradeBuilder<'_> {
+        TradeBuilder::new(self, TradeSide::Buy, token)
+    }
+
+    /// Start a fluent sell order for `token`
+    pub fn sell_order(&self, token: Address) -> TradeBuilder<'_> {
+        TradeBuilder::new(self, TradeSide::Sell, token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{constants::ChainConfig, trading::TradeOptions};
+    use alloy::{
+        primitives::Bytes,
+        providers::{DynProvider, ProviderBuilder, mock::Asserter},
+        signers::local::PrivateKeySigner,
+        sol_types::SolValue,
+    };
+    use std::sync::Arc;
+
+    fn trade(asserter: &Asserter, options: TradeOptions) -> Trade {
+        let provider = ProviderBuilder::default().connect_mocked_client(asserter.clone());
+        Trade::from_provider(
+            Arc::new(DynProvider::new(provider)),
+            PrivateKeySigner::random(),
+            ChainConfig::default(),
+        )
+        .with_options(options)
+    }
+
+    #[tokio::test]
+    async fn test_build_rejects_invalid_orders() {
+        let asserter = Asserter::new();
+        let trade = trade(&asserter, TradeOptions::default());
+        let token = Address::repeat_byte(0x11);
+        let amount = U256::from(1_000u64);
+
+        let err = trade.buy_order(token).build().await.unwrap_err();
+        assert!(err.to_string().contains("amount not set"));
+
+        let err = trade
+            .buy_order(token)
+            .amount(amount)
+            .slippage_bps(10_000)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Slippage"));
+
+        let cold = Address::repeat_byte(0x22);
+        let err = trade
+            .buy_order(token)
+            .amount(amount)
+            .to(cold)
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not the trading wallet"));
+
+        // Opted in, but the token contract itself is never a valid recipient
+        let err = trade
+            .buy_order(token)
+            .amount(amount)
+            .to(token)
+            .allow_third_party_recipient()
+            .build()
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("token"), "{err}");
+
+        // All rejected before touching the network
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_build_applies_trade_options() {
+        let asserter = Asserter::new();
+        let options = TradeOptions {
+            default_slippage_bps: 300,
+            default_deadline_secs: 120,
+            default_gas_strategy: GasMode::Limit(400_000),
+        };
+        let trade = trade(&asserter, options);
+        let router = ChainConfig::default().bonding_curve_router;
+        let expected_out = U256::from(1_000_000u64);
+        asserter.push_success(&Bytes::from((router, expected_out).abi_encode()));
+        asserter.push_success(&"0x7");
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let order = trade
+            .sell_order(Address::repeat_byte(0x11))
+            .amount(U256::from(5_000u64))
+            .build()
+            .await
+            .unwrap();
+
+        let BuiltOrder::Sell(params, built_router) = order else {
+            panic!("expected a sell order");
+        };
+        assert_eq!(built_router, Router::BondingCurve(router));
+        assert_eq!(
+            params.amount_out_min,
+            SlippageUtils::calculate_amount_out_min(expected_out, 3.0)
+        );
+        let deadline = params.deadline.to::<u64>();
+        assert!((now + 120..=now + 125).contains(&deadline));
+        assert_eq!(params.gas_limit, Some(400_000));
+        assert_eq!(params.nonce, Some(7));
+        assert_eq!(params.to, trade.wallet_address());
+    }
+}
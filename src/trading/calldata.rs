@@ -0,0 +1,244 @@
+//! Router calldata for external signers
+//!
+//! Multisigs, relayers and hardware signers often build or review transactions
+//! outside [`Trade`](crate::Trade). These helpers produce the exact calldata the
+//! SDK would send and decode router calldata back into its parameters:
+//!
+//! ```rust,ignore
+//! let data = encode_buy_calldata(&buy_params, &router);
+//! // to: router.address(), value: buy_params.amount_in, data
+//!
+//! let call = decode_router_calldata(&proposed_tx.input)?;
+//! assert_eq!(call.token(), expected_token);
+//! assert_eq!(call.to(), treasury);
+//! ```
+//!
+//! Both routers share the same trading ABI, so calldata decodes the same way
+//! whichever router it targets.
+
+use crate::{
+    contracts::bonding_curve::IBondingCurveRouter,
+    trading::gas::TradeOperation,
+    types::{BuyParams, Router, SellParams, SellPermitParams},
+};
+use alloy::{
+    primitives::{Address, B256, Bytes, U256},
+    sol_types::SolCall,
+};
+use anyhow::Result;
+
+/// Calldata of a router `buy`; send it with `amount_in` as value
+pub fn encode_buy_calldata(params: &BuyParams, router: &Router) -> Bytes {
+    TradeOperation::from(params).calldata(router.kind())
+}
+
+/// Calldata of a router `sell`
+pub fn encode_sell_calldata(params: &SellParams, router: &Router) -> Bytes {
+    TradeOperation::from(params).calldata(router.kind())
+}
+
+/// Calldata of a router `sellPermit`
+pub fn encode_sell_permit_calldata(params: &SellPermitParams, router: &Router) -> Bytes {
+    TradeOperation::from(params).calldata(router.kind())
+}
+
+/// A decoded router trading call
+///
+/// Buys carry their MON input as transaction value, not in calldata; use
+/// [`RouterCall::into_operation`] with the transaction value to recover it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RouterCall {
+    Buy {
+        token: Address,
+        amount_out_min: U256,
+        to: Address,
+        deadline: U256,
+    },
+    Sell {
+        token: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+        to: Address,
+        deadline: U256,
+    },
+    SellPermit {
+        token: Address,
+        amount_in: U256,
+        amount_out_min: U256,
+        amount_allowance: U256,
+        to: Address,
+        deadline: U256,
+        v: u8,
+        r: B256,
+        s: B256,
+    },
+}
+
+impl RouterCall {
+    pub fn token(&self) -> Address {
+        match self {
+            RouterCall::Buy { token, .. }
+            | RouterCall::Sell { token, .. }
+            | RouterCall::SellPermit { token, .. } => *token,
+        }
+    }
+
+    /// Recipient of the trade output
+    pub fn to(&self) -> Address {
+        match self {
+            RouterCall::Buy { to, .. }
+            | RouterCall::Sell { to, .. }
+            | RouterCall::SellPermit { to, .. } => *to,
+        }
+    }
+
+    pub fn deadline(&self) -> U256 {
+        match self {
+            RouterCall::Buy { deadline, .. }
+            | RouterCall::Sell { deadline, .. }
+            | RouterCall::SellPermit { deadline, .. } => *deadline,
+        }
+    }
+
+    /// The full operation, taking a buy's `amount_in` from the transaction `value`
+    pub fn into_operation(self, value: U256) -> TradeOperation {
+        match self {
+            RouterCall::Buy {
+                token,
+                amount_out_min,
+                to,
+                deadline,
+            } => TradeOperation::Buy {
+                token,
+                amount_in: value,
+                amount_out_min,
+                to,
+                deadline,
+            },
+            RouterCall::Sell {
+                token,
+                amount_in,
+                amount_out_min,
+                to,
+                deadline,
+            } => TradeOperation::Sell {
+                token,
+                amount_in,
+                amount_out_min,
+                to,
+                deadline,
+            },
+            RouterCall::SellPermit {
+                token,
+                amount_in,
+                amount_out_min,
+                amount_allowance,
+                to,
+                deadline,
+                v,
+                r,
+                s,
+            } => TradeOperation::SellPermit {
+                token,
+                amount_in,
+                amount_out_min,
+                to,
+                deadline,
+                amount_allowance,
+                v,
+                r: r.0,
+                s: s.0,
+            },
+        }
+    }
+}
+
+/// Decode `buy`, `sell` or `sellPermit` calldata of either router
+pub fn decode_router_calldata(data: &[u8]) -> Result<RouterCall> {
+    let selector: [u8; 4] = data
+        .get(..4)
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Calldata is shorter than a selector"))?;
+
+    match selector {
+        IBondingCurveRouter::buyCall::SELECTOR => {
+            let p = IBondingCurveRouter::buyCall::abi_decode(data)?.params;
+            Ok(RouterCall::Buy {
+                token: p.token,
+                amount_out_min: p.amountOutMin,
+                to: p.to,
+                deadline: p.deadline,
+            })
+        }
+        IBondingCurveRouter::sellCall::SELECTOR => {
+            let p = IBondingCurveRouter::sellCall::abi_decode(data)?.params;
+            Ok(RouterCall::Sell {
+                token: p.token,
+                amount_in: p.amountIn,
+                amount_out_min: p.amountOutMin,
+                to: p.to,
+                deadline: p.deadline,
+            })
+        }
+        IBondingCurveRouter::sellPermitCall::SELECTOR => {
+            let p = IBondingCurveRouter::sellPermitCall::abi_decode(data)?.params;
+            Ok(RouterCall::SellPermit {
+                token: p.token,
+                amount_in: p.amountIn,
+                amount_out_min: p.amountOutMin,
+                amount_allowance: p.amountAllowance,
+                to: p.to,
+                deadline: p.deadline,
+                v: p.v,
+                r: p.r,
+                s: p.s,
+            })
+        }
+        _ => Err(anyhow::anyhow!(
+            "Unknown router selector 0x{}",
+            alloy::hex::encode(selector)
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::dex::IDexRouter;
+
+    #[test]
+    fn test_calldata_round_trip() {
+        // Decoding relies on both routers sharing their trading selectors
+        assert_eq!(
+            IBondingCurveRouter::buyCall::SELECTOR,
+            IDexRouter::buyCall::SELECTOR
+        );
+        assert_eq!(
+            IBondingCurveRouter::sellPermitCall::SELECTOR,
+            IDexRouter::sellPermitCall::SELECTOR
+        );
+
+        let params = BuyParams {
+            token: Address::repeat_byte(0x01),
+            amount_in: U256::from(10),
+            amount_out_min: U256::from(5),
+            to: Address::repeat_byte(0x02),
+            deadline: U256::from(1_000),
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+        };
+        let router = Router::Dex(Address::repeat_byte(0x03));
+        let data = encode_buy_calldata(&params, &router);
+
+        let call = decode_router_calldata(&data).unwrap();
+        assert_eq!(call.token(), params.token);
+        assert_eq!(call.to(), params.to);
+        let operation = call.into_operation(params.amount_in);
+        assert_eq!(operation.calldata(router.kind()), data);
+        assert_eq!(operation.value(), params.amount_in);
+
+        assert!(decode_router_calldata(&[0xde, 0xad]).is_err());
+        assert!(decode_router_calldata(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Reorg-safe transaction confirmation
+//!
+//! A receipt only says the transaction was in *a* block. Before crediting a
+//! user, downstream systems want it buried under `depth` blocks and still on the
+//! canonical chain. [`Trade::confirm`] waits for that and re-checks inclusion:
+//!
+//! ```rust,ignore
+//! let result = trade.buy(params, router).await?;
+//! match trade.confirm(result.transaction_hash, 3).await {
+//!     Ok(confirmed) => credit(confirmed.block_number, confirmed.result),
+//!     Err(e) if e.downcast_ref::<Reorged>().is_some() => resubmit(),
+//!     Err(e) => return Err(e),
+//! }
+//! ```
+//!
+//! A transaction that a reorg moved to another block keeps being followed in
+//! its new block; only a transaction that disappeared fails with [`Reorged`].
+//! Reverted transactions confirm like any other; check `result.status`.
+
+use crate::{
+    trading::{Trade, TxUtils},
+    types::TransactionResult,
+};
+use alloy::{eips::BlockNumberOrTag, primitives::B256, providers::Provider};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// How long [`Trade::confirm`] waits before giving up
+pub const DEFAULT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A receipt on the canonical chain with at least the requested depth
+#[derive(Debug, Clone)]
+pub struct ConfirmedReceipt {
+    pub result: TransactionResult,
+    pub block_number: u64,
+    pub block_hash: B256,
+    /// Blocks on top of the containing block when it was last verified
+    pub confirmations: u64,
+}
+
+/// The transaction was mined, then dropped from the canonical chain
+///
+/// Returned inside an `anyhow::Error`; match it with `downcast_ref::<Reorged>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reorged {
+    pub transaction_hash: B256,
+    /// Block the transaction was last seen in
+    pub block_number: u64,
+    pub block_hash: B256,
+}
+
+impl std::fmt::Display for Reorged {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Transaction {} was reorged out of block {} ({})",
+            self.transaction_hash, self.block_number, self.block_hash
+        )
+    }
+}
+
+impl std::error::Error for Reorged {}
+
+/// Where a mined transaction stands against the current chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Inclusion {
+    /// Fewer than `depth` blocks on top so far
+    Pending,
+    /// Deep enough and the block is canonical
+    Confirmed { confirmations: u64 },
+    /// The canonical block at that height is a different one
+    Replaced,
+}
+
+fn check_inclusion(
+    block_number: u64,
+    block_hash: B256,
+    latest: u64,
+    canonical_hash: Option<B256>,
+    depth: u64,
+) -> Inclusion {
+    let confirmations = latest.saturating_sub(block_number);
+    if latest < block_number || confirmations < depth {
+        return Inclusion::Pending;
+    }
+    match canonical_hash {
+        Some(hash) if hash == block_hash => Inclusion::Confirmed { confirmations },
+        _ => Inclusion::Replaced,
+    }
+}
+
+impl Trade {
+    /// Wait until `tx_hash` has `depth` blocks on top of it and is still canonical
+    ///
+    /// Gives up after [`DEFAULT_CONFIRM_TIMEOUT`]. Fails with [`Reorged`] if the
+    /// transaction was mined and then dropped.
+    pub async fn confirm(&self, tx_hash: B256, depth: u64) -> Result<ConfirmedReceipt> {
+        self.confirm_within(tx_hash, depth, DEFAULT_CONFIRM_TIMEOUT)
+            .await
+    }
+
+    /// [`Trade::confirm`] with a custom timeout
+    pub async fn confirm_within(
+        &self,
+        tx_hash: B256,
+        depth: u64,
+        timeout: Duration,
+    ) -> Result<ConfirmedReceipt> {
+        let provider = self.provider();
+        let started = Instant::now();
+        let mut last_seen: Option<(u64, B256)> = None;
+
+        loop {
+            match provider.get_transaction_receipt(tx_hash).await? {
+                Some(receipt) => {
+                    let block_number = receipt
+                        .block_number
+                        .ok_or_else(|| anyhow::anyhow!("Receipt of {} has no block", tx_hash))?;
+                    let block_hash = receipt.block_hash.unwrap_or_default();
+                    last_seen = Some((block_number, block_hash));
+
+                    let latest = provider.get_block_number().await?;
+                    let canonical_hash = if latest >= block_number + depth {
+                        provider
+                            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                            .await?
+                            .map(|block| block.header.hash)
+                    } else {
+                        None
+                    };
+
+                    // Replaced: the node may not have re-indexed the receipt yet, look again
+                    if let Inclusion::Confirmed { confirmations } =
+                        check_inclusion(block_number, block_hash, latest, canonical_hash, depth)
+                    {
+                        return Ok(ConfirmedReceipt {
+                            result: TransactionResult::from(receipt),
+                            block_number,
+                            block_hash,
+                            confirmations,
+                        });
+                    }
+                }
+                None => {
+                    if let Some((block_number, block_hash)) = last_seen {
+                        return Err(Reorged {
+                            transaction_hash: tx_hash,
+                            block_number,
+                            block_hash,
+                        }
+                        .into());
+                    }
+                }
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(anyhow::anyhow!(
+                    "Timed out waiting for {} confirmations of {}",
+                    depth,
+                    tx_hash
+                ));
+            }
+            tokio::time::sleep(TxUtils::POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_inclusion() {
+        let mined = B256::repeat_byte(0x01);
+        let other = B256::repeat_byte(0x02);
+
+        assert_eq!(
+            check_inclusion(100, mined, 101, None, 3),
+            Inclusion::Pending
+        );
+        assert_eq!(
+            check_inclusion(100, mined, 103, Some(mined), 3),
+            Inclusion::Confirmed { confirmations: 3 }
+        );
+        assert_eq!(
+            check_inclusion(100, mined, 105, Some(other), 3),
+            Inclusion::Replaced
+        );
+        // A lagging node reporting an older head than the receipt
+        assert_eq!(check_inclusion(100, mined, 99, None, 0), Inclusion::Pending);
+
+        let error: anyhow::Error = Reorged {
+            transaction_hash: B256::ZERO,
+            block_number: 100,
+            block_hash: mined,
+        }
+        .into();
+        assert_eq!(error.downcast_ref::<Reorged>().unwrap().block_number, 100);
+    }
+}
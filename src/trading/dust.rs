@@ -0,0 +1,264 @@
+//! Dust position cleanup
+//!
+//! Small leftover balances clutter a wallet. [`Trade::dust_cleanup`] quotes the
+//! full balance of each token: if selling it would return at least `min_value`
+//! MON it is sold, otherwise it is burned with [`TokenHelper::burn`]. Run it in
+//! dry-run mode first to see what would happen:
+//!
+//! ```rust,ignore
+//! let mut config = DustCleanupConfig::new(tokens, parse_ether("0.01")?);
+//! config.dry_run = true;
+//! let report = trade.dust_cleanup(&token_helper, &config).await?;
+//! for entry in &report.entries {
+//!     println!("{}: {:?} ({:?} MON)", entry.token, entry.action, entry.expected_out);
+//! }
+//! ```
+
+use crate::{
+    token::TokenHelper,
+    trading::{SellExecution, Trade, builder::DEFAULT_SLIPPAGE_BPS},
+    types::TransactionResult,
+};
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+
+/// Dust cleanup settings
+#[derive(Debug, Clone)]
+pub struct DustCleanupConfig {
+    /// Tokens whose full balances are cleaned up
+    pub tokens: Vec<Address>,
+    /// Minimum quoted MON proceeds for a balance to be sold instead of burned
+    pub min_value: U256,
+    /// Slippage tolerance for the sales in basis points
+    pub slippage_bps: u64,
+    /// Quote and report without sending any transactions
+    pub dry_run: bool,
+}
+
+impl DustCleanupConfig {
+    pub fn new(tokens: Vec<Address>, min_value: U256) -> Self {
+        Self {
+            tokens,
+            min_value,
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            dry_run: false,
+        }
+    }
+}
+
+/// What happens to one token balance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DustAction {
+    /// Proceeds reach `min_value`: sell the full balance
+    Sell,
+    /// Proceeds are below `min_value`: burn the full balance
+    Burn,
+    /// Left alone because the balance or quote could not be read
+    Skip,
+}
+
+/// A single planned or executed cleanup
+#[derive(Debug, Clone)]
+pub struct DustEntry {
+    pub token: Address,
+    pub balance: U256,
+    /// Quoted MON for selling the full balance
+    pub expected_out: Option<U256>,
+    pub action: DustAction,
+    /// Sale transactions (None for burns and in dry-run mode)
+    pub sell: Option<SellExecution>,
+    /// Burn receipt (None for sales and in dry-run mode)
+    pub burn: Option<TransactionResult>,
+    /// Set if the entry was skipped, or its transaction failed or reverted
+    pub error: Option<String>,
+}
+
+/// Result of a dust cleanup run
+#[derive(Debug, Clone, Default)]
+pub struct DustReport {
+    pub dry_run: bool,
+    /// One entry per token with a non-zero balance, in config order
+    pub entries: Vec<DustEntry>,
+}
+
+impl DustReport {
+    /// Entries sold (or to be sold) without errors
+    pub fn sold(&self) -> impl Iterator<Item = &DustEntry> {
+        self.succeeded(DustAction::Sell)
+    }
+
+    /// Entries burned (or to be burned) without errors
+    pub fn burned(&self) -> impl Iterator<Item = &DustEntry> {
+        self.succeeded(DustAction::Burn)
+    }
+
+    /// Entries that were skipped or failed
+    pub fn failed(&self) -> impl Iterator<Item = &DustEntry> {
+        self.entries.iter().filter(|e| e.error.is_some())
+    }
+
+    /// Quoted MON proceeds of all sales
+    pub fn expected_proceeds(&self) -> U256 {
+        self.sold()
+            .filter_map(|e| e.expected_out)
+            .fold(U256::ZERO, |acc, out| acc + out)
+    }
+
+    fn succeeded(&self, action: DustAction) -> impl Iterator<Item = &DustEntry> {
+        self.entries
+            .iter()
+            .filter(move |e| e.action == action && e.error.is_none())
+    }
+}
+
+/// Sell when the quote reaches `min_value`, burn otherwise
+pub fn dust_action(expected_out: U256, min_value: U256) -> DustAction {
+    if expected_out >= min_value {
+        DustAction::Sell
+    } else {
+        DustAction::Burn
+    }
+}
+
+impl Trade {
+    /// Sell or burn the full balance of each token in `config.tokens`
+    ///
+    /// Tokens are processed one after another, so approvals, sales and burns
+    /// never race for a nonce. A failed token is recorded in its entry and does
+    /// not stop the run. `token_helper` must use the same wallet as the trade.
+    pub async fn dust_cleanup(
+        &self,
+        token_helper: &TokenHelper,
+        config: &DustCleanupConfig,
+    ) -> Result<DustReport> {
+        let wallet = self.wallet_address();
+        if token_helper.wallet_address() != wallet {
+            return Err(anyhow::anyhow!(
+                "Token helper wallet {} differs from trade wallet {}",
+                token_helper.wallet_address(),
+                wallet
+            ));
+        }
+
+        let mut report = DustReport {
+            dry_run: config.dry_run,
+            ..Default::default()
+        };
+
+        for &token in &config.tokens {
+            let balance = match token_helper.balance_of(token, wallet).await {
+                Ok(balance) if balance.is_zero() => continue,
+                Ok(balance) => balance,
+                Err(e) => {
+                    report.entries.push(skipped(token, U256::ZERO, e));
+                    continue;
+                }
+            };
+
+            // A token that can't be quoted is left alone rather than burned
+            let expected_out = match self.get_amount_out(token, balance, false).await {
+                Ok((_, expected_out)) => expected_out,
+                Err(e) => {
+                    report.entries.push(skipped(token, balance, e));
+                    continue;
+                }
+            };
+
+            let mut entry = DustEntry {
+                token,
+                balance,
+                expected_out: Some(expected_out),
+                action: dust_action(expected_out, config.min_value),
+                sell: None,
+                burn: None,
+                error: None,
+            };
+
+            if !config.dry_run {
+                match entry.action {
+                    DustAction::Sell => {
+                        let sold = match self.plan_sell(token, balance, config.slippage_bps).await {
+                            Ok(plan) => self.execute_sell_plan(&plan).await,
+                            Err(e) => Err(e),
+                        };
+                        match sold {
+                            Ok(execution) if !execution.sell.status => {
+                                entry.error = Some(format!(
+                                    "Sale {} reverted",
+                                    execution.sell.transaction_hash
+                                ));
+                                entry.sell = Some(execution);
+                            }
+                            Ok(execution) => entry.sell = Some(execution),
+                            Err(e) => entry.error = Some(e.to_string()),
+                        }
+                    }
+                    DustAction::Burn => match token_helper.burn(token, balance).await {
+                        Ok(result) if !result.status => {
+                            entry.error =
+                                Some(format!("Burn {} reverted", result.transaction_hash));
+                            entry.burn = Some(result);
+                        }
+                        Ok(result) => entry.burn = Some(result),
+                        Err(e) => entry.error = Some(e.to_string()),
+                    },
+                    DustAction::Skip => {}
+                }
+            }
+
+            report.entries.push(entry);
+        }
+
+        Ok(report)
+    }
+}
+
+fn skipped(token: Address, balance: U256, error: anyhow::Error) -> DustEntry {
+    DustEntry {
+        token,
+        balance,
+        expected_out: None,
+        action: DustAction::Skip,
+        sell: None,
+        burn: None,
+        error: Some(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(action: DustAction, expected_out: u64, error: Option<&str>) -> DustEntry {
+        DustEntry {
+            token: Address::ZERO,
+            balance: U256::from(1),
+            expected_out: Some(U256::from(expected_out)),
+            action,
+            sell: None,
+            burn: None,
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_dust_action_and_report() {
+        let min_value = U256::from(100);
+        assert_eq!(dust_action(U256::from(100), min_value), DustAction::Sell);
+        assert_eq!(dust_action(U256::from(99), min_value), DustAction::Burn);
+
+        let report = DustReport {
+            dry_run: true,
+            entries: vec![
+                entry(DustAction::Sell, 150, None),
+                entry(DustAction::Sell, 500, Some("reverted")),
+                entry(DustAction::Burn, 3, None),
+                entry(DustAction::Skip, 0, Some("no quote")),
+            ],
+        };
+        assert_eq!(report.sold().count(), 1);
+        assert_eq!(report.burned().count(), 1);
+        assert_eq!(report.failed().count(), 2);
+        assert_eq!(report.expected_proceeds(), U256::from(150));
+    }
+}
@@ -0,0 +1,308 @@
+//! Buy and sell with automatic route fallback
+//!
+//! A token can graduate between the quote and the trade: the lens picked the
+//! bonding curve router, but by the time the transaction lands the curve is
+//! locked and only the DEX accepts it (or the other way around when a quote hit
+//! a stale lens). [`Trade::buy_with_fallback`] and [`Trade::sell_with_fallback`]
+//! retry a reverted trade on the other router after re-quoting it:
+//!
+//! ```rust,ignore
+//! let outcome = trade
+//!     .buy_with_fallback(token, parse_ether("0.1")?, &FallbackConfig::default())
+//!     .await?;
+//! println!("bought via {:?} after {} attempts", outcome.router, outcome.attempts.len());
+//! ```
+//!
+//! Only reverts (failed gas estimation or a failed receipt) trigger a fallback;
+//! network errors are returned immediately.
+
+use crate::{
+    contracts::{BondingCurveRouter, DexRouter},
+    token::token::IToken,
+    trading::{
+        SlippageUtils, Trade,
+        builder::{DEFAULT_DEADLINE_SECS, DEFAULT_SLIPPAGE_BPS},
+        options::TradeOptions,
+    },
+    types::{BuyParams, Router, RouterKind, SellParams, TransactionResult},
+};
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Settings for [`Trade::buy_with_fallback`] and [`Trade::sell_with_fallback`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FallbackConfig {
+    /// Retries after the first attempt; each retry switches router
+    pub max_retries: u32,
+    /// Slippage tolerance in basis points, applied to every quote
+    pub slippage_bps: u64,
+    /// Deadline offset in seconds, set again for every attempt
+    pub deadline_secs: u64,
+    /// Approve the fallback router for `amount_in` if its allowance is short (sells only)
+    pub approve_fallback_router: bool,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 1,
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            deadline_secs: DEFAULT_DEADLINE_SECS,
+            approve_fallback_router: true,
+        }
+    }
+}
+
+impl FallbackConfig {
+    /// Slippage and deadline from a trade's [`TradeOptions`], one retry
+    pub fn from_options(options: &TradeOptions) -> Self {
+        Self {
+            slippage_bps: options.default_slippage_bps,
+            deadline_secs: options.default_deadline_secs,
+            ..Self::default()
+        }
+    }
+}
+
+/// One try on one router
+#[derive(Debug, Clone)]
+pub struct RouteAttempt {
+    pub router: Router,
+    /// Quote the attempt's minimum output was derived from
+    pub expected_out: U256,
+    /// Receipt, if the transaction was mined (reverted or not)
+    pub result: Option<TransactionResult>,
+    /// Why the attempt failed
+    pub error: Option<String>,
+}
+
+/// A trade that eventually executed
+#[derive(Debug, Clone)]
+pub struct FallbackOutcome {
+    /// Router that executed the trade
+    pub router: Router,
+    pub result: TransactionResult,
+    /// Every attempt in order; the last one succeeded
+    pub attempts: Vec<RouteAttempt>,
+}
+
+impl FallbackOutcome {
+    /// True if the trade executed on a different router than first quoted
+    pub fn fell_back(&self) -> bool {
+        self.attempts.len() > 1
+    }
+}
+
+/// Whether `error` is a contract revert rather than a transport failure
+pub fn is_revert(error: &anyhow::Error) -> bool {
+    format!("{:#}", error).to_lowercase().contains("revert")
+}
+
+impl Trade {
+    /// Buy with `amount_in` MON, retrying on the other router if the trade reverts
+    pub async fn buy_with_fallback(
+        &self,
+        token: Address,
+        amount_in: U256,
+        config: &FallbackConfig,
+    ) -> Result<FallbackOutcome> {
+        self.trade_with_fallback(token, amount_in, true, config)
+            .await
+    }
+
+    /// Sell `amount_in` tokens, retrying on the other router if the trade reverts
+    pub async fn sell_with_fallback(
+        &self,
+        token: Address,
+        amount_in: U256,
+        config: &FallbackConfig,
+    ) -> Result<FallbackOutcome> {
+        self.trade_with_fallback(token, amount_in, false, config)
+            .await
+    }
+
+    /// The router of the other kind: the configured DEX router for a curve
+    /// router and vice versa
+    pub fn alternate_router(&self, router: &Router) -> Router {
+        match router.kind() {
+            RouterKind::BondingCurve => Router::Dex(self.chain_config().dex_router),
+            RouterKind::Dex => Router::BondingCurve(self.chain_config().bonding_curve_router),
+        }
+    }
+
+    /// Quote on a specific router instead of asking the lens for one
+    pub async fn quote_on(
+        &self,
+        router: &Router,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+    ) -> Result<U256> {
+        match router.kind() {
+            RouterKind::Dex => {
+                DexRouter::new(router.address(), self.provider().clone())
+                    .get_amount_out(token, amount_in, is_buy)
+                    .await
+            }
+            RouterKind::BondingCurve => {
+                BondingCurveRouter::new(
+                    router.address(),
+                    self.chain_config().bonding_curve,
+                    self.provider().clone(),
+                )
+                .get_amount_out(token, amount_in, is_buy)
+                .await
+            }
+        }
+    }
+
+    async fn trade_with_fallback(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+        config: &FallbackConfig,
+    ) -> Result<FallbackOutcome> {
+        if config.slippage_bps >= 10_000 {
+            return Err(anyhow::anyhow!(
+                "Slippage must be below 10000 bps, got {}",
+                config.slippage_bps
+            ));
+        }
+
+        let (mut router, mut expected_out) = self.get_amount_out(token, amount_in, is_buy).await?;
+        let mut attempts = Vec::new();
+
+        loop {
+            let mut attempt = RouteAttempt {
+                router: router.clone(),
+                expected_out,
+                result: None,
+                error: None,
+            };
+
+            match self
+                .attempt_route(
+                    &router,
+                    token,
+                    amount_in,
+                    expected_out,
+                    is_buy,
+                    config,
+                    !attempts.is_empty(),
+                )
+                .await
+            {
+                Ok(result) if result.status => {
+                    attempt.result = Some(result.clone());
+                    attempts.push(attempt);
+                    return Ok(FallbackOutcome {
+                        router,
+                        result,
+                        attempts,
+                    });
+                }
+                Ok(result) => {
+                    attempt.error =
+                        Some(format!("Transaction {} reverted", result.transaction_hash));
+                    attempt.result = Some(result);
+                }
+                Err(e) if is_revert(&e) => attempt.error = Some(format!("{:#}", e)),
+                Err(e) => return Err(e),
+            }
+
+            let error = attempt.error.clone().unwrap_or_default();
+            attempts.push(attempt);
+            if attempts.len() > config.max_retries as usize {
+                return Err(anyhow::anyhow!(
+                    "Trade failed after {} attempts, last on {:?}: {}",
+                    attempts.len(),
+                    router,
+                    error
+                ));
+            }
+
+            router = self.alternate_router(&router);
+            expected_out = self.quote_on(&router, token, amount_in, is_buy).await?;
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn attempt_route(
+        &self,
+        router: &Router,
+        token: Address,
+        amount_in: U256,
+        expected_out: U256,
+        is_buy: bool,
+        config: &FallbackConfig,
+        is_fallback: bool,
+    ) -> Result<TransactionResult> {
+        let amount_out_min = SlippageUtils::calculate_amount_out_min(
+            expected_out,
+            config.slippage_bps as f64 / 100.0,
+        );
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let deadline = U256::from(now + config.deadline_secs);
+        let wallet = self.wallet_address();
+
+        if is_buy {
+            let params = BuyParams {
+                token,
+                amount_in,
+                amount_out_min,
+                to: wallet,
+                deadline,
+                gas_limit: None,
+                gas_price: None,
+                nonce: None,
+            };
+            return self.submit_buy(params, router.clone()).await;
+        }
+
+        if is_fallback && config.approve_fallback_router {
+            let contract = IToken::new(token, self.provider().as_ref());
+            let allowance = contract.allowance(wallet, router.address()).call().await?;
+            if allowance < amount_in {
+                let tx = contract.approve(router.address(), amount_in).send().await?;
+                let approval = TransactionResult::from(tx.get_receipt().await?);
+                if !approval.status {
+                    return Err(anyhow::anyhow!(
+                        "Approval {} of fallback router reverted",
+                        approval.transaction_hash
+                    ));
+                }
+            }
+        }
+
+        let params = SellParams {
+            amount_in,
+            amount_out_min,
+            token,
+            to: wallet,
+            deadline,
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+        };
+        self.sell(params, router.clone()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_revert() {
+        let revert = anyhow::anyhow!(
+            "server returned an error response: error code 3: execution reverted: LOCKED"
+        );
+        assert!(is_revert(&revert));
+        assert!(is_revert(&revert.context("Gas estimation failed")));
+        assert!(!is_revert(&anyhow::anyhow!(
+            "error sending request: connection refused"
+        )));
+    }
+}
@@ -1,15 +1,23 @@
-use crate::types::Router;
+use crate::types::{Router, RouterKind};
 use alloy::{
-    primitives::{Address, U256},
+    primitives::{Address, Bytes, U256},
     providers::Provider,
     rpc::types::TransactionRequest,
+    sol_types::SolCall,
 };
 use anyhow::Result;
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
 
-/// Parameters for gas estimation
+/// A router call with its parameters
+///
+/// The single description of a trade used for calldata, gas estimation and the
+/// default gas limits; the routers submit trades from it as well, so the three
+/// can't drift apart.
 #[derive(Debug, Clone)]
-pub enum GasEstimationParams {
+pub enum TradeOperation {
     Buy {
         token: Address,
         amount_in: U256,
@@ -30,22 +38,32 @@ pub enum GasEstimationParams {
         amount_out_min: U256,
         to: Address,
         deadline: U256,
+        /// Allowance granted by the permit, usually `amount_in`
+        amount_allowance: U256,
         v: u8,
         r: [u8; 32],
         s: [u8; 32],
     },
 }
 
+/// Former name of [`TradeOperation`]
+///
+/// Only the name is kept: `SellPermit` now also requires `amount_allowance`, so
+/// literals written against the old variant need that field added.
+pub type GasEstimationParams = TradeOperation;
+
 /// Estimate gas for any trading operation
 ///
 /// This is the main entry point for gas estimation. It takes gas estimation parameters
 /// and the router information, then calls the appropriate specialized function.
+/// The transaction is simulated from the recipient (`to`); use [`estimate_gas_from`]
+/// when the sender differs.
 ///
 /// # Example
 /// ```rust,ignore
-/// use nadfun_sdk::{estimate_gas, GasEstimationParams};
+/// use nadfun_sdk::{estimate_gas, TradeOperation};
 ///
-/// let params = GasEstimationParams::Sell {
+/// let params = TradeOperation::Sell {
 ///     token,
 ///     amount_in: token_amount,
 ///     amount_out_min: U256::from(1),
@@ -58,72 +76,26 @@ pub enum GasEstimationParams {
 pub async fn estimate_gas<P: Provider>(
     provider: Arc<P>,
     router: &Router,
-    params: GasEstimationParams,
+    params: TradeOperation,
 ) -> Result<u64> {
-    match params {
-        GasEstimationParams::Buy {
-            token,
-            amount_in,
-            amount_out_min,
-            to,
-            deadline,
-        } => {
-            estimate_buy_gas(
-                provider,
-                router,
-                token,
-                amount_in,
-                amount_out_min,
-                to,
-                deadline,
-            )
-            .await
-        }
+    let from = params.recipient();
+    estimate_gas_from(provider, router, from, params).await
+}
 
-        GasEstimationParams::Sell {
-            token,
-            amount_in,
-            amount_out_min,
-            to,
-            deadline,
-        } => {
-            estimate_sell_gas(
-                provider,
-                router,
-                token,
-                amount_in,
-                amount_out_min,
-                to,
-                deadline,
-            )
-            .await
-        }
+/// Estimate gas for a trading operation sent by `from`
+///
+/// Buys attach `amount_in` as the transaction value; sells send no value.
+pub async fn estimate_gas_from<P: Provider>(
+    provider: Arc<P>,
+    router: &Router,
+    from: Address,
+    params: TradeOperation,
+) -> Result<u64> {
+    let gas = provider
+        .estimate_gas(params.transaction(router).from(from))
+        .await?;
 
-        GasEstimationParams::SellPermit {
-            token,
-            amount_in,
-            amount_out_min,
-            to,
-            deadline,
-            v,
-            r,
-            s,
-        } => {
-            estimate_sell_permit_gas(
-                provider,
-                router,
-                token,
-                amount_in,
-                amount_out_min,
-                to,
-                deadline,
-                v,
-                r,
-                s,
-            )
-            .await
-        }
-    }
+    Ok(gas)
 }
 
 /// Estimate gas for buy operation
@@ -136,60 +108,14 @@ pub async fn estimate_buy_gas<P: Provider>(
     to: Address,
     deadline: U256,
 ) -> Result<u64> {
-    match router {
-        Router::BondingCurve(router_addr) => {
-            use crate::contracts::bonding_curve::IBondingCurveRouter;
-
-            let contract_params = IBondingCurveRouter::BuyParams {
-                amountOutMin: amount_out_min,
-                token,
-                to,
-                deadline,
-            };
-
-            let contract = IBondingCurveRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.buy(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .value(amount_in)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas.try_into().map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
-        }
-        Router::Dex(router_addr) => {
-            use crate::contracts::dex::IDexRouter;
-
-            let contract_params = IDexRouter::BuyParams {
-                amountOutMin: amount_out_min,
-                token,
-                to,
-                deadline,
-            };
-
-            let contract = IDexRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.buy(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .value(amount_in)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas.try_into().map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
-        }
-    }
+    let params = TradeOperation::Buy {
+        token,
+        amount_in,
+        amount_out_min,
+        to,
+        deadline,
+    };
+    estimate_gas_from(provider, router, to, params).await
 }
 
 /// Estimate gas for sell operation
@@ -202,63 +128,18 @@ pub async fn estimate_sell_gas<P: Provider>(
     to: Address,
     deadline: U256,
 ) -> Result<u64> {
-    match router {
-        Router::BondingCurve(router_addr) => {
-            use crate::contracts::bonding_curve::IBondingCurveRouter;
-
-            let contract_params = IBondingCurveRouter::SellParams {
-                amountIn: amount_in,
-                amountOutMin: amount_out_min,
-                token,
-                to,
-                deadline,
-            };
-
-            let contract = IBondingCurveRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.sell(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas.try_into().map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
-        }
-        Router::Dex(router_addr) => {
-            use crate::contracts::dex::IDexRouter;
-
-            let contract_params = IDexRouter::SellParams {
-                amountIn: amount_in,
-                amountOutMin: amount_out_min,
-                token,
-                to,
-                deadline,
-            };
-
-            let contract = IDexRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.sell(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas.try_into().map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
-        }
-    }
+    let params = TradeOperation::Sell {
+        token,
+        amount_in,
+        amount_out_min,
+        to,
+        deadline,
+    };
+    estimate_gas_from(provider, router, to, params).await
 }
 
 /// Estimate gas for sell permit operation
+#[allow(clippy::too_many_arguments)]
 pub async fn estimate_sell_permit_gas<P: Provider>(
     provider: Arc<P>,
     router: &Router,
@@ -271,69 +152,571 @@ pub async fn estimate_sell_permit_gas<P: Provider>(
     r: [u8; 32],
     s: [u8; 32],
 ) -> Result<u64> {
-    match router {
-        Router::BondingCurve(router_addr) => {
-            use crate::contracts::bonding_curve::IBondingCurveRouter;
-
-            let contract_params = IBondingCurveRouter::SellPermitParams {
-                amountIn: amount_in,
-                amountOutMin: amount_out_min,
-                amountAllowance: amount_in, // Same as amount_in
-                token,
-                to,
-                deadline,
-                v,
-                r: r.into(),
-                s: s.into(),
-            };
-
-            let contract = IBondingCurveRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.sellPermit(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas
-                .try_into()
-                .map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
+    let params = TradeOperation::SellPermit {
+        token,
+        amount_in,
+        amount_out_min,
+        to,
+        deadline,
+        amount_allowance: amount_in,
+        v,
+        r,
+        s,
+    };
+    estimate_gas_from(provider, router, to, params).await
+}
+
+impl TradeOperation {
+    /// Recipient of the trade output
+    pub fn recipient(&self) -> Address {
+        match self {
+            TradeOperation::Buy { to, .. }
+            | TradeOperation::Sell { to, .. }
+            | TradeOperation::SellPermit { to, .. } => *to,
+        }
+    }
+
+    /// Native value sent with the transaction (the MON input for buys)
+    pub fn value(&self) -> U256 {
+        match self {
+            TradeOperation::Buy { amount_in, .. } => *amount_in,
+            _ => U256::ZERO,
+        }
+    }
+
+    /// Router calldata for these parameters
+    pub fn calldata(&self, kind: RouterKind) -> Bytes {
+        use crate::contracts::bonding_curve::IBondingCurveRouter;
+        use crate::contracts::dex::IDexRouter;
+
+        match (self, kind) {
+            (
+                TradeOperation::Buy {
+                    token,
+                    amount_out_min,
+                    to,
+                    deadline,
+                    ..
+                },
+                RouterKind::BondingCurve,
+            ) => IBondingCurveRouter::buyCall {
+                params: IBondingCurveRouter::BuyParams {
+                    amountOutMin: *amount_out_min,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                },
+            }
+            .abi_encode()
+            .into(),
+            (
+                TradeOperation::Buy {
+                    token,
+                    amount_out_min,
+                    to,
+                    deadline,
+                    ..
+                },
+                RouterKind::Dex,
+            ) => IDexRouter::buyCall {
+                params: IDexRouter::BuyParams {
+                    amountOutMin: *amount_out_min,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                },
+            }
+            .abi_encode()
+            .into(),
+            (
+                TradeOperation::Sell {
+                    token,
+                    amount_in,
+                    amount_out_min,
+                    to,
+                    deadline,
+                },
+                RouterKind::BondingCurve,
+            ) => IBondingCurveRouter::sellCall {
+                params: IBondingCurveRouter::SellParams {
+                    amountIn: *amount_in,
+                    amountOutMin: *amount_out_min,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                },
+            }
+            .abi_encode()
+            .into(),
+            (
+                TradeOperation::Sell {
+                    token,
+                    amount_in,
+                    amount_out_min,
+                    to,
+                    deadline,
+                },
+                RouterKind::Dex,
+            ) => IDexRouter::sellCall {
+                params: IDexRouter::SellParams {
+                    amountIn: *amount_in,
+                    amountOutMin: *amount_out_min,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                },
+            }
+            .abi_encode()
+            .into(),
+            (
+                TradeOperation::SellPermit {
+                    token,
+                    amount_in,
+                    amount_out_min,
+                    to,
+                    deadline,
+                    amount_allowance,
+                    v,
+                    r,
+                    s,
+                },
+                RouterKind::BondingCurve,
+            ) => IBondingCurveRouter::sellPermitCall {
+                params: IBondingCurveRouter::SellPermitParams {
+                    amountIn: *amount_in,
+                    amountOutMin: *amount_out_min,
+                    amountAllowance: *amount_allowance,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                    v: *v,
+                    r: (*r).into(),
+                    s: (*s).into(),
+                },
+            }
+            .abi_encode()
+            .into(),
+            (
+                TradeOperation::SellPermit {
+                    token,
+                    amount_in,
+                    amount_out_min,
+                    to,
+                    deadline,
+                    amount_allowance,
+                    v,
+                    r,
+                    s,
+                },
+                RouterKind::Dex,
+            ) => IDexRouter::sellPermitCall {
+                params: IDexRouter::SellPermitParams {
+                    amountIn: *amount_in,
+                    amountOutMin: *amount_out_min,
+                    amountAllowance: *amount_allowance,
+                    token: *token,
+                    to: *to,
+                    deadline: *deadline,
+                    v: *v,
+                    r: (*r).into(),
+                    s: (*s).into(),
+                },
+            }
+            .abi_encode()
+            .into(),
+        }
+    }
+}
+
+/// Trading operation kinds without parameters, used to key gas limits
+///
+/// [`TradeOperation::operation`] maps an operation to its kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Buy,
+    Sell,
+    SellPermit,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 3] = [Operation::Buy, Operation::Sell, Operation::SellPermit];
+
+    fn key(&self) -> &'static str {
+        match self {
+            Operation::Buy => "buy",
+            Operation::Sell => "sell",
+            Operation::SellPermit => "sell_permit",
+        }
+    }
+}
+
+impl TradeOperation {
+    /// Operation these parameters estimate
+    pub fn operation(&self) -> Operation {
+        match self {
+            TradeOperation::Buy { .. } => Operation::Buy,
+            TradeOperation::Sell { .. } => Operation::Sell,
+            TradeOperation::SellPermit { .. } => Operation::SellPermit,
+        }
+    }
+
+    /// Unsigned router transaction: target, value and calldata
+    ///
+    /// Gas, price, nonce and sender are left for the caller or the provider's
+    /// fillers.
+    pub fn transaction(&self, router: &Router) -> TransactionRequest {
+        TransactionRequest::default()
+            .to(router.address())
+            .value(self.value())
+            .input(self.calldata(router.kind()).into())
+    }
+
+    /// Estimate gas for this operation sent by `from`, see [`estimate_gas_from`]
+    pub async fn estimate_gas<P: Provider>(
+        &self,
+        provider: Arc<P>,
+        router: &Router,
+        from: Address,
+    ) -> Result<u64> {
+        estimate_gas_from(provider, router, from, self.clone()).await
+    }
+
+    /// Default gas limit for this operation on `router`, see [`get_default_gas_limit`]
+    pub fn default_gas_limit(&self, router: &Router) -> u64 {
+        get_default_gas_limit(router, self.operation())
+    }
+}
+
+impl From<&crate::types::BuyParams> for TradeOperation {
+    fn from(params: &crate::types::BuyParams) -> Self {
+        TradeOperation::Buy {
+            token: params.token,
+            amount_in: params.amount_in,
+            amount_out_min: params.amount_out_min,
+            to: params.to,
+            deadline: params.deadline,
+        }
+    }
+}
+
+impl From<&crate::types::SellParams> for TradeOperation {
+    fn from(params: &crate::types::SellParams) -> Self {
+        TradeOperation::Sell {
+            token: params.token,
+            amount_in: params.amount_in,
+            amount_out_min: params.amount_out_min,
+            to: params.to,
+            deadline: params.deadline,
+        }
+    }
+}
+
+impl From<&crate::types::SellPermitParams> for TradeOperation {
+    fn from(params: &crate::types::SellPermitParams) -> Self {
+        TradeOperation::SellPermit {
+            token: params.token,
+            amount_in: params.amount_in,
+            amount_out_min: params.amount_out_min,
+            to: params.to,
+            deadline: params.deadline,
+            amount_allowance: params.amount_allowance,
+            v: params.v,
+            r: params.r.0,
+            s: params.s.0,
+        }
+    }
+}
+
+/// Gas settings applied by [`crate::Trade`] when params leave `gas_limit` as None
+///
+/// Resolution order: per-operation override, then network estimate plus
+/// `default_buffer_pct`, then the default gas table if estimation fails and
+/// `fallback_to_defaults` is set.
+#[derive(Debug, Clone)]
+pub struct GasConfig {
+    /// Percentage added on top of network estimates (20 = +20%)
+    pub default_buffer_pct: u64,
+    /// Fixed gas limits that skip estimation entirely
+    pub per_operation_overrides: HashMap<Operation, u64>,
+    /// Use [`get_default_gas_limit`] when estimation fails
+    pub fallback_to_defaults: bool,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            default_buffer_pct: 20,
+            per_operation_overrides: HashMap::new(),
+            fallback_to_defaults: true,
+        }
+    }
+}
+
+impl GasConfig {
+    pub fn with_buffer_pct(mut self, buffer_pct: u64) -> Self {
+        self.default_buffer_pct = buffer_pct;
+        self
+    }
+
+    pub fn with_override(mut self, operation: Operation, gas_limit: u64) -> Self {
+        self.per_operation_overrides.insert(operation, gas_limit);
+        self
+    }
+
+    /// Apply the configured buffer to a raw estimate
+    pub fn apply_buffer(&self, estimated_gas: u64) -> u64 {
+        let pct = self.default_buffer_pct;
+        let buffer = (estimated_gas / 100)
+            .saturating_mul(pct)
+            .saturating_add((estimated_gas % 100).saturating_mul(pct) / 100);
+        estimated_gas.saturating_add(buffer)
+    }
+
+    /// Resolve the gas limit for an operation sent by `from` using this configuration
+    pub async fn resolve<P: Provider>(
+        &self,
+        provider: Arc<P>,
+        router: &Router,
+        from: Address,
+        params: TradeOperation,
+    ) -> Result<u64> {
+        let operation = params.operation();
+
+        if let Some(gas_limit) = self.per_operation_overrides.get(&operation) {
+            return Ok(*gas_limit);
+        }
+
+        match estimate_gas_from(provider, router, from, params).await {
+            Ok(gas) => Ok(self.apply_buffer(gas)),
+            Err(_) if self.fallback_to_defaults => Ok(get_default_gas_limit(router, operation)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Gas limits for each operation on a single router
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationGas {
+    pub buy: u64,
+    pub sell: u64,
+    pub sell_permit: u64,
+}
+
+impl OperationGas {
+    pub fn get(&self, operation: Operation) -> u64 {
+        match operation {
+            Operation::Buy => self.buy,
+            Operation::Sell => self.sell,
+            Operation::SellPermit => self.sell_permit,
+        }
+    }
+
+    pub fn set(&mut self, operation: Operation, gas: u64) {
+        match operation {
+            Operation::Buy => self.buy = gas,
+            Operation::Sell => self.sell = gas,
+            Operation::SellPermit => self.sell_permit = gas,
+        }
+    }
+}
+
+/// Gas limit table for both routers
+///
+/// Can be refreshed at runtime with [`crate::Trade::calibrate_gas`] and persisted
+/// with [`GasTable::save`] / [`GasTable::load`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasTable {
+    pub bonding_curve: OperationGas,
+    pub dex: OperationGas,
+}
+
+/// Conservative fallback limits used until a calibrated table is installed
+pub const BASELINE_GAS_TABLE: GasTable = GasTable {
+    bonding_curve: OperationGas {
+        buy: 320_000,
+        sell: 200_000,
+        sell_permit: 250_000,
+    },
+    dex: OperationGas {
+        buy: 350_000,
+        sell: 350_000,
+        sell_permit: 400_000,
+    },
+};
+
+impl Default for GasTable {
+    fn default() -> Self {
+        BASELINE_GAS_TABLE
+    }
+}
+
+impl GasTable {
+    pub fn get(&self, router: &Router, operation: Operation) -> u64 {
+        match router.kind() {
+            RouterKind::BondingCurve => self.bonding_curve.get(operation),
+            RouterKind::Dex => self.dex.get(operation),
+        }
+    }
+
+    pub fn set(&mut self, router: &Router, operation: Operation, gas: u64) {
+        match router.kind() {
+            RouterKind::BondingCurve => self.bonding_curve.set(operation, gas),
+            RouterKind::Dex => self.dex.set(operation, gas),
         }
-        Router::Dex(router_addr) => {
-            use crate::contracts::dex::IDexRouter;
-
-            let contract_params = IDexRouter::SellPermitParams {
-                amountIn: amount_in,
-                amountOutMin: amount_out_min,
-                amountAllowance: amount_in, // Same as amount_in
-                token,
-                to,
-                deadline,
-                v,
-                r: r.into(),
-                s: s.into(),
-            };
-
-            let contract = IDexRouter::new(*router_addr, provider.as_ref());
-            let call_builder = contract.sellPermit(contract_params);
-            let call_data = call_builder.calldata();
-
-            let gas = provider
-                .estimate_gas(
-                    TransactionRequest::default()
-                        .to(*router_addr)
-                        .from(to)
-                        .input(call_data.clone().into()),
-                )
-                .await?;
-
-            Ok(gas.try_into().map_err(|_| anyhow::anyhow!("Gas estimation overflow"))?)
+    }
+
+    /// Serialize as `router.operation=gas` lines
+    pub fn to_config_string(&self) -> String {
+        let mut out = String::new();
+        for (prefix, gas) in [("bonding_curve", &self.bonding_curve), ("dex", &self.dex)] {
+            for operation in Operation::ALL {
+                out.push_str(&format!(
+                    "{}.{}={}\n",
+                    prefix,
+                    operation.key(),
+                    gas.get(operation)
+                ));
+            }
         }
+        out
+    }
+
+    /// Parse a table written by [`GasTable::to_config_string`]
+    /// Missing entries keep their baseline value
+    pub fn from_config_str(input: &str) -> Result<Self> {
+        let mut table = BASELINE_GAS_TABLE;
+
+        for line in input
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        {
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid gas table line: {}", line))?;
+            let (prefix, op_key) = key
+                .trim()
+                .split_once('.')
+                .ok_or_else(|| anyhow::anyhow!("Invalid gas table key: {}", key))?;
+            let operation = Operation::ALL
+                .into_iter()
+                .find(|op| op.key() == op_key)
+                .ok_or_else(|| anyhow::anyhow!("Unknown operation: {}", op_key))?;
+            let gas: u64 = value.trim().parse()?;
+
+            match prefix {
+                "bonding_curve" => table.bonding_curve.set(operation, gas),
+                "dex" => table.dex.set(operation, gas),
+                _ => return Err(anyhow::anyhow!("Unknown router: {}", prefix)),
+            }
+        }
+
+        Ok(table)
+    }
+
+    /// Persist the table to a file
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, self.to_config_string())?;
+        Ok(())
+    }
+
+    /// Load a table previously written with [`GasTable::save`]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        Self::from_config_str(&std::fs::read_to_string(path)?)
+    }
+}
+
+static DEFAULT_GAS_TABLE: RwLock<GasTable> = RwLock::new(BASELINE_GAS_TABLE);
+
+/// Current process-wide default gas table
+pub fn default_gas_table() -> GasTable {
+    *DEFAULT_GAS_TABLE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Replace the process-wide default gas table (e.g. with a calibrated one)
+pub fn set_default_gas_table(table: GasTable) {
+    *DEFAULT_GAS_TABLE.write().unwrap_or_else(|e| e.into_inner()) = table;
+}
+
+/// Default gas limit for an operation on the given router
+pub fn get_default_gas_limit(router: &Router, operation: Operation) -> u64 {
+    default_gas_table().get(router, operation)
+}
+
+/// Outcome of a gas calibration run
+#[derive(Debug, Clone)]
+pub struct GasCalibration {
+    /// Router the token currently trades on
+    pub router: Router,
+    /// Raw estimates for operations that could be estimated
+    pub measured: Vec<(Operation, u64)>,
+    /// Operations that could not be estimated, with the reason
+    pub skipped: Vec<(Operation, String)>,
+    /// Default table updated with the buffered measurements
+    pub table: GasTable,
+}
+
+impl GasCalibration {
+    /// Install the calibrated table as the process-wide default
+    pub fn apply(&self) {
+        set_default_gas_table(self.table);
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_table_round_trip() {
+        let mut table = GasTable::default();
+        let router = Router::Dex(Address::ZERO);
+        table.set(&router, Operation::SellPermit, 123_456);
+
+        let parsed = GasTable::from_config_str(&table.to_config_string()).unwrap();
+        assert_eq!(parsed, table);
+        assert_eq!(parsed.get(&router, Operation::SellPermit), 123_456);
+    }
+
+    #[test]
+    fn test_gas_config_buffer() {
+        let config = GasConfig::default().with_buffer_pct(25);
+        assert_eq!(config.apply_buffer(200_000), 250_000);
+        assert_eq!(config.apply_buffer(u64::MAX), u64::MAX);
+        assert_eq!(config.apply_buffer(99), 123);
+    }
+
+    #[test]
+    fn test_trade_operation_transaction() {
+        use crate::contracts::bonding_curve::IBondingCurveRouter;
+
+        let router = Router::BondingCurve(Address::repeat_byte(0x01));
+        let operation = TradeOperation::SellPermit {
+            token: Address::repeat_byte(0x02),
+            amount_in: U256::from(100),
+            amount_out_min: U256::from(1),
+            to: Address::repeat_byte(0x03),
+            deadline: U256::from(1_000),
+            amount_allowance: U256::from(250),
+            v: 27,
+            r: [0x04; 32],
+            s: [0x05; 32],
+        };
+
+        let tx = operation.transaction(&router);
+        assert_eq!(tx.value, Some(U256::ZERO));
+        let input = tx.input.input().unwrap();
+        let call = IBondingCurveRouter::sellPermitCall::abi_decode(input).unwrap();
+        assert_eq!(call.params.amountAllowance, U256::from(250));
+        assert_eq!(
+            operation.default_gas_limit(&router),
+            get_default_gas_limit(&router, Operation::SellPermit)
+        );
+    }
+
+    #[test]
+    fn test_gas_table_rejects_unknown_keys() {
+        assert!(GasTable::from_config_str("bonding_curve.mint=1").is_err());
+        assert!(GasTable::from_config_str("pool.buy=1").is_err());
+    }
+}
@@ -0,0 +1,328 @@
+//! Observed gas usage of past router trades
+//!
+//! The default [`GasTable`] limits come from contract tests. A
+//! [`GasUsageScanner`] looks at what trades actually used on chain: it takes the
+//! transactions behind indexed events, fetches their receipts in concurrent
+//! batches, classifies each one by router and operation from its calldata and
+//! reports the distribution per operation:
+//!
+//! ```rust,ignore
+//! let scanner = GasUsageScanner::new(provider.clone(), &ChainConfig::default());
+//! let report = scanner.scan(from_block, to_block).await?;
+//! let swaps = swap_indexer.fetch_events(from_block, to_block).await?;
+//! let report = report.merge(scanner.collect_events(&swaps).await?);
+//!
+//! if let Some(stats) = report.stats(RouterKind::BondingCurve, Operation::Buy) {
+//!     println!("curve buys: p50 {} p99 {} max {}", stats.p50, stats.p99, stats.max);
+//! }
+//! let table = report.suggested_table(0.99, &GasConfig::default());
+//! ```
+//!
+//! Transactions that didn't call a router directly (aggregators, other
+//! contracts) are counted as skipped.
+
+use crate::{
+    constants::ChainConfig,
+    stream::{CurveIndexer, TransactionEvent},
+    trading::{
+        calldata::decode_router_calldata,
+        gas::{GasConfig, GasTable, Operation, default_gas_table},
+    },
+    types::{EventType, Router, RouterKind},
+};
+use alloy::{consensus::Transaction, primitives::B256, providers::Provider};
+use anyhow::Result;
+use futures_util::future::try_join_all;
+use std::{collections::HashSet, sync::Arc};
+
+/// Receipts fetched concurrently per batch by default
+pub const DEFAULT_RECEIPT_CONCURRENCY: usize = 16;
+
+/// Gas used by one router transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasSample {
+    pub transaction_hash: B256,
+    pub block_number: u64,
+    pub router: Router,
+    pub operation: Operation,
+    pub gas_used: u64,
+    /// Gas limit the sender set
+    pub gas_limit: u64,
+    /// False if the transaction reverted
+    pub status: bool,
+}
+
+/// Distribution of gas used over a set of transactions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasStats {
+    pub count: usize,
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl GasStats {
+    /// Statistics of `values`, None if empty
+    pub fn from_values(values: &[u64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_unstable();
+
+        Some(Self {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().map(|&v| v as f64).sum::<f64>() / sorted.len() as f64,
+            p50: percentile(&sorted, 0.5),
+            p90: percentile(&sorted, 0.9),
+            p99: percentile(&sorted, 0.99),
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`
+fn percentile(sorted: &[u64], q: f64) -> u64 {
+    let rank = (q.clamp(0.0, 1.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.saturating_sub(1).min(sorted.len() - 1)]
+}
+
+/// Gas samples collected by a [`GasUsageScanner`]
+#[derive(Debug, Clone, Default)]
+pub struct GasUsageReport {
+    pub samples: Vec<GasSample>,
+    /// Transactions that weren't a direct router trade
+    pub skipped: usize,
+}
+
+impl GasUsageReport {
+    /// Gas used by successful `operation`s on routers of `kind`
+    pub fn stats(&self, kind: RouterKind, operation: Operation) -> Option<GasStats> {
+        let values: Vec<u64> = self
+            .samples
+            .iter()
+            .filter(|s| s.status && s.router.kind() == kind && s.operation == operation)
+            .map(|s| s.gas_used)
+            .collect();
+        GasStats::from_values(&values)
+    }
+
+    /// Statistics for every router kind and operation with samples
+    pub fn by_operation(&self) -> Vec<(RouterKind, Operation, GasStats)> {
+        [RouterKind::BondingCurve, RouterKind::Dex]
+            .into_iter()
+            .flat_map(|kind| Operation::ALL.into_iter().map(move |op| (kind, op)))
+            .filter_map(|(kind, op)| self.stats(kind, op).map(|stats| (kind, op, stats)))
+            .collect()
+    }
+
+    /// Reverted transactions, e.g. to spot limits set too low
+    pub fn reverted(&self) -> impl Iterator<Item = &GasSample> {
+        self.samples.iter().filter(|s| !s.status)
+    }
+
+    /// Current default table with every sampled entry replaced by the
+    /// `quantile` of its gas used, buffered by `config`
+    pub fn suggested_table(&self, quantile: f64, config: &GasConfig) -> GasTable {
+        let mut table = default_gas_table();
+        for (kind, operation, _) in self.by_operation() {
+            let mut values: Vec<u64> = self
+                .samples
+                .iter()
+                .filter(|s| s.status && s.router.kind() == kind && s.operation == operation)
+                .map(|s| s.gas_used)
+                .collect();
+            values.sort_unstable();
+            let gas = config.apply_buffer(percentile(&values, quantile));
+            let router = match kind {
+                RouterKind::BondingCurve => &mut table.bonding_curve,
+                RouterKind::Dex => &mut table.dex,
+            };
+            router.set(operation, gas);
+        }
+        table
+    }
+
+    /// Combine with another report, dropping repeated transactions
+    pub fn merge(mut self, other: GasUsageReport) -> Self {
+        let mut seen: HashSet<B256> = self.samples.iter().map(|s| s.transaction_hash).collect();
+        self.samples.extend(
+            other
+                .samples
+                .into_iter()
+                .filter(|s| seen.insert(s.transaction_hash)),
+        );
+        self.skipped += other.skipped;
+        self
+    }
+}
+
+/// Collects gas usage of past router transactions
+pub struct GasUsageScanner<P> {
+    provider: Arc<P>,
+    routers: Vec<Router>,
+    concurrency: usize,
+}
+
+impl<P: Provider + Clone> GasUsageScanner<P> {
+    /// Classify transactions sent to the configured bonding curve and DEX routers
+    pub fn new(provider: Arc<P>, config: &ChainConfig) -> Self {
+        Self {
+            provider,
+            routers: vec![
+                Router::BondingCurve(config.bonding_curve_router),
+                Router::Dex(config.dex_router),
+            ],
+            concurrency: DEFAULT_RECEIPT_CONCURRENCY,
+        }
+    }
+
+    /// Also classify transactions sent to `router` (e.g. an older deployment)
+    pub fn with_router(mut self, router: Router) -> Self {
+        self.routers.push(router);
+        self
+    }
+
+    /// Receipts fetched concurrently per batch
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Gas usage of curve Buy/Sell transactions in a block range
+    pub async fn scan(&self, from_block: u64, to_block: u64) -> Result<GasUsageReport> {
+        let events = CurveIndexer::new(self.provider.clone())
+            .fetch_events(
+                from_block,
+                to_block,
+                vec![EventType::Buy, EventType::Sell],
+                None,
+            )
+            .await?;
+        self.collect_events(&events).await
+    }
+
+    /// Gas usage of the transactions behind indexed events (curve events or swaps)
+    pub async fn collect_events<T: TransactionEvent>(
+        &self,
+        events: &[T],
+    ) -> Result<GasUsageReport> {
+        let hashes: Vec<B256> = events.iter().map(|e| e.transaction_hash()).collect();
+        self.collect(&hashes).await
+    }
+
+    /// Gas usage of `tx_hashes`, each distinct hash fetched once
+    pub async fn collect(&self, tx_hashes: &[B256]) -> Result<GasUsageReport> {
+        let mut seen = HashSet::new();
+        let unique: Vec<B256> = tx_hashes
+            .iter()
+            .copied()
+            .filter(|hash| seen.insert(*hash))
+            .collect();
+
+        let mut report = GasUsageReport::default();
+        for chunk in unique.chunks(self.concurrency) {
+            let samples = try_join_all(chunk.iter().map(|&hash| self.sample(hash))).await?;
+            for sample in samples {
+                match sample {
+                    Some(sample) => report.samples.push(sample),
+                    None => report.skipped += 1,
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Gas sample of one transaction, None if it isn't a direct router trade
+    pub async fn sample(&self, tx_hash: B256) -> Result<Option<GasSample>> {
+        let (tx, receipt) = tokio::try_join!(
+            async { Ok::<_, anyhow::Error>(self.provider.get_transaction_by_hash(tx_hash).await?) },
+            async { Ok::<_, anyhow::Error>(self.provider.get_transaction_receipt(tx_hash).await?) },
+        )?;
+        let (Some(tx), Some(receipt)) = (tx, receipt) else {
+            return Err(anyhow::anyhow!("Transaction not found: {}", tx_hash));
+        };
+
+        let Some(router) = tx
+            .to()
+            .and_then(|to| self.routers.iter().find(|r| r.address() == to))
+        else {
+            return Ok(None);
+        };
+        let Ok(call) = decode_router_calldata(tx.input()) else {
+            return Ok(None);
+        };
+
+        Ok(Some(GasSample {
+            transaction_hash: tx_hash,
+            block_number: receipt.block_number.unwrap_or_default(),
+            router: router.clone(),
+            operation: call.into_operation(tx.value()).operation(),
+            gas_used: receipt.gas_used,
+            gas_limit: tx.gas_limit(),
+            status: receipt.status(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::Address;
+
+    fn sample(kind: RouterKind, operation: Operation, gas_used: u64, status: bool) -> GasSample {
+        let router = match kind {
+            RouterKind::BondingCurve => Router::BondingCurve(Address::ZERO),
+            RouterKind::Dex => Router::Dex(Address::ZERO),
+        };
+        GasSample {
+            transaction_hash: B256::left_padding_from(&gas_used.to_be_bytes()),
+            block_number: 1,
+            router,
+            operation,
+            gas_used,
+            gas_limit: gas_used * 2,
+            status,
+        }
+    }
+
+    #[test]
+    fn test_gas_usage_report_stats() {
+        let mut report = GasUsageReport::default();
+        for gas in (1..=100).map(|i| 100_000 + i * 1_000) {
+            report
+                .samples
+                .push(sample(RouterKind::BondingCurve, Operation::Buy, gas, true));
+        }
+        report.samples.push(sample(
+            RouterKind::BondingCurve,
+            Operation::Buy,
+            999_999,
+            false,
+        ));
+
+        let stats = report
+            .stats(RouterKind::BondingCurve, Operation::Buy)
+            .unwrap();
+        assert_eq!(stats.count, 100);
+        assert_eq!((stats.min, stats.max), (101_000, 200_000));
+        assert_eq!(
+            (stats.p50, stats.p90, stats.p99),
+            (150_000, 190_000, 199_000)
+        );
+        assert!(report.stats(RouterKind::Dex, Operation::Buy).is_none());
+        assert_eq!(report.reverted().count(), 1);
+
+        let table = report.suggested_table(0.99, &GasConfig::default().with_buffer_pct(10));
+        let curve = Router::BondingCurve(Address::ZERO);
+        assert_eq!(table.get(&curve, Operation::Buy), 218_900);
+        assert_eq!(
+            table.get(&curve, Operation::Sell),
+            default_gas_table().get(&curve, Operation::Sell)
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! What-if simulation of a token's graduation to the DEX
+//!
+//! A curve graduates once buyers have taken every token above
+//! `target_token_amount`. The real MON raised and the `target_token_amount`
+//! tokens left on the curve then seed the token's WMON pool. Holders deciding
+//! whether to sell before or after that want to know the listing price and what
+//! their position fetches on each side:
+//!
+//! ```rust,ignore
+//! let holdings = token_helper.balance_of(token, wallet).await?;
+//! let sim = trade
+//!     .simulate_graduation(token, holdings, &GraduationAssumptions::default())
+//!     .await?;
+//!
+//! println!("{} MON more buying graduates it", format_ether(sim.mon_to_graduation));
+//! println!("sell now: {} MON, after listing: {} MON",
+//!     format_ether(sim.sell_now), format_ether(sim.sell_after_listing));
+//! ```
+//!
+//! This is an estimate: it assumes the pool is seeded at full range with all
+//! raised MON minus [`GraduationAssumptions::listing_fee`], and that nobody
+//! else trades between now and the sale after listing.
+
+use crate::{oracle::curve_price, trading::Trade, types::CurveState};
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+
+const MAX_BPS: u64 = 10_000;
+
+/// Fees used by [`simulate_graduation`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraduationAssumptions {
+    /// MON withheld from the raised reserve when the pool is seeded
+    pub listing_fee: U256,
+    /// Bonding curve trading fee in basis points
+    pub curve_fee_bps: u64,
+    /// Pool swap fee in basis points (the default 1% tier)
+    pub pool_fee_bps: u64,
+}
+
+impl Default for GraduationAssumptions {
+    fn default() -> Self {
+        Self {
+            listing_fee: U256::ZERO,
+            curve_fee_bps: 100,
+            pool_fee_bps: 100,
+        }
+    }
+}
+
+impl GraduationAssumptions {
+    pub fn with_listing_fee(mut self, listing_fee: U256) -> Self {
+        self.listing_fee = listing_fee;
+        self
+    }
+
+    pub fn with_curve_fee_bps(mut self, bps: u64) -> Self {
+        self.curve_fee_bps = bps.min(MAX_BPS - 1);
+        self
+    }
+
+    pub fn with_pool_fee_bps(mut self, bps: u64) -> Self {
+        self.pool_fee_bps = bps.min(MAX_BPS - 1);
+        self
+    }
+}
+
+/// Estimated state at graduation and the value of a position on both sides of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraduationSimulation {
+    /// Tokens still to be bought from the curve
+    pub tokens_to_graduation: U256,
+    /// MON buyers still have to spend, fee included
+    pub mon_to_graduation: U256,
+    /// Curve price now, MON per token
+    pub current_price: f64,
+    /// Curve price at the last buy before graduation
+    pub graduation_price: f64,
+    /// MON and tokens the pool is seeded with
+    pub pool_mon: U256,
+    pub pool_tokens: U256,
+    /// Pool price right after listing, MON per token
+    pub listing_price: f64,
+    pub holdings: U256,
+    /// MON from selling the holdings on the curve now, after fees
+    pub sell_now: U256,
+    /// MON from selling the holdings into the fresh pool, after fees
+    pub sell_after_listing: U256,
+    /// Holdings at the listing price, before price impact
+    pub value_at_listing: U256,
+}
+
+impl GraduationSimulation {
+    /// True if selling after listing is estimated to return more than selling now
+    pub fn better_after_listing(&self) -> bool {
+        self.sell_after_listing > self.sell_now
+    }
+
+    /// Listing price relative to the current curve price, in percent
+    pub fn listing_price_change_pct(&self) -> f64 {
+        if self.current_price == 0.0 {
+            return 0.0;
+        }
+        (self.listing_price / self.current_price - 1.0) * 100.0
+    }
+}
+
+/// Simulate graduation of a curve in `state` for a position of `holdings` tokens
+pub fn simulate_graduation(
+    state: &CurveState,
+    holdings: U256,
+    assumptions: &GraduationAssumptions,
+) -> Result<GraduationSimulation> {
+    let vm = state.virtual_mon_reserve;
+    let vt = state.virtual_token_reserve;
+    if vm.is_zero() || vt.is_zero() {
+        return Err(anyhow::anyhow!(
+            "Curve has no reserves; the token may already be listed"
+        ));
+    }
+    let k = if state.k.is_zero() { vm * vt } else { state.k };
+
+    let tokens_to_graduation = state
+        .real_token_reserve
+        .saturating_sub(state.target_token_amount);
+    let graduation_vt = vt
+        .checked_sub(tokens_to_graduation)
+        .ok_or_else(|| anyhow::anyhow!("Virtual token reserve is below the tokens left to sell"))?;
+    if graduation_vt.is_zero() {
+        return Err(anyhow::anyhow!(
+            "Curve would sell its whole virtual reserve"
+        ));
+    }
+    let graduation_vm = k.div_ceil(graduation_vt);
+    let mon_in = graduation_vm.saturating_sub(vm);
+
+    let pool_mon = (state.real_mon_reserve + mon_in).saturating_sub(assumptions.listing_fee);
+    let pool_tokens = state.target_token_amount;
+
+    let sell_now = if holdings.is_zero() {
+        U256::ZERO
+    } else {
+        let out = vm.saturating_sub(k.div_ceil(vt + holdings));
+        less_bps(out, assumptions.curve_fee_bps)
+    };
+
+    Ok(GraduationSimulation {
+        tokens_to_graduation,
+        mon_to_graduation: with_bps(mon_in, assumptions.curve_fee_bps),
+        current_price: curve_price(vm, vt),
+        graduation_price: curve_price(graduation_vm, graduation_vt),
+        pool_mon,
+        pool_tokens,
+        listing_price: curve_price(pool_mon, pool_tokens),
+        holdings,
+        sell_now,
+        sell_after_listing: constant_product_out(
+            holdings,
+            pool_tokens,
+            pool_mon,
+            assumptions.pool_fee_bps,
+        ),
+        value_at_listing: if pool_tokens.is_zero() {
+            U256::ZERO
+        } else {
+            holdings * pool_mon / pool_tokens
+        },
+    })
+}
+
+/// `amount` minus `bps` of it
+fn less_bps(amount: U256, bps: u64) -> U256 {
+    amount * U256::from(MAX_BPS - bps) / U256::from(MAX_BPS)
+}
+
+/// Gross amount that leaves `net` after a fee of `bps`
+fn with_bps(net: U256, bps: u64) -> U256 {
+    (net * U256::from(MAX_BPS)).div_ceil(U256::from(MAX_BPS - bps))
+}
+
+/// Output of swapping `amount_in` into a full-range pool, fee taken from the input
+fn constant_product_out(
+    amount_in: U256,
+    reserve_in: U256,
+    reserve_out: U256,
+    fee_bps: u64,
+) -> U256 {
+    let amount_in = less_bps(amount_in, fee_bps);
+    let denominator = reserve_in + amount_in;
+    if denominator.is_zero() {
+        return U256::ZERO;
+    }
+    reserve_out * amount_in / denominator
+}
+
+impl Trade {
+    /// [`simulate_graduation`] against the current curve state of `token`
+    pub async fn simulate_graduation(
+        &self,
+        token: Address,
+        holdings: U256,
+        assumptions: &GraduationAssumptions,
+    ) -> Result<GraduationSimulation> {
+        let state = self.get_curve_state(token).await?;
+        simulate_graduation(&state, holdings, assumptions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ether(amount: u64) -> U256 {
+        U256::from(amount) * U256::from(10u64).pow(U256::from(18))
+    }
+
+    #[test]
+    fn test_simulate_graduation() {
+        // 1B virtual tokens against 100 virtual MON; graduates at 200M tokens left
+        let state = CurveState {
+            real_mon_reserve: ether(20),
+            real_token_reserve: ether(400_000_000),
+            virtual_mon_reserve: ether(125),
+            virtual_token_reserve: ether(800_000_000),
+            k: ether(125) * ether(800_000_000),
+            target_token_amount: ether(200_000_000),
+            init_virtual_mon_reserve: ether(100),
+            init_virtual_token_reserve: ether(1_000_000_000),
+        };
+        let no_fees = GraduationAssumptions::default()
+            .with_curve_fee_bps(0)
+            .with_pool_fee_bps(0);
+        let sim = simulate_graduation(&state, ether(10_000_000), &no_fees).unwrap();
+
+        assert_eq!(sim.tokens_to_graduation, ether(200_000_000));
+        // k / 600M tokens = 166.67 MON virtual, so 41.67 MON more
+        assert_eq!(
+            sim.mon_to_graduation,
+            U256::from(41_666_666_666_666_666_667u128)
+        );
+        assert_eq!(sim.pool_tokens, ether(200_000_000));
+        assert_eq!(sim.pool_mon, ether(20) + sim.mon_to_graduation);
+        assert!(sim.listing_price > sim.current_price);
+        assert!(sim.better_after_listing());
+        assert!(sim.value_at_listing > sim.sell_after_listing);
+
+        let fees =
+            simulate_graduation(&state, ether(10_000_000), &GraduationAssumptions::default())
+                .unwrap();
+        assert!(fees.sell_now < sim.sell_now);
+        assert!(fees.mon_to_graduation > sim.mon_to_graduation);
+
+        let listed = CurveState {
+            virtual_mon_reserve: U256::ZERO,
+            ..state
+        };
+        assert!(simulate_graduation(&listed, U256::ZERO, &no_fees).is_err());
+    }
+}
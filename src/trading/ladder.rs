@@ -0,0 +1,174 @@
+//! Quote ladders: depth views over a range of order sizes
+//!
+//! Market makers want the output of many sizes at once, like the levels of an
+//! order book. [`Trade::quote_ladder`] quotes every size against the same block,
+//! concurrently, so the rungs are consistent with each other:
+//!
+//! ```rust,ignore
+//! let sizes: Vec<U256> = [1u64, 5, 10, 50, 100].map(|mon| U256::from(mon) * U256::from(10).pow(U256::from(18))).to_vec();
+//! let ladder = trade.quote_ladder(token, &sizes, true).await?;
+//!
+//! for (quote, marginal) in ladder.quotes.iter().zip(ladder.marginal_prices()) {
+//!     println!("{} -> {} (level price {:.9})", quote.amount_in, quote.amount_out, marginal);
+//! }
+//! ```
+//!
+//! Over a batching provider ([`Trade::new_with_batching`]) the whole ladder
+//! goes out as one JSON-RPC batch instead of one request per size.
+
+use crate::{
+    trading::{Trade, fallback::is_revert},
+    types::Router,
+};
+use alloy::{
+    eips::BlockId,
+    primitives::{Address, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use futures_util::future::join_all;
+
+/// Quote for one size of a ladder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LadderQuote {
+    pub amount_in: U256,
+    pub amount_out: U256,
+    /// Router the lens picked for this size
+    pub router: Router,
+}
+
+/// Quotes for a range of sizes, all read at one block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteLadder {
+    pub token: Address,
+    pub is_buy: bool,
+    pub block: u64,
+    /// Quoted sizes, in the order requested
+    pub quotes: Vec<LadderQuote>,
+    /// Sizes the lens reverted on (e.g. larger than the remaining curve)
+    pub unquoted: Vec<U256>,
+}
+
+impl QuoteLadder {
+    /// Average price of each rung in MON per token (raw units)
+    pub fn average_prices(&self) -> Vec<f64> {
+        self.quotes
+            .iter()
+            .map(|q| price(self.is_buy, q.amount_in, q.amount_out))
+            .collect()
+    }
+
+    /// Price of the increment from the previous rung to each rung, MON per token
+    ///
+    /// The first rung's increment starts at zero. Assumes sizes are ascending.
+    pub fn marginal_prices(&self) -> Vec<f64> {
+        let mut previous = (U256::ZERO, U256::ZERO);
+        self.quotes
+            .iter()
+            .map(|q| {
+                let step_in = q.amount_in.saturating_sub(previous.0);
+                let step_out = q.amount_out.saturating_sub(previous.1);
+                previous = (q.amount_in, q.amount_out);
+                price(self.is_buy, step_in, step_out)
+            })
+            .collect()
+    }
+
+    /// True if the lens routed every size through the same router
+    pub fn single_route(&self) -> bool {
+        self.quotes.windows(2).all(|w| w[0].router == w[1].router)
+    }
+}
+
+/// MON per token for a buy (MON in) or a sell (tokens in); zero on empty amounts
+fn price(is_buy: bool, amount_in: U256, amount_out: U256) -> f64 {
+    let (mon, tokens) = if is_buy {
+        (amount_in, amount_out)
+    } else {
+        (amount_out, amount_in)
+    };
+    if mon.is_zero() || tokens.is_zero() {
+        return 0.0;
+    }
+    f64::from(mon) / f64::from(tokens)
+}
+
+impl Trade {
+    /// Quote every size in `amounts` at the current block
+    ///
+    /// `amounts` are MON for buys and tokens for sells. Sizes the lens reverts
+    /// on are listed in [`QuoteLadder::unquoted`]; transport errors fail the
+    /// whole ladder.
+    pub async fn quote_ladder(
+        &self,
+        token: Address,
+        amounts: &[U256],
+        is_buy: bool,
+    ) -> Result<QuoteLadder> {
+        let block = self.provider().get_block_number().await?;
+        let at = BlockId::number(block);
+
+        let results = join_all(
+            amounts
+                .iter()
+                .map(|&amount| self.lens().get_amount_out_at(token, amount, is_buy, at)),
+        )
+        .await;
+
+        let mut ladder = QuoteLadder {
+            token,
+            is_buy,
+            block,
+            quotes: Vec::with_capacity(amounts.len()),
+            unquoted: Vec::new(),
+        };
+        for (&amount_in, result) in amounts.iter().zip(results) {
+            match result {
+                Ok((router_address, amount_out)) => ladder.quotes.push(LadderQuote {
+                    amount_in,
+                    amount_out,
+                    router: self.resolve_router(router_address)?,
+                }),
+                Err(e) if is_revert(&e) => ladder.unquoted.push(amount_in),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(ladder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ladder_prices() {
+        let router = Router::BondingCurve(Address::ZERO);
+        let quote = |amount_in: u64, amount_out: u64| LadderQuote {
+            amount_in: U256::from(amount_in),
+            amount_out: U256::from(amount_out),
+            router: router.clone(),
+        };
+        let ladder = QuoteLadder {
+            token: Address::ZERO,
+            is_buy: true,
+            block: 1,
+            quotes: vec![quote(10, 100), quote(20, 180), quote(40, 300)],
+            unquoted: vec![],
+        };
+
+        assert_eq!(
+            ladder.average_prices(),
+            vec![0.1, 20.0 / 180.0, 40.0 / 300.0]
+        );
+        assert_eq!(ladder.marginal_prices(), vec![0.1, 0.125, 20.0 / 120.0]);
+        assert!(ladder.single_route());
+
+        let sells = QuoteLadder {
+            is_buy: false,
+            quotes: vec![quote(100, 10)],
+            ..ladder
+        };
+        assert_eq!(sells.average_prices(), vec![0.1]);
+    }
+}
@@ -0,0 +1,122 @@
+//! Per-token serialization of trades
+//!
+//! Two tasks selling the same token from one wallet race: both quote the same
+//! balance and the second sell reverts. With [`TokenLocks`] attached to a
+//! [`Trade`](crate::Trade), buys and sells of the same token wait for each
+//! other while trades of different tokens still run concurrently:
+//!
+//! ```rust,ignore
+//! let locks = TokenLocks::new();
+//! let trade = Trade::new(rpc_url, private_key).await?.with_token_locks(locks.clone());
+//!
+//! // Strategy A and B can both call trade.sell(..) for `token`; the second
+//! // sell is only sent after the first one's receipt came back.
+//! ```
+//!
+//! Clones share their locks, so one `TokenLocks` can guard several `Trade`
+//! instances signing for the same wallet. Waiters are served in FIFO order.
+
+use alloy::primitives::Address;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Async locks keyed by token address
+#[derive(Debug, Clone, Default)]
+pub struct TokenLocks {
+    locks: Arc<Mutex<HashMap<Address, Arc<AsyncMutex<()>>>>>,
+}
+
+/// Exclusive access to trading one token, released on drop
+#[derive(Debug)]
+pub struct TokenLockGuard {
+    token: Address,
+    _guard: OwnedMutexGuard<()>,
+}
+
+impl TokenLockGuard {
+    pub fn token(&self) -> Address {
+        self.token
+    }
+}
+
+impl TokenLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait until no other holder trades `token`
+    ///
+    /// [`Trade::buy`](crate::Trade::buy) and [`Trade::sell`](crate::Trade::sell)
+    /// take this lock themselves; holding the guard while calling them on the
+    /// same `Trade` deadlocks. Use it to serialize custom flows (e.g. quote,
+    /// approve and send) with another `TokenLocks` clone.
+    pub async fn lock(&self, token: Address) -> TokenLockGuard {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            // Drop entries nobody holds or waits for, keeping the map small
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            locks.entry(token).or_default().clone()
+        };
+
+        TokenLockGuard {
+            token,
+            _guard: lock.lock_owned().await,
+        }
+    }
+
+    /// Lock `token` only if no one else holds it
+    pub fn try_lock(&self, token: Address) -> Option<TokenLockGuard> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+            locks.entry(token).or_default().clone()
+        };
+
+        lock.try_lock_owned().ok().map(|guard| TokenLockGuard {
+            token,
+            _guard: guard,
+        })
+    }
+
+    /// True while a trade of `token` holds its lock
+    pub fn is_locked(&self, token: Address) -> bool {
+        let locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks
+            .get(&token)
+            .is_some_and(|lock| lock.try_lock().is_err())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_token_serializes_other_tokens_do_not() {
+        let locks = TokenLocks::new();
+        let token_a = Address::repeat_byte(0xaa);
+        let token_b = Address::repeat_byte(0xbb);
+
+        let held = locks.lock(token_a).await;
+        assert!(locks.is_locked(token_a));
+        assert!(locks.try_lock(token_a).is_none());
+
+        // A different token is not blocked
+        let other = tokio::time::timeout(Duration::from_millis(50), locks.lock(token_b)).await;
+        assert!(other.is_ok());
+
+        // The same token waits until the first guard is dropped
+        let waiter = {
+            let locks = locks.clone();
+            tokio::spawn(async move { locks.lock(token_a).await.token() })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(held);
+        assert_eq!(waiter.await.unwrap(), token_a);
+        assert!(!locks.is_locked(token_a));
+    }
+}
@@ -53,6 +53,16 @@
 //! };
 //!
 //! let result = trade.buy(buy_params, router).await?;
+//!
+//! // Or let the fluent builder quote and fill everything
+//! let result = trade
+//!     .buy_order(token)
+//!     .amount(mon_amount)
+//!     .slippage_bps(500)
+//!     .deadline_in(60)
+//!     .gas_auto()
+//!     .send()
+//!     .await?;
 //! ```
 //!
 //! ## Advanced Features
@@ -61,6 +71,13 @@
 //! - **Gas Estimation**: Built-in gas estimation with safety margins
 //! - **Deadline Management**: Automatic deadline calculation for time-sensitive trades
 //! - **Error Handling**: Comprehensive error types for different failure scenarios
+//! - **Recipient Safety**: buys into another wallet need [`Trade::buy_to`] or an explicit opt-in
+//! - **Sell Planning**: [`Trade::plan_sell`] picks approve + sell or sellPermit from allowance, permit support and gas
+//! - **Arbitrage Detection**: [`ArbWatcher`] compares curve and DEX quotes around graduation
+//! - **Token Locks**: [`TokenLocks`] serializes concurrent trades of the same token
+//! - **Signer Rotation**: [`MultiWalletTrader`] spreads buys over several keys with spend limits
+//! - **Replacement**: [`Trade::speed_up`] and [`Trade::cancel`] replace a stuck transaction
+//! - **Risk Limits**: [`RiskManager`] caps per-trade and daily spend and open positions
 
 /// Core trading interface and execution logic
 pub mod trade;
@@ -71,8 +88,98 @@ pub mod utils;
 /// Default gas limits for trading operations based on contract testing
 pub mod gas;
 
+/// Observed gas usage of past router trades, from receipts
+pub mod gas_usage;
+
+/// Fluent order builder (`trade.buy_order(token).amount(..).send()`)
+pub mod builder;
+
+/// Per-instance slippage, deadline and gas defaults
+pub mod options;
+
+/// Per-token async locks serializing trades of the same token
+pub mod locks;
+
+/// Allowance-aware sell planning (approve + sell vs sellPermit)
+pub mod planner;
+
+/// Recipient checks for buying into another wallet
+pub mod recipient;
+
+/// Arbitrage detection between bonding curve and DEX pool prices
+pub mod arb;
+
+/// Router calldata encoding and decoding for external signers
+pub mod calldata;
+
+/// Safe multisig transaction payloads for router trades
+pub mod safe;
+
+/// Router version and ABI feature detection
+pub mod version;
+
+/// Sell-or-burn cleanup of dust token balances
+pub mod dust;
+
+/// Reorg-safe confirmation of mined transactions
+pub mod confirm;
+
+/// Buy/sell that retries on the other router when graduation races a trade
+pub mod fallback;
+
+/// Quotes for a range of order sizes at one block
+pub mod ladder;
+
+/// What-if simulation of graduation: listing price and position value
+pub mod graduation;
+
+/// Signer rotation with per-key spend limits across several wallets
+pub mod rotation;
+
+/// Speed-up and cancellation of pending transactions
+pub mod replace;
+
+/// Per-trade, daily and position limits with a journal of refused trades
+pub mod risk;
+
 // Re-export main types for convenience
 pub use trade::Trade;
 pub use crate::types::Router;
-pub use utils::SlippageUtils;
-pub use gas::{estimate_gas, estimate_buy_gas, estimate_sell_gas, estimate_sell_permit_gas, GasEstimationParams};
+pub use utils::{SizingUtils, SlippageSuggestion, SlippageUtils, TxUtils};
+pub use gas::{estimate_gas, estimate_gas_from, estimate_buy_gas, estimate_sell_gas, estimate_sell_permit_gas, GasEstimationParams, TradeOperation};
+pub use gas::{
+    default_gas_table, get_default_gas_limit, set_default_gas_table, GasCalibration, GasConfig,
+    GasTable, Operation, OperationGas, BASELINE_GAS_TABLE,
+};
+pub use gas_usage::{GasSample, GasStats, GasUsageReport, GasUsageScanner};
+pub use builder::{BuiltOrder, GasMode, TradeBuilder, TradeSide};
+pub use options::TradeOptions;
+pub use locks::{TokenLockGuard, TokenLocks};
+pub use planner::{SellExecution, SellPlan};
+pub use calldata::{
+    decode_router_calldata, encode_buy_calldata, encode_sell_calldata,
+    encode_sell_permit_calldata, RouterCall,
+};
+pub use safe::{safe_nonce, SafeOperation, SafeTransaction};
+#[cfg(feature = "safe-service")]
+pub use safe::SafeTxService;
+pub use version::{
+    expected_selectors, probe_router, scan_selectors, RouterInfo, SelectorCheck, SelectorStatus,
+    KNOWN_ROUTER_VERSIONS,
+};
+pub use confirm::{ConfirmedReceipt, Reorged, DEFAULT_CONFIRM_TIMEOUT};
+pub use replace::{
+    Replacement, ReplacementFees, ReplacementOutcome, MIN_REPLACEMENT_BUMP_PERCENT,
+};
+pub use graduation::{simulate_graduation, GraduationAssumptions, GraduationSimulation};
+pub use ladder::{LadderQuote, QuoteLadder};
+pub use risk::{
+    BuyReservation, RiskJournalEntry, RiskLimits, RiskManager, RiskViolation,
+    DEFAULT_RISK_JOURNAL_CAPACITY,
+};
+pub use rotation::{MultiWalletTrader, RotatedTrade, RotationPolicy, DEFAULT_GAS_RESERVE};
+pub use fallback::{is_revert, FallbackConfig, FallbackOutcome, RouteAttempt};
+pub use dust::{dust_action, DustAction, DustCleanupConfig, DustEntry, DustReport};
+pub use arb::{
+    ArbConfig, ArbDirection, ArbEvent, ArbExecution, ArbLimits, ArbOpportunity, ArbWatcher,
+};
@@ -0,0 +1,94 @@
+//! Per-instance trading defaults
+//!
+//! [`TradeOptions`] sets the slippage, deadline and gas policy once, when the
+//! [`Trade`](crate::Trade) is constructed, instead of at every call site:
+//!
+//! ```rust,ignore
+//! let options = TradeOptions {
+//!     default_slippage_bps: 200,
+//!     default_deadline_secs: 60,
+//!     default_gas_strategy: GasMode::Auto,
+//! };
+//! let trade = Trade::new_with_options(rpc_url, private_key, options).await?;
+//!
+//! // 2% slippage, 60s deadline and an estimated gas limit, without saying so
+//! trade.buy_order(token).amount(parse_ether("0.1")?).send().await?;
+//! ```
+
+use crate::trading::builder::{DEFAULT_DEADLINE_SECS, DEFAULT_SLIPPAGE_BPS, GasMode};
+use alloy::primitives::U256;
+use anyhow::Result;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Defaults applied wherever a trade leaves slippage, deadline or gas unset
+///
+/// - Order builders, [`Trade::plan_sell`](crate::Trade::plan_sell) and
+///   [`FallbackConfig::from_options`](crate::trading::FallbackConfig::from_options)
+///   start from these values.
+/// - `BuyParams`/`SellParams` with a zero `deadline` get one
+///   `default_deadline_secs` from now.
+/// - Params with `gas_limit: None` follow `default_gas_strategy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeOptions {
+    /// Slippage tolerance in basis points (100 = 1%)
+    pub default_slippage_bps: u64,
+    /// Deadline offset from now, in seconds
+    pub default_deadline_secs: u64,
+    /// How gas limits are picked when none is given
+    pub default_gas_strategy: GasMode,
+}
+
+impl Default for TradeOptions {
+    fn default() -> Self {
+        Self {
+            default_slippage_bps: DEFAULT_SLIPPAGE_BPS,
+            default_deadline_secs: DEFAULT_DEADLINE_SECS,
+            default_gas_strategy: GasMode::Default,
+        }
+    }
+}
+
+impl TradeOptions {
+    /// Check that the slippage leaves a non-zero minimum output
+    pub fn validate(&self) -> Result<()> {
+        if self.default_slippage_bps >= 10_000 {
+            return Err(anyhow::anyhow!(
+                "Slippage must be below 10000 bps, got {}",
+                self.default_slippage_bps
+            ));
+        }
+        Ok(())
+    }
+
+    /// Absolute deadline `default_deadline_secs` from now
+    pub fn deadline(&self) -> Result<U256> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        Ok(U256::from(now + self.default_deadline_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_options_defaults() {
+        let options = TradeOptions::default();
+        assert_eq!(options.default_slippage_bps, DEFAULT_SLIPPAGE_BPS);
+        assert_eq!(options.default_gas_strategy, GasMode::Default);
+        assert!(options.validate().is_ok());
+
+        let deadline = options.deadline().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert!(deadline >= U256::from(now + DEFAULT_DEADLINE_SECS - 1));
+
+        let invalid = TradeOptions {
+            default_slippage_bps: 10_000,
+            ..options
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
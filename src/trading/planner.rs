@@ -0,0 +1,340 @@
+//! Allowance-aware sell planning
+//!
+//! Selling needs either an existing allowance, an `approve()` first, or a
+//! `sellPermit()` signature. [`Trade::plan_sell`] looks at the wallet's current
+//! allowance, whether the token accepts EIP-2612 permits and the gas of each
+//! path, and returns a [`SellPlan`] that can be inspected before it is executed:
+//!
+//! ```rust,ignore
+//! let plan = trade.plan_sell(token, amount, 500).await?;
+//! println!(
+//!     "{:?}: {} gas, min {} MON",
+//!     plan.path,
+//!     plan.cost.cheapest_gas(),
+//!     plan.amount_out_min
+//! );
+//! for warning in &plan.warnings {
+//!     println!("warning: {}", warning);
+//! }
+//!
+//! if confirm() {
+//!     let execution = trade.execute_sell_plan(&plan).await?;
+//!     println!("sold in {}", execution.sell.transaction_hash);
+//! }
+//! ```
+//!
+//! Fee-on-transfer tokens deliver less than `amount_in` to the router. The
+//! planner simulates the transfer first and, when a fee shows up, quotes the
+//! sale on the amount that arrives and says so in [`SellPlan::warnings`].
+
+use crate::{
+    token::{
+        token::{IToken, estimate_sell_path_cost, probe_permit_support, sign_permit},
+        transfer_fee::{TransferFee, detect_transfer_fee},
+    },
+    trading::{SlippageUtils, Trade},
+    types::{Router, SellParams, SellPath, SellPathCost, SellPermitParams, TransactionResult},
+};
+use alloy::primitives::{Address, U256};
+use anyhow::Result;
+
+/// A quoted sale with the chosen approval path
+#[derive(Debug, Clone)]
+pub struct SellPlan {
+    pub token: Address,
+    pub router: Router,
+    pub amount_in: U256,
+    pub expected_out: U256,
+    pub amount_out_min: U256,
+    /// Absolute deadline; execute the plan before it passes
+    pub deadline: U256,
+    /// Whether the token answered the EIP-2612 probe calls
+    pub permit_supported: bool,
+    pub path: SellPath,
+    pub cost: SellPathCost,
+    /// Simulated transfer to the router, None if the check failed
+    pub transfer_fee: Option<TransferFee>,
+    /// Things to review before executing, e.g. a detected transfer fee
+    pub warnings: Vec<String>,
+}
+
+impl SellPlan {
+    /// True when the plan sends an `approve()` before selling
+    pub fn needs_approval(&self) -> bool {
+        self.path == SellPath::ApproveAndSell
+    }
+
+    /// True when the token keeps part of the transfer to the router
+    pub fn has_transfer_fee(&self) -> bool {
+        self.transfer_fee
+            .is_some_and(|fee| fee.is_fee_on_transfer())
+    }
+}
+
+/// Transactions sent by [`Trade::execute_sell_plan`]
+#[derive(Debug, Clone)]
+pub struct SellExecution {
+    pub path: SellPath,
+    /// Receipt of the approval, for [`SellPath::ApproveAndSell`]
+    pub approval: Option<TransactionResult>,
+    pub sell: TransactionResult,
+}
+
+/// Amount to quote again on when the token keeps part of the transfer
+fn requote_amount(fee: &TransferFee) -> Option<U256> {
+    fee.is_fee_on_transfer().then_some(fee.received)
+}
+
+fn transfer_fee_warning(fee: &TransferFee, amount_in: U256) -> Option<String> {
+    fee.is_fee_on_transfer().then(|| {
+        format!(
+            "Token keeps {} bps per transfer; quoted on the {} received instead of {}",
+            fee.fee_bps, fee.received, amount_in
+        )
+    })
+}
+
+/// Cheapest path, ignoring the permit estimate when the token has no permits
+fn choose_sell_path(cost: &mut SellPathCost, permit_supported: bool) -> SellPath {
+    if !permit_supported {
+        cost.sell_permit_gas = None;
+    }
+    cost.cheapest()
+}
+
+impl Trade {
+    /// Quote a sale of `amount_in` on what the router receives after transfer fees
+    ///
+    /// Simulates the wallet's transfer to the quoted router (see
+    /// [`detect_transfer_fee`]) and, for fee-on-transfer tokens, quotes again on
+    /// the received amount. The wallet must hold `amount_in`.
+    pub async fn quote_sell_after_fee(
+        &self,
+        token: Address,
+        amount_in: U256,
+    ) -> Result<(Router, U256, TransferFee)> {
+        let (router, amount_out) = self.get_amount_out(token, amount_in, false).await?;
+        let fee = detect_transfer_fee(
+            self.provider().as_ref(),
+            token,
+            self.wallet_address(),
+            router.address(),
+            amount_in,
+        )
+        .await?;
+        let Some(received) = requote_amount(&fee) else {
+            return Ok((router, amount_out, fee));
+        };
+
+        let (router, amount_out) = self.get_amount_out(token, received, false).await?;
+        Ok((router, amount_out, fee))
+    }
+
+    /// Quote a sale of `amount_in` tokens and pick the cheapest approval path
+    ///
+    /// Nothing is sent; pass the plan to [`Trade::execute_sell_plan`] to run it.
+    /// The quote accounts for transfer fees when the token charges one.
+    pub async fn plan_sell(
+        &self,
+        token: Address,
+        amount_in: U256,
+        slippage_bps: u64,
+    ) -> Result<SellPlan> {
+        if slippage_bps >= 10_000 {
+            return Err(anyhow::anyhow!(
+                "Slippage must be below 10000 bps, got {}",
+                slippage_bps
+            ));
+        }
+
+        let mut warnings = Vec::new();
+        let (router, expected_out, transfer_fee) =
+            match self.quote_sell_after_fee(token, amount_in).await {
+                Ok((router, expected_out, fee)) => {
+                    warnings.extend(transfer_fee_warning(&fee, amount_in));
+                    (router, expected_out, Some(fee))
+                }
+                Err(e) => {
+                    warnings.push(format!("Could not check for a transfer fee: {}", e));
+                    let (router, expected_out) =
+                        self.get_amount_out(token, amount_in, false).await?;
+                    (router, expected_out, None)
+                }
+            };
+        let amount_out_min =
+            SlippageUtils::calculate_amount_out_min(expected_out, slippage_bps as f64 / 100.0);
+
+        let deadline = self.options().deadline()?;
+
+        let permit_supported = match probe_permit_support(
+            self.provider().as_ref(),
+            token,
+            self.wallet_address(),
+        )
+        .await
+        {
+            Ok(supported) => supported,
+            Err(e) => {
+                warnings.push(format!("Could not check permit support: {}", e));
+                false
+            }
+        };
+
+        let mut cost = estimate_sell_path_cost(
+            self.provider().clone(),
+            self.signer(),
+            &router,
+            token,
+            amount_in,
+            amount_out_min,
+        )
+        .await?;
+        let path = choose_sell_path(&mut cost, permit_supported);
+
+        Ok(SellPlan {
+            token,
+            router,
+            amount_in,
+            expected_out,
+            amount_out_min,
+            deadline,
+            permit_supported,
+            path,
+            cost,
+            transfer_fee,
+            warnings,
+        })
+    }
+
+    /// Execute a plan from [`Trade::plan_sell`]
+    ///
+    /// For [`SellPath::ApproveAndSell`] the approval is awaited before the sale is
+    /// sent; for [`SellPath::SellPermit`] a fresh permit for `amount_in` is signed.
+    pub async fn execute_sell_plan(&self, plan: &SellPlan) -> Result<SellExecution> {
+        let wallet = self.wallet_address();
+        let spender = plan.router.address();
+
+        let approval = if plan.path == SellPath::ApproveAndSell {
+            let contract = IToken::new(plan.token, self.provider().as_ref());
+            let tx = contract.approve(spender, plan.amount_in).send().await?;
+            let result = TransactionResult::from(tx.get_receipt().await?);
+            if !result.status {
+                return Err(anyhow::anyhow!(
+                    "Approval {} reverted",
+                    result.transaction_hash
+                ));
+            }
+            Some(result)
+        } else {
+            None
+        };
+
+        let sell = match plan.path {
+            SellPath::Sell | SellPath::ApproveAndSell => {
+                let params = SellParams {
+                    amount_in: plan.amount_in,
+                    amount_out_min: plan.amount_out_min,
+                    token: plan.token,
+                    to: wallet,
+                    deadline: plan.deadline,
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                };
+                self.sell(params, plan.router.clone()).await?
+            }
+            SellPath::SellPermit => {
+                let (v, r, s) = sign_permit(
+                    self.provider().as_ref(),
+                    self.signer(),
+                    plan.token,
+                    wallet,
+                    spender,
+                    plan.amount_in,
+                    plan.deadline,
+                )
+                .await?;
+                let params = SellPermitParams {
+                    amount_in: plan.amount_in,
+                    amount_out_min: plan.amount_out_min,
+                    amount_allowance: plan.amount_in,
+                    token: plan.token,
+                    to: wallet,
+                    deadline: plan.deadline,
+                    v,
+                    r,
+                    s,
+                    gas_limit: None,
+                    gas_price: None,
+                    nonce: None,
+                };
+                self.sell_permit(params, plan.router.clone()).await?
+            }
+        };
+
+        Ok(SellExecution {
+            path: plan.path,
+            approval,
+            sell,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(approve_gas: Option<u64>, sell_permit_gas: Option<u64>) -> SellPathCost {
+        SellPathCost {
+            allowance: U256::ZERO,
+            approve_gas,
+            sell_gas: 150_000,
+            sell_gas_estimated: true,
+            sell_permit_gas,
+        }
+    }
+
+    #[test]
+    fn test_choose_sell_path() {
+        let mut permit_cheaper = cost(Some(50_000), Some(180_000));
+        assert_eq!(
+            choose_sell_path(&mut permit_cheaper, true),
+            SellPath::SellPermit
+        );
+
+        // The estimate alone doesn't count when the permit probe failed
+        assert_eq!(
+            choose_sell_path(&mut permit_cheaper, false),
+            SellPath::ApproveAndSell
+        );
+        assert_eq!(permit_cheaper.sell_permit_gas, None);
+
+        let mut approved = cost(None, Some(180_000));
+        assert_eq!(choose_sell_path(&mut approved, true), SellPath::Sell);
+        assert_eq!(approved.sell_permit_gas, Some(180_000));
+    }
+
+    #[test]
+    fn test_transfer_fee_requote_and_warning() {
+        let amount = U256::from(1_000u64);
+        let no_fee = TransferFee {
+            token: Address::ZERO,
+            amount,
+            received: amount,
+            fee_bps: 0,
+        };
+        assert_eq!(requote_amount(&no_fee), None);
+        assert_eq!(transfer_fee_warning(&no_fee, amount), None);
+
+        let fee = TransferFee {
+            received: U256::from(950u64),
+            fee_bps: 500,
+            ..no_fee
+        };
+        assert_eq!(requote_amount(&fee), Some(U256::from(950u64)));
+        assert_eq!(
+            transfer_fee_warning(&fee, amount).unwrap(),
+            "Token keeps 500 bps per transfer; quoted on the 950 received instead of 1000"
+        );
+    }
+}
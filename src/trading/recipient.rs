@@ -0,0 +1,109 @@
+//! Buying into a wallet other than the signer
+//!
+//! `BuyParams::to` decides who receives the tokens. A `to` that differs from the
+//! trading wallet is usually a bug (a stale config, a copy-pasted address), so
+//! [`Trade::buy`] rejects it unless third-party recipients are allowed. Buying
+//! into a separate cold wallet on purpose goes through [`Trade::buy_to`]:
+//!
+//! ```rust,ignore
+//! // The hot wallet pays, the cold wallet receives the tokens
+//! let result = trade.buy_to(cold_wallet, buy_params, router).await?;
+//!
+//! // Or with the fluent builder
+//! let result = trade
+//!     .buy_order(token)
+//!     .amount(parse_ether("1")?)
+//!     .to(cold_wallet)
+//!     .allow_third_party_recipient()
+//!     .send()
+//!     .await?;
+//! ```
+
+use crate::{
+    trading::Trade,
+    types::{BuyParams, Router, TransactionResult},
+};
+use alloy::primitives::Address;
+use anyhow::Result;
+
+/// Check a buy recipient against the trading wallet
+///
+/// The zero address is always rejected. Any other recipient besides `wallet`
+/// needs `allow_third_party`.
+pub fn check_recipient(wallet: Address, to: Address, allow_third_party: bool) -> Result<()> {
+    if to == Address::ZERO {
+        return Err(anyhow::anyhow!("Buy recipient is the zero address"));
+    }
+    if to != wallet && !allow_third_party {
+        return Err(anyhow::anyhow!(
+            "Buy recipient {} is not the trading wallet {} (use Trade::buy_to or allow_third_party_recipient)",
+            to,
+            wallet
+        ));
+    }
+    Ok(())
+}
+
+impl Trade {
+    /// Reject recipients that would lose the bought tokens
+    ///
+    /// Fails for the zero address, the token itself, and the nad.fun contracts
+    /// (routers, bonding curve, lens). Contract wallets such as multisigs are
+    /// accepted.
+    pub fn verify_recipient(&self, token: Address, recipient: Address) -> Result<()> {
+        let config = self.chain_config();
+        let protocol = [
+            config.bonding_curve_router,
+            config.dex_router,
+            config.bonding_curve,
+            config.lens,
+        ];
+
+        if recipient == Address::ZERO {
+            Err(anyhow::anyhow!("Buy recipient is the zero address"))
+        } else if recipient == token {
+            Err(anyhow::anyhow!(
+                "Buy recipient {} is the token itself",
+                recipient
+            ))
+        } else if protocol.contains(&recipient) || self.custom_routers().contains_key(&recipient) {
+            Err(anyhow::anyhow!(
+                "Buy recipient {} is a nad.fun contract",
+                recipient
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Buy with the trading wallet paying and `recipient` receiving the tokens
+    ///
+    /// Overrides `params.to` and runs [`Trade::verify_recipient`] first; the
+    /// third-party recipient setting doesn't apply.
+    pub async fn buy_to(
+        &self,
+        recipient: Address,
+        mut params: BuyParams,
+        router: Router,
+    ) -> Result<TransactionResult> {
+        self.verify_recipient(params.token, recipient)?;
+        params.to = recipient;
+        self.submit_buy(params, router).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_recipient() {
+        let wallet = Address::repeat_byte(0x11);
+        let cold = Address::repeat_byte(0x22);
+
+        assert!(check_recipient(wallet, wallet, false).is_ok());
+        assert!(check_recipient(wallet, cold, false).is_err());
+        assert!(check_recipient(wallet, cold, true).is_ok());
+        assert!(check_recipient(wallet, Address::ZERO, true).is_err());
+    }
+}
@@ -0,0 +1,365 @@
+//! Speeding up and cancelling pending transactions
+//!
+//! An underpriced transaction can sit in the mempool for a long time. Sending
+//! another transaction with the same nonce and higher fees replaces it: either
+//! the same call again ([`Trade::speed_up`]) or a zero-value transfer to the
+//! wallet itself ([`Trade::cancel`]). Only one of them can be mined, and
+//! [`Trade::wait_for_replacement`] reports which:
+//!
+//! ```rust,ignore
+//! // `pending` is the hash of a transaction stuck in the mempool
+//! let fees = trade.bumped_fees(pending, 20).await?; // +20%
+//! let replacement = trade.speed_up(pending, fees).await?;
+//! match trade.wait_for_replacement(&replacement, Duration::from_secs(60)).await? {
+//!     ReplacementOutcome::Replacement(result) => println!("sped up: {}", result.transaction_hash),
+//!     ReplacementOutcome::Original(result) => println!("original landed first"),
+//! }
+//! ```
+//!
+//! Nodes only accept a replacement whose fees exceed the pending ones by a
+//! minimum bump, usually 10% of both the max fee and the priority fee.
+
+use crate::{
+    trading::{Trade, TxUtils},
+    types::TransactionResult,
+};
+use alloy::{
+    consensus::Transaction as _,
+    primitives::{Address, B256, U256},
+    providers::Provider,
+    rpc::types::{Transaction, TransactionRequest},
+};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// Minimum bump most nodes require for a replacement, in percent
+pub const MIN_REPLACEMENT_BUMP_PERCENT: u64 = 10;
+
+/// EIP-1559 fees of a replacement transaction, in wei per gas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplacementFees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl ReplacementFees {
+    pub fn new(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Self {
+        Self {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+
+    /// `percent` above the given fees, rounded up
+    ///
+    /// A legacy transaction's gas price is passed as both fees.
+    pub fn bumped(max_fee_per_gas: u128, max_priority_fee_per_gas: u128, percent: u64) -> Self {
+        let bump = |fee: u128| fee.saturating_mul(100 + percent as u128).div_ceil(100);
+        Self {
+            max_fee_per_gas: bump(max_fee_per_gas),
+            max_priority_fee_per_gas: bump(max_priority_fee_per_gas),
+        }
+    }
+
+    /// True if these fees are at least `percent` above `other` on both values
+    pub fn exceeds(&self, other: &ReplacementFees, percent: u64) -> bool {
+        let required = ReplacementFees::bumped(
+            other.max_fee_per_gas,
+            other.max_priority_fee_per_gas,
+            percent,
+        );
+        self.max_fee_per_gas >= required.max_fee_per_gas
+            && self.max_priority_fee_per_gas >= required.max_priority_fee_per_gas
+    }
+}
+
+/// A replacement sent for a pending transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Replacement {
+    /// Hash of the replaced transaction, if known
+    pub original: Option<B256>,
+    pub replacement: B256,
+    pub nonce: u64,
+    /// True for a cancellation (self-transfer), false for a speed-up
+    pub cancel: bool,
+}
+
+/// Which transaction of a [`Replacement`] was mined
+#[derive(Debug, Clone)]
+pub enum ReplacementOutcome {
+    Original(TransactionResult),
+    Replacement(TransactionResult),
+}
+
+impl ReplacementOutcome {
+    pub fn result(&self) -> &TransactionResult {
+        match self {
+            ReplacementOutcome::Original(result) | ReplacementOutcome::Replacement(result) => {
+                result
+            }
+        }
+    }
+
+    pub fn is_replacement(&self) -> bool {
+        matches!(self, ReplacementOutcome::Replacement(_))
+    }
+}
+
+/// `tx` resent with `fees`: same nonce, recipient, calldata, value and gas limit
+fn speed_up_request(tx: Transaction, fees: ReplacementFees) -> TransactionRequest {
+    let from = tx.inner.signer();
+    let mut request = <TransactionRequest as From<Transaction>>::from(tx);
+    request.gas_price = None;
+    request.transaction_type = None;
+    request
+        .from(from)
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+}
+
+/// A zero-value self-transfer of `wallet` at `nonce`
+fn cancel_request(wallet: Address, nonce: u64, fees: ReplacementFees) -> TransactionRequest {
+    TransactionRequest::default()
+        .from(wallet)
+        .to(wallet)
+        .value(U256::ZERO)
+        .nonce(nonce)
+        .gas_limit(21_000)
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+}
+
+impl Trade {
+    /// Fees `percent` above those of the pending transaction `tx_hash`
+    pub async fn bumped_fees(&self, tx_hash: B256, percent: u64) -> Result<ReplacementFees> {
+        let tx = self.pending_transaction(tx_hash).await?;
+        let max_fee = tx.max_fee_per_gas();
+        let priority = tx.max_priority_fee_per_gas().unwrap_or(max_fee);
+        Ok(ReplacementFees::bumped(max_fee, priority, percent))
+    }
+
+    /// Resend the pending transaction `tx_hash` with higher fees
+    ///
+    /// The replacement has the same nonce, recipient, calldata, value and gas
+    /// limit. Fails if the transaction was already mined or isn't from this
+    /// trade's wallet.
+    pub async fn speed_up(&self, tx_hash: B256, fees: ReplacementFees) -> Result<Replacement> {
+        let tx = self.pending_transaction(tx_hash).await?;
+        let nonce = tx.nonce();
+        let request = speed_up_request(tx, fees);
+
+        let replacement = *self.provider().send_transaction(request).await?.tx_hash();
+        Ok(Replacement {
+            original: Some(tx_hash),
+            replacement,
+            nonce,
+            cancel: false,
+        })
+    }
+
+    /// Replace whatever is pending at `nonce` with a zero-value self-transfer
+    pub async fn cancel(&self, nonce: u64, fees: ReplacementFees) -> Result<Replacement> {
+        let request = cancel_request(self.wallet_address(), nonce, fees);
+        let replacement = *self.provider().send_transaction(request).await?.tx_hash();
+        Ok(Replacement {
+            original: None,
+            replacement,
+            nonce,
+            cancel: true,
+        })
+    }
+
+    /// [`Trade::cancel`] for the pending transaction `tx_hash`
+    pub async fn cancel_transaction(
+        &self,
+        tx_hash: B256,
+        fees: ReplacementFees,
+    ) -> Result<Replacement> {
+        let tx = self.pending_transaction(tx_hash).await?;
+        let mut replacement = self.cancel(tx.nonce(), fees).await?;
+        replacement.original = Some(tx_hash);
+        Ok(replacement)
+    }
+
+    /// Wait until the original or the replacement is mined
+    ///
+    /// Fails if `timeout` elapses, or if the nonce was used by a third
+    /// transaction (e.g. a later replacement).
+    pub async fn wait_for_replacement(
+        &self,
+        replacement: &Replacement,
+        timeout: Duration,
+    ) -> Result<ReplacementOutcome> {
+        let started = Instant::now();
+
+        loop {
+            if let Some(outcome) = self.mined_replacement(replacement).await? {
+                return Ok(outcome);
+            }
+
+            let mined_nonce = self
+                .provider()
+                .get_transaction_count(self.wallet_address())
+                .await?;
+            if mined_nonce > replacement.nonce {
+                // Receipts may lag the nonce on load-balanced RPCs; look once more
+                tokio::time::sleep(TxUtils::POLL_INTERVAL).await;
+                return self.mined_replacement(replacement).await?.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Nonce {} was used by another transaction",
+                        replacement.nonce
+                    )
+                });
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(anyhow::anyhow!(
+                    "Timed out waiting for nonce {} to be mined",
+                    replacement.nonce
+                ));
+            }
+
+            tokio::time::sleep(TxUtils::POLL_INTERVAL).await;
+        }
+    }
+
+    /// The receipt of whichever side of `replacement` is mined, if any
+    async fn mined_replacement(
+        &self,
+        replacement: &Replacement,
+    ) -> Result<Option<ReplacementOutcome>> {
+        let provider = self.provider();
+        if let Some(receipt) = provider
+            .get_transaction_receipt(replacement.replacement)
+            .await?
+        {
+            return Ok(Some(ReplacementOutcome::Replacement(
+                TransactionResult::from(receipt),
+            )));
+        }
+        if let Some(original) = replacement.original {
+            if let Some(receipt) = provider.get_transaction_receipt(original).await? {
+                return Ok(Some(ReplacementOutcome::Original(TransactionResult::from(
+                    receipt,
+                ))));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The transaction `tx_hash`, checked to be pending and from this wallet
+    async fn pending_transaction(&self, tx_hash: B256) -> Result<Transaction> {
+        let tx = self
+            .provider()
+            .get_transaction_by_hash(tx_hash)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction {} not found", tx_hash))?;
+        if tx.block_number.is_some() {
+            return Err(anyhow::anyhow!("Transaction {} is already mined", tx_hash));
+        }
+        if tx.inner.signer() != self.wallet_address() {
+            return Err(anyhow::anyhow!(
+                "Transaction {} was sent by {}, not this wallet",
+                tx_hash,
+                tx.inner.signer()
+            ));
+        }
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        consensus::{Signed, TxEip1559, TxEnvelope},
+        primitives::{Bytes, Signature, TxKind},
+    };
+
+    fn pending_fixture(from: Address) -> Transaction {
+        let tx = TxEip1559 {
+            chain_id: 143,
+            nonce: 42,
+            gas_limit: 250_000,
+            max_fee_per_gas: 100_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(Address::repeat_byte(0x22)),
+            value: U256::from(5u64),
+            input: Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]),
+            ..Default::default()
+        };
+        let envelope = TxEnvelope::Eip1559(Signed::new_unchecked(
+            tx,
+            Signature::test_signature(),
+            B256::repeat_byte(0x01),
+        ));
+        Transaction {
+            inner: alloy::consensus::transaction::Recovered::new_unchecked(envelope, from),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            effective_gas_price: None,
+        }
+    }
+
+    #[test]
+    fn test_speed_up_request_keeps_call_and_bumps_fees() {
+        let from = Address::repeat_byte(0x11);
+        let tx = pending_fixture(from);
+        let fees = ReplacementFees::bumped(
+            tx.max_fee_per_gas(),
+            tx.max_priority_fee_per_gas().unwrap(),
+            MIN_REPLACEMENT_BUMP_PERCENT,
+        );
+
+        let request = speed_up_request(tx, fees);
+        assert_eq!(request.from, Some(from));
+        assert_eq!(request.nonce, Some(42));
+        assert_eq!(request.to, Some(TxKind::Call(Address::repeat_byte(0x22))));
+        assert_eq!(request.value, Some(U256::from(5u64)));
+        assert_eq!(request.gas, Some(250_000));
+        assert_eq!(
+            request.input.input(),
+            Some(&Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]))
+        );
+        assert_eq!(request.max_fee_per_gas, Some(110_000_000_000));
+        assert_eq!(request.max_priority_fee_per_gas, Some(1_100_000_000));
+        assert_eq!(request.gas_price, None);
+    }
+
+    #[test]
+    fn test_cancel_request_is_self_transfer_at_nonce() {
+        let wallet = Address::repeat_byte(0x11);
+        let tx = pending_fixture(wallet);
+        let fees = ReplacementFees::bumped(
+            tx.max_fee_per_gas(),
+            tx.max_priority_fee_per_gas().unwrap(),
+            MIN_REPLACEMENT_BUMP_PERCENT,
+        );
+
+        let request = cancel_request(wallet, tx.nonce(), fees);
+        assert_eq!(request.from, Some(wallet));
+        assert_eq!(request.to, Some(TxKind::Call(wallet)));
+        assert_eq!(request.nonce, Some(42));
+        assert_eq!(request.value, Some(U256::ZERO));
+        assert_eq!(request.gas, Some(21_000));
+        assert_eq!(request.max_fee_per_gas, Some(110_000_000_000));
+        assert_eq!(request.max_priority_fee_per_gas, Some(1_100_000_000));
+    }
+
+    #[test]
+    fn test_bumped_fees_round_up_and_meet_minimum() {
+        let current = ReplacementFees::new(100_000_000_001, 1_000_000_001);
+        let bumped = ReplacementFees::bumped(
+            current.max_fee_per_gas,
+            current.max_priority_fee_per_gas,
+            MIN_REPLACEMENT_BUMP_PERCENT,
+        );
+        assert_eq!(bumped.max_fee_per_gas, 110_000_000_002);
+        assert_eq!(bumped.max_priority_fee_per_gas, 1_100_000_002);
+        assert!(bumped.exceeds(&current, MIN_REPLACEMENT_BUMP_PERCENT));
+
+        let too_small = ReplacementFees::new(105_000_000_000, 1_100_000_002);
+        assert!(!too_small.exceeds(&current, MIN_REPLACEMENT_BUMP_PERCENT));
+    }
+}
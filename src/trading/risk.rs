@@ -0,0 +1,515 @@
+//! Global spend and exposure limits for a trading wallet
+//!
+//! A bot with a bug can drain its wallet in minutes. A [`RiskManager`]
+//! attached to a [`Trade`](crate::Trade) checks every buy against a few hard
+//! limits before it is signed, and keeps a journal of what it allowed and
+//! refused:
+//!
+//! ```rust,ignore
+//! let risk = RiskManager::new(
+//!     RiskLimits::new()
+//!         .with_max_trade(parse_ether("5")?)
+//!         .with_max_daily_spend(parse_ether("50")?)
+//!         .with_max_open_positions(10)
+//!         .deny_token(known_rug),
+//! );
+//! let trade = Trade::new(rpc_url, private_key).await?.with_risk_manager(risk.clone());
+//!
+//! match trade.buy(params, router).await {
+//!     Err(e) if e.downcast_ref::<RiskViolation>().is_some() => println!("refused: {e}"),
+//!     other => { other?; }
+//! }
+//! for entry in risk.journal() {
+//!     println!("{:?} {} {}: {:?}", entry.side, entry.token, entry.amount, entry.violation);
+//! }
+//! ```
+//!
+//! Sells reduce exposure and are never refused; they are journaled only.
+//! Daily spend resets at midnight UTC. A checked buy of a new token takes a
+//! position slot right away, so concurrent buys can't overshoot the limit; the
+//! slot is given back if the buy fails. Positions close once a sell leaves the
+//! wallet without any of the token (or with [`RiskManager::close_position`]). All state is in memory and shared by
+//! clones, so one manager can guard several `Trade` instances.
+
+use crate::trading::builder::TradeSide;
+use alloy::primitives::{Address, U256, utils::format_ether};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Journal entries kept before the oldest are dropped
+pub const DEFAULT_RISK_JOURNAL_CAPACITY: usize = 1000;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Limits enforced on buys; every limit is off unless set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RiskLimits {
+    /// Largest MON amount of a single buy, in wei
+    pub max_trade: Option<U256>,
+    /// MON spent on buys per UTC day, in wei
+    pub max_daily_spend: Option<U256>,
+    /// Tokens held at once; buying more of an open position is always allowed
+    pub max_open_positions: Option<usize>,
+    /// Tokens that may never be bought
+    pub denied_tokens: HashSet<Address>,
+}
+
+impl RiskLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_trade(mut self, mon: U256) -> Self {
+        self.max_trade = Some(mon);
+        self
+    }
+
+    pub fn with_max_daily_spend(mut self, mon: U256) -> Self {
+        self.max_daily_spend = Some(mon);
+        self
+    }
+
+    pub fn with_max_open_positions(mut self, positions: usize) -> Self {
+        self.max_open_positions = Some(positions);
+        self
+    }
+
+    pub fn deny_token(mut self, token: Address) -> Self {
+        self.denied_tokens.insert(token);
+        self
+    }
+
+    pub fn deny_tokens(mut self, tokens: impl IntoIterator<Item = Address>) -> Self {
+        self.denied_tokens.extend(tokens);
+        self
+    }
+}
+
+/// Why a buy was refused
+///
+/// Returned inside an `anyhow::Error`; match it with `downcast_ref::<RiskViolation>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RiskViolation {
+    DeniedToken(Address),
+    TradeTooLarge {
+        amount: U256,
+        limit: U256,
+    },
+    DailySpendExceeded {
+        spent: U256,
+        amount: U256,
+        limit: U256,
+    },
+    TooManyPositions {
+        open: usize,
+        limit: usize,
+    },
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskViolation::DeniedToken(token) => write!(f, "Token {} is on the deny-list", token),
+            RiskViolation::TradeTooLarge { amount, limit } => write!(
+                f,
+                "Buy of {} MON exceeds the per-trade limit of {} MON",
+                format_ether(*amount),
+                format_ether(*limit)
+            ),
+            RiskViolation::DailySpendExceeded {
+                spent,
+                amount,
+                limit,
+            } => write!(
+                f,
+                "Buy of {} MON would exceed the daily limit of {} MON ({} MON spent today)",
+                format_ether(*amount),
+                format_ether(*limit),
+                format_ether(*spent)
+            ),
+            RiskViolation::TooManyPositions { open, limit } => {
+                write!(f, "{} positions are open, the limit is {}", open, limit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RiskViolation {}
+
+/// MON and position slot a checked buy reserved
+///
+/// Pass it to [`RiskManager::release_buy`] if the buy fails or is never sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuyReservation {
+    pub token: Address,
+    pub amount: U256,
+    /// UTC day number the amount was counted on
+    pub day: u64,
+    /// True if the buy opened a new position rather than adding to one
+    pub opened_position: bool,
+}
+
+/// One trade the manager was consulted about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RiskJournalEntry {
+    /// Unix timestamp of the check in seconds
+    pub timestamp: u64,
+    pub side: TradeSide,
+    pub token: Address,
+    /// MON in for buys, tokens in for sells
+    pub amount: U256,
+    /// None if the trade was allowed
+    pub violation: Option<RiskViolation>,
+}
+
+#[derive(Debug, Default)]
+struct RiskState {
+    /// UTC day number `spent_today` belongs to
+    day: u64,
+    spent_today: U256,
+    open_positions: HashSet<Address>,
+    journal: VecDeque<RiskJournalEntry>,
+}
+
+/// Enforces [`RiskLimits`] across every trade it is consulted about
+#[derive(Debug, Clone)]
+pub struct RiskManager {
+    limits: Arc<RiskLimits>,
+    journal_capacity: usize,
+    state: Arc<Mutex<RiskState>>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskLimits) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            journal_capacity: DEFAULT_RISK_JOURNAL_CAPACITY,
+            state: Arc::new(Mutex::new(RiskState::default())),
+        }
+    }
+
+    /// Keep at most `capacity` journal entries (default 1000)
+    pub fn with_journal_capacity(mut self, capacity: usize) -> Self {
+        self.journal_capacity = capacity;
+        self
+    }
+
+    pub fn limits(&self) -> &RiskLimits {
+        &self.limits
+    }
+
+    /// Check a buy of `token` with `mon_amount` and reserve it against the limits
+    ///
+    /// The amount counts against the daily spend and a new token takes a
+    /// position slot until the reservation is released.
+    ///
+    /// [`Trade`](crate::Trade) calls this before signing; call
+    /// [`RiskManager::release_buy`] if a buy checked by hand is never sent.
+    pub fn check_buy(
+        &self,
+        token: Address,
+        mon_amount: U256,
+    ) -> Result<BuyReservation, RiskViolation> {
+        self.check_buy_at(token, mon_amount, unix_now())
+    }
+
+    /// Record a sell; sells are always allowed
+    pub fn check_sell(&self, token: Address, token_amount: U256) {
+        let mut state = self.lock();
+        self.record(
+            &mut state,
+            unix_now(),
+            TradeSide::Sell,
+            token,
+            token_amount,
+            None,
+        );
+    }
+
+    /// Give back the reservation of a buy that failed or was never sent
+    ///
+    /// Frees the position slot the buy took. A reservation from an earlier UTC
+    /// day has no spend left to give back.
+    pub fn release_buy(&self, reservation: BuyReservation) {
+        let mut state = self.lock();
+        if state.day == reservation.day {
+            state.spent_today = state.spent_today.saturating_sub(reservation.amount);
+        }
+        if reservation.opened_position {
+            state.open_positions.remove(&reservation.token);
+        }
+    }
+
+    /// Count `token` as an open position
+    pub fn open_position(&self, token: Address) {
+        self.lock().open_positions.insert(token);
+    }
+
+    /// Stop counting `token` as an open position (e.g. after selling all of it)
+    pub fn close_position(&self, token: Address) {
+        self.lock().open_positions.remove(&token);
+    }
+
+    pub fn open_positions(&self) -> Vec<Address> {
+        self.lock().open_positions.iter().copied().collect()
+    }
+
+    /// MON reserved by buys since midnight UTC
+    pub fn spent_today(&self) -> U256 {
+        let mut state = self.lock();
+        roll_day(&mut state, unix_now());
+        state.spent_today
+    }
+
+    /// Journal entries, oldest first
+    pub fn journal(&self) -> Vec<RiskJournalEntry> {
+        self.lock().journal.iter().cloned().collect()
+    }
+
+    /// Remove and return the journal entries, oldest first
+    pub fn take_journal(&self) -> Vec<RiskJournalEntry> {
+        self.lock().journal.drain(..).collect()
+    }
+
+    /// Refused trades in the journal
+    pub fn violations(&self) -> Vec<RiskJournalEntry> {
+        self.lock()
+            .journal
+            .iter()
+            .filter(|entry| entry.violation.is_some())
+            .cloned()
+            .collect()
+    }
+
+    fn check_buy_at(
+        &self,
+        token: Address,
+        amount: U256,
+        now: u64,
+    ) -> Result<BuyReservation, RiskViolation> {
+        let mut state = self.lock();
+        roll_day(&mut state, now);
+
+        let violation = self.violation(&state, token, amount);
+        self.record(
+            &mut state,
+            now,
+            TradeSide::Buy,
+            token,
+            amount,
+            violation.clone(),
+        );
+        if let Some(violation) = violation {
+            return Err(violation);
+        }
+
+        state.spent_today = state.spent_today.saturating_add(amount);
+        let opened_position = state.open_positions.insert(token);
+        Ok(BuyReservation {
+            token,
+            amount,
+            day: state.day,
+            opened_position,
+        })
+    }
+
+    fn violation(&self, state: &RiskState, token: Address, amount: U256) -> Option<RiskViolation> {
+        let limits = &self.limits;
+        if limits.denied_tokens.contains(&token) {
+            return Some(RiskViolation::DeniedToken(token));
+        }
+        if let Some(limit) = limits.max_trade {
+            if amount > limit {
+                return Some(RiskViolation::TradeTooLarge { amount, limit });
+            }
+        }
+        if let Some(limit) = limits.max_daily_spend {
+            if state.spent_today.saturating_add(amount) > limit {
+                return Some(RiskViolation::DailySpendExceeded {
+                    spent: state.spent_today,
+                    amount,
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = limits.max_open_positions {
+            let open = state.open_positions.len();
+            if !state.open_positions.contains(&token) && open >= limit {
+                return Some(RiskViolation::TooManyPositions { open, limit });
+            }
+        }
+        None
+    }
+
+    fn record(
+        &self,
+        state: &mut RiskState,
+        timestamp: u64,
+        side: TradeSide,
+        token: Address,
+        amount: U256,
+        violation: Option<RiskViolation>,
+    ) {
+        if self.journal_capacity == 0 {
+            return;
+        }
+        while state.journal.len() >= self.journal_capacity {
+            state.journal.pop_front();
+        }
+        state.journal.push_back(RiskJournalEntry {
+            timestamp,
+            side,
+            token,
+            amount,
+            violation,
+        });
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RiskState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Reset the daily spend when `now` falls on a later UTC day
+fn roll_day(state: &mut RiskState, now: u64) {
+    let day = now / SECS_PER_DAY;
+    if day != state.day {
+        state.day = day;
+        state.spent_today = U256::ZERO;
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_are_enforced_and_journaled() {
+        let mon = |n: u64| U256::from(n);
+        let (a, b, c, denied) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+            Address::repeat_byte(9),
+        );
+        let risk = RiskManager::new(
+            RiskLimits::new()
+                .with_max_trade(mon(10))
+                .with_max_daily_spend(mon(25))
+                .with_max_open_positions(2)
+                .deny_token(denied),
+        );
+        let day = 20_000 * SECS_PER_DAY;
+
+        assert_eq!(
+            risk.check_buy_at(denied, mon(1), day),
+            Err(RiskViolation::DeniedToken(denied))
+        );
+        assert!(matches!(
+            risk.check_buy_at(a, mon(11), day),
+            Err(RiskViolation::TradeTooLarge { .. })
+        ));
+
+        risk.check_buy_at(a, mon(10), day).unwrap();
+        risk.open_position(a);
+        let reservation = risk.check_buy_at(b, mon(10), day).unwrap();
+        risk.open_position(b);
+        assert_eq!(
+            risk.check_buy_at(c, mon(1), day),
+            Err(RiskViolation::TooManyPositions { open: 2, limit: 2 })
+        );
+        assert_eq!(
+            risk.check_buy_at(a, mon(6), day),
+            Err(RiskViolation::DailySpendExceeded {
+                spent: mon(20),
+                amount: mon(6),
+                limit: mon(25)
+            })
+        );
+
+        // A failed buy gives its reservation back; a new day starts from zero
+        risk.release_buy(reservation);
+        risk.check_buy_at(a, mon(10), day + 60).unwrap();
+        risk.close_position(b);
+        risk.check_buy_at(c, mon(10), day + SECS_PER_DAY).unwrap();
+
+        risk.check_sell(a, mon(1_000));
+        let journal = risk.journal();
+        assert_eq!(journal.len(), 9);
+        assert_eq!(risk.violations().len(), 4);
+        assert_eq!(journal.last().unwrap().side, TradeSide::Sell);
+        assert!(journal.last().unwrap().violation.is_none());
+    }
+
+    #[test]
+    fn test_release_after_midnight_keeps_new_day_spend() {
+        let risk = RiskManager::new(RiskLimits::new().with_max_daily_spend(U256::from(10)));
+        let day = 20_000 * SECS_PER_DAY;
+
+        let reservation = risk
+            .check_buy_at(Address::ZERO, U256::from(4), day - 1)
+            .unwrap();
+        risk.check_buy_at(Address::ZERO, U256::from(6), day)
+            .unwrap();
+        risk.release_buy(reservation);
+        assert_eq!(risk.lock().spent_today, U256::from(6));
+        assert!(
+            risk.check_buy_at(Address::ZERO, U256::from(5), day + 1)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_reservations_take_position_slots() {
+        let risk = RiskManager::new(RiskLimits::new().with_max_open_positions(2));
+        let (a, b, c) = (
+            Address::repeat_byte(1),
+            Address::repeat_byte(2),
+            Address::repeat_byte(3),
+        );
+        risk.open_position(a);
+
+        // One slot left: the second in-flight buy can't take it too
+        let first = risk.check_buy_at(b, U256::from(1), 0).unwrap();
+        assert!(first.opened_position);
+        assert_eq!(
+            risk.check_buy_at(c, U256::from(1), 0),
+            Err(RiskViolation::TooManyPositions { open: 2, limit: 2 })
+        );
+
+        // Adding to a position doesn't take or free a slot
+        let more_of_a = risk.check_buy_at(a, U256::from(1), 0).unwrap();
+        assert!(!more_of_a.opened_position);
+        risk.release_buy(more_of_a);
+        assert!(risk.open_positions().contains(&a));
+
+        // The failed buy frees its slot
+        risk.release_buy(first);
+        risk.check_buy_at(c, U256::from(1), 0).unwrap();
+        let mut open = risk.open_positions();
+        open.sort();
+        assert_eq!(open, vec![a, c]);
+    }
+
+    #[test]
+    fn test_journal_capacity() {
+        let risk = RiskManager::new(RiskLimits::new()).with_journal_capacity(2);
+        for n in 0..3u64 {
+            risk.check_sell(Address::ZERO, U256::from(n));
+        }
+        let amounts: Vec<U256> = risk.take_journal().iter().map(|e| e.amount).collect();
+        assert_eq!(amounts, vec![U256::from(1), U256::from(2)]);
+        assert!(risk.journal().is_empty());
+    }
+}
@@ -0,0 +1,317 @@
+//! Signer rotation across several trading wallets
+//!
+//! Buying from one wallet links every position to it. A [`MultiWalletTrader`]
+//! holds one [`Trade`] per private key and picks a different signer for each
+//! buy, round-robin or at random, so activity isn't clustered on one address:
+//!
+//! ```rust,ignore
+//! let trader = MultiWalletTrader::new(rpc_url, private_keys, ChainConfig::default())
+//!     .await?
+//!     .with_policy(RotationPolicy::Random)
+//!     .with_default_spend_limit(parse_ether("50")?)
+//!     .with_spend_limit(hot_wallet, parse_ether("5")?);
+//!
+//! let rotated = trader.buy(token, parse_ether("1")?).await?;
+//! println!("bought from {} in {}", rotated.wallet, rotated.result.transaction_hash);
+//! ```
+//!
+//! A key is skipped for a buy when it would exceed its spend limit or its MON
+//! balance doesn't cover the amount plus a gas reserve. Spend limits count the
+//! MON sent by buys through this trader; they are kept in memory and can be
+//! cleared with [`MultiWalletTrader::reset_spent`].
+
+use crate::{
+    constants::ChainConfig,
+    trading::{Trade, TradeBuilder},
+    types::TransactionResult,
+};
+use alloy::{
+    primitives::{Address, U256, utils::format_ether},
+    providers::Provider,
+};
+use anyhow::Result;
+use std::{
+    collections::{HashMap, hash_map::RandomState},
+    hash::BuildHasher,
+    sync::{Arc, Mutex},
+};
+
+/// MON kept back for gas when checking whether a key can afford a buy (0.01 MON)
+pub const DEFAULT_GAS_RESERVE: U256 = U256::from_limbs([10_000_000_000_000_000, 0, 0, 0]);
+
+/// How the next signer is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RotationPolicy {
+    /// Each key in turn, continuing after the last one used
+    #[default]
+    RoundRobin,
+    /// A random key, falling through to the following ones if it is excluded
+    Random,
+}
+
+/// A buy and the wallet that signed it
+#[derive(Debug, Clone)]
+pub struct RotatedTrade {
+    pub wallet: Address,
+    pub result: TransactionResult,
+}
+
+#[derive(Debug, Default)]
+struct RotationState {
+    /// Round-robin position of the next candidate
+    cursor: usize,
+    spent: HashMap<Address, U256>,
+}
+
+impl RotationState {
+    /// Count `amount` against `wallet` if that stays within `limit`
+    fn reserve(&mut self, wallet: Address, amount: U256, limit: Option<U256>) -> bool {
+        let spent = self.spent.entry(wallet).or_default();
+        let total = spent.saturating_add(amount);
+        if limit.is_some_and(|limit| total > limit) {
+            return false;
+        }
+        *spent = total;
+        true
+    }
+
+    fn release(&mut self, wallet: Address, amount: U256) {
+        if let Some(spent) = self.spent.get_mut(&wallet) {
+            *spent = spent.saturating_sub(amount);
+        }
+    }
+}
+
+/// Order in which `len` keys are tried, starting at `cursor` or a random key
+fn rotation_order(len: usize, cursor: usize, policy: RotationPolicy, seed: u64) -> Vec<usize> {
+    if len == 0 {
+        return Vec::new();
+    }
+    let start = match policy {
+        RotationPolicy::RoundRobin => cursor % len,
+        RotationPolicy::Random => (seed % len as u64) as usize,
+    };
+    (0..len).map(|offset| (start + offset) % len).collect()
+}
+
+/// Trades from several wallets, rotating the signer per buy
+pub struct MultiWalletTrader {
+    traders: Vec<Trade>,
+    policy: RotationPolicy,
+    spend_limits: HashMap<Address, U256>,
+    default_spend_limit: Option<U256>,
+    gas_reserve: U256,
+    state: Arc<Mutex<RotationState>>,
+}
+
+impl MultiWalletTrader {
+    /// One [`Trade`] per private key, all against `chain_config`
+    pub async fn new(
+        rpc_url: String,
+        private_keys: Vec<String>,
+        chain_config: ChainConfig,
+    ) -> Result<Self> {
+        let mut traders = Vec::with_capacity(private_keys.len());
+        for private_key in private_keys {
+            traders.push(
+                Trade::new_with_config(rpc_url.clone(), private_key, chain_config.clone()).await?,
+            );
+        }
+        Self::from_trades(traders)
+    }
+
+    /// Rotate over existing [`Trade`] instances (e.g. built with [`Trade::new_with_pool`])
+    pub fn from_trades(traders: Vec<Trade>) -> Result<Self> {
+        if traders.is_empty() {
+            return Err(anyhow::anyhow!("MultiWalletTrader needs at least one key"));
+        }
+        Ok(Self {
+            traders,
+            policy: RotationPolicy::default(),
+            spend_limits: HashMap::new(),
+            default_spend_limit: None,
+            gas_reserve: DEFAULT_GAS_RESERVE,
+            state: Arc::new(Mutex::new(RotationState::default())),
+        })
+    }
+
+    pub fn with_policy(mut self, policy: RotationPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Most MON `wallet` may spend on buys through this trader
+    pub fn with_spend_limit(mut self, wallet: Address, limit: U256) -> Self {
+        self.spend_limits.insert(wallet, limit);
+        self
+    }
+
+    /// Spend limit of every wallet without its own
+    pub fn with_default_spend_limit(mut self, limit: U256) -> Self {
+        self.default_spend_limit = Some(limit);
+        self
+    }
+
+    /// MON a key must hold beyond the buy amount to be used
+    pub fn with_gas_reserve(mut self, reserve: U256) -> Self {
+        self.gas_reserve = reserve;
+        self
+    }
+
+    pub fn wallet_addresses(&self) -> Vec<Address> {
+        self.traders.iter().map(|t| t.wallet_address()).collect()
+    }
+
+    /// The [`Trade`] signing with `wallet`, e.g. to sell what it bought
+    pub fn trader(&self, wallet: Address) -> Option<&Trade> {
+        self.traders.iter().find(|t| t.wallet_address() == wallet)
+    }
+
+    pub fn spend_limit(&self, wallet: Address) -> Option<U256> {
+        self.spend_limits
+            .get(&wallet)
+            .copied()
+            .or(self.default_spend_limit)
+    }
+
+    /// MON spent by `wallet` on buys through this trader
+    pub fn spent(&self, wallet: Address) -> U256 {
+        let state = self.state.lock().unwrap();
+        state.spent.get(&wallet).copied().unwrap_or_default()
+    }
+
+    /// MON `wallet` may still spend, None without a limit
+    pub fn remaining(&self, wallet: Address) -> Option<U256> {
+        self.spend_limit(wallet)
+            .map(|limit| limit.saturating_sub(self.spent(wallet)))
+    }
+
+    /// Forget recorded spending, e.g. at the start of a new day
+    pub fn reset_spent(&self) {
+        self.state.lock().unwrap().spent.clear();
+    }
+
+    /// Buy `token` for `mon_amount` from the next eligible key
+    ///
+    /// Slippage, deadline and gas follow each [`Trade`]'s options.
+    pub async fn buy(&self, token: Address, mon_amount: U256) -> Result<RotatedTrade> {
+        self.buy_with(token, mon_amount, |order| order).await
+    }
+
+    /// [`MultiWalletTrader::buy`] with the order adjusted by `configure`
+    /// (slippage, deadline, gas)
+    ///
+    /// The recipient is always the signing wallet.
+    pub async fn buy_with<F>(
+        &self,
+        token: Address,
+        mon_amount: U256,
+        configure: F,
+    ) -> Result<RotatedTrade>
+    where
+        F: for<'a> FnOnce(TradeBuilder<'a>) -> TradeBuilder<'a>,
+    {
+        let trade = self.select(mon_amount).await?;
+        let wallet = trade.wallet_address();
+        let order = configure(trade.buy_order(token).amount(mon_amount)).to(wallet);
+
+        match order.send().await {
+            Ok(result) => Ok(RotatedTrade { wallet, result }),
+            Err(e) => {
+                self.state.lock().unwrap().release(wallet, mon_amount);
+                Err(e)
+            }
+        }
+    }
+
+    /// Pick the next key that can spend `mon_amount` and count it against its limit
+    ///
+    /// Fails listing why every key was excluded. Callers that don't send the
+    /// trade afterwards should undo the reservation with
+    /// [`MultiWalletTrader::release`].
+    pub async fn select(&self, mon_amount: U256) -> Result<&Trade> {
+        let order = {
+            let state = self.state.lock().unwrap();
+            let seed = RandomState::new().hash_one(state.cursor);
+            rotation_order(self.traders.len(), state.cursor, self.policy, seed)
+        };
+
+        let mut excluded = Vec::new();
+        for index in order {
+            let trade = &self.traders[index];
+            let wallet = trade.wallet_address();
+            let limit = self.spend_limit(wallet);
+
+            if !self
+                .state
+                .lock()
+                .unwrap()
+                .reserve(wallet, mon_amount, limit)
+            {
+                excluded.push(format!("{}: spend limit reached", wallet));
+                continue;
+            }
+
+            let balance = match trade.provider().get_balance(wallet).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    self.release(wallet, mon_amount);
+                    excluded.push(format!("{}: balance lookup failed ({})", wallet, e));
+                    continue;
+                }
+            };
+            if balance < mon_amount.saturating_add(self.gas_reserve) {
+                self.release(wallet, mon_amount);
+                excluded.push(format!(
+                    "{}: balance {} MON too low",
+                    wallet,
+                    format_ether(balance)
+                ));
+                continue;
+            }
+
+            self.state.lock().unwrap().cursor = index + 1;
+            return Ok(trade);
+        }
+
+        Err(anyhow::anyhow!(
+            "No key can spend {} MON: {}",
+            format_ether(mon_amount),
+            excluded.join(", ")
+        ))
+    }
+
+    /// Undo a reservation made by [`MultiWalletTrader::select`]
+    pub fn release(&self, wallet: Address, mon_amount: U256) {
+        self.state.lock().unwrap().release(wallet, mon_amount);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_order_and_spend_limits() {
+        assert_eq!(
+            rotation_order(3, 4, RotationPolicy::RoundRobin, 0),
+            vec![1, 2, 0]
+        );
+        assert_eq!(
+            rotation_order(3, 0, RotationPolicy::Random, 5),
+            vec![2, 0, 1]
+        );
+        assert!(rotation_order(0, 0, RotationPolicy::RoundRobin, 0).is_empty());
+
+        let wallet = Address::repeat_byte(1);
+        let limit = Some(U256::from(100));
+        let mut state = RotationState::default();
+        assert!(state.reserve(wallet, U256::from(60), limit));
+        assert!(!state.reserve(wallet, U256::from(60), limit));
+        assert!(state.reserve(wallet, U256::from(40), limit));
+
+        state.release(wallet, U256::from(40));
+        assert_eq!(state.spent[&wallet], U256::from(60));
+        assert!(state.reserve(Address::repeat_byte(2), U256::MAX, None));
+    }
+}
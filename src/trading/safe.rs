@@ -0,0 +1,289 @@
+//! Trading from a Safe (Gnosis Safe) multisig
+//!
+//! A Safe can't sign router transactions with a private key; its owners approve
+//! a `SafeTx` instead. [`SafeTransaction`] wraps router calldata into that
+//! payload, computes the hash owners sign, and (with the `safe-service`
+//! feature) proposes it to the Safe Transaction Service:
+//!
+//! ```rust,ignore
+//! let nonce = safe_nonce(&provider, safe).await?;
+//! let approve = SafeTransaction::approve(safe, token, router.address(), amount, nonce);
+//! let sell = SafeTransaction::sell(safe, &sell_params, &router, nonce + U256::from(1))?;
+//!
+//! // An owner (or delegate) signs the hash, e.g. on a hardware wallet
+//! let signature = sell.sign(&owner_signer, chain_id)?;
+//!
+//! SafeTxService::new(tx_service_url)
+//!     .propose(&sell, chain_id, owner_signer.address(), &signature)
+//!     .await?;
+//! ```
+//!
+//! Permit sells don't work from a Safe: EIP-2612 permits need an ECDSA signature
+//! of the token owner. Approve and sell as two Safe transactions (or batch them
+//! through MultiSend with [`SafeOperation::DelegateCall`]).
+
+use crate::{
+    token::token::IToken,
+    trading::{gas::TradeOperation, recipient::check_recipient},
+    types::{BuyParams, Router, SellParams},
+};
+use alloy::{
+    primitives::{Address, B256, Bytes, Signature, U256},
+    providers::Provider,
+    signers::SignerSync,
+    sol,
+    sol_types::{SolCall, SolStruct, eip712_domain},
+};
+use anyhow::Result;
+
+sol! {
+    #[sol(rpc)]
+    interface ISafe {
+        function nonce() external view returns (uint256);
+        function getThreshold() external view returns (uint256);
+        function getOwners() external view returns (address[]);
+    }
+
+    /// EIP-712 struct owners sign (Safe >= 1.3.0)
+    struct SafeTx {
+        address to;
+        uint256 value;
+        bytes data;
+        uint8 operation;
+        uint256 safeTxGas;
+        uint256 baseGas;
+        uint256 gasPrice;
+        address gasToken;
+        address refundReceiver;
+        uint256 nonce;
+    }
+}
+
+/// How the Safe executes the transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafeOperation {
+    Call = 0,
+    /// Runs `data` in the Safe's context; only for trusted libraries like MultiSend
+    DelegateCall = 1,
+}
+
+/// A Safe transaction payload
+///
+/// Gas and refund fields default to zero, meaning the executor pays gas and
+/// nobody is refunded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeTransaction {
+    /// The Safe executing the transaction
+    pub safe: Address,
+    pub to: Address,
+    pub value: U256,
+    pub data: Bytes,
+    pub operation: SafeOperation,
+    pub safe_tx_gas: U256,
+    pub base_gas: U256,
+    pub gas_price: U256,
+    pub gas_token: Address,
+    pub refund_receiver: Address,
+    pub nonce: U256,
+}
+
+impl SafeTransaction {
+    /// A plain call from the Safe
+    pub fn call(safe: Address, to: Address, value: U256, data: Bytes, nonce: U256) -> Self {
+        Self {
+            safe,
+            to,
+            value,
+            data,
+            operation: SafeOperation::Call,
+            safe_tx_gas: U256::ZERO,
+            base_gas: U256::ZERO,
+            gas_price: U256::ZERO,
+            gas_token: Address::ZERO,
+            refund_receiver: Address::ZERO,
+            nonce,
+        }
+    }
+
+    /// A router trade from the Safe
+    pub fn trade(safe: Address, operation: &TradeOperation, router: &Router, nonce: U256) -> Self {
+        Self::call(
+            safe,
+            router.address(),
+            operation.value(),
+            operation.calldata(router.kind()),
+            nonce,
+        )
+    }
+
+    /// Buy paid with the Safe's MON; `params.to` must be the Safe
+    pub fn buy(safe: Address, params: &BuyParams, router: &Router, nonce: U256) -> Result<Self> {
+        check_recipient(safe, params.to, false)?;
+        Ok(Self::trade(safe, &params.into(), router, nonce))
+    }
+
+    /// Sell of the Safe's tokens; the router needs an allowance (see [`SafeTransaction::approve`])
+    pub fn sell(safe: Address, params: &SellParams, router: &Router, nonce: U256) -> Result<Self> {
+        check_recipient(safe, params.to, false)?;
+        Ok(Self::trade(safe, &params.into(), router, nonce))
+    }
+
+    /// ERC-20 approval from the Safe, e.g. of the router before a sell
+    pub fn approve(
+        safe: Address,
+        token: Address,
+        spender: Address,
+        amount: U256,
+        nonce: U256,
+    ) -> Self {
+        let data = IToken::approveCall {
+            spender,
+            value: amount,
+        }
+        .abi_encode();
+        Self::call(safe, token, U256::ZERO, data.into(), nonce)
+    }
+
+    fn to_struct(&self) -> SafeTx {
+        SafeTx {
+            to: self.to,
+            value: self.value,
+            data: self.data.clone(),
+            operation: self.operation as u8,
+            safeTxGas: self.safe_tx_gas,
+            baseGas: self.base_gas,
+            gasPrice: self.gas_price,
+            gasToken: self.gas_token,
+            refundReceiver: self.refund_receiver,
+            nonce: self.nonce,
+        }
+    }
+
+    /// The EIP-712 hash owners sign (`safeTxHash`)
+    pub fn safe_tx_hash(&self, chain_id: u64) -> B256 {
+        let domain = eip712_domain! {
+            chain_id: chain_id,
+            verifying_contract: self.safe,
+        };
+        self.to_struct().eip712_signing_hash(&domain)
+    }
+
+    /// Sign the `safeTxHash` with an owner key
+    ///
+    /// `signature.as_bytes()` is the 65-byte form Safe expects (v = 27/28).
+    pub fn sign<S: SignerSync>(&self, signer: &S, chain_id: u64) -> Result<Signature> {
+        Ok(signer.sign_hash_sync(&self.safe_tx_hash(chain_id))?)
+    }
+}
+
+/// Current nonce of `safe`, the nonce of its next transaction
+pub async fn safe_nonce<P: Provider>(provider: &P, safe: Address) -> Result<U256> {
+    Ok(ISafe::new(safe, provider).nonce().call().await?)
+}
+
+/// Proposes Safe transactions to a Safe Transaction Service
+#[cfg(feature = "safe-service")]
+#[derive(Debug, Clone)]
+pub struct SafeTxService {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+#[cfg(feature = "safe-service")]
+impl SafeTxService {
+    /// `base_url` is the service root, e.g. `https://safe-transaction-<network>.safe.global`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Submit `tx` with the signature of `sender`, an owner or delegate of the Safe
+    ///
+    /// Returns the `safeTxHash`. Other owners confirm it in the Safe app.
+    pub async fn propose(
+        &self,
+        tx: &SafeTransaction,
+        chain_id: u64,
+        sender: Address,
+        signature: &Signature,
+    ) -> Result<B256> {
+        let safe_tx_hash = tx.safe_tx_hash(chain_id);
+        let body = serde_json::json!({
+            "to": tx.to.to_checksum(None),
+            "value": tx.value.to_string(),
+            "data": tx.data.to_string(),
+            "operation": tx.operation as u8,
+            "safeTxGas": tx.safe_tx_gas.to_string(),
+            "baseGas": tx.base_gas.to_string(),
+            "gasPrice": tx.gas_price.to_string(),
+            "gasToken": tx.gas_token.to_checksum(None),
+            "refundReceiver": tx.refund_receiver.to_checksum(None),
+            "nonce": tx.nonce.to_string(),
+            "contractTransactionHash": safe_tx_hash.to_string(),
+            "sender": sender.to_checksum(None),
+            "signature": Bytes::copy_from_slice(&signature.as_bytes()).to_string(),
+            "origin": "nadfun-sdk",
+        });
+
+        let url = format!(
+            "{}/api/v1/safes/{}/multisig-transactions/",
+            self.base_url,
+            tx.safe.to_checksum(None)
+        );
+        let response = self.client.post(&url).json(&body).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "Safe Transaction Service rejected the proposal ({}): {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
+            ));
+        }
+        Ok(safe_tx_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::signers::local::PrivateKeySigner;
+
+    #[test]
+    fn test_safe_buy_payload_and_signature() {
+        let safe = Address::repeat_byte(0x5a);
+        let router = Router::BondingCurve(Address::repeat_byte(0x01));
+        let mut params = BuyParams {
+            token: Address::repeat_byte(0x02),
+            amount_in: U256::from(1_000),
+            amount_out_min: U256::from(1),
+            to: safe,
+            deadline: U256::from(9_999),
+            gas_limit: None,
+            gas_price: None,
+            nonce: None,
+        };
+
+        let tx = SafeTransaction::buy(safe, &params, &router, U256::from(3)).unwrap();
+        assert_eq!(tx.to, router.address());
+        assert_eq!(tx.value, params.amount_in);
+        assert_eq!(
+            tx.data,
+            TradeOperation::from(&params).calldata(router.kind())
+        );
+
+        // The hash binds the chain and the Safe
+        assert_ne!(tx.safe_tx_hash(1), tx.safe_tx_hash(10143));
+
+        let owner = PrivateKeySigner::random();
+        let signature = tx.sign(&owner, 10143).unwrap();
+        let recovered = signature
+            .recover_address_from_prehash(&tx.safe_tx_hash(10143))
+            .unwrap();
+        assert_eq!(recovered, owner.address());
+
+        // Tokens bought by the Safe must land in the Safe
+        params.to = Address::repeat_byte(0x77);
+        assert!(SafeTransaction::buy(safe, &params, &router, U256::ZERO).is_err());
+    }
+}
@@ -1,17 +1,31 @@
 use crate::{
     constants::*,
     contracts::{BondingCurveRouter, DexRouter, LensContract},
+    rpc::EndpointPool,
+    token::token::{sign_permit, IToken},
+    token_state::TokenStateCache,
+    trading::builder::GasMode,
+    trading::fallback::is_revert,
+    trading::locks::{TokenLockGuard, TokenLocks},
+    trading::options::TradeOptions,
+    trading::recipient::check_recipient,
+    trading::risk::RiskManager,
+    trading::version::{probe_router, RouterInfo},
+    trading::gas::{
+        default_gas_table, estimate_gas_from, GasCalibration, GasConfig, TradeOperation,
+        Operation,
+    },
     types::*,
-    trading::gas::{estimate_gas, GasEstimationParams},
 };
 use alloy::{
+    eips::BlockId,
     network::EthereumWallet,
     primitives::{Address, U256},
-    providers::{DynProvider, ProviderBuilder},
+    providers::{DynProvider, Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
 };
 use anyhow::Result;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 pub struct Trade {
     bonding_curve_router: BondingCurveRouter<DynProvider>,
@@ -19,41 +33,377 @@ pub struct Trade {
     lens: LensContract<DynProvider>,
     provider: Arc<DynProvider>,
     wallet_address: Address,
+    signer: PrivateKeySigner,
+    gas_config: Option<GasConfig>,
+    chain_config: ChainConfig,
+    custom_routers: HashMap<Address, RouterKind>,
+    allow_third_party_recipient: bool,
+    token_state: Option<TokenStateCache>,
+    options: TradeOptions,
+    token_locks: Option<TokenLocks>,
+    risk: Option<RiskManager>,
 }
 
 impl Trade {
     /// Create a new Trade instance from a private key string (recommended)
     pub async fn new(rpc_url: String, private_key: String) -> Result<Trade> {
-        let signer: PrivateKeySigner = private_key.parse()?;
-        let wallet_address = signer.address();
+        Self::new_with_config(rpc_url, private_key, ChainConfig::default()).await
+    }
 
-        // Use default contract addresses
-        let lens_address: Address = LENS_ADDRESS.parse()?;
-        let bonding_curve_router_address: Address = BONDING_CURVE_ROUTER.parse()?;
-        let dex_router_address: Address = DEX_ROUTER.parse()?;
-        let bonding_curve_address: Address = BONDING_CURVE.parse()?;
+    /// Create a new Trade instance with default slippage, deadline and gas policy
+    pub async fn new_with_options(
+        rpc_url: String,
+        private_key: String,
+        options: TradeOptions,
+    ) -> Result<Trade> {
+        options.validate()?;
+        Ok(Self::new(rpc_url, private_key).await?.with_options(options))
+    }
 
-        let wallet = EthereumWallet::from(signer);
+    /// Create a new Trade instance against a custom set of contract addresses
+    pub async fn new_with_config(
+        rpc_url: String,
+        private_key: String,
+        chain_config: ChainConfig,
+    ) -> Result<Trade> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
         let url = rpc_url.parse()?;
         let provider = ProviderBuilder::new().wallet(wallet).connect_http(url);
-        let dyn_provider = Arc::new(DynProvider::new(provider));
+
+        Ok(Self::from_provider(
+            Arc::new(DynProvider::new(provider)),
+            signer,
+            chain_config,
+        ))
+    }
+
+    /// Create a Trade instance on a warm, failover-capable [`EndpointPool`]
+    ///
+    /// All RPC traffic (quotes, gas estimation, submission) shares the pool's
+    /// keep-alive connections and endpoint ranking.
+    pub fn new_with_pool(
+        pool: &EndpointPool,
+        private_key: String,
+        chain_config: ChainConfig,
+    ) -> Result<Trade> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = pool.wallet_provider(wallet);
+
+        Ok(Self::from_provider(
+            Arc::new(provider),
+            signer,
+            chain_config,
+        ))
+    }
+
+    /// Create a Trade instance whose RPC calls are counted under [`Component::Trade`]
+    ///
+    /// [`Component::Trade`]: crate::metrics::Component::Trade
+    #[cfg(feature = "metrics")]
+    pub fn new_with_metrics(
+        rpc_url: String,
+        private_key: String,
+        chain_config: ChainConfig,
+        metrics: &crate::metrics::RpcMetrics,
+    ) -> Result<Trade> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = metrics
+            .http(crate::metrics::Component::Trade, &rpc_url)?
+            .wallet_provider(wallet);
+
+        Ok(Self::from_provider(
+            Arc::new(provider),
+            signer,
+            chain_config,
+        ))
+    }
+
+    /// Create a Trade instance whose concurrent quotes and view calls share
+    /// JSON-RPC batches (see [`CoalescingTransport`])
+    ///
+    /// [`CoalescingTransport`]: crate::coalesce::CoalescingTransport
+    pub fn new_with_batching(
+        rpc_url: String,
+        private_key: String,
+        chain_config: ChainConfig,
+        config: crate::coalesce::CoalesceConfig,
+    ) -> Result<Trade> {
+        let signer: PrivateKeySigner = private_key.parse()?;
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = crate::coalesce::CoalescingTransport::http(&rpc_url, config)?
+            .wallet_provider(wallet);
+
+        Ok(Self::from_provider(
+            Arc::new(provider),
+            signer,
+            chain_config,
+        ))
+    }
+
+    /// Create a Trade on an existing provider, e.g. one shared with other components
+    ///
+    /// The provider must sign with `signer`'s wallet for trades to be sent.
+    pub fn from_provider(
+        dyn_provider: Arc<DynProvider>,
+        signer: PrivateKeySigner,
+        chain_config: ChainConfig,
+    ) -> Trade {
+        let wallet_address = signer.address();
 
         let bonding_curve_router = BondingCurveRouter::new(
-            bonding_curve_router_address,
-            bonding_curve_address,
+            chain_config.bonding_curve_router,
+            chain_config.bonding_curve,
             dyn_provider.clone(),
         );
 
-        let dex_router = DexRouter::new(dex_router_address, dyn_provider.clone());
-        let lens = LensContract::new(lens_address, dyn_provider.clone());
+        let dex_router = DexRouter::new(chain_config.dex_router, dyn_provider.clone());
+        let lens = LensContract::new(chain_config.lens, dyn_provider.clone());
 
-        Ok(Trade {
+        Trade {
             bonding_curve_router,
             dex_router,
             lens,
             provider: dyn_provider,
             wallet_address,
-        })
+            signer,
+            gas_config: None,
+            chain_config,
+            custom_routers: HashMap::new(),
+            allow_third_party_recipient: false,
+            token_state: None,
+            options: TradeOptions::default(),
+            token_locks: None,
+            risk: None,
+        }
+    }
+
+    /// Contract addresses this instance trades against
+    pub fn chain_config(&self) -> &ChainConfig {
+        &self.chain_config
+    }
+
+    /// Check that the configured lens, routers and bonding curve are deployed and respond
+    ///
+    /// `probe_token` is any token launched on nad.fun: the lens is asked for a
+    /// zero-amount buy quote of it and must answer with one of the configured
+    /// routers, and both routers must answer (or revert on) the same quote. Intended as a startup check; never fails on an unhealthy contract,
+    /// only on transport errors while fetching the chain id.
+    pub async fn validate_deployment(&self, probe_token: Address) -> Result<DeploymentDiagnostics> {
+        let chain_id = self.provider.get_chain_id().await?;
+        let config = &self.chain_config;
+
+        let mut checks = Vec::new();
+        for (name, address) in [
+            ("lens", config.lens),
+            ("bonding_curve", config.bonding_curve),
+            ("bonding_curve_router", config.bonding_curve_router),
+            ("dex_router", config.dex_router),
+            ("wmon", config.wmon),
+        ] {
+            let mut check = ContractCheck {
+                name,
+                address,
+                has_code: false,
+                responds: None,
+                error: None,
+            };
+
+            match self.provider.get_code_at(address).await {
+                Ok(code) => check.has_code = !code.is_empty(),
+                Err(e) => check.error = Some(e.to_string()),
+            }
+
+            // A quote proves the lens answers and still routes to a router we can call
+            if name == "lens" && check.has_code {
+                let quote = self
+                    .lens
+                    .get_amount_out(probe_token, U256::ZERO, true)
+                    .await
+                    .and_then(|(router, _)| self.resolve_router(router));
+                match quote {
+                    Ok(_) => check.responds = Some(true),
+                    Err(e) => {
+                        check.responds = Some(false);
+                        check.error = Some(e.to_string());
+                    }
+                }
+            }
+
+            // A zero-amount quote runs each router's view path; a revert (the token
+            // trades on the other venue) still proves the router executes
+            let router_quote = match name {
+                "bonding_curve_router" if check.has_code => Some(
+                    self.bonding_curve_router
+                        .get_amount_out(probe_token, U256::ZERO, true)
+                        .await,
+                ),
+                "dex_router" if check.has_code => Some(
+                    self.dex_router
+                        .get_amount_out(probe_token, U256::ZERO, true)
+                        .await,
+                ),
+                _ => None,
+            };
+            match router_quote {
+                Some(Ok(_)) => check.responds = Some(true),
+                Some(Err(e)) if is_revert(&e) => check.responds = Some(true),
+                Some(Err(e)) => {
+                    check.responds = Some(false);
+                    check.error = Some(e.to_string());
+                }
+                None => {}
+            }
+
+            // The curve exposes a cheap view that must succeed for any address
+            if name == "bonding_curve" && check.has_code {
+                match self.bonding_curve_router.is_listed(Address::ZERO).await {
+                    Ok(_) => check.responds = Some(true),
+                    Err(e) => {
+                        check.responds = Some(false);
+                        check.error = Some(e.to_string());
+                    }
+                }
+            }
+
+            checks.push(check);
+        }
+
+        Ok(DeploymentDiagnostics { chain_id, checks })
+    }
+
+    /// Fill `gas_limit` automatically for params that leave it as None
+    ///
+    /// See [`GasConfig`] for how limits are resolved.
+    pub fn with_gas_config(mut self, gas_config: GasConfig) -> Self {
+        self.gas_config = Some(gas_config);
+        self
+    }
+
+    pub fn gas_config(&self) -> Option<&GasConfig> {
+        self.gas_config.as_ref()
+    }
+
+    /// Defaults for slippage, deadline and gas where a trade leaves them unset
+    ///
+    /// See [`TradeOptions`] for where each default applies.
+    pub fn with_options(mut self, options: TradeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn options(&self) -> &TradeOptions {
+        &self.options
+    }
+
+    /// Serialize buys and sells of the same token through `locks`
+    ///
+    /// Trades of different tokens still run concurrently. Pass clones of one
+    /// [`TokenLocks`] to every `Trade` that signs for the same wallet.
+    pub fn with_token_locks(mut self, locks: TokenLocks) -> Self {
+        self.token_locks = Some(locks);
+        self
+    }
+
+    pub fn token_locks(&self) -> Option<&TokenLocks> {
+        self.token_locks.as_ref()
+    }
+
+    /// Wait for the token's lock, if token locks are enabled
+    async fn lock_token(&self, token: Address) -> Option<TokenLockGuard> {
+        match &self.token_locks {
+            Some(locks) => Some(locks.lock(token).await),
+            None => None,
+        }
+    }
+
+    /// Check every buy against `risk` before signing it
+    ///
+    /// Refused buys fail with a [`RiskViolation`](crate::trading::RiskViolation);
+    /// buys and sells are recorded in the manager's journal.
+    pub fn with_risk_manager(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    pub fn risk_manager(&self) -> Option<&RiskManager> {
+        self.risk.as_ref()
+    }
+
+    /// Let [`Trade::buy`] send tokens to a `to` other than the trading wallet
+    ///
+    /// Without this, such buys are rejected as a likely bug. [`Trade::buy_to`]
+    /// is the per-call alternative.
+    pub fn allow_third_party_recipient(mut self) -> Self {
+        self.allow_third_party_recipient = true;
+        self
+    }
+
+    pub fn allows_third_party_recipient(&self) -> bool {
+        self.allow_third_party_recipient
+    }
+
+    /// Accept an additional router address returned by the lens
+    ///
+    /// Lets trading continue across router upgrades without an SDK release:
+    /// the lens result is mapped to `Router::Custom(address, kind)` and calls are
+    /// encoded with the ABI of `kind`.
+    pub fn with_router(mut self, address: Address, kind: RouterKind) -> Self {
+        self.custom_routers.insert(address, kind);
+        self
+    }
+
+    /// Routers registered with [`Trade::with_router`]
+    pub fn custom_routers(&self) -> &HashMap<Address, RouterKind> {
+        &self.custom_routers
+    }
+
+    /// Map a router address returned by the lens to a known router
+    pub fn resolve_router(&self, router_address: Address) -> Result<Router> {
+        if router_address == self.dex_router.address {
+            Ok(Router::Dex(router_address))
+        } else if router_address == self.bonding_curve_router.address {
+            Ok(Router::BondingCurve(router_address))
+        } else if let Some(kind) = self.custom_routers.get(&router_address) {
+            Ok(Router::Custom(router_address, *kind))
+        } else {
+            Err(anyhow::anyhow!(
+                "Unknown router address: {} (register it with Trade::with_router)",
+                router_address
+            ))
+        }
+    }
+
+    /// Probe the version and ABI of a router, see [`probe_router`]
+    pub async fn router_info(&self, router: &Router) -> Result<RouterInfo> {
+        probe_router(&*self.provider, router).await
+    }
+
+    /// Probe the configured and custom routers
+    ///
+    /// Intended as a startup check next to [`Trade::validate_deployment`]: an
+    /// upgraded router is reported here instead of reverting mid-trade. Never
+    /// fails as a whole; each router comes with its own probe result, and
+    /// [`RouterInfo::warnings`] lists unknown versions and missing selectors.
+    pub async fn check_routers(&self) -> Vec<(Router, Result<RouterInfo>)> {
+        let mut routers = vec![
+            Router::BondingCurve(self.bonding_curve_router.address),
+            Router::Dex(self.dex_router.address),
+        ];
+        routers.extend(
+            self.custom_routers
+                .iter()
+                .map(|(address, kind)| Router::Custom(*address, *kind)),
+        );
+
+        let mut results = Vec::new();
+        for router in routers {
+            let info = self.router_info(&router).await;
+            results.push((router, info));
+        }
+        results
     }
 }
 
@@ -68,16 +418,7 @@ impl Trade {
         let (router_address, amount_out) =
             self.lens.get_amount_out(token, amount_in, is_buy).await?;
 
-        let router = if router_address == self.dex_router.address {
-            Router::Dex(router_address)
-        } else if router_address == self.bonding_curve_router.address {
-            Router::BondingCurve(router_address)
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unknown router address: {}",
-                router_address
-            ));
-        };
+        let router = self.resolve_router(router_address)?;
 
         Ok((router, amount_out))
     }
@@ -91,44 +432,247 @@ impl Trade {
         let (router_address, amount_in) =
             self.lens.get_amount_in(token, amount_out, is_buy).await?;
 
-        let router = if router_address == self.dex_router.address {
-            Router::Dex(router_address)
-        } else if router_address == self.bonding_curve_router.address {
-            Router::BondingCurve(router_address)
-        } else {
-            return Err(anyhow::anyhow!(
-                "Unknown router address: {}",
-                router_address
-            ));
-        };
+        let router = self.resolve_router(router_address)?;
 
         Ok((router, amount_in))
     }
 
+    /// [`Trade::get_amount_out`] against the state at `block`
+    ///
+    /// `BlockId::pending()` quotes on the node's pending state, which can better
+    /// reflect where a transaction sent now will land; `BlockId::number(n)` pins
+    /// an archive block.
+    pub async fn get_amount_out_at(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<(Router, U256)> {
+        let (router_address, amount_out) = self
+            .lens
+            .get_amount_out_at(token, amount_in, is_buy, block)
+            .await?;
+        Ok((self.resolve_router(router_address)?, amount_out))
+    }
+
+    /// [`Trade::get_amount_in`] against the state at `block`
+    pub async fn get_amount_in_at(
+        &self,
+        token: Address,
+        amount_out: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<(Router, U256)> {
+        let (router_address, amount_in) = self
+            .lens
+            .get_amount_in_at(token, amount_out, is_buy, block)
+            .await?;
+        Ok((self.resolve_router(router_address)?, amount_in))
+    }
+
+    /// Quote directly on the bonding curve router, skipping the lens
+    ///
+    /// Saves the lens round trip for tokens known to be pre-graduation. Trade the
+    /// result with `Router::BondingCurve`; after graduation the router rejects the
+    /// quote, and [`Trade::get_amount_out`] is needed to find the DEX route.
+    pub async fn quote_curve(&self, token: Address, amount_in: U256, is_buy: bool) -> Result<U256> {
+        self.bonding_curve_router
+            .get_amount_out(token, amount_in, is_buy)
+            .await
+    }
+
+    /// [`Trade::quote_curve`] against the state at `block`
+    pub async fn quote_curve_at(
+        &self,
+        token: Address,
+        amount_in: U256,
+        is_buy: bool,
+        block: BlockId,
+    ) -> Result<U256> {
+        self.bonding_curve_router
+            .get_amount_out_at(token, amount_in, is_buy, block)
+            .await
+    }
+
+    /// Buy with `params`
+    ///
+    /// Fails if `params.to` isn't the trading wallet, unless third-party
+    /// recipients are allowed (see [`Trade::allow_third_party_recipient`]).
     pub async fn buy(&self, params: BuyParams, router: Router) -> Result<TransactionResult> {
+        check_recipient(
+            self.wallet_address,
+            params.to,
+            self.allow_third_party_recipient,
+        )?;
+        self.submit_buy(params, router).await
+    }
+
+    /// Buy without the recipient check
+    pub(crate) async fn submit_buy(
+        &self,
+        params: BuyParams,
+        router: Router,
+    ) -> Result<TransactionResult> {
+        let Some(risk) = &self.risk else {
+            return self.send_buy(params, router).await;
+        };
+
+        let reservation = risk.check_buy(params.token, params.amount_in)?;
+        let result = self.send_buy(params, router).await;
+        // The reservation already holds the position slot
+        if !matches!(&result, Ok(result) if result.status) {
+            risk.release_buy(reservation);
+        }
+        result
+    }
+
+    /// Close the risk manager's position in `token` if a sell left none of it
+    async fn settle_sell(&self, token: Address, result: &Result<TransactionResult>) {
+        let (Some(risk), Ok(result)) = (&self.risk, result) else {
+            return;
+        };
+        if !result.status {
+            return;
+        }
+        let balance = IToken::new(token, self.provider.as_ref())
+            .balanceOf(self.wallet_address)
+            .call()
+            .await;
+        // If the balance can't be read the position stays open
+        if matches!(balance, Ok(balance) if balance.is_zero()) {
+            risk.close_position(token);
+        }
+    }
+
+    async fn send_buy(&self, mut params: BuyParams, router: Router) -> Result<TransactionResult> {
+        let _token_lock = self.lock_token(params.token).await;
+        if params.deadline.is_zero() {
+            params.deadline = self.options.deadline()?;
+        }
+        if params.gas_limit.is_none() {
+            params.gas_limit = self.configured_gas_limit(&router, (&params).into()).await?;
+        }
+
         match router {
             Router::Dex(_) => self.dex_router.buy(params).await,
             Router::BondingCurve(_) => self.bonding_curve_router.buy(params).await,
+            Router::Custom(address, RouterKind::Dex) => {
+                DexRouter::new(address, self.provider.clone())
+                    .buy(params)
+                    .await
+            }
+            Router::Custom(address, RouterKind::BondingCurve) => {
+                BondingCurveRouter::new(
+                    address,
+                    self.chain_config.bonding_curve,
+                    self.provider.clone(),
+                )
+                .buy(params)
+                .await
+            }
         }
     }
 
-    pub async fn sell(&self, params: SellParams, router: Router) -> Result<TransactionResult> {
-        match router {
+    /// Sell with `params`
+    ///
+    /// With [`Trade::with_token_locks`], waits for other trades of the same token.
+    pub async fn sell(&self, mut params: SellParams, router: Router) -> Result<TransactionResult> {
+        if let Some(risk) = &self.risk {
+            risk.check_sell(params.token, params.amount_in);
+        }
+        let _token_lock = self.lock_token(params.token).await;
+        if params.deadline.is_zero() {
+            params.deadline = self.options.deadline()?;
+        }
+        if params.gas_limit.is_none() {
+            params.gas_limit = self.configured_gas_limit(&router, (&params).into()).await?;
+        }
+
+        let token = params.token;
+        let result = match router {
             Router::Dex(_) => self.dex_router.sell(params).await,
             Router::BondingCurve(_) => self.bonding_curve_router.sell(params).await,
-        }
+            Router::Custom(address, RouterKind::Dex) => {
+                DexRouter::new(address, self.provider.clone())
+                    .sell(params)
+                    .await
+            }
+            Router::Custom(address, RouterKind::BondingCurve) => {
+                BondingCurveRouter::new(
+                    address,
+                    self.chain_config.bonding_curve,
+                    self.provider.clone(),
+                )
+                .sell(params)
+                .await
+            }
+        };
+        self.settle_sell(token, &result).await;
+        result
     }
 
     /// Sell tokens using SellPermitParams struct
     /// User must provide valid permit signature (v, r, s)
     pub async fn sell_permit(
         &self,
-        params: SellPermitParams,
+        mut params: SellPermitParams,
         router: Router,
     ) -> Result<TransactionResult> {
-        match router {
+        if let Some(risk) = &self.risk {
+            risk.check_sell(params.token, params.amount_in);
+        }
+        let _token_lock = self.lock_token(params.token).await;
+        if params.gas_limit.is_none() {
+            params.gas_limit = self.configured_gas_limit(&router, (&params).into()).await?;
+        }
+
+        let token = params.token;
+        let result = match router {
             Router::Dex(_) => self.dex_router.sell_permit(params).await,
             Router::BondingCurve(_) => self.bonding_curve_router.sell_permit(params).await,
+            Router::Custom(address, RouterKind::Dex) => {
+                DexRouter::new(address, self.provider.clone())
+                    .sell_permit(params)
+                    .await
+            }
+            Router::Custom(address, RouterKind::BondingCurve) => {
+                BondingCurveRouter::new(
+                    address,
+                    self.chain_config.bonding_curve,
+                    self.provider.clone(),
+                )
+                .sell_permit(params)
+                .await
+            }
+        };
+        self.settle_sell(token, &result).await;
+        result
+    }
+
+    /// Resolve a gas limit from the configured [`GasConfig`], if any
+    async fn configured_gas_limit(
+        &self,
+        router: &Router,
+        params: TradeOperation,
+    ) -> Result<Option<u64>> {
+        match self.options.default_gas_strategy {
+            GasMode::Limit(limit) => return Ok(Some(limit)),
+            GasMode::Auto => {
+                let estimated = self.estimate_gas(router, params).await?;
+                let buffer = self.gas_config.clone().unwrap_or_default();
+                return Ok(Some(buffer.apply_buffer(estimated)));
+            }
+            GasMode::Default => {}
+        }
+
+        match &self.gas_config {
+            Some(config) => Ok(Some(
+                config
+                    .resolve(self.provider.clone(), router, self.wallet_address(), params)
+                    .await?,
+            )),
+            None => Ok(None),
         }
     }
 
@@ -141,13 +685,29 @@ impl Trade {
         self.bonding_curve_router.get_curve_state(token).await
     }
 
+    /// Answer `is_listed`/`is_locked` from a shared [`TokenStateCache`]
+    pub fn with_token_state_cache(mut self, cache: TokenStateCache) -> Self {
+        self.token_state = Some(cache);
+        self
+    }
+
+    pub fn token_state_cache(&self) -> Option<&TokenStateCache> {
+        self.token_state.as_ref()
+    }
+
     // Utility functions
     pub async fn is_listed(&self, token: Address) -> Result<bool> {
-        self.bonding_curve_router.is_listed(token).await
+        match &self.token_state {
+            Some(cache) => cache.is_listed(token).await,
+            None => self.bonding_curve_router.is_listed(token).await,
+        }
     }
 
     pub async fn is_locked(&self, token: Address) -> Result<bool> {
-        self.bonding_curve_router.is_locked(token).await
+        match &self.token_state {
+            Some(cache) => cache.is_locked(token).await,
+            None => self.bonding_curve_router.is_locked(token).await,
+        }
     }
 
     // Access to individual routers (advanced usage)
@@ -171,16 +731,30 @@ impl Trade {
         self.wallet_address
     }
 
+    pub(crate) fn signer(&self) -> &PrivateKeySigner {
+        &self.signer
+    }
+
+    /// Wait for a previously submitted transaction to be mined
+    pub async fn wait_for_transaction(
+        &self,
+        tx_hash: alloy::primitives::B256,
+        timeout: std::time::Duration,
+    ) -> Result<TransactionResult> {
+        crate::trading::TxUtils::wait_for_receipt(self.provider.as_ref(), tx_hash, timeout).await
+    }
+
     /// Estimate gas for trading operations using the unified gas estimation system
     ///
-    /// This is a convenience method that wraps the standalone estimate_gas function
-    /// and automatically provides the provider and handles the common use case.
+    /// The transaction is simulated from this trade's wallet (not the recipient),
+    /// and buys carry `amount_in` as value. Sell estimates require the router to
+    /// already hold an allowance; for sell permit, see [`Trade::estimate_sell_permit_gas`].
     ///
     /// # Example
     /// ```rust,ignore
-    /// use nadfun_sdk::{Trade, GasEstimationParams};
+    /// use nadfun_sdk::{Trade, TradeOperation};
     ///
-    /// let params = GasEstimationParams::Buy {
+    /// let params = TradeOperation::Buy {
     ///     token,
     ///     amount_in: mon_amount,
     ///     amount_out_min: min_tokens,
@@ -191,11 +765,126 @@ impl Trade {
     /// let estimated_gas = trade.estimate_gas(&router, params).await?;
     /// let gas_with_buffer = estimated_gas * 120 / 100; // Add 20% buffer
     /// ```
-    pub async fn estimate_gas(
+    pub async fn estimate_gas(&self, router: &Router, params: TradeOperation) -> Result<u64> {
+        estimate_gas_from(self.provider.clone(), router, self.wallet_address, params).await
+    }
+
+    /// Estimate gas for a sell permit without managing signatures yourself
+    ///
+    /// Signs a throwaway permit for `amount_in` with this trade's wallet and the
+    /// router as spender. The permit is only used in `eth_estimateGas` and never
+    /// broadcast, so it doesn't consume the permit nonce.
+    pub async fn estimate_sell_permit_gas(
         &self,
         router: &Router,
-        params: GasEstimationParams,
+        token: Address,
+        amount_in: U256,
+        amount_out_min: U256,
     ) -> Result<u64> {
-        estimate_gas(self.provider.clone(), router, params).await
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?;
+        let deadline = U256::from(now.as_secs() + 3600);
+
+        let (v, r, s) = sign_permit(
+            self.provider.as_ref(),
+            &self.signer,
+            token,
+            self.wallet_address,
+            router.address(),
+            amount_in,
+            deadline,
+        )
+        .await?;
+
+        let params = TradeOperation::SellPermit {
+            token,
+            amount_in,
+            amount_out_min,
+            to: self.wallet_address,
+            deadline,
+            amount_allowance: amount_in,
+            v,
+            r: r.0,
+            s: s.0,
+        };
+
+        self.estimate_gas(router, params).await
+    }
+
+    /// Refresh default gas limits by estimating each operation against the live network
+    ///
+    /// Quotes a buy of `mon_amount` to find the router the token currently trades on,
+    /// then estimates buy, sell (using the wallet's token balance, which must be
+    /// approved for the router) and sell permit (with a throwaway permit signature).
+    /// Measurements get `buffer_percent` added and are merged into the current
+    /// default table; call [`GasCalibration::apply`] to install the result or
+    /// `calibration.table.save(path)` to persist it.
+    pub async fn calibrate_gas(
+        &self,
+        token: Address,
+        mon_amount: U256,
+        buffer_percent: u64,
+    ) -> Result<GasCalibration> {
+        let wallet = self.wallet_address;
+        let deadline = U256::from(u64::MAX);
+        let (router, _) = self.get_amount_out(token, mon_amount, true).await?;
+
+        let token_balance = IToken::new(token, self.provider.as_ref())
+            .balanceOf(wallet)
+            .call()
+            .await?;
+
+        let mut measured = Vec::new();
+        let mut skipped = Vec::new();
+
+        for operation in Operation::ALL {
+            let estimate = match operation {
+                Operation::Buy => {
+                    let params = TradeOperation::Buy {
+                        token,
+                        amount_in: mon_amount,
+                        amount_out_min: U256::ZERO,
+                        to: wallet,
+                        deadline,
+                    };
+                    self.estimate_gas(&router, params).await
+                }
+                _ if token_balance.is_zero() => {
+                    skipped.push((operation, "wallet holds no tokens to sell".to_string()));
+                    continue;
+                }
+                Operation::Sell => {
+                    let params = TradeOperation::Sell {
+                        token,
+                        amount_in: token_balance,
+                        amount_out_min: U256::ZERO,
+                        to: wallet,
+                        deadline,
+                    };
+                    self.estimate_gas(&router, params).await
+                }
+                Operation::SellPermit => {
+                    self.estimate_sell_permit_gas(&router, token, token_balance, U256::ZERO)
+                        .await
+                }
+            };
+
+            match estimate {
+                Ok(gas) => measured.push((operation, gas)),
+                Err(e) => skipped.push((operation, e.to_string())),
+            }
+        }
+
+        let buffer = GasConfig::default().with_buffer_pct(buffer_percent);
+        let mut table = default_gas_table();
+        for (operation, gas) in &measured {
+            table.set(&router, *operation, buffer.apply_buffer(*gas));
+        }
+
+        Ok(GasCalibration {
+            router,
+            measured,
+            skipped,
+            table,
+        })
     }
 }
@@ -1,4 +1,14 @@
-use alloy::primitives::U256;
+use crate::{
+    oracle::curve_price,
+    stream::CurveIndexer,
+    types::{BondingCurveEvent, CurveState, EventType, TransactionResult},
+};
+use alloy::{
+    primitives::{Address, B256, U256},
+    providers::Provider,
+};
+use anyhow::Result;
+use std::time::{Duration, Instant};
 
 /// Utility functions for calculating amounts with slippage protection
 pub struct SlippageUtils;
@@ -13,7 +23,7 @@ impl SlippageUtils {
     /// # Returns
     /// Minimum amount out considering slippage
     pub fn calculate_amount_out_min(amount_out: U256, slippage_percent: f64) -> U256 {
-        if slippage_percent < 0.0 || slippage_percent >= 100.0 {
+        if !(0.0..100.0).contains(&slippage_percent) {
             return U256::ZERO; // Invalid slippage
         }
 
@@ -32,7 +42,7 @@ impl SlippageUtils {
     /// # Returns
     /// Maximum amount in considering slippage
     pub fn calculate_amount_in_max(amount_in: U256, slippage_percent: f64) -> U256 {
-        if slippage_percent < 0.0 || slippage_percent >= 100.0 {
+        if !(0.0..100.0).contains(&slippage_percent) {
             return U256::MAX; // Invalid slippage
         }
 
@@ -41,6 +51,194 @@ impl SlippageUtils {
         let total_bp = 10000 + slippage_bp;
         amount_in * U256::from(total_bp) / U256::from(10000)
     }
+
+    /// Suggest a slippage tolerance for `token` from its last `window_blocks` of curve trades
+    ///
+    /// Uses the price after every trade (Sync events), so it only works before
+    /// graduation; for pool trading feed swap prices to [`SlippageUtils::suggest_from_prices`].
+    pub async fn suggest<P: Provider + Clone>(
+        indexer: &CurveIndexer<P>,
+        token: Address,
+        window_blocks: u64,
+    ) -> Result<SlippageSuggestion> {
+        let to_block = indexer.provider().get_block_number().await?;
+        let from_block = to_block.saturating_sub(window_blocks);
+        let events = indexer
+            .fetch_events(from_block, to_block, vec![EventType::Sync], Some(vec![token]))
+            .await?;
+
+        let prices: Vec<f64> = events
+            .iter()
+            .filter_map(|event| match event {
+                BondingCurveEvent::Sync(sync) if !sync.removed => Some(curve_price(
+                    sync.virtual_mon_reserve,
+                    sync.virtual_token_reserve,
+                )),
+                _ => None,
+            })
+            .collect();
+
+        Self::suggest_from_prices(&prices).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Only {} trades of {} in the last {} blocks, too few to estimate volatility",
+                prices.len(),
+                token,
+                window_blocks
+            )
+        })
+    }
+
+    /// Suggest a slippage tolerance from consecutive trade prices (oldest first)
+    ///
+    /// The tolerance covers a one-sided 95% adverse move between two trades,
+    /// assuming normally distributed log returns. The bounds are the same
+    /// tolerance at the ends of the 95% confidence interval of the volatility,
+    /// so they tighten as more trades are sampled. None with fewer than 3 prices.
+    pub fn suggest_from_prices(prices: &[f64]) -> Option<SlippageSuggestion> {
+        let returns: Vec<f64> = prices
+            .iter()
+            .filter(|price| price.is_finite() && **price > 0.0)
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| (pair[1] / pair[0]).ln())
+            .collect();
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let volatility = variance.sqrt();
+
+        // Normal approximation of the volatility's sampling error
+        let margin = 1.96 / (2.0 * (n - 1.0)).sqrt();
+        let to_percent = |sigma: f64| {
+            let percent = (1.0 - (-SLIPPAGE_Z_SCORE * sigma).exp()) * 100.0;
+            percent.clamp(MIN_SUGGESTED_SLIPPAGE, MAX_SUGGESTED_SLIPPAGE)
+        };
+        let upper_percent = if margin < 1.0 {
+            to_percent(volatility / (1.0 - margin))
+        } else {
+            MAX_SUGGESTED_SLIPPAGE
+        };
+
+        Some(SlippageSuggestion {
+            percent: to_percent(volatility),
+            lower_percent: to_percent(volatility / (1.0 + margin)),
+            upper_percent,
+            volatility,
+            samples: returns.len(),
+        })
+    }
+}
+
+/// One-sided 95% quantile of the standard normal distribution
+const SLIPPAGE_Z_SCORE: f64 = 1.645;
+/// Suggestions never go below this, to absorb rounding and fee changes
+pub const MIN_SUGGESTED_SLIPPAGE: f64 = 0.1;
+/// Suggestions never exceed this; wider tolerances invite sandwiching
+pub const MAX_SUGGESTED_SLIPPAGE: f64 = 50.0;
+
+/// Slippage tolerance suggested from recent volatility, in percent (1.0 = 1%)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlippageSuggestion {
+    /// Pass to [`SlippageUtils::calculate_amount_out_min`]
+    pub percent: f64,
+    /// Suggestion at the low end of the volatility's confidence interval
+    pub lower_percent: f64,
+    /// Suggestion at the high end of the volatility's confidence interval
+    pub upper_percent: f64,
+    /// Standard deviation of trade-to-trade log returns
+    pub volatility: f64,
+    /// Number of trade-to-trade returns the estimate is based on
+    pub samples: usize,
+}
+
+impl SlippageSuggestion {
+    /// [`SlippageSuggestion::percent`] in basis points, e.g. for the order builder
+    pub fn bps(&self) -> u64 {
+        (self.percent * 100.0).ceil() as u64
+    }
+}
+
+/// Order sizing from balances and curve reserves
+///
+/// Percentages are basis points (100 = 1%) and are capped at 10000. Results
+/// round down, so they never exceed what they were derived from.
+pub struct SizingUtils;
+
+impl SizingUtils {
+    const MAX_BPS: u64 = 10_000;
+
+    /// Largest MON amount to buy with while keeping `gas_reserve` for fees
+    ///
+    /// Zero if the balance doesn't cover the reserve.
+    pub fn max_affordable_buy(wallet_balance: U256, gas_reserve: U256) -> U256 {
+        wallet_balance.saturating_sub(gas_reserve)
+    }
+
+    /// MON amount equal to `bps` of the curve's virtual MON reserve
+    ///
+    /// The virtual reserve sets the curve price, so this bounds the price impact
+    /// of a buy independent of the token's progress.
+    pub fn size_by_pct_of_reserves(curve_state: &CurveState, bps: u64) -> U256 {
+        Self::apply_bps(curve_state.virtual_mon_reserve, bps)
+    }
+
+    /// Token amount equal to `bps` of `holdings`
+    ///
+    /// 10000 bps returns `holdings` exactly, leaving no dust behind.
+    pub fn sell_by_pct_of_holdings(holdings: U256, bps: u64) -> U256 {
+        Self::apply_bps(holdings, bps)
+    }
+
+    fn apply_bps(amount: U256, bps: u64) -> U256 {
+        let bps = bps.min(Self::MAX_BPS);
+        if bps == Self::MAX_BPS {
+            return amount;
+        }
+        // Divide first when the product would overflow
+        match amount.checked_mul(U256::from(bps)) {
+            Some(product) => product / U256::from(Self::MAX_BPS),
+            None => amount / U256::from(Self::MAX_BPS) * U256::from(bps),
+        }
+    }
+}
+
+/// Utility functions for submitted transactions
+pub struct TxUtils;
+
+impl TxUtils {
+    /// Default interval between receipt polls
+    pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Wait for a submitted transaction to be mined
+    ///
+    /// Polls for the receipt until it is available or `timeout` elapses.
+    /// Pairs with the `*_submit` methods that return only the pending hash.
+    pub async fn wait_for_receipt<P: Provider>(
+        provider: &P,
+        tx_hash: B256,
+        timeout: Duration,
+    ) -> Result<TransactionResult> {
+        let started = Instant::now();
+
+        loop {
+            if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? {
+                return Ok(TransactionResult::from(receipt));
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(anyhow::anyhow!(
+                    "Timed out waiting for receipt of {}",
+                    tx_hash
+                ));
+            }
+
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -48,6 +246,29 @@ mod tests {
     use super::*;
     use alloy::primitives::U256;
 
+    #[test]
+    fn test_suggest_from_prices() {
+        assert_eq!(SlippageUtils::suggest_from_prices(&[1.0, 1.01]), None);
+
+        // A flat price still gets the minimum tolerance
+        let flat = SlippageUtils::suggest_from_prices(&[1.0; 10]).unwrap();
+        assert_eq!(flat.percent, MIN_SUGGESTED_SLIPPAGE);
+
+        // Alternating +-1% moves: volatility ~1%, tolerance ~1.6%
+        let prices: Vec<f64> = (0..41)
+            .map(|i| if i % 2 == 0 { 1.0 } else { 1.01 })
+            .collect();
+        let choppy = SlippageUtils::suggest_from_prices(&prices).unwrap();
+        assert_eq!(choppy.samples, 40);
+        assert!((choppy.percent - 1.62).abs() < 0.05, "{}", choppy.percent);
+        assert!(choppy.lower_percent < choppy.percent && choppy.percent < choppy.upper_percent);
+        assert_eq!(choppy.bps(), 165);
+
+        // Fewer samples, wider bounds
+        let short = SlippageUtils::suggest_from_prices(&prices[..5]).unwrap();
+        assert!(short.upper_percent - short.lower_percent > choppy.upper_percent - choppy.lower_percent);
+    }
+
     #[test]
     fn test_calculate_amount_out_min() {
         let amount_out = U256::from(1000000000000000000u64); // 1 token
@@ -85,4 +306,48 @@ mod tests {
         let max_in = SlippageUtils::calculate_amount_in_max(amount_in, 0.5);
         assert_eq!(max_in, U256::from(1005000000000000000u64)); // 1.005 ETH
     }
+
+    #[test]
+    fn test_sizing_utils() {
+        let one = U256::from(10u64).pow(U256::from(18));
+
+        assert_eq!(
+            SizingUtils::max_affordable_buy(one, one / U256::from(10)),
+            one * U256::from(9) / U256::from(10)
+        );
+        assert_eq!(
+            SizingUtils::max_affordable_buy(one, one * U256::from(2)),
+            U256::ZERO
+        );
+
+        let holdings = U256::from(1_000_001u64);
+        assert_eq!(
+            SizingUtils::sell_by_pct_of_holdings(holdings, 10_000),
+            holdings
+        );
+        assert_eq!(
+            SizingUtils::sell_by_pct_of_holdings(holdings, 20_000),
+            holdings
+        );
+        assert_eq!(
+            SizingUtils::sell_by_pct_of_holdings(holdings, 5_000),
+            U256::from(500_000u64)
+        );
+        assert_eq!(
+            SizingUtils::sell_by_pct_of_holdings(U256::MAX, 5_000),
+            U256::MAX / U256::from(10_000) * U256::from(5_000)
+        );
+
+        let state = CurveState {
+            real_mon_reserve: U256::ZERO,
+            real_token_reserve: U256::ZERO,
+            virtual_mon_reserve: one * U256::from(100),
+            virtual_token_reserve: U256::ZERO,
+            k: U256::ZERO,
+            target_token_amount: U256::ZERO,
+            init_virtual_mon_reserve: U256::ZERO,
+            init_virtual_token_reserve: U256::ZERO,
+        };
+        assert_eq!(SizingUtils::size_by_pct_of_reserves(&state, 100), one);
+    }
 }
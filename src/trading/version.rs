@@ -0,0 +1,278 @@
+//! Router version and ABI feature detection
+//!
+//! A router upgrade that changes the trading ABI would otherwise surface as a
+//! revert in the middle of a trade. [`probe_router`] reports what a deployed
+//! router looks like before any trade is sent:
+//!
+//! - its `version()` string, when it has such a getter
+//! - whether each selector the SDK calls appears in its bytecode, looking
+//!   through EIP-1967 proxies to the implementation
+//!
+//! ```rust,ignore
+//! for (router, info) in trade.check_routers().await {
+//!     match info {
+//!         Ok(info) => println!("{} {:?} compatible: {}", info.address, info.version, info.is_compatible()),
+//!         Err(e) => println!("could not probe {}: {}", router.address(), e),
+//!     }
+//! }
+//! ```
+
+use crate::{
+    contracts::{bonding_curve::IBondingCurveRouter, dex::IDexRouter},
+    types::{Router, RouterKind},
+};
+use alloy::{
+    primitives::{Address, B256, Bytes, U256, b256},
+    providers::Provider,
+    sol,
+    sol_types::SolCall,
+};
+use anyhow::Result;
+
+sol! {
+    #[sol(rpc)]
+    interface IVersioned {
+        function version() external view returns (string);
+    }
+}
+
+/// Router versions this SDK was tested against
+///
+/// The current routers have no `version()` getter, which also counts as known.
+pub const KNOWN_ROUTER_VERSIONS: &[&str] = &[];
+
+/// EIP-1967 implementation slot: `keccak256("eip1967.proxy.implementation") - 1`
+const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// `PUSH4`, which Solidity's function dispatcher uses to compare selectors
+const PUSH4: u8 = 0x63;
+
+/// Whether the router bytecode contains a selector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorStatus {
+    Present,
+    Missing,
+    /// The bytecode couldn't be inspected (e.g. a non-standard proxy)
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorCheck {
+    pub function: &'static str,
+    pub selector: [u8; 4],
+    pub status: SelectorStatus,
+}
+
+/// What a deployed router looks like
+#[derive(Debug, Clone)]
+pub struct RouterInfo {
+    pub address: Address,
+    pub kind: RouterKind,
+    /// Result of `version()`, None if the router has no such getter
+    pub version: Option<String>,
+    /// Implementation behind an EIP-1967 proxy
+    pub implementation: Option<Address>,
+    pub selectors: Vec<SelectorCheck>,
+}
+
+impl RouterInfo {
+    /// False if any selector the SDK calls is missing from the bytecode
+    pub fn is_compatible(&self) -> bool {
+        self.selectors
+            .iter()
+            .all(|check| check.status != SelectorStatus::Missing)
+    }
+
+    /// True if the router reports a version outside [`KNOWN_ROUTER_VERSIONS`]
+    pub fn is_unknown_version(&self) -> bool {
+        self.version
+            .as_deref()
+            .is_some_and(|version| !KNOWN_ROUTER_VERSIONS.contains(&version))
+    }
+
+    pub fn missing_selectors(&self) -> Vec<&'static str> {
+        self.selectors
+            .iter()
+            .filter(|check| check.status == SelectorStatus::Missing)
+            .map(|check| check.function)
+            .collect()
+    }
+
+    /// Human-readable problems, empty when the router looks as expected
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.is_unknown_version() {
+            warnings.push(format!(
+                "Router {} reports unknown version {:?}",
+                self.address,
+                self.version.as_deref().unwrap_or_default()
+            ));
+        }
+        let missing = self.missing_selectors();
+        if !missing.is_empty() {
+            warnings.push(format!(
+                "Router {} is missing {} (ABI changed?)",
+                self.address,
+                missing.join(", ")
+            ));
+        }
+        warnings
+    }
+}
+
+/// Functions the SDK calls on a router of `kind`
+pub fn expected_selectors(kind: RouterKind) -> Vec<(&'static str, [u8; 4])> {
+    match kind {
+        RouterKind::BondingCurve => vec![
+            ("buy", IBondingCurveRouter::buyCall::SELECTOR),
+            ("sell", IBondingCurveRouter::sellCall::SELECTOR),
+            ("sellPermit", IBondingCurveRouter::sellPermitCall::SELECTOR),
+            (
+                "getAmountOut",
+                IBondingCurveRouter::getAmountOutCall::SELECTOR,
+            ),
+            (
+                "getAmountIn",
+                IBondingCurveRouter::getAmountInCall::SELECTOR,
+            ),
+            (
+                "availableBuyTokens",
+                IBondingCurveRouter::availableBuyTokensCall::SELECTOR,
+            ),
+        ],
+        RouterKind::Dex => vec![
+            ("buy", IDexRouter::buyCall::SELECTOR),
+            ("sell", IDexRouter::sellCall::SELECTOR),
+            ("sellPermit", IDexRouter::sellPermitCall::SELECTOR),
+            ("getAmountOut", IDexRouter::getAmountOutCall::SELECTOR),
+            ("getAmountIn", IDexRouter::getAmountInCall::SELECTOR),
+        ],
+    }
+}
+
+/// Look up each selector as a `PUSH4` operand in `code`
+///
+/// Code without any `PUSH4` can't be a Solidity dispatcher (typically a proxy
+/// we couldn't follow), so every selector is reported as unknown.
+pub fn scan_selectors(code: &[u8], selectors: &[(&'static str, [u8; 4])]) -> Vec<SelectorCheck> {
+    let pushed = push4_operands(code);
+    selectors
+        .iter()
+        .map(|&(function, selector)| SelectorCheck {
+            function,
+            selector,
+            status: if pushed.is_empty() {
+                SelectorStatus::Unknown
+            } else if pushed.contains(&selector) {
+                SelectorStatus::Present
+            } else {
+                SelectorStatus::Missing
+            },
+        })
+        .collect()
+}
+
+/// Operands of every `PUSH4`, skipping over other push data
+fn push4_operands(code: &[u8]) -> Vec<[u8; 4]> {
+    let mut operands = Vec::new();
+    let mut i = 0;
+    while i < code.len() {
+        let op = code[i];
+        if op == PUSH4 {
+            if let Some(operand) = code.get(i + 1..i + 5) {
+                operands.push(operand.try_into().expect("slice of length 4"));
+            }
+        }
+        // PUSH1..PUSH32 carry 1..32 bytes of immediate data
+        let immediate = if (0x60..=0x7f).contains(&op) {
+            (op - 0x5f) as usize
+        } else {
+            0
+        };
+        i += 1 + immediate;
+    }
+    operands
+}
+
+/// Probe the version and ABI of a deployed router
+///
+/// Fails only if the router has no code or an RPC call fails.
+pub async fn probe_router<P: Provider>(provider: &P, router: &Router) -> Result<RouterInfo> {
+    let address = router.address();
+    let code = provider.get_code_at(address).await?;
+    if code.is_empty() {
+        return Err(anyhow::anyhow!(
+            "No contract deployed at router {}",
+            address
+        ));
+    }
+
+    let slot = provider
+        .get_storage_at(address, U256::from_be_bytes(EIP1967_IMPLEMENTATION_SLOT.0))
+        .await?;
+    let implementation = (slot != U256::ZERO).then(|| Address::from_word(B256::from(slot)));
+    let code: Bytes = match implementation {
+        Some(implementation) => provider.get_code_at(implementation).await?,
+        None => code,
+    };
+
+    // Routers without the getter revert or return nothing
+    let version = IVersioned::new(address, provider)
+        .version()
+        .call()
+        .await
+        .ok()
+        .filter(|version| !version.is_empty());
+
+    Ok(RouterInfo {
+        address,
+        kind: router.kind(),
+        version,
+        implementation,
+        selectors: scan_selectors(&code, &expected_selectors(router.kind())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_selectors() {
+        let buy = IDexRouter::buyCall::SELECTOR;
+        let sell = IDexRouter::sellCall::SELECTOR;
+        let selectors = [("buy", buy), ("sell", sell)];
+
+        // PUSH4 buy, then a PUSH32 whose data happens to contain the sell selector
+        let mut code = vec![PUSH4];
+        code.extend_from_slice(&buy);
+        code.push(0x7f);
+        let mut data = [0u8; 32];
+        data[0] = PUSH4;
+        data[1..5].copy_from_slice(&sell);
+        code.extend_from_slice(&data);
+
+        let checks = scan_selectors(&code, &selectors);
+        assert_eq!(checks[0].status, SelectorStatus::Present);
+        assert_eq!(checks[1].status, SelectorStatus::Missing);
+
+        let checks = scan_selectors(&[0x60, 0x00], &selectors);
+        assert!(
+            checks
+                .iter()
+                .all(|check| check.status == SelectorStatus::Unknown)
+        );
+
+        let info = RouterInfo {
+            address: Address::ZERO,
+            kind: RouterKind::Dex,
+            version: Some("9.9.9".to_string()),
+            implementation: None,
+            selectors: scan_selectors(&code, &selectors),
+        };
+        assert!(!info.is_compatible());
+        assert!(info.is_unknown_version());
+        assert_eq!(info.warnings().len(), 2);
+    }
+}
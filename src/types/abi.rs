@@ -0,0 +1,132 @@
+//! Event ABIs and topics decoded by the SDK
+//!
+//! Generated from the same `sol!` definitions the decoders use, so external
+//! indexers (or code in other languages) can subscribe to and decode exactly
+//! what the SDK does:
+//!
+//! ```rust,ignore
+//! for event in nadfun_sdk::supported_event_abis() {
+//!     println!("{:?} {} {}", event.contract, event.signature, event.topic0);
+//! }
+//! let abi = nadfun_sdk::supported_events_abi(); // alloy JsonAbi, serde-serializable
+//! ```
+
+use crate::types::{DexEventType, EventType};
+use alloy::{
+    json_abi::{Event, JsonAbi},
+    primitives::B256,
+};
+use serde::{Deserialize, Serialize};
+
+/// Contract emitting an event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventContract {
+    /// The Nad.fun bonding curve
+    BondingCurve,
+    /// Any Uniswap V3 pool of a graduated token
+    UniswapV3Pool,
+}
+
+/// One supported event: where it comes from, its signature and ABI item
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventAbi {
+    pub contract: EventContract,
+    pub name: String,
+    /// Canonical signature, e.g. `CurveBuy(address,address,uint256,uint256)`
+    pub signature: String,
+    /// keccak256 of `signature`, the log's first topic
+    pub topic0: B256,
+    pub abi: Event,
+}
+
+impl EventAbi {
+    fn new(contract: EventContract, signature: &str, topic0: B256, abi: Event) -> Self {
+        Self {
+            contract,
+            name: abi.name.clone(),
+            signature: signature.to_string(),
+            topic0,
+            abi,
+        }
+    }
+}
+
+impl From<EventType> for EventAbi {
+    fn from(event_type: EventType) -> Self {
+        EventAbi::new(
+            EventContract::BondingCurve,
+            event_type.signature_string(),
+            event_type.signature(),
+            event_type.abi(),
+        )
+    }
+}
+
+impl From<DexEventType> for EventAbi {
+    fn from(event_type: DexEventType) -> Self {
+        EventAbi::new(
+            EventContract::UniswapV3Pool,
+            event_type.signature_string(),
+            event_type.signature(),
+            event_type.abi(),
+        )
+    }
+}
+
+/// Every curve and pool event the SDK decodes, curve events first
+pub fn supported_event_abis() -> Vec<EventAbi> {
+    EventType::all()
+        .into_iter()
+        .map(EventAbi::from)
+        .chain(DexEventType::all().into_iter().map(EventAbi::from))
+        .collect()
+}
+
+/// topic0 of every supported event, in the order of [`supported_event_abis`]
+pub fn supported_event_topics() -> Vec<B256> {
+    supported_event_abis()
+        .into_iter()
+        .map(|event| event.topic0)
+        .collect()
+}
+
+/// An events-only JSON ABI with every supported event
+///
+/// Curve and pool events have distinct names, so they fit in one ABI.
+pub fn supported_events_abi() -> JsonAbi {
+    let mut abi = JsonAbi::default();
+    for event in supported_event_abis() {
+        abi.events.entry(event.name).or_default().push(event.abi);
+    }
+    abi
+}
+
+/// [`supported_events_abi`] as a standard JSON ABI array
+#[cfg(feature = "abi-json")]
+pub fn supported_events_abi_json() -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&supported_events_abi())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::keccak256;
+
+    #[test]
+    fn test_event_abis_match_decoder_signatures() {
+        let events = supported_event_abis();
+        assert_eq!(events.len(), 10);
+
+        for event in &events {
+            assert_eq!(keccak256(event.signature.as_bytes()), event.topic0);
+            assert_eq!(event.abi.signature(), event.signature);
+            assert_eq!(event.abi.selector(), event.topic0);
+        }
+
+        let abi = supported_events_abi();
+        assert_eq!(abi.events.values().map(Vec::len).sum::<usize>(), 10);
+        assert!(abi.events.contains_key("CurveBuy"));
+        assert!(abi.events.contains_key("Swap"));
+    }
+}
@@ -0,0 +1,156 @@
+//! Address formatting for user interfaces
+//!
+//! [`AddressExt`] adds the helpers every front end ends up writing: the
+//! EIP-55 checksummed form, a `0x1234…abcd` short form, and a label that
+//! prefers a known name (from a [`NameResolver`]) over the short form:
+//!
+//! ```rust,ignore
+//! use nadfun_sdk::types::{AddressBook, AddressExt};
+//!
+//! println!("{}", token.checksummed()); // 0x7d4A…full checksummed address
+//! println!("{}", sender.short());      // 0x7d4A…c9E1
+//!
+//! let mut names = AddressBook::new();
+//! names.insert(router, "Nad.fun router");
+//! println!("{}", router.label(&names)); // Nad.fun router
+//! println!("{}", sender.label(&names)); // 0x7d4A…c9E1
+//! ```
+
+use alloy::primitives::Address;
+use std::collections::HashMap;
+
+/// Hex digits kept on each side by [`AddressExt::short`]
+pub const SHORT_ADDRESS_DIGITS: usize = 4;
+
+/// Looks up a display name for an address (ENS, a contact list, known contracts...)
+///
+/// Implemented for closures, so any lookup can be passed in:
+/// `address.label(&|a| ens_cache.get(&a).cloned())`.
+pub trait NameResolver {
+    fn resolve(&self, address: Address) -> Option<String>;
+}
+
+impl<F> NameResolver for F
+where
+    F: Fn(Address) -> Option<String>,
+{
+    fn resolve(&self, address: Address) -> Option<String> {
+        self(address)
+    }
+}
+
+/// In-memory names for known addresses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AddressBook {
+    names: HashMap<Address, String>,
+}
+
+impl AddressBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name `address`, replacing any previous name
+    pub fn insert(&mut self, address: Address, name: impl Into<String>) {
+        self.names.insert(address, name.into());
+    }
+
+    pub fn remove(&mut self, address: &Address) -> Option<String> {
+        self.names.remove(address)
+    }
+
+    pub fn get(&self, address: &Address) -> Option<&str> {
+        self.names.get(address).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+impl NameResolver for AddressBook {
+    fn resolve(&self, address: Address) -> Option<String> {
+        self.names.get(&address).cloned()
+    }
+}
+
+impl<A: Into<Address>, S: Into<String>> FromIterator<(A, S)> for AddressBook {
+    fn from_iter<I: IntoIterator<Item = (A, S)>>(iter: I) -> Self {
+        Self {
+            names: iter
+                .into_iter()
+                .map(|(address, name)| (address.into(), name.into()))
+                .collect(),
+        }
+    }
+}
+
+/// Display helpers for addresses
+pub trait AddressExt {
+    /// EIP-55 checksummed hex, e.g. `0x760AfE86e5de5fa0Ee542fc7B7B713e1c5425701`
+    fn checksummed(&self) -> String;
+
+    /// Checksummed form shortened to `0x1234…abcd`
+    fn short(&self) -> String {
+        self.short_with(SHORT_ADDRESS_DIGITS, SHORT_ADDRESS_DIGITS)
+    }
+
+    /// Checksummed form keeping `leading` and `trailing` hex digits around an ellipsis
+    ///
+    /// Returns the full address if nothing would be cut.
+    fn short_with(&self, leading: usize, trailing: usize) -> String {
+        let full = self.checksummed();
+        let digits = &full[2..];
+        if leading + trailing >= digits.len() {
+            return full;
+        }
+        format!(
+            "0x{}…{}",
+            &digits[..leading],
+            &digits[digits.len() - trailing..]
+        )
+    }
+
+    /// Name from `resolver`, or the short form if it has none
+    fn label<R: NameResolver + ?Sized>(&self, resolver: &R) -> String;
+}
+
+impl AddressExt for Address {
+    fn checksummed(&self) -> String {
+        self.to_checksum(None)
+    }
+
+    fn label<R: NameResolver + ?Sized>(&self, resolver: &R) -> String {
+        resolver.resolve(*self).unwrap_or_else(|| self.short())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::address;
+
+    #[test]
+    fn test_checksum_short_and_label() {
+        let wmon = address!("0x760afe86e5de5fa0ee542fc7b7b713e1c5425701");
+        assert_eq!(
+            wmon.checksummed(),
+            "0x760AfE86e5de5fa0Ee542fc7B7B713e1c5425701"
+        );
+        assert_eq!(wmon.short(), "0x760A…5701");
+        assert_eq!(wmon.short_with(6, 2), "0x760AfE…01");
+        assert_eq!(wmon.short_with(20, 20), wmon.checksummed());
+
+        let book: AddressBook = [(wmon, "WMON")].into_iter().collect();
+        assert_eq!(wmon.label(&book), "WMON");
+        assert_eq!(Address::ZERO.label(&book), "0x0000…0000");
+        assert_eq!(
+            Address::ZERO.label(&|_: Address| Some("zero".to_string())),
+            "zero"
+        );
+    }
+}
@@ -2,17 +2,29 @@
 //!
 //! Contains all bonding curve related event types, enums, and decoding logic.
 
+use crate::constants::BONDING_CURVE;
 use alloy::{
     primitives::{Address, B256, U256},
-    rpc::types::Log,
+    rpc::types::{Filter, Log},
+    json_abi::Event,
     sol,
-    sol_types::SolEvent,
+    sol_types::{
+        abi::{
+            self,
+            token::{PackedSeqToken, WordToken},
+        },
+        JsonAbiExt, SolEvent,
+    },
 };
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
 
 // Bonding curve contract interface for events
 sol! {
-    #[sol(rpc)]
+    #[allow(clippy::too_many_arguments)]
+    #[sol(rpc, abi)]
     contract IBondingCurve {
         event CurveCreate(
             address indexed creator,
@@ -55,7 +67,8 @@ sol! {
 }
 
 /// Event types that can be subscribed to
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum EventType {
     Create,
     Buy,
@@ -77,10 +90,101 @@ impl EventType {
             EventType::Listed => IBondingCurve::CurveTokenListed::SIGNATURE_HASH,
         }
     }
+
+    /// Canonical event signature, e.g. `CurveBuy(address,address,uint256,uint256)`
+    pub fn signature_string(&self) -> &'static str {
+        match self {
+            EventType::Create => IBondingCurve::CurveCreate::SIGNATURE,
+            EventType::Buy => IBondingCurve::CurveBuy::SIGNATURE,
+            EventType::Sell => IBondingCurve::CurveSell::SIGNATURE,
+            EventType::Sync => IBondingCurve::CurveSync::SIGNATURE,
+            EventType::Lock => IBondingCurve::CurveTokenLocked::SIGNATURE,
+            EventType::Listed => IBondingCurve::CurveTokenListed::SIGNATURE,
+        }
+    }
+
+    /// JSON ABI item of this event, as decoded by the SDK
+    pub fn abi(&self) -> Event {
+        match self {
+            EventType::Create => IBondingCurve::CurveCreate::abi(),
+            EventType::Buy => IBondingCurve::CurveBuy::abi(),
+            EventType::Sell => IBondingCurve::CurveSell::abi(),
+            EventType::Sync => IBondingCurve::CurveSync::abi(),
+            EventType::Lock => IBondingCurve::CurveTokenLocked::abi(),
+            EventType::Listed => IBondingCurve::CurveTokenListed::abi(),
+        }
+    }
+
+    /// Topic position of the indexed token address
+    pub fn token_topic_index(&self) -> usize {
+        match self {
+            EventType::Create | EventType::Buy | EventType::Sell => 2,
+            EventType::Sync | EventType::Lock | EventType::Listed => 1,
+        }
+    }
+
+    /// Every bonding curve event type
+    pub fn all() -> Vec<EventType> {
+        vec![
+            EventType::Create,
+            EventType::Buy,
+            EventType::Sell,
+            EventType::Sync,
+            EventType::Lock,
+            EventType::Listed,
+        ]
+    }
+}
+
+/// Log filter for bonding curve `event_types`, as used by the SDK's streams and indexers
+///
+/// `tokens` is applied as a topic filter when every event type indexes the
+/// token at the same position (Create/Buy/Sell, or Sync/Lock/Listed); for a mix
+/// the node can't filter by token, so check
+/// [`BondingCurveEvent::token`] after decoding. `range` sets from/to blocks.
+/// Decode the returned logs with [`decode_bonding_curve_event`].
+pub fn curve_filter(
+    event_types: &[EventType],
+    tokens: Option<&[Address]>,
+    range: Option<RangeInclusive<u64>>,
+) -> Filter {
+    let bonding_curve_address: Address = BONDING_CURVE
+        .parse()
+        .expect("Invalid bonding curve address");
+    let mut filter = Filter::new()
+        .address(bonding_curve_address)
+        .event_signature(signatures_for(event_types));
+
+    if let Some(tokens) = tokens {
+        let positions: Vec<usize> = event_types
+            .iter()
+            .map(|et| et.token_topic_index())
+            .collect();
+        let topics: Vec<B256> = tokens.iter().map(|token| token.into_word()).collect();
+        match positions.first() {
+            Some(1) if positions.iter().all(|&p| p == 1) => filter = filter.topic1(topics),
+            Some(2) if positions.iter().all(|&p| p == 2) => filter = filter.topic2(topics),
+            _ => {}
+        }
+    }
+
+    if let Some(range) = range {
+        filter = filter.from_block(*range.start()).to_block(*range.end());
+    }
+    filter
+}
+
+/// Log filter for the Create events of tokens launched by `creators`
+///
+/// The creator is the first indexed topic of `CurveCreate`, so the node does
+/// the filtering. `range` sets from/to blocks.
+pub fn creator_filter(creators: &[Address], range: Option<RangeInclusive<u64>>) -> Filter {
+    let topics: Vec<B256> = creators.iter().map(|creator| creator.into_word()).collect();
+    curve_filter(&[EventType::Create], None, range).topic1(topics)
 }
 
 /// Create event - when a new token is created
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CreateEvent {
     pub creator: Address,
     pub token: Address,
@@ -92,39 +196,48 @@ pub struct CreateEvent {
     pub virtual_token: U256,
     pub target_token_amount: U256,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Buy event - when someone buys tokens with MON
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BuyEvent {
     pub sender: Address,
     pub token: Address,
     pub amount_in: U256,
     pub amount_out: U256,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Sell event - when someone sells tokens for MON
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SellEvent {
     pub sender: Address,
     pub token: Address,
     pub amount_in: U256,
     pub amount_out: U256,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Sync event - when pool reserves are updated
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SyncEvent {
     pub token: Address,
     pub real_mon_reserve: U256,
@@ -132,34 +245,43 @@ pub struct SyncEvent {
     pub virtual_mon_reserve: U256,
     pub virtual_token_reserve: U256,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Lock event - when token trading is locked
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LockEvent {
     pub token: Address,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Listed event - when token is listed on Uniswap
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ListedEvent {
     pub token: Address,
     pub pool: Address,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 /// Unified event type for all bonding curve events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BondingCurveEvent {
     Create(CreateEvent),
     Buy(BuyEvent),
@@ -203,6 +325,28 @@ impl BondingCurveEvent {
         }
     }
 
+    pub fn block_hash(&self) -> B256 {
+        match self {
+            BondingCurveEvent::Create(e) => e.block_hash,
+            BondingCurveEvent::Buy(e) => e.block_hash,
+            BondingCurveEvent::Sell(e) => e.block_hash,
+            BondingCurveEvent::Sync(e) => e.block_hash,
+            BondingCurveEvent::Lock(e) => e.block_hash,
+            BondingCurveEvent::Listed(e) => e.block_hash,
+        }
+    }
+
+    pub fn transaction_hash(&self) -> B256 {
+        match self {
+            BondingCurveEvent::Create(e) => e.transaction_hash,
+            BondingCurveEvent::Buy(e) => e.transaction_hash,
+            BondingCurveEvent::Sell(e) => e.transaction_hash,
+            BondingCurveEvent::Sync(e) => e.transaction_hash,
+            BondingCurveEvent::Lock(e) => e.transaction_hash,
+            BondingCurveEvent::Listed(e) => e.transaction_hash,
+        }
+    }
+
     pub fn transaction_index(&self) -> u64 {
         match self {
             BondingCurveEvent::Create(e) => e.transaction_index,
@@ -224,42 +368,186 @@ impl BondingCurveEvent {
             BondingCurveEvent::Listed(e) => e.log_index,
         }
     }
+
+    /// Whether this event was removed by a reorg and should be invalidated
+    pub fn removed(&self) -> bool {
+        match self {
+            BondingCurveEvent::Create(e) => e.removed,
+            BondingCurveEvent::Buy(e) => e.removed,
+            BondingCurveEvent::Sell(e) => e.removed,
+            BondingCurveEvent::Sync(e) => e.removed,
+            BondingCurveEvent::Lock(e) => e.removed,
+            BondingCurveEvent::Listed(e) => e.removed,
+        }
+    }
+}
+
+/// Create event whose strings borrow from the log data
+///
+/// Name, symbol and URI stay borrowed unless they are not valid UTF-8, in which
+/// case they are decoded lossily like [`CreateEvent`]'s.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreateEventRef<'a> {
+    pub creator: Address,
+    pub token: Address,
+    pub pool: Address,
+    pub name: Cow<'a, str>,
+    pub symbol: Cow<'a, str>,
+    pub token_uri: Cow<'a, str>,
+    pub virtual_mon: U256,
+    pub virtual_token: U256,
+    pub target_token_amount: U256,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+impl CreateEventRef<'_> {
+    /// Copy the strings out of the log into a [`CreateEvent`]
+    pub fn into_owned(self) -> CreateEvent {
+        CreateEvent {
+            creator: self.creator,
+            token: self.token,
+            pool: self.pool,
+            name: self.name.into_owned(),
+            symbol: self.symbol.into_owned(),
+            token_uri: self.token_uri.into_owned(),
+            virtual_mon: self.virtual_mon,
+            virtual_token: self.virtual_token,
+            target_token_amount: self.target_token_amount,
+            block_number: self.block_number,
+            block_hash: self.block_hash,
+            transaction_hash: self.transaction_hash,
+            transaction_index: self.transaction_index,
+            log_index: self.log_index,
+            removed: self.removed,
+        }
+    }
+}
+
+/// Bonding curve event decoded without allocating, borrowing from its log
+///
+/// Only [`CreateEventRef`] borrows; the other events hold no heap data. Use it to
+/// filter or aggregate logs and call [`into_owned`](Self::into_owned) for the
+/// events you keep.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BondingCurveEventRef<'a> {
+    Create(CreateEventRef<'a>),
+    Buy(BuyEvent),
+    Sell(SellEvent),
+    Sync(SyncEvent),
+    Lock(LockEvent),
+    Listed(ListedEvent),
+}
+
+impl BondingCurveEventRef<'_> {
+    pub fn token(&self) -> Address {
+        match self {
+            BondingCurveEventRef::Create(e) => e.token,
+            BondingCurveEventRef::Buy(e) => e.token,
+            BondingCurveEventRef::Sell(e) => e.token,
+            BondingCurveEventRef::Sync(e) => e.token,
+            BondingCurveEventRef::Lock(e) => e.token,
+            BondingCurveEventRef::Listed(e) => e.token,
+        }
+    }
+
+    pub fn event_type(&self) -> EventType {
+        match self {
+            BondingCurveEventRef::Create(_) => EventType::Create,
+            BondingCurveEventRef::Buy(_) => EventType::Buy,
+            BondingCurveEventRef::Sell(_) => EventType::Sell,
+            BondingCurveEventRef::Sync(_) => EventType::Sync,
+            BondingCurveEventRef::Lock(_) => EventType::Lock,
+            BondingCurveEventRef::Listed(_) => EventType::Listed,
+        }
+    }
+
+    pub fn into_owned(self) -> BondingCurveEvent {
+        match self {
+            BondingCurveEventRef::Create(e) => BondingCurveEvent::Create(e.into_owned()),
+            BondingCurveEventRef::Buy(e) => BondingCurveEvent::Buy(e),
+            BondingCurveEventRef::Sell(e) => BondingCurveEvent::Sell(e),
+            BondingCurveEventRef::Sync(e) => BondingCurveEvent::Sync(e),
+            BondingCurveEventRef::Lock(e) => BondingCurveEvent::Lock(e),
+            BondingCurveEventRef::Listed(e) => BondingCurveEvent::Listed(e),
+        }
+    }
 }
 
 /// Decode a log into a BondingCurveEvent
 pub fn decode_bonding_curve_event(log: Log) -> Result<BondingCurveEvent> {
+    decode_bonding_curve_event_ref(&log).map(BondingCurveEventRef::into_owned)
+}
+
+/// Token address of a bonding curve log, read from its indexed topics
+///
+/// Every curve event indexes the token, so callers can filter logs before paying
+/// for ABI decoding (which allocates name/symbol/URI strings for Create events).
+pub fn curve_log_token(log: &Log) -> Option<Address> {
+    let topics = log.topics();
+    let topic0 = topics.first()?;
+
+    let index = if *topic0 == IBondingCurve::CurveCreate::SIGNATURE_HASH
+        || *topic0 == IBondingCurve::CurveBuy::SIGNATURE_HASH
+        || *topic0 == IBondingCurve::CurveSell::SIGNATURE_HASH
+    {
+        2
+    } else if *topic0 == IBondingCurve::CurveSync::SIGNATURE_HASH
+        || *topic0 == IBondingCurve::CurveTokenLocked::SIGNATURE_HASH
+        || *topic0 == IBondingCurve::CurveTokenListed::SIGNATURE_HASH
+    {
+        1
+    } else {
+        return None;
+    };
+
+    topics.get(index).map(|topic| Address::from_word(*topic))
+}
+
+/// Decode a borrowed log without allocating
+///
+/// Create event strings borrow from the log data, so filtering or aggregating
+/// backfilled logs costs no heap allocation per event.
+pub fn decode_bonding_curve_event_ref(log: &Log) -> Result<BondingCurveEventRef<'_>> {
     let topic0 = log
         .topics()
         .first()
         .ok_or_else(|| anyhow::anyhow!("No topic0 found"))?;
 
     if *topic0 == IBondingCurve::CurveCreate::SIGNATURE_HASH {
-        let IBondingCurve::CurveCreate {
-            creator,
-            token,
-            pool,
-            name,
-            symbol,
-            tokenURI,
-            virtualMon,
-            virtualToken,
-            targetTokenAmount,
-        } = log.log_decode()?.inner.data;
+        let [_, creator, token, pool] = log.topics() else {
+            return Err(anyhow::anyhow!("CurveCreate log needs 4 topics"));
+        };
+        let (name, symbol, token_uri, virtual_mon, virtual_token, target_token_amount): (
+            PackedSeqToken<'_>,
+            PackedSeqToken<'_>,
+            PackedSeqToken<'_>,
+            WordToken,
+            WordToken,
+            WordToken,
+        ) = abi::decode_sequence(&log.data().data)?;
 
-        Ok(BondingCurveEvent::Create(CreateEvent {
-            creator,
-            token,
-            pool,
-            name,
-            symbol,
-            token_uri: tokenURI,
-            virtual_mon: virtualMon,
-            virtual_token: virtualToken,
-            target_token_amount: targetTokenAmount,
+        Ok(BondingCurveEventRef::Create(CreateEventRef {
+            creator: Address::from_word(*creator),
+            token: Address::from_word(*token),
+            pool: Address::from_word(*pool),
+            name: String::from_utf8_lossy(name.0),
+            symbol: String::from_utf8_lossy(symbol.0),
+            token_uri: String::from_utf8_lossy(token_uri.0),
+            virtual_mon: virtual_mon.0.into(),
+            virtual_token: virtual_token.0.into(),
+            target_token_amount: target_token_amount.0.into(),
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else if *topic0 == IBondingCurve::CurveBuy::SIGNATURE_HASH {
         let IBondingCurve::CurveBuy {
@@ -269,15 +557,17 @@ pub fn decode_bonding_curve_event(log: Log) -> Result<BondingCurveEvent> {
             amountOut,
         } = log.log_decode()?.inner.data;
 
-        Ok(BondingCurveEvent::Buy(BuyEvent {
+        Ok(BondingCurveEventRef::Buy(BuyEvent {
             sender,
             token,
             amount_in: amountIn,
             amount_out: amountOut,
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else if *topic0 == IBondingCurve::CurveSell::SIGNATURE_HASH {
         let IBondingCurve::CurveSell {
@@ -287,15 +577,17 @@ pub fn decode_bonding_curve_event(log: Log) -> Result<BondingCurveEvent> {
             amountOut,
         } = log.log_decode()?.inner.data;
 
-        Ok(BondingCurveEvent::Sell(SellEvent {
+        Ok(BondingCurveEventRef::Sell(SellEvent {
             sender,
             token,
             amount_in: amountIn,
             amount_out: amountOut,
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else if *topic0 == IBondingCurve::CurveSync::SIGNATURE_HASH {
         let IBondingCurve::CurveSync {
@@ -306,37 +598,43 @@ pub fn decode_bonding_curve_event(log: Log) -> Result<BondingCurveEvent> {
             virtualTokenReserve,
         } = log.log_decode()?.inner.data;
 
-        Ok(BondingCurveEvent::Sync(SyncEvent {
+        Ok(BondingCurveEventRef::Sync(SyncEvent {
             token,
             real_mon_reserve: realMonReserve,
             real_token_reserve: realTokenReserve,
             virtual_mon_reserve: virtualMonReserve,
             virtual_token_reserve: virtualTokenReserve,
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else if *topic0 == IBondingCurve::CurveTokenLocked::SIGNATURE_HASH {
         let IBondingCurve::CurveTokenLocked { token } = log.log_decode()?.inner.data;
 
-        Ok(BondingCurveEvent::Lock(LockEvent {
+        Ok(BondingCurveEventRef::Lock(LockEvent {
             token,
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else if *topic0 == IBondingCurve::CurveTokenListed::SIGNATURE_HASH {
         let IBondingCurve::CurveTokenListed { token, pool } = log.log_decode()?.inner.data;
 
-        Ok(BondingCurveEvent::Listed(ListedEvent {
+        Ok(BondingCurveEventRef::Listed(ListedEvent {
             token,
             pool,
             block_number: log.block_number.unwrap_or(0),
+            block_hash: log.block_hash.unwrap_or(B256::ZERO),
             transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
             transaction_index: log.transaction_index.unwrap_or(0),
             log_index: log.log_index.unwrap_or(0),
+            removed: log.removed,
         }))
     } else {
         Err(anyhow::anyhow!("Unknown event signature: {:?}", topic0))
@@ -350,3 +648,96 @@ pub const CURVE_SELL_SIGNATURE: B256 = IBondingCurve::CurveSell::SIGNATURE_HASH;
 pub const CURVE_SYNC_SIGNATURE: B256 = IBondingCurve::CurveSync::SIGNATURE_HASH;
 pub const CURVE_TOKEN_LOCKED_SIGNATURE: B256 = IBondingCurve::CurveTokenLocked::SIGNATURE_HASH;
 pub const CURVE_TOKEN_LISTED_SIGNATURE: B256 = IBondingCurve::CurveTokenListed::SIGNATURE_HASH;
+
+/// Topic0 of every bonding curve event, in [`EventType::all`] order
+pub const ALL_EVENT_SIGNATURES: [B256; 6] = [
+    CURVE_CREATE_SIGNATURE,
+    CURVE_BUY_SIGNATURE,
+    CURVE_SELL_SIGNATURE,
+    CURVE_SYNC_SIGNATURE,
+    CURVE_TOKEN_LOCKED_SIGNATURE,
+    CURVE_TOKEN_LISTED_SIGNATURE,
+];
+
+/// Topic0 of the trade events (Buy and Sell)
+pub const TRADE_EVENT_SIGNATURES: [B256; 2] = [CURVE_BUY_SIGNATURE, CURVE_SELL_SIGNATURE];
+
+/// Topic0 values of `event_types`, without duplicates, for a filter's `event_signature`
+pub fn signatures_for(event_types: &[EventType]) -> Vec<B256> {
+    let mut signatures: Vec<B256> = Vec::with_capacity(event_types.len());
+    for signature in event_types.iter().map(EventType::signature) {
+        if !signatures.contains(&signature) {
+            signatures.push(signature);
+        }
+    }
+    signatures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_sets() {
+        assert_eq!(signatures_for(&EventType::all()), ALL_EVENT_SIGNATURES.to_vec());
+        assert_eq!(
+            signatures_for(&[EventType::Buy, EventType::Sell, EventType::Buy]),
+            TRADE_EVENT_SIGNATURES.to_vec()
+        );
+        assert!(signatures_for(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_ref_decode_borrows_create_strings() {
+        let fixture = crate::fixtures::curve_create();
+        let BondingCurveEventRef::Create(create) =
+            decode_bonding_curve_event_ref(&fixture.log).unwrap()
+        else {
+            panic!("expected a Create event");
+        };
+        assert!(matches!(create.name, Cow::Borrowed("Fixture Token")));
+        assert!(matches!(create.token_uri, Cow::Borrowed(_)));
+        assert_eq!(
+            BondingCurveEvent::Create(create.into_owned()),
+            fixture.expected
+        );
+
+        for fixture in crate::fixtures::curve_fixtures() {
+            let event = decode_bonding_curve_event_ref(&fixture.log).unwrap();
+            assert_eq!(event.token(), fixture.expected.token());
+            assert_eq!(event.event_type(), fixture.expected.event_type());
+            assert_eq!(event.into_owned(), fixture.expected);
+        }
+    }
+
+    #[test]
+    fn test_curve_filter_token_topics() {
+        let token = Address::repeat_byte(7);
+        let trades = curve_filter(
+            &[EventType::Buy, EventType::Sell],
+            Some(&[token][..]),
+            Some(10..=20),
+        );
+        assert!(trades.topics[2].matches(&token.into_word()));
+        assert!(!trades.topics[2].matches(&Address::ZERO.into_word()));
+        assert!(trades.topics[1].is_empty());
+        assert_eq!(
+            (trades.get_from_block(), trades.get_to_block()),
+            (Some(10), Some(20))
+        );
+
+        let creator = Address::repeat_byte(9);
+        let creates = creator_filter(&[creator], Some(10..=20));
+        assert!(creates.topics[1].matches(&creator.into_word()));
+        assert!(!creates.topics[1].matches(&token.into_word()));
+        assert!(creates.topics[0].matches(&CURVE_CREATE_SIGNATURE));
+        assert!(!creates.topics[0].matches(&CURVE_BUY_SIGNATURE));
+
+        let syncs = curve_filter(&[EventType::Sync], Some(&[token][..]), None);
+        assert!(syncs.topics[1].matches(&token.into_word()));
+
+        // Token sits at different positions; left to the caller
+        let mixed = curve_filter(&[EventType::Buy, EventType::Sync], Some(&[token][..]), None);
+        assert!(mixed.topics[1].is_empty() && mixed.topics[2].is_empty());
+    }
+}
@@ -0,0 +1,343 @@
+//! Human-readable formatting of events for log lines
+//!
+//! Every event type implements `Display`, rendering amounts in whole MON/token
+//! units instead of raw wei. The formatter's precision sets the number of
+//! fractional digits (default 4, trailing zeros trimmed):
+//!
+//! ```rust,ignore
+//! println!("{event}");     // Buy 0x…: 1.5 MON -> 48211.0932 tokens by 0x… @ 1234567
+//! println!("{event:.8}");  // same with 8 fractional digits
+//!
+//! // Tokens with other decimals than MON
+//! println!("{}", event.display().token_decimals(6).precision(2));
+//! ```
+//!
+//! Amounts are truncated, not rounded, so a displayed value never exceeds the
+//! real one.
+
+use crate::types::{
+    BondingCurveEvent, BuyEvent, CreateEvent, ListedEvent, LockEvent, SellEvent, SwapEvent,
+    SyncEvent,
+};
+use alloy::primitives::{I256, U256, utils::format_units};
+use std::fmt;
+
+/// Fractional digits shown when the formatter has no precision
+pub const DEFAULT_DISPLAY_PRECISION: usize = 4;
+
+/// Decimals of MON and of tokens launched on Nad.fun
+const DEFAULT_DECIMALS: u8 = 18;
+
+/// `value` in whole units with at most `precision` fractional digits
+///
+/// Digits beyond `precision` are cut off and trailing zeros trimmed:
+/// `1_500_000_000_000_000_000` with 18 decimals is `"1.5"`.
+pub fn format_units_compact(value: U256, decimals: u8, precision: usize) -> String {
+    let full = format_units(value, decimals).unwrap_or_else(|_| value.to_string());
+    let Some((whole, fraction)) = full.split_once('.') else {
+        return full;
+    };
+    let fraction = fraction[..fraction.len().min(precision)].trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Signed [`format_units_compact`], for swap deltas
+pub fn format_signed_units_compact(value: I256, decimals: u8, precision: usize) -> String {
+    let magnitude = format_units_compact(value.unsigned_abs(), decimals, precision);
+    if value.is_negative() && magnitude != "0" {
+        format!("-{magnitude}")
+    } else {
+        magnitude
+    }
+}
+
+/// Amount settings passed to [`FormatEvent::fmt_event`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AmountStyle {
+    pub token_decimals: u8,
+    pub precision: usize,
+}
+
+impl AmountStyle {
+    fn mon(&self, value: U256) -> String {
+        format_units_compact(value, DEFAULT_DECIMALS, self.precision)
+    }
+
+    fn tokens(&self, value: U256) -> String {
+        format_units_compact(value, self.token_decimals, self.precision)
+    }
+
+    fn signed(&self, value: I256) -> String {
+        format_signed_units_compact(value, self.token_decimals, self.precision)
+    }
+}
+
+/// Events with a one-line, unit-adjusted rendering
+pub trait FormatEvent {
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result;
+
+    /// Marks the rendering as retracted by a reorg
+    fn is_removed(&self) -> bool;
+
+    /// Formatter with configurable token decimals and precision
+    fn display(&self) -> EventDisplay<'_, Self>
+    where
+        Self: Sized,
+    {
+        EventDisplay {
+            event: self,
+            token_decimals: DEFAULT_DECIMALS,
+            precision: None,
+        }
+    }
+}
+
+/// Configurable `Display` of an event, from [`FormatEvent::display`]
+#[derive(Debug, Clone, Copy)]
+pub struct EventDisplay<'a, E> {
+    event: &'a E,
+    token_decimals: u8,
+    precision: Option<usize>,
+}
+
+impl<E> EventDisplay<'_, E> {
+    /// Decimals of the traded token (MON amounts always use 18)
+    pub fn token_decimals(mut self, decimals: u8) -> Self {
+        self.token_decimals = decimals;
+        self
+    }
+
+    /// Fractional digits, overriding the formatter's precision
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+}
+
+impl<E: FormatEvent> fmt::Display for EventDisplay<'_, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = AmountStyle {
+            token_decimals: self.token_decimals,
+            precision: self
+                .precision
+                .or(f.precision())
+                .unwrap_or(DEFAULT_DISPLAY_PRECISION),
+        };
+        self.event.fmt_event(f, style)?;
+        if self.event.is_removed() {
+            write!(f, " (removed)")?;
+        }
+        Ok(())
+    }
+}
+
+impl FormatEvent for CreateEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Create {} ({}) {} by {}, virtual {} MON / {} tokens @ {}",
+            self.symbol,
+            self.name,
+            self.token,
+            self.creator,
+            style.mon(self.virtual_mon),
+            style.tokens(self.virtual_token),
+            self.block_number
+        )
+    }
+}
+
+impl FormatEvent for BuyEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Buy {}: {} MON -> {} tokens by {} @ {}",
+            self.token,
+            style.mon(self.amount_in),
+            style.tokens(self.amount_out),
+            self.sender,
+            self.block_number
+        )
+    }
+}
+
+impl FormatEvent for SellEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Sell {}: {} tokens -> {} MON by {} @ {}",
+            self.token,
+            style.tokens(self.amount_in),
+            style.mon(self.amount_out),
+            self.sender,
+            self.block_number
+        )
+    }
+}
+
+impl FormatEvent for SyncEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Sync {}: real {} MON / {} tokens, virtual {} MON / {} tokens @ {}",
+            self.token,
+            style.mon(self.real_mon_reserve),
+            style.tokens(self.real_token_reserve),
+            style.mon(self.virtual_mon_reserve),
+            style.tokens(self.virtual_token_reserve),
+            self.block_number
+        )
+    }
+}
+
+impl FormatEvent for LockEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, _style: AmountStyle) -> fmt::Result {
+        write!(f, "Lock {} @ {}", self.token, self.block_number)
+    }
+}
+
+impl FormatEvent for ListedEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, _style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Listed {} on pool {} @ {}",
+            self.token, self.pool, self.block_number
+        )
+    }
+}
+
+impl FormatEvent for BondingCurveEvent {
+    fn is_removed(&self) -> bool {
+        self.removed()
+    }
+
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        match self {
+            BondingCurveEvent::Create(e) => e.fmt_event(f, style),
+            BondingCurveEvent::Buy(e) => e.fmt_event(f, style),
+            BondingCurveEvent::Sell(e) => e.fmt_event(f, style),
+            BondingCurveEvent::Sync(e) => e.fmt_event(f, style),
+            BondingCurveEvent::Lock(e) => e.fmt_event(f, style),
+            BondingCurveEvent::Listed(e) => e.fmt_event(f, style),
+        }
+    }
+}
+
+impl FormatEvent for SwapEvent {
+    fn is_removed(&self) -> bool {
+        self.removed
+    }
+
+    /// Both pool tokens are rendered with `token_decimals` (WMON and Nad.fun
+    /// tokens both have 18)
+    fn fmt_event(&self, f: &mut fmt::Formatter<'_>, style: AmountStyle) -> fmt::Result {
+        write!(
+            f,
+            "Swap {}: amount0 {} / amount1 {} by {} @ {}",
+            self.pool_address,
+            style.signed(self.amount0),
+            style.signed(self.amount1),
+            self.sender,
+            self.block_number
+        )
+    }
+}
+
+macro_rules! impl_display_via_format_event {
+    ($($event:ty),* $(,)?) => {
+        $(
+            impl fmt::Display for $event {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.display(), f)
+                }
+            }
+        )*
+    };
+}
+
+impl_display_via_format_event!(
+    CreateEvent,
+    BuyEvent,
+    SellEvent,
+    SyncEvent,
+    LockEvent,
+    ListedEvent,
+    BondingCurveEvent,
+    SwapEvent,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_format_units_compact_truncates_and_trims() {
+        let value = U256::from(1_234_567_890_000_000_000u128);
+        assert_eq!(format_units_compact(value, 18, 4), "1.2345");
+        assert_eq!(format_units_compact(value, 18, 0), "1");
+        assert_eq!(
+            format_units_compact(U256::from(1_500_000_000_000_000_000u128), 18, 4),
+            "1.5"
+        );
+        assert_eq!(format_units_compact(U256::from(1u8), 18, 4), "0");
+        assert_eq!(
+            format_signed_units_compact(I256::try_from(-2_500).unwrap(), 3, 2),
+            "-2.5"
+        );
+    }
+
+    #[test]
+    fn test_event_display_uses_units_and_precision() {
+        let BondingCurveEvent::Buy(buy) = fixtures::curve_buy().expected else {
+            panic!("fixture is a buy");
+        };
+        let line = buy.to_string();
+        assert!(line.starts_with("Buy "));
+        assert!(line.contains(&format!(
+            "{} MON",
+            format_units_compact(buy.amount_in, 18, 4)
+        )));
+        assert!(!line.contains(&buy.amount_in.to_string()));
+
+        let precise = format!("{:.8}", BondingCurveEvent::Buy(buy.clone()));
+        assert!(precise.contains(&format!(
+            "{} tokens",
+            format_units_compact(buy.amount_out, 18, 8)
+        )));
+
+        let custom = buy.display().token_decimals(6).precision(0).to_string();
+        assert!(custom.contains(&format!(
+            "{} tokens",
+            format_units_compact(buy.amount_out, 6, 0)
+        )));
+    }
+}
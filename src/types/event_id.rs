@@ -0,0 +1,144 @@
+//! Stable event identifiers for pagination cursors
+//!
+//! An [`EventId`] pins a log to its position in the chain. Ids order the same
+//! way the indexers sort events, so the id of the last processed event is an
+//! exact cursor to resume from after a restart.
+
+use crate::types::{
+    BondingCurveEvent, BurnEvent, BuyEvent, CollectEvent, CreateEvent, DexEvent, ListedEvent,
+    LockEvent, MintEvent, SellEvent, SwapEvent, SyncEvent,
+};
+use alloy::rpc::types::Log;
+use std::{fmt, str::FromStr};
+
+/// Position of a log in the chain, ordered by block, transaction index, then log index
+///
+/// Displays and parses as `block:tx_index:log_index`, e.g. `1000000:3:12`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct EventId {
+    pub block: u64,
+    pub tx_index: u64,
+    pub log_index: u64,
+}
+
+impl EventId {
+    pub fn new(block: u64, tx_index: u64, log_index: u64) -> Self {
+        Self {
+            block,
+            tx_index,
+            log_index,
+        }
+    }
+
+    /// Id of a raw RPC log (None for pending logs without position data)
+    pub fn from_log(log: &Log) -> Option<Self> {
+        Some(Self::new(
+            log.block_number?,
+            log.transaction_index?,
+            log.log_index?,
+        ))
+    }
+}
+
+impl fmt::Display for EventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.block, self.tx_index, self.log_index)
+    }
+}
+
+impl FromStr for EventId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split(':').collect();
+        if parts.len() != 3 {
+            return Err(anyhow::anyhow!(
+                "Invalid event id '{}': expected block:tx_index:log_index",
+                s
+            ));
+        }
+
+        let parse = |part: &str, what: &str| {
+            part.parse::<u64>()
+                .map_err(|e| anyhow::anyhow!("Invalid event id '{}': bad {}: {}", s, what, e))
+        };
+
+        Ok(Self::new(
+            parse(parts[0], "block")?,
+            parse(parts[1], "tx_index")?,
+            parse(parts[2], "log_index")?,
+        ))
+    }
+}
+
+macro_rules! impl_event_id {
+    ($($event:ty),* $(,)?) => {
+        $(
+            impl $event {
+                /// Position of this event's log in the chain
+                pub fn event_id(&self) -> EventId {
+                    EventId::new(self.block_number, self.transaction_index, self.log_index)
+                }
+            }
+        )*
+    };
+}
+
+impl_event_id!(
+    CreateEvent,
+    BuyEvent,
+    SellEvent,
+    SyncEvent,
+    LockEvent,
+    ListedEvent,
+    SwapEvent,
+    MintEvent,
+    BurnEvent,
+    CollectEvent,
+);
+
+impl BondingCurveEvent {
+    /// Position of this event's log in the chain
+    pub fn event_id(&self) -> EventId {
+        EventId::new(
+            self.block_number(),
+            self.transaction_index(),
+            self.log_index(),
+        )
+    }
+}
+
+impl DexEvent {
+    /// Position of this event's log in the chain
+    pub fn event_id(&self) -> EventId {
+        EventId::new(
+            self.block_number(),
+            self.transaction_index(),
+            self.log_index(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_id_ordering() {
+        let a = EventId::new(10, 5, 9);
+        let b = EventId::new(10, 6, 0);
+        let c = EventId::new(11, 0, 0);
+        assert!(a < b && b < c);
+        assert!(EventId::new(10, 5, 8) < a);
+    }
+
+    #[test]
+    fn test_event_id_display_roundtrip() {
+        let id = EventId::new(1_000_000, 3, 12);
+        assert_eq!(id.to_string(), "1000000:3:12");
+        assert_eq!("1000000:3:12".parse::<EventId>().unwrap(), id);
+
+        assert!("1000000:3".parse::<EventId>().is_err());
+        assert!("a:b:c".parse::<EventId>().is_err());
+    }
+}
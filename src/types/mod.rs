@@ -1,10 +1,18 @@
 //! All types for the Nad.fun SDK
 
+pub mod abi;
+pub mod address;
 pub mod bonding_curve;
+pub mod display;
+pub mod event_id;
 pub mod trade;
 pub mod uniswap;
 
 // Re-export all types for easy access
+pub use abi::*;
+pub use address::*;
 pub use bonding_curve::*;
+pub use display::*;
+pub use event_id::*;
 pub use trade::*;
 pub use uniswap::*;
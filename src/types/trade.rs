@@ -1,9 +1,18 @@
 use alloy::primitives::{Address, B256, U256};
 
-#[derive(Debug, Clone)]
+/// ABI family a router implements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouterKind {
+    Dex,
+    BondingCurve,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Router {
     Dex(Address),
     BondingCurve(Address),
+    /// A router registered at runtime (e.g. after a contract upgrade) with the ABI it implements
+    Custom(Address, RouterKind),
 }
 
 impl Router {
@@ -11,6 +20,15 @@ impl Router {
         match self {
             Router::Dex(addr) => *addr,
             Router::BondingCurve(addr) => *addr,
+            Router::Custom(addr, _) => *addr,
+        }
+    }
+
+    pub fn kind(&self) -> RouterKind {
+        match self {
+            Router::Dex(_) => RouterKind::Dex,
+            Router::BondingCurve(_) => RouterKind::BondingCurve,
+            Router::Custom(_, kind) => *kind,
         }
     }
 }
@@ -55,6 +73,15 @@ pub struct SellPermitParams {
     pub nonce: Option<u64>,
 }
 
+#[derive(Debug, Clone)]
+pub struct NativeTransferParams {
+    pub to: Address,
+    pub amount: U256, // Amount of MON in wei
+    pub gas_limit: Option<u64>,
+    pub gas_price: Option<u128>,
+    pub nonce: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CurveState {
     pub real_mon_reserve: U256,
@@ -74,6 +101,30 @@ pub struct TokenMetadata {
     pub symbol: String,
     pub decimals: u8,
     pub total_supply: U256,
+    /// Fields the token couldn't provide; they hold fallback values
+    /// (empty name/symbol, 18 decimals, zero supply)
+    pub missing: Vec<MetadataField>,
+}
+
+impl TokenMetadata {
+    /// True when every field was read from the token
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// True when `decimals` is the 18 fallback rather than the token's value
+    pub fn decimals_assumed(&self) -> bool {
+        self.missing.contains(&MetadataField::Decimals)
+    }
+}
+
+/// An ERC-20 metadata getter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetadataField {
+    Name,
+    Symbol,
+    Decimals,
+    TotalSupply,
 }
 
 #[derive(Debug, Clone)]
@@ -91,15 +142,169 @@ pub struct AllowanceStatus {
     pub is_unlimited: bool,
 }
 
-#[derive(Debug)]
+/// Ways to sell a token amount through a router
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SellPath {
+    /// The router's allowance already covers the amount; just `sell()`
+    Sell,
+    /// `approve()` the router, then `sell()`
+    ApproveAndSell,
+    /// `sellPermit()` with an EIP-2612 signature in a single transaction
+    SellPermit,
+}
+
+/// Estimated gas for each way of selling a token amount
+#[derive(Debug, Clone)]
+pub struct SellPathCost {
+    /// Current allowance of the wallet for the router
+    pub allowance: U256,
+    /// Gas for `approve()`, or None when the allowance already covers the sale
+    pub approve_gas: Option<u64>,
+    pub sell_gas: u64,
+    /// False when `sell_gas` comes from the default gas table because the sale
+    /// couldn't be simulated, even with an overridden allowance
+    pub sell_gas_estimated: bool,
+    /// Gas for `sellPermit()`, or None when the estimate failed (e.g. no permit support)
+    pub sell_permit_gas: Option<u64>,
+}
+
+impl SellPathCost {
+    /// Total gas of the approve + sell path (just the sell if no approval is needed)
+    pub fn approve_and_sell_gas(&self) -> u64 {
+        self.approve_gas.unwrap_or(0) + self.sell_gas
+    }
+
+    /// The path with the lowest total gas
+    pub fn cheapest(&self) -> SellPath {
+        let classic = if self.approve_gas.is_some() {
+            SellPath::ApproveAndSell
+        } else {
+            SellPath::Sell
+        };
+
+        match self.sell_permit_gas {
+            Some(permit_gas) if permit_gas < self.approve_and_sell_gas() => SellPath::SellPermit,
+            _ => classic,
+        }
+    }
+
+    /// Total gas of [`SellPathCost::cheapest`]
+    pub fn cheapest_gas(&self) -> u64 {
+        match self.cheapest() {
+            SellPath::SellPermit => self.sell_permit_gas.unwrap_or_default(),
+            _ => self.approve_and_sell_gas(),
+        }
+    }
+}
+
+/// Health of a single deployed contract
+#[derive(Debug, Clone)]
+pub struct ContractCheck {
+    pub name: &'static str,
+    pub address: Address,
+    pub has_code: bool,
+    /// Whether a read-only probe call succeeded (None if no probe applies)
+    pub responds: Option<bool>,
+    pub error: Option<String>,
+}
+
+impl ContractCheck {
+    pub fn is_healthy(&self) -> bool {
+        self.has_code && self.responds.unwrap_or(true) && self.error.is_none()
+    }
+}
+
+/// Result of [`crate::Trade::validate_deployment`]
+#[derive(Debug, Clone)]
+pub struct DeploymentDiagnostics {
+    pub chain_id: u64,
+    pub checks: Vec<ContractCheck>,
+}
+
+impl DeploymentDiagnostics {
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(|c| c.is_healthy())
+    }
+
+    /// Checks that failed
+    pub fn failures(&self) -> Vec<&ContractCheck> {
+        self.checks.iter().filter(|c| !c.is_healthy()).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TransactionResult {
     pub transaction_hash: B256,
     pub block_number: Option<u64>,
     pub gas_used: Option<U256>,
+    /// Price per gas actually paid (base fee plus priority fee), in wei
+    pub effective_gas_price: Option<u128>,
     pub status: bool,
     pub logs: Vec<alloy::rpc::types::Log>,
 }
 
+/// An ERC-20 transfer emitted by a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+}
+
+impl TransactionResult {
+    /// MON paid for gas in wei: `gas_used * effective_gas_price`
+    ///
+    /// Reverted transactions pay too, so this is set whenever the receipt is.
+    pub fn fee_paid(&self) -> Option<U256> {
+        Some(self.gas_used? * U256::from(self.effective_gas_price?))
+    }
+
+    /// All ERC-20 Transfer logs of the receipt, in log order
+    ///
+    /// Multi-hop routes yield one entry per hop (e.g. token -> pool -> router
+    /// -> wallet). Logs that share the Transfer signature but aren't ERC-20
+    /// transfers (ERC-721 with an indexed token id) are skipped.
+    pub fn token_transfers(&self) -> Vec<TokenTransfer> {
+        use crate::token::token::IToken;
+        use alloy::sol_types::SolEvent;
+
+        self.logs
+            .iter()
+            .filter_map(|log| {
+                let event = IToken::Transfer::decode_log(&log.inner).ok()?;
+                Some(TokenTransfer {
+                    token: log.address(),
+                    from: event.data.from,
+                    to: event.data.to,
+                    amount: event.data.value,
+                })
+            })
+            .collect()
+    }
+
+    /// Transfers into or out of `wallet`
+    pub fn transfers_of(&self, wallet: Address) -> Vec<TokenTransfer> {
+        self.token_transfers()
+            .into_iter()
+            .filter(|t| t.from == wallet || t.to == wallet)
+            .collect()
+    }
+}
+
+impl From<alloy::rpc::types::TransactionReceipt> for TransactionResult {
+    fn from(receipt: alloy::rpc::types::TransactionReceipt) -> Self {
+        Self {
+            transaction_hash: receipt.transaction_hash,
+            block_number: receipt.block_number,
+            gas_used: Some(U256::from(receipt.gas_used)),
+            effective_gas_price: Some(receipt.effective_gas_price),
+            status: receipt.status(),
+            logs: receipt.logs().to_vec(),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,18 +415,117 @@ mod tests {
         assert_eq!(curve_state.k, U256::from(5000000));
     }
 
+    #[test]
+    fn test_router_kind() {
+        let addr: Address = "0x1234567890123456789012345678901234567890"
+            .parse()
+            .unwrap();
+
+        let custom = Router::Custom(addr, RouterKind::Dex);
+        assert_eq!(custom.address(), addr);
+        assert_eq!(custom.kind(), RouterKind::Dex);
+        assert_eq!(Router::BondingCurve(addr).kind(), RouterKind::BondingCurve);
+    }
+
+    #[test]
+    fn test_sell_path_cost_cheapest() {
+        let mut cost = SellPathCost {
+            allowance: U256::ZERO,
+            approve_gas: Some(46_000),
+            sell_gas: 150_000,
+            sell_gas_estimated: false,
+            sell_permit_gas: Some(180_000),
+        };
+        assert_eq!(cost.approve_and_sell_gas(), 196_000);
+        assert_eq!(cost.cheapest(), SellPath::SellPermit);
+        assert_eq!(cost.cheapest_gas(), 180_000);
+
+        cost.approve_gas = None;
+        assert_eq!(cost.cheapest(), SellPath::Sell);
+
+        cost.approve_gas = Some(46_000);
+        cost.sell_permit_gas = None;
+        assert_eq!(cost.cheapest(), SellPath::ApproveAndSell);
+    }
+
     #[test]
     fn test_transaction_result_creation() {
         let tx_result = TransactionResult {
             transaction_hash: B256::ZERO,
             block_number: Some(12345),
             gas_used: Some(U256::from(21000)),
+            effective_gas_price: Some(50_000_000_000),
             status: true,
             logs: vec![],
         };
 
         assert_eq!(tx_result.block_number, Some(12345));
-        assert_eq!(tx_result.status, true);
+        assert!(tx_result.status);
         assert_eq!(tx_result.gas_used, Some(U256::from(21000)));
+        assert_eq!(
+            tx_result.fee_paid(),
+            Some(U256::from(1_050_000_000_000_000u64))
+        );
+
+        let unknown_price = TransactionResult {
+            effective_gas_price: None,
+            ..tx_result
+        };
+        assert_eq!(unknown_price.fee_paid(), None);
+    }
+
+    #[test]
+    fn test_token_transfers_from_logs() {
+        use crate::token::token::IToken;
+        use alloy::sol_types::SolEvent;
+
+        let token = Address::repeat_byte(0x11);
+        let pool = Address::repeat_byte(0x22);
+        let wallet = Address::repeat_byte(0x33);
+        let log = |from: Address, to: Address, value: u64| alloy::rpc::types::Log {
+            inner: alloy::primitives::Log {
+                address: token,
+                data: IToken::Transfer {
+                    from,
+                    to,
+                    value: U256::from(value),
+                }
+                .encode_log_data(),
+            },
+            ..Default::default()
+        };
+        let mut not_a_transfer = log(pool, wallet, 1);
+        not_a_transfer.inner.data = IToken::Approval {
+            owner: wallet,
+            spender: pool,
+            value: U256::from(1),
+        }
+        .encode_log_data();
+
+        let tx_result = TransactionResult {
+            transaction_hash: B256::ZERO,
+            block_number: Some(1),
+            gas_used: None,
+            effective_gas_price: None,
+            status: true,
+            logs: vec![
+                log(pool, Address::ZERO, 500),
+                not_a_transfer,
+                log(Address::ZERO, wallet, 495),
+            ],
+        };
+
+        let transfers = tx_result.token_transfers();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(
+            transfers[1],
+            TokenTransfer {
+                token,
+                from: Address::ZERO,
+                to: wallet,
+                amount: U256::from(495),
+            }
+        );
+        assert_eq!(tx_result.transfers_of(wallet), vec![transfers[1]]);
     }
 }
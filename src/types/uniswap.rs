@@ -2,19 +2,22 @@
 //!
 //! Contains all Uniswap V3 event types and pool metadata helpers.
 
+use crate::metadata::MetadataCache;
 use alloy::{
     primitives::{Address, B256, I256, U256},
     providers::Provider,
-    rpc::types::Log,
+    rpc::types::{Filter, Log},
+    json_abi::Event,
     sol,
-    sol_types::SolEvent,
+    sol_types::{JsonAbiExt, SolEvent},
 };
 use anyhow::Result;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, ops::RangeInclusive};
 
 // Uniswap V3 Pool contract definition
 sol! {
-    #[sol(rpc)]
+    #[sol(rpc, abi)]
     contract UniswapV3Pool {
         /// @notice Emitted by the pool for any swaps between token0 and token1
         event Swap(
@@ -27,6 +30,37 @@ sol! {
             int24 tick
         );
 
+        /// @notice Emitted when liquidity is minted for a given position
+        event Mint(
+            address sender,
+            address indexed owner,
+            int24 indexed tickLower,
+            int24 indexed tickUpper,
+            uint128 amount,
+            uint256 amount0,
+            uint256 amount1
+        );
+
+        /// @notice Emitted when a position's liquidity is removed
+        event Burn(
+            address indexed owner,
+            int24 indexed tickLower,
+            int24 indexed tickUpper,
+            uint128 amount,
+            uint256 amount0,
+            uint256 amount1
+        );
+
+        /// @notice Emitted when fees (and burned liquidity) are collected by a position owner
+        event Collect(
+            address indexed owner,
+            address recipient,
+            int24 indexed tickLower,
+            int24 indexed tickUpper,
+            uint128 amount0,
+            uint128 amount1
+        );
+
         /// @notice The first of the two tokens of the pool, sorted by address
         /// @return The token contract address
         function token0() external view returns (address);
@@ -34,11 +68,28 @@ sol! {
         /// @notice The second of the two tokens of the pool, sorted by address
         /// @return The token contract address
         function token1() external view returns (address);
+
+        /// @notice The pool's fee in hundredths of a bip
+        function fee() external view returns (uint24);
+
+        /// @notice The currently in range liquidity available to the pool
+        function liquidity() external view returns (uint128);
+
+        /// @notice The current price and tick of the pool, plus oracle bookkeeping
+        function slot0() external view returns (
+            uint160 sqrtPriceX96,
+            int24 tick,
+            uint16 observationIndex,
+            uint16 observationCardinality,
+            uint16 observationCardinalityNext,
+            uint8 feeProtocol,
+            bool unlocked
+        );
     }
 }
 
 /// Uniswap V3 Swap event with Nad.fun-specific analysis methods
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SwapEvent {
     pub sender: Address,
     pub recipient: Address,
@@ -49,9 +100,12 @@ pub struct SwapEvent {
     pub tick: i32, // int24 fits in i32
     pub pool_address: Address,
     pub block_number: u64,
+    pub block_hash: B256,
     pub transaction_hash: B256,
     pub transaction_index: u64,
     pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
 }
 
 impl SwapEvent {
@@ -114,16 +168,26 @@ impl SwapEvent {
 }
 
 /// Pool metadata helper for determining which token is WMON
+///
+/// Backed by a [`MetadataCache`], so pools looked up here are also known to
+/// every other component sharing the cache.
+#[derive(Debug, Clone, Default)]
 pub struct PoolMetadata {
-    /// Cache of pool address -> whether WMON is token0
-    wmon_is_token0_cache: HashMap<Address, bool>,
+    cache: MetadataCache,
 }
 
 impl PoolMetadata {
     pub fn new() -> Self {
-        Self {
-            wmon_is_token0_cache: HashMap::new(),
-        }
+        Self::default()
+    }
+
+    /// Share pool lookups with other components
+    pub fn with_cache(cache: MetadataCache) -> Self {
+        Self { cache }
+    }
+
+    pub fn cache(&self) -> &MetadataCache {
+        &self.cache
     }
 
     /// Check if WMON is token0 in the given pool
@@ -132,23 +196,9 @@ impl PoolMetadata {
         provider: &P,
         pool_address: Address,
     ) -> Result<bool> {
-        // Check cache first
-        if let Some(&cached) = self.wmon_is_token0_cache.get(&pool_address) {
-            return Ok(cached);
-        }
-
-        // Query the pool contract
-        let pool = UniswapV3Pool::new(pool_address, provider);
-        let token0 = pool.token0().call().await?;
+        let tokens = self.cache.pool_tokens(provider, pool_address).await?;
         let wmon_address: Address = crate::constants::WMON.parse()?;
-
-        let is_wmon_token0 = token0 == wmon_address;
-
-        // Cache the result
-        self.wmon_is_token0_cache
-            .insert(pool_address, is_wmon_token0);
-
-        Ok(is_wmon_token0)
+        Ok(tokens.token0 == wmon_address)
     }
 
     /// Get token addresses for a pool (token0, token1)
@@ -157,10 +207,8 @@ impl PoolMetadata {
         provider: &P,
         pool_address: Address,
     ) -> Result<(Address, Address)> {
-        let pool = UniswapV3Pool::new(pool_address, provider);
-        let token0 = pool.token0().call().await?;
-        let token1 = pool.token1().call().await?;
-        Ok((token0, token1))
+        let tokens = self.cache.pool_tokens(provider, pool_address).await?;
+        Ok((tokens.token0, tokens.token1))
     }
 }
 
@@ -198,11 +246,313 @@ pub fn decode_swap_event(log: Log) -> Result<SwapEvent> {
         tick: tick.try_into().unwrap_or(0), // int24 -> i32
         pool_address,
         block_number: log.block_number.unwrap_or(0),
+        block_hash: log.block_hash.unwrap_or(B256::ZERO),
         transaction_hash: log.transaction_hash.unwrap_or(B256::ZERO),
         transaction_index: log.transaction_index.unwrap_or(0),
         log_index: log.log_index.unwrap_or(0),
+        removed: log.removed,
     })
 }
 
 // Export swap event signature for convenience
 pub const SWAP_SIGNATURE: B256 = UniswapV3Pool::Swap::SIGNATURE_HASH;
+
+/// Pool event types that can be indexed or subscribed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DexEventType {
+    Swap,
+    Mint,
+    Burn,
+    Collect,
+}
+
+impl DexEventType {
+    /// Get the signature hash for this event type
+    pub fn signature(&self) -> B256 {
+        match self {
+            DexEventType::Swap => UniswapV3Pool::Swap::SIGNATURE_HASH,
+            DexEventType::Mint => UniswapV3Pool::Mint::SIGNATURE_HASH,
+            DexEventType::Burn => UniswapV3Pool::Burn::SIGNATURE_HASH,
+            DexEventType::Collect => UniswapV3Pool::Collect::SIGNATURE_HASH,
+        }
+    }
+
+    /// Canonical event signature, e.g. `Burn(address,int24,int24,uint128,uint256,uint256)`
+    pub fn signature_string(&self) -> &'static str {
+        match self {
+            DexEventType::Swap => UniswapV3Pool::Swap::SIGNATURE,
+            DexEventType::Mint => UniswapV3Pool::Mint::SIGNATURE,
+            DexEventType::Burn => UniswapV3Pool::Burn::SIGNATURE,
+            DexEventType::Collect => UniswapV3Pool::Collect::SIGNATURE,
+        }
+    }
+
+    /// JSON ABI item of this event, as decoded by the SDK
+    pub fn abi(&self) -> Event {
+        match self {
+            DexEventType::Swap => UniswapV3Pool::Swap::abi(),
+            DexEventType::Mint => UniswapV3Pool::Mint::abi(),
+            DexEventType::Burn => UniswapV3Pool::Burn::abi(),
+            DexEventType::Collect => UniswapV3Pool::Collect::abi(),
+        }
+    }
+
+    /// Every pool event type
+    pub fn all() -> Vec<DexEventType> {
+        vec![
+            DexEventType::Swap,
+            DexEventType::Mint,
+            DexEventType::Burn,
+            DexEventType::Collect,
+        ]
+    }
+
+    /// The liquidity events: Mint, Burn and Collect
+    pub fn liquidity() -> Vec<DexEventType> {
+        vec![
+            DexEventType::Mint,
+            DexEventType::Burn,
+            DexEventType::Collect,
+        ]
+    }
+}
+
+/// Liquidity added to a position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MintEvent {
+    pub sender: Address,
+    pub owner: Address,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// Liquidity added
+    pub amount: u128,
+    pub amount0: U256,
+    pub amount1: U256,
+    pub pool_address: Address,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+/// Liquidity removed from a position; the tokens are owed until collected
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnEvent {
+    pub owner: Address,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    /// Liquidity removed
+    pub amount: u128,
+    pub amount0: U256,
+    pub amount1: U256,
+    pub pool_address: Address,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+/// Tokens owed to a position (fees and burned liquidity) paid out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectEvent {
+    pub owner: Address,
+    pub recipient: Address,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub amount0: u128,
+    pub amount1: u128,
+    pub pool_address: Address,
+    pub block_number: u64,
+    pub block_hash: B256,
+    pub transaction_hash: B256,
+    pub transaction_index: u64,
+    pub log_index: u64,
+    /// True if the log was removed due to a chain reorganization
+    pub removed: bool,
+}
+
+/// Any decoded Uniswap V3 pool event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DexEvent {
+    Swap(SwapEvent),
+    Mint(MintEvent),
+    Burn(BurnEvent),
+    Collect(CollectEvent),
+}
+
+macro_rules! dex_event_field {
+    ($self:ident, $field:ident) => {
+        match $self {
+            DexEvent::Swap(e) => e.$field,
+            DexEvent::Mint(e) => e.$field,
+            DexEvent::Burn(e) => e.$field,
+            DexEvent::Collect(e) => e.$field,
+        }
+    };
+}
+
+impl DexEvent {
+    pub fn event_type(&self) -> DexEventType {
+        match self {
+            DexEvent::Swap(_) => DexEventType::Swap,
+            DexEvent::Mint(_) => DexEventType::Mint,
+            DexEvent::Burn(_) => DexEventType::Burn,
+            DexEvent::Collect(_) => DexEventType::Collect,
+        }
+    }
+
+    pub fn pool_address(&self) -> Address {
+        dex_event_field!(self, pool_address)
+    }
+
+    pub fn block_number(&self) -> u64 {
+        dex_event_field!(self, block_number)
+    }
+
+    pub fn block_hash(&self) -> B256 {
+        dex_event_field!(self, block_hash)
+    }
+
+    pub fn transaction_hash(&self) -> B256 {
+        dex_event_field!(self, transaction_hash)
+    }
+
+    pub fn transaction_index(&self) -> u64 {
+        dex_event_field!(self, transaction_index)
+    }
+
+    pub fn log_index(&self) -> u64 {
+        dex_event_field!(self, log_index)
+    }
+
+    pub fn removed(&self) -> bool {
+        dex_event_field!(self, removed)
+    }
+}
+
+/// Log filter for `event_types` emitted by `pool_addresses`
+pub fn dex_filter(pool_addresses: &[Address], event_types: &[DexEventType]) -> Filter {
+    let signatures: HashSet<B256> = event_types.iter().map(|et| et.signature()).collect();
+    Filter::new()
+        .address(pool_addresses.to_vec())
+        .event_signature(signatures.into_iter().collect::<Vec<_>>())
+}
+
+/// Log filter for Swap events of `pool_addresses`, as used by the SDK's swap streams and indexers
+///
+/// `range` sets from/to blocks. Decode the returned logs with [`decode_swap_event`].
+pub fn swap_filter(pool_addresses: &[Address], range: Option<RangeInclusive<u64>>) -> Filter {
+    let filter = dex_filter(pool_addresses, &[DexEventType::Swap]);
+    match range {
+        Some(range) => filter.from_block(*range.start()).to_block(*range.end()),
+        None => filter,
+    }
+}
+
+/// Decode a Swap, Mint, Burn or Collect log into a DexEvent
+pub fn decode_dex_event(log: Log) -> Result<DexEvent> {
+    let topic0 = *log
+        .topics()
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("No topic0 found"))?;
+
+    if topic0 == UniswapV3Pool::Swap::SIGNATURE_HASH {
+        return decode_swap_event(log).map(DexEvent::Swap);
+    }
+
+    let pool_address = log.address();
+    let block_number = log.block_number.unwrap_or(0);
+    let block_hash = log.block_hash.unwrap_or(B256::ZERO);
+    let transaction_hash = log.transaction_hash.unwrap_or(B256::ZERO);
+    let transaction_index = log.transaction_index.unwrap_or(0);
+    let log_index = log.log_index.unwrap_or(0);
+    let removed = log.removed;
+
+    if topic0 == UniswapV3Pool::Mint::SIGNATURE_HASH {
+        let UniswapV3Pool::Mint {
+            sender,
+            owner,
+            tickLower,
+            tickUpper,
+            amount,
+            amount0,
+            amount1,
+        } = log.log_decode()?.inner.data;
+
+        Ok(DexEvent::Mint(MintEvent {
+            sender,
+            owner,
+            tick_lower: tickLower.try_into().unwrap_or(0),
+            tick_upper: tickUpper.try_into().unwrap_or(0),
+            amount,
+            amount0,
+            amount1,
+            pool_address,
+            block_number,
+            block_hash,
+            transaction_hash,
+            transaction_index,
+            log_index,
+            removed,
+        }))
+    } else if topic0 == UniswapV3Pool::Burn::SIGNATURE_HASH {
+        let UniswapV3Pool::Burn {
+            owner,
+            tickLower,
+            tickUpper,
+            amount,
+            amount0,
+            amount1,
+        } = log.log_decode()?.inner.data;
+
+        Ok(DexEvent::Burn(BurnEvent {
+            owner,
+            tick_lower: tickLower.try_into().unwrap_or(0),
+            tick_upper: tickUpper.try_into().unwrap_or(0),
+            amount,
+            amount0,
+            amount1,
+            pool_address,
+            block_number,
+            block_hash,
+            transaction_hash,
+            transaction_index,
+            log_index,
+            removed,
+        }))
+    } else if topic0 == UniswapV3Pool::Collect::SIGNATURE_HASH {
+        let UniswapV3Pool::Collect {
+            owner,
+            recipient,
+            tickLower,
+            tickUpper,
+            amount0,
+            amount1,
+        } = log.log_decode()?.inner.data;
+
+        Ok(DexEvent::Collect(CollectEvent {
+            owner,
+            recipient,
+            tick_lower: tickLower.try_into().unwrap_or(0),
+            tick_upper: tickUpper.try_into().unwrap_or(0),
+            amount0,
+            amount1,
+            pool_address,
+            block_number,
+            block_hash,
+            transaction_hash,
+            transaction_index,
+            log_index,
+            removed,
+        }))
+    } else {
+        Err(anyhow::anyhow!("Unknown pool event signature: {}", topic0))
+    }
+}
@@ -0,0 +1,188 @@
+//! Validation helpers for user-supplied input
+//!
+//! CLI and front-end wrappers can use these to turn raw strings into addresses
+//! and amounts with consistent, descriptive error messages, and to check that a
+//! token actually exists on chain before building a transaction around it.
+
+use crate::token::token::IToken;
+use alloy::{
+    primitives::{Address, U256, utils::parse_units},
+    providers::Provider,
+};
+use anyhow::Result;
+
+/// Parse an address, validating the EIP-55 checksum when the input is mixed-case
+///
+/// All-lowercase or all-uppercase hex is accepted as is, since it carries no checksum.
+pub fn parse_address(input: &str) -> Result<Address> {
+    let input = input.trim();
+    let hex = input
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow::anyhow!("Invalid address '{}': must start with 0x", input))?;
+
+    if hex.len() != 40 {
+        return Err(anyhow::anyhow!(
+            "Invalid address '{}': expected 40 hex characters after 0x, got {}",
+            input,
+            hex.len()
+        ));
+    }
+
+    if let Some(bad) = hex.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(anyhow::anyhow!(
+            "Invalid address '{}': '{}' is not a hex character",
+            input,
+            bad
+        ));
+    }
+
+    let has_lower = hex.chars().any(|c| c.is_ascii_lowercase());
+    let has_upper = hex.chars().any(|c| c.is_ascii_uppercase());
+
+    if has_lower && has_upper {
+        return Address::parse_checksummed(input, None).map_err(|_| {
+            let expected = input.parse::<Address>().map(|a| a.to_checksum(None));
+            anyhow::anyhow!(
+                "Invalid address '{}': checksum mismatch (expected {})",
+                input,
+                expected.unwrap_or_default()
+            )
+        });
+    }
+
+    Ok(input.parse()?)
+}
+
+/// Parse a decimal amount (e.g. "1.5") into base units
+pub fn parse_amount(input: &str, decimals: u8) -> Result<U256> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(anyhow::anyhow!("Invalid amount: empty input"));
+    }
+    if input.starts_with('-') {
+        return Err(anyhow::anyhow!(
+            "Invalid amount '{}': must not be negative",
+            input
+        ));
+    }
+
+    let amount: U256 = parse_units(input, decimals)
+        .map_err(|e| anyhow::anyhow!("Invalid amount '{}': {}", input, e))?
+        .into();
+
+    Ok(amount)
+}
+
+/// Parse an amount and reject it if it exceeds `max`
+///
+/// `what` names the limit in the error message, e.g. "balance" or "total supply".
+pub fn parse_amount_within(input: &str, decimals: u8, max: U256, what: &str) -> Result<U256> {
+    let amount = parse_amount(input, decimals)?;
+    ensure_within(amount, max, what)?;
+    Ok(amount)
+}
+
+/// Reject an amount that exceeds `max`
+pub fn ensure_within(amount: U256, max: U256, what: &str) -> Result<()> {
+    if amount > max {
+        return Err(anyhow::anyhow!(
+            "Amount {} exceeds {} ({})",
+            amount,
+            what,
+            max
+        ));
+    }
+    Ok(())
+}
+
+/// Check that `token` has contract code deployed
+pub async fn verify_token_exists<P: Provider>(provider: &P, token: Address) -> Result<()> {
+    let code = provider.get_code_at(token).await?;
+    if code.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Token {} does not exist: no contract code at this address",
+            token
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a token amount using the token's decimals and reject it if it exceeds
+/// the owner's balance
+pub async fn parse_token_amount_for_owner<P: Provider>(
+    provider: &P,
+    token: Address,
+    owner: Address,
+    input: &str,
+) -> Result<U256> {
+    verify_token_exists(provider, token).await?;
+
+    let contract = IToken::new(token, provider);
+    let decimals = contract.decimals().call().await?;
+    let balance = contract.balanceOf(owner).call().await?;
+
+    parse_amount_within(input, decimals, balance, "balance")
+}
+
+/// Parse a token amount using the token's decimals and reject it if it exceeds
+/// the total supply
+pub async fn parse_token_amount<P: Provider>(
+    provider: &P,
+    token: Address,
+    input: &str,
+) -> Result<U256> {
+    verify_token_exists(provider, token).await?;
+
+    let contract = IToken::new(token, provider);
+    let decimals = contract.decimals().call().await?;
+    let total_supply = contract.totalSupply().call().await?;
+
+    parse_amount_within(input, decimals, total_supply, "total supply")
+}
+
+/// Parse a MON amount and reject it if it exceeds the owner's native balance
+pub async fn parse_mon_amount_for_owner<P: Provider>(
+    provider: &P,
+    owner: Address,
+    input: &str,
+) -> Result<U256> {
+    let balance = provider.get_balance(owner).await?;
+    parse_amount_within(input, 18, balance, "MON balance")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_address() {
+        let checksummed = "0x52D34d8536350Cd997bCBD0b9E9d722452f341F5";
+        assert!(parse_address(checksummed).is_ok());
+        assert!(parse_address(&checksummed.to_lowercase()).is_ok());
+
+        let bad_checksum = "0x52d34d8536350Cd997bCBD0b9E9d722452f341F5";
+        let err = parse_address(bad_checksum).unwrap_err().to_string();
+        assert!(err.contains("checksum mismatch"));
+        assert!(err.contains(checksummed));
+
+        assert!(parse_address("52D34d8536350Cd997bCBD0b9E9d722452f341F5").is_err());
+        assert!(parse_address("0x1234").is_err());
+        assert!(parse_address("0xZZD34d8536350Cd997bCBD0b9E9d722452f341F5").is_err());
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(
+            parse_amount("1.5", 18).unwrap(),
+            U256::from(1_500_000_000_000_000_000u128)
+        );
+        assert!(parse_amount("", 18).is_err());
+        assert!(parse_amount("-1", 18).is_err());
+        assert!(parse_amount("abc", 18).is_err());
+
+        let err = parse_amount_within("2", 0, U256::from(1), "balance")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exceeds balance"));
+    }
+}